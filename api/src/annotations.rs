@@ -0,0 +1,190 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use crate::qa::{CiReport, QaIssue};
+use crate::source_locations::{location_path, locations_for, SourceLocations};
+use crate::validation::ValidationIssue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[doc = "Уровень аннотации GitHub Actions"]
+pub enum AnnotationLevel {
+    Error,
+    Warning,
+    Notice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[doc = "Аннотация GitHub Actions: команда ::error file=…,line=…::сообщение, которую GitHub показывает рядом со строкой файла в pull request"]
+pub struct Annotation {
+    pub level: AnnotationLevel,
+    /// Путь к файлу от корня git-репозитория. Без него аннотация показывается только в сводке запуска
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub title: String,
+    pub message: String,
+}
+
+// Экранирование сообщения команды GitHub Actions
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+// Экранирование значения параметра команды: кроме переводов строк экранируются : и ,
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.level {
+            AnnotationLevel::Error => "error",
+            AnnotationLevel::Warning => "warning",
+            AnnotationLevel::Notice => "notice",
+        };
+        let mut properties = vec![];
+        if let Some(file) = &self.file {
+            properties.push(format!("file={}", escape_property(file)));
+        }
+        if let Some(line) = self.line {
+            properties.push(format!("line={}", line));
+        }
+        properties.push(format!("title={}", escape_property(&self.title)));
+        write!(
+            f,
+            "::{} {}::{}",
+            level,
+            properties.join(","),
+            escape_data(&self.message)
+        )
+    }
+}
+
+impl Annotation {
+    #[doc = "Ошибка в первом месте использования ключа из сохраненных при сканировании. Если мест нет, аннотация ставится на fallback_file без строки"]
+    pub fn error_at_key(
+        locations: &SourceLocations,
+        tag: &str,
+        fallback_file: Option<String>,
+        title: &str,
+        message: String,
+    ) -> Annotation {
+        let (file, line) = match locations_for(locations, tag).first() {
+            Some(location) => (Some(location.file.clone()), Some(location.line)),
+            None => (fallback_file, None),
+        };
+        Annotation {
+            level: AnnotationLevel::Error,
+            file,
+            line,
+            title: title.to_owned(),
+            message,
+        }
+    }
+}
+
+#[doc = "Аннотации для проблем проверки качества переводов"]
+pub fn qa_annotations(issues: &[QaIssue], locations: &SourceLocations) -> Vec<Annotation> {
+    issues
+        .iter()
+        .map(|issue| {
+            Annotation::error_at_key(
+                locations,
+                &issue.tag,
+                None,
+                &format!("dms check [{}]", issue.language),
+                issue.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[doc = "Аннотации для проверки в CI: непереведенные ключи, устаревшие переводы и проблемы проверки качества"]
+pub fn ci_annotations(report: &CiReport, locations: &SourceLocations) -> Vec<Annotation> {
+    let mut annotations: Vec<Annotation> = report
+        .keys
+        .iter()
+        .map(|key| {
+            Annotation::error_at_key(
+                locations,
+                &key.tag,
+                None,
+                &format!("dms check [{}]", key.language),
+                format!("[{}] {}: {}", key.language, key.tag, key.problem),
+            )
+        })
+        .collect();
+    annotations.extend(qa_annotations(&report.issues, locations));
+    annotations
+}
+
+#[doc = "Аннотации для нарушений целостности. Проблема ключа ставится на место его использования, остальные - на файл словаря"]
+pub fn validation_annotations(
+    dictionary_dir: &str,
+    issues: &[ValidationIssue],
+    locations: &SourceLocations,
+) -> Vec<Annotation> {
+    issues
+        .iter()
+        .map(|issue| {
+            let dictionary_file = location_path(&Path::new(dictionary_dir).join(&issue.file));
+            match &issue.tag {
+                Some(tag) => Annotation::error_at_key(
+                    locations,
+                    tag,
+                    Some(dictionary_file),
+                    "dms validate",
+                    issue.to_string(),
+                ),
+                None => Annotation {
+                    level: AnnotationLevel::Error,
+                    file: Some(dictionary_file),
+                    line: None,
+                    title: "dms validate".to_owned(),
+                    message: issue.to_string(),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_locations::SourceLocation;
+
+    #[test]
+    fn test_annotations_use_source_locations_and_escape_values() {
+        let locations = SourceLocations::from([(
+            "Корзина".to_owned(),
+            vec![SourceLocation {
+                file: "src/cart.tsx".to_owned(),
+                line: 12,
+            }],
+        )]);
+        let annotation = Annotation::error_at_key(
+            &locations,
+            "Корзина",
+            None,
+            "dms check [en]",
+            "нет перевода\n50%".to_owned(),
+        );
+        assert_eq!(
+            annotation.to_string(),
+            "::error file=src/cart.tsx,line=12,title=dms check [en]::нет перевода%0A50%25"
+        );
+        let annotation = Annotation::error_at_key(
+            &locations,
+            "Вход",
+            Some("dictionaries/dictionary-en.json".to_owned()),
+            "a: b, c",
+            "пустой перевод".to_owned(),
+        );
+        assert_eq!(
+            annotation.to_string(),
+            "::error file=dictionaries/dictionary-en.json,title=a%3A b%2C c::пустой перевод"
+        );
+    }
+}
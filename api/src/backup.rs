@@ -0,0 +1,228 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::atomic_write;
+use crate::parser::get_dictionary_language;
+use crate::source_locations::LOCATIONS_FILE;
+
+#[doc = "Каталог резервных копий внутри репозитория словарей"]
+pub const BACKUP_DIR: &str = ".dms/backups";
+
+#[doc = "Сколько последних резервных копий хранится, более старые удаляются"]
+pub const MAX_BACKUPS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Снимок файлов репозитория словарей"]
+pub struct Backup {
+    /// Имя каталога копии: время создания в UTC и операция, перед которой она сделана
+    pub name: String,
+    /// Файлы словарей и мест использования фраз, сохраненные в копии
+    pub files: Vec<String>,
+}
+
+#[doc = "Время в UTC вида 20240131-235959-123. Такие строки сортируются в хронологическом порядке"]
+pub fn utc_timestamp(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = duration.as_secs();
+    let (days, day_seconds) = ((seconds / 86400) as i64, seconds % 86400);
+    // Перевод номера дня в дату григорианского календаря
+    let shifted = days + 719468;
+    let era = shifted.div_euclid(146097);
+    let day_of_era = shifted - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        year,
+        month,
+        day,
+        day_seconds / 3600,
+        day_seconds / 60 % 60,
+        day_seconds % 60,
+        duration.subsec_millis()
+    )
+}
+
+fn backups_path(dictionary_dir: &str) -> PathBuf {
+    Path::new(dictionary_dir).join(BACKUP_DIR)
+}
+
+// Файлы репозитория, которые попадают в копию: словари и места использования фраз
+fn repository_files(dir: &Path) -> Result<Vec<String>, StaticDictionaryErrors> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let filename = entry.file_name().to_string_lossy().into_owned();
+        if filename == LOCATIONS_FILE || get_dictionary_language(&filename).is_ok() {
+            files.push(filename);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[doc = "Возвращает имена резервных копий репозитория от самой старой к самой новой"]
+pub fn list_backups(dictionary_dir: &str) -> Result<Vec<String>, StaticDictionaryErrors> {
+    let path = backups_path(dictionary_dir);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups = vec![];
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            backups.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+#[doc = "Сохраняет словари и места использования фраз в новую резервную копию в .dms/backups. operation попадает в имя копии. Копии сверх MAX_BACKUPS удаляются, начиная с самых старых"]
+pub fn create_backup(
+    dictionary_dir: &str,
+    operation: &str,
+) -> Result<Backup, StaticDictionaryErrors> {
+    let files = repository_files(Path::new(dictionary_dir))?;
+    let timestamp = utc_timestamp(SystemTime::now());
+    let mut name = format!("{}-{}", timestamp, operation);
+    let mut attempt = 1;
+    while backups_path(dictionary_dir).join(&name).exists() {
+        attempt += 1;
+        name = format!("{}-{}-{}", timestamp, operation, attempt);
+    }
+    let target = backups_path(dictionary_dir).join(&name);
+    fs::create_dir_all(&target)?;
+    for filename in &files {
+        fs::copy(
+            Path::new(dictionary_dir).join(filename),
+            target.join(filename),
+        )?;
+    }
+
+    tracing::info!("Резервная копия {}: файлов {}", target.display(), files.len());
+
+    let backups = list_backups(dictionary_dir)?;
+    for old in &backups[..backups.len().saturating_sub(MAX_BACKUPS)] {
+        tracing::debug!("Удалена старая резервная копия {}", old);
+        fs::remove_dir_all(backups_path(dictionary_dir).join(old))?;
+    }
+    Ok(Backup { name, files })
+}
+
+#[doc = "Восстанавливает репозиторий из резервной копии с указанным именем или из последней копии. Файлы копии записываются атомарно, а словари, которых нет в копии, удаляются только после этого. Сама копия после восстановления сохраняется"]
+pub fn rollback(
+    dictionary_dir: &str,
+    name: Option<&str>,
+) -> Result<Backup, StaticDictionaryErrors> {
+    let backups = list_backups(dictionary_dir)?;
+    let name = match name {
+        Some(name) if backups.iter().any(|backup| backup == name) => name.to_owned(),
+        Some(name) => return Err(StaticDictionaryErrors::BackupNotFound(name.to_owned())),
+        None => backups.last().cloned().ok_or_else(|| {
+            StaticDictionaryErrors::BackupNotFound(format!(
+                "в {} нет резервных копий",
+                backups_path(dictionary_dir).display()
+            ))
+        })?,
+    };
+    let source = backups_path(dictionary_dir).join(&name);
+    let files = repository_files(&source)?;
+    // Сначала файлы копии заменяют текущие атомарно, и только потом удаляются лишние словари: если копирование прервется, в репозитории останутся целые файлы
+    for filename in &files {
+        atomic_write(
+            Path::new(dictionary_dir).join(filename),
+            fs::read(source.join(filename))?,
+        )?;
+    }
+    for filename in repository_files(Path::new(dictionary_dir))? {
+        if !files.contains(&filename) {
+            fs::remove_file(Path::new(dictionary_dir).join(filename))?;
+        }
+    }
+    Ok(Backup { name, files })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_timestamp() {
+        assert_eq!(utc_timestamp(UNIX_EPOCH), "19700101-000000-000");
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(1_709_251_199_250);
+        assert_eq!(utc_timestamp(time), "20240229-235959-250");
+    }
+
+    #[test]
+    fn test_backup_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Вход"]"#).unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in"}"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "не словарь").unwrap();
+
+        let backup = create_backup(repo, "sync").unwrap();
+        assert!(backup.name.ends_with("-sync"));
+        assert_eq!(
+            backup.files,
+            vec!["dictionary-en.json", "dictionary-ru.base.json"]
+        );
+        let second = create_backup(repo, "sync").unwrap();
+        assert_ne!(backup.name, second.name);
+        assert_eq!(
+            list_backups(repo).unwrap(),
+            vec![backup.name.clone(), second.name]
+        );
+
+        fs::write(dir.path().join("dictionary-en.json"), "{}").unwrap();
+        fs::write(dir.path().join("dictionary-de.json"), "{}").unwrap();
+        rollback(repo, Some(&backup.name)).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap(),
+            r#"{"Вход": "Sign in"}"#
+        );
+        assert!(!dir.path().join("dictionary-de.json").exists());
+        assert!(dir.path().join("notes.txt").exists());
+        assert!(matches!(
+            rollback(repo, Some("missing")),
+            Err(StaticDictionaryErrors::BackupNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_failed_rollback_keeps_current_dictionaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Вход"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-fr.json"), r#"{"Вход": "Connexion"}"#).unwrap();
+        create_backup(repo, "sync").unwrap();
+
+        // Файл копии нельзя записать на место директории, восстановление прерывается
+        fs::remove_file(dir.path().join("dictionary-fr.json")).unwrap();
+        fs::create_dir(dir.path().join("dictionary-fr.json")).unwrap();
+        fs::write(dir.path().join("dictionary-de.json"), r#"{"Вход": "Anmelden"}"#).unwrap();
+        assert!(rollback(repo, None).is_err());
+        assert!(dir.path().join("dictionary-de.json").exists());
+    }
+}
@@ -0,0 +1,123 @@
+use std::fs;
+
+use serde_json::{json, Map, Value};
+
+use super::templating::{resolve_output_path, template_variables};
+use super::xliff::{to_xliff, xliff_units, XliffVersion};
+use super::{
+    key_path, nest_entries, to_json_output, words_with_fallback, BuildOptions, BuildReport,
+};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::static_translate::get_basic_language;
+use crate::types::Word;
+
+#[doc = "Шаблон пути итоговых словарей ngx-translate по умолчанию"]
+pub const DEFAULT_NGX_TRANSLATE_TEMPLATE: &str = "{output_dir}/{lang}.json";
+
+#[doc = "Словарь ngx-translate: плоский или с ключами с точками, развернутыми во вложенные объекты. Переменные {{name}} у ngx-translate такие же, как у i18next"]
+pub fn ngx_translate_messages(
+    words: &[Word],
+    nested: bool,
+) -> Result<Map<String, Value>, BuildSystemErrors> {
+    if !nested {
+        return Ok(words
+            .iter()
+            .map(|word| (word.suffixed_tag(), json!(word.word)))
+            .collect());
+    }
+    nest_entries(
+        words
+            .iter()
+            .map(|word| (key_path(&word.suffixed_tag()), json!(word.word))),
+    )
+}
+
+#[doc = "Собирает словари <lang>.json для ngx-translate. Недостающие переводы берутся по цепочке запасных локалей"]
+pub fn build_for_ngx_translate(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+    nested: bool,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_NGX_TRANSLATE_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, language, "translation"),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let messages = ngx_translate_messages(&words, nested)?;
+        atomic_write(output_path, to_json_output(&messages, options)?)?;
+        report.inherited.insert(language.to_owned(), inherited);
+    }
+    Ok(report)
+}
+
+#[doc = "Шаблон пути итоговых файлов XLIFF для Angular по умолчанию"]
+pub const DEFAULT_ANGULAR_XLF_TEMPLATE: &str = "{output_dir}/messages.{lang}.xlf";
+
+#[doc = "Собирает файлы messages.<lang>.xlf (или по шаблону пути из параметров) для встроенной интернационализации Angular (XLIFF 1.2). Ключи словаря становятся id единиц перевода, поэтому в шаблонах нужно указывать их как пользовательские id (@@ключ). Возвращает пути созданных файлов"]
+pub fn build_for_angular_xlf(
+    dictionary_dir: &str,
+    output_dir: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<Vec<String>, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_ANGULAR_XLF_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let source_language = get_basic_language(dictionary_dir)?;
+    let mut written = vec![];
+    for language in &languages {
+        let units = xliff_units(dictionary_dir, language)?;
+        let path = resolve_output_path(
+            output_template,
+            &template_variables(output_dir, language, "messages"),
+        )?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(
+            &path,
+            to_xliff(&source_language, language, &units, XliffVersion::V1_2),
+        )?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ngx_translate_flat_and_nested() {
+        let words = vec![
+            Word::new("Pay".into(), "checkout.pay".into(), "en".into()),
+            Word::new("Hello, {{name}}".into(), "greeting".into(), "en".into()),
+        ];
+        let flat = ngx_translate_messages(&words, false).unwrap();
+        assert_eq!(flat["checkout.pay"], "Pay");
+        let nested = ngx_translate_messages(&words, true).unwrap();
+        assert_eq!(nested["checkout"]["pay"], "Pay");
+        assert_eq!(nested["greeting"], "Hello, {{name}}");
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use super::templating::{resolve_output_path, template_variables};
+use super::{convert_placeholders, to_json_output, words_with_fallback, BuildOptions, BuildReport};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::Word;
+
+#[doc = "Шаблон пути итоговых файлов ARB по умолчанию"]
+pub const DEFAULT_ARB_TEMPLATE: &str = "{output_dir}/app_{lang}.arb";
+
+#[doc = "Переменная ICU для форм множественного числа"]
+pub const PLURAL_PLACEHOLDER: &str = "count";
+#[doc = "Переменная ICU для вариантов по роду"]
+pub const SELECT_PLACEHOLDER: &str = "gender";
+
+#[doc = "Локаль в формате Flutter: pt-BR -> pt_BR"]
+pub fn arb_locale(language: &str) -> String {
+    language.replace('-', "_")
+}
+
+#[doc = "Переводит переменные i18next {{name}} в формат ICU {name}"]
+pub fn to_icu_placeholders(text: &str) -> String {
+    convert_placeholders(text, |name| format!("{{{}}}", name))
+}
+
+#[doc = "Находит переменные ICU {name} в сообщении"]
+pub fn icu_placeholders(message: &str) -> Vec<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let mut found: Vec<String> = vec![];
+    for captures in PATTERN
+        .get_or_init(|| Regex::new(r"\{(\w+)[},]").unwrap())
+        .captures_iter(message)
+    {
+        if !found.contains(&captures[1].to_owned()) {
+            found.push(captures[1].to_owned());
+        }
+    }
+    found
+}
+
+// Сообщение ICU для ключа: обычная строка, plural для форм множественного числа или select для вариантов
+fn icu_message(words: &[&Word]) -> String {
+    let branches = |selector: fn(&Word) -> Option<&String>| {
+        words
+            .iter()
+            .filter_map(|word| {
+                selector(word)
+                    .map(|name| format!("{}{{{}}}", name, to_icu_placeholders(&word.word)))
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    if words.iter().any(|word| word.plural_form.is_some()) {
+        return format!(
+            "{{{}, plural, {}}}",
+            PLURAL_PLACEHOLDER,
+            branches(|word| word.plural_form.as_ref())
+        );
+    }
+    if words.iter().any(|word| word.variant.is_some()) {
+        return format!(
+            "{{{}, select, {}}}",
+            SELECT_PLACEHOLDER,
+            branches(|word| word.variant.as_ref())
+        );
+    }
+    words
+        .first()
+        .map(|word| to_icu_placeholders(&word.word))
+        .unwrap_or_default()
+}
+
+#[doc = "Формирует содержимое файла ARB: @@locale, сообщения и блоки @key с описанием и переменными"]
+pub fn arb_document(language: &str, words: &[Word]) -> Value {
+    let mut by_tag: BTreeMap<&str, Vec<&Word>> = BTreeMap::new();
+    for word in words {
+        by_tag.entry(&word.tag).or_default().push(word);
+    }
+    let mut document = Map::new();
+    document.insert("@@locale".to_owned(), json!(arb_locale(language)));
+    for (tag, words) in by_tag {
+        let message = icu_message(&words);
+        let description: Vec<&str> = words
+            .first()
+            .into_iter()
+            .flat_map(|word| [&word.context, &word.comment])
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let placeholders: Map<String, Value> = icu_placeholders(&message)
+            .into_iter()
+            .map(|name| {
+                let metadata = match name.as_str() {
+                    PLURAL_PLACEHOLDER => json!({"type": "int"}),
+                    SELECT_PLACEHOLDER => json!({"type": "String"}),
+                    _ => json!({}),
+                };
+                (name, metadata)
+            })
+            .collect();
+        document.insert(tag.to_owned(), json!(message));
+        let mut metadata = Map::new();
+        if !description.is_empty() {
+            metadata.insert("description".to_owned(), json!(description.join("; ")));
+        }
+        if !placeholders.is_empty() {
+            metadata.insert("placeholders".to_owned(), Value::Object(placeholders));
+        }
+        if !metadata.is_empty() {
+            document.insert(format!("@{}", tag), Value::Object(metadata));
+        }
+    }
+    Value::Object(document)
+}
+
+#[doc = "Собирает файлы app_<lang>.arb для Flutter. Недостающие переводы берутся по цепочке запасных локалей, как и при сборке для i18next"]
+pub fn build_for_arb(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_ARB_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, &arb_locale(language), "app"),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let document = arb_document(language, &words);
+        atomic_write(output_path, to_json_output(&document, options)?)?;
+        report.inherited.insert(language.to_owned(), inherited);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arb_document_with_metadata() {
+        let words = vec![
+            Word::new("Hello, {{name}}".into(), "greeting".into(), "en".into())
+                .with_context(Some("Главный экран".into())),
+            Word::new("{{count}} apple".into(), "apples".into(), "en".into())
+                .with_plural_form(Some("one".into())),
+            Word::new("{{count}} apples".into(), "apples".into(), "en".into())
+                .with_plural_form(Some("other".into())),
+            Word::new("Welcome".into(), "welcome".into(), "en".into()),
+        ];
+        let document = arb_document("pt-BR", &words);
+        assert_eq!(document["@@locale"], "pt_BR");
+        assert_eq!(document["greeting"], "Hello, {name}");
+        assert_eq!(document["@greeting"]["description"], "Главный экран");
+        assert_eq!(document["@greeting"]["placeholders"]["name"], json!({}));
+        assert_eq!(
+            document["apples"],
+            "{count, plural, one{{count} apple} other{{count} apples}}"
+        );
+        assert_eq!(
+            document["@apples"]["placeholders"]["count"],
+            json!({"type": "int"})
+        );
+        assert_eq!(document["welcome"], "Welcome");
+        assert!(document.get("@welcome").is_none());
+    }
+}
@@ -0,0 +1,660 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::templating::{resolve_output_path, template_variables};
+use super::BuildOptions;
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::spreadsheet::{TableRow, TranslationTable};
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_existing_dictionary,
+};
+use crate::types::{plural_categories, EntryStatus, TranslatedDictionary};
+
+#[doc = "Название файла шаблона POT"]
+pub const POT_FILE_NAME: &str = "messages.pot";
+
+#[doc = "Шаблон пути каталогов PO по умолчанию. Переменная {namespace} - домен gettext messages, например {output_dir}/{lang}/LC_MESSAGES/{namespace}.po"]
+pub const DEFAULT_PO_TEMPLATE: &str = "{output_dir}/{lang}.po";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Сообщение каталога gettext"]
+pub struct PoMessage {
+    /// Ключ словаря, если он отличается от исходной фразы
+    pub context: Option<String>,
+    pub id: String,
+    pub id_plural: Option<String>,
+    /// Переводы: один для обычной фразы или по одному на каждую форму множественного числа
+    pub strings: Vec<String>,
+    /// Комментарии для переводчика (#.)
+    pub comments: Vec<String>,
+    /// Перевод требует проверки (машинный перевод)
+    pub fuzzy: bool,
+}
+
+impl PoMessage {
+    #[doc = "Ключ словаря сообщения: msgctxt, а если его нет - msgid"]
+    pub fn key(&self) -> &str {
+        self.context.as_deref().unwrap_or(&self.id)
+    }
+}
+
+#[doc = "Выражение Plural-Forms для языка. Номера форм соответствуют порядку категорий plural_categories, формы для дробных чисел (many в польском, чешском и литовском) целым числам не выбираются. Для языков без известной формулы заголовок не формируется"]
+pub fn plural_forms_header(language: &str) -> Option<&'static str> {
+    let language = language.split(['-', '_']).next().unwrap_or(language);
+    match language.to_lowercase().as_str() {
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" => Some("nplurals=1; plural=0;"),
+        "en" | "de" | "nl" | "sv" | "da" | "no" | "nb" | "nn" | "fi" | "et" | "it" | "es" | "el"
+        | "hu" | "bg" | "ca" | "eu" | "gl" | "af" => Some("nplurals=2; plural=(n != 1);"),
+        "fr" => Some("nplurals=2; plural=(n > 1);"),
+        "ru" | "uk" | "be" => Some(
+            "nplurals=4; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<12 || n%100>14) ? 1 : n%10==0 || (n%10>=5 && n%10<=9) || (n%100>=11 && n%100<=14) ? 2 : 3);",
+        ),
+        "pl" => Some(
+            "nplurals=4; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<12 || n%100>14) ? 1 : 2);",
+        ),
+        "cs" | "sk" => Some("nplurals=4; plural=(n==1 ? 0 : n>=2 && n<=4 ? 1 : 3);"),
+        "lt" => Some(
+            "nplurals=4; plural=(n%10==1 && (n%100<11 || n%100>19) ? 0 : n%10>=2 && (n%100<11 || n%100>19) ? 1 : 3);",
+        ),
+        "bs" | "hr" | "sr" => Some(
+            "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<12 || n%100>14) ? 1 : 2);",
+        ),
+        "ro" => Some("nplurals=3; plural=(n==1 ? 0 : n==0 || (n%100>0 && n%100<20) ? 1 : 2);"),
+        "he" => Some("nplurals=3; plural=(n==1 ? 0 : n==2 ? 1 : 2);"),
+        "lv" => Some(
+            "nplurals=3; plural=(n%10==0 || (n%100>=11 && n%100<=19) ? 0 : n%10==1 && n%100!=11 ? 1 : 2);",
+        ),
+        "sl" => Some("nplurals=4; plural=(n%100==1 ? 0 : n%100==2 ? 1 : n%100==3 || n%100==4 ? 2 : 3);"),
+        "ga" => Some("nplurals=5; plural=(n==1 ? 0 : n==2 ? 1 : n>=3 && n<=6 ? 2 : n>=7 && n<=10 ? 3 : 4);"),
+        "ar" => Some(
+            "nplurals=6; plural=(n==0 ? 0 : n==1 ? 1 : n==2 ? 2 : n%100>=3 && n%100<=10 ? 3 : n%100>=11 ? 4 : 5);",
+        ),
+        "cy" => Some("nplurals=6; plural=(n==0 ? 0 : n==1 ? 1 : n==2 ? 2 : n==3 ? 3 : n==6 ? 4 : 5);"),
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[doc = "Сообщения каталога для языка, упорядоченные по ключу словаря. Без языка формируется шаблон POT с пустыми переводами"]
+pub fn po_messages(
+    dictionary_dir: &str,
+    language: Option<&str>,
+) -> Result<Vec<PoMessage>, BuildSystemErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let target_language = language.unwrap_or(&basic_language);
+    let dictionary = match language {
+        Some(language) => read_existing_dictionary(dictionary_dir, language)?,
+        None => TranslatedDictionary::new(),
+    };
+    let translations: HashMap<String, String> = dictionary
+        .iter()
+        .flat_map(|(tag, entry)| entry.words(tag, target_language))
+        .map(|word| (word.suffixed_tag(), word.word))
+        .collect();
+    let translation = |key: &str| translations.get(key).cloned().unwrap_or_default();
+    let context_for = |key: &str, id: &str| (key != id).then(|| key.to_owned());
+
+    let mut messages = vec![];
+    for entry in parse_static_basic_entries(dictionary_dir)? {
+        let comments: Vec<String> = [&entry.context, &entry.comment]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let fuzzy = dictionary
+            .get(entry.tag())
+            .is_some_and(|translated| translated.status == EntryStatus::Machine);
+        if let Some(forms) = &entry.plural_forms {
+            let id = forms.get("one").unwrap_or(&entry.phrase).to_owned();
+            let strings = match language {
+                Some(_) => plural_categories(target_language)
+                    .iter()
+                    .map(|category| translation(&format!("{}_{}", entry.tag(), category)))
+                    .collect(),
+                None => vec![String::new(), String::new()],
+            };
+            messages.push(PoMessage {
+                context: context_for(entry.tag(), &id),
+                id_plural: Some(forms.get("other").unwrap_or(&entry.phrase).to_owned()),
+                id,
+                strings,
+                comments,
+                fuzzy,
+            });
+            continue;
+        }
+        for word in entry.words_for_translation(&basic_language, target_language) {
+            let key = word.suffixed_tag();
+            messages.push(PoMessage {
+                context: context_for(&key, &word.word),
+                strings: vec![translation(&key)],
+                id: word.word,
+                comments: comments.clone(),
+                fuzzy,
+                ..Default::default()
+            });
+        }
+    }
+    messages.sort_by(|a, b| a.key().cmp(b.key()));
+    Ok(messages)
+}
+
+#[doc = "Заголовок каталога (перевод пустого msgid) для языка или для шаблона POT"]
+pub fn catalog_header(language: Option<&str>) -> String {
+    let mut header =
+        String::from("Content-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n");
+    match language {
+        Some(language) => {
+            let _ = writeln!(header, "Language: {}", language);
+            if let Some(plural_forms) = plural_forms_header(language) {
+                let _ = writeln!(header, "Plural-Forms: {}", plural_forms);
+            }
+        }
+        None => header.push_str("Plural-Forms: nplurals=INTEGER; plural=EXPRESSION;\n"),
+    }
+    header
+}
+
+#[doc = "Формирует текст каталога PO (или шаблона POT, если язык не передан)"]
+pub fn to_po(language: Option<&str>, messages: &[PoMessage]) -> String {
+    let mut po = String::from("msgid \"\"\nmsgstr \"\"\n");
+    for line in catalog_header(language).lines() {
+        let _ = writeln!(po, "\"{}\\n\"", escape(line));
+    }
+    for message in messages {
+        po.push('\n');
+        for comment in &message.comments {
+            let _ = writeln!(po, "#. {}", comment.replace('\n', " "));
+        }
+        if message.fuzzy {
+            po.push_str("#, fuzzy\n");
+        }
+        if let Some(context) = &message.context {
+            let _ = writeln!(po, "msgctxt \"{}\"", escape(context));
+        }
+        let _ = writeln!(po, "msgid \"{}\"", escape(&message.id));
+        match &message.id_plural {
+            Some(id_plural) => {
+                let _ = writeln!(po, "msgid_plural \"{}\"", escape(id_plural));
+                for (index, string) in message.strings.iter().enumerate() {
+                    let _ = writeln!(po, "msgstr[{}] \"{}\"", index, escape(string));
+                }
+            }
+            None => {
+                let string = message.strings.first().map(String::as_str).unwrap_or("");
+                let _ = writeln!(po, "msgstr \"{}\"", escape(string));
+            }
+        }
+    }
+    po
+}
+
+#[doc = "Компилирует каталог в бинарный формат MO (как msgfmt). Непереведенные сообщения и сообщения с флагом fuzzy не попадают в каталог"]
+pub fn compile_mo(language: &str, messages: &[PoMessage]) -> Vec<u8> {
+    let mut entries: Vec<(Vec<u8>, Vec<u8>)> =
+        vec![(vec![], catalog_header(Some(language)).into_bytes())];
+    for message in messages {
+        if message.fuzzy || message.strings.iter().all(|string| string.is_empty()) {
+            continue;
+        }
+        let mut key = vec![];
+        if let Some(context) = &message.context {
+            key.extend_from_slice(context.as_bytes());
+            key.push(0x04);
+        }
+        key.extend_from_slice(message.id.as_bytes());
+        if let Some(id_plural) = &message.id_plural {
+            key.push(0);
+            key.extend_from_slice(id_plural.as_bytes());
+        }
+        entries.push((key, message.strings.join("\0").into_bytes()));
+    }
+    // gettext ищет сообщения двоичным поиском, поэтому ключи должны быть отсортированы
+    entries.sort();
+
+    const HEADER_SIZE: u32 = 28;
+    let count = entries.len() as u32;
+    let originals_offset = HEADER_SIZE;
+    let translations_offset = originals_offset + count * 8;
+    let mut data_offset = translations_offset + count * 8;
+    let mut originals = vec![];
+    let mut translations = vec![];
+    let mut data = vec![];
+    for (table, strings) in [
+        (
+            &mut originals,
+            entries.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+        ),
+        (
+            &mut translations,
+            entries.iter().map(|(_, value)| value).collect::<Vec<_>>(),
+        ),
+    ] {
+        for string in strings {
+            table.extend_from_slice(&(string.len() as u32).to_le_bytes());
+            table.extend_from_slice(&data_offset.to_le_bytes());
+            data.extend_from_slice(string);
+            data.push(0);
+            data_offset += string.len() as u32 + 1;
+        }
+    }
+
+    let mut mo = vec![];
+    for value in [
+        0x950412de_u32,
+        0,
+        count,
+        originals_offset,
+        translations_offset,
+        0,
+        data_offset,
+    ] {
+        mo.extend_from_slice(&value.to_le_bytes());
+    }
+    mo.extend(originals);
+    mo.extend(translations);
+    mo.extend(data);
+    mo
+}
+
+#[doc = "Собирает шаблон messages.pot в директории output_dir и каталоги {lang}.po (или по шаблону пути из параметров), а с compile_mo - еще и бинарные каталоги .mo рядом с .po. Возвращает пути созданных файлов"]
+pub fn build_for_gettext(
+    dictionary_dir: &str,
+    output_dir: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+    compile: bool,
+) -> Result<Vec<String>, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_PO_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    fs::create_dir_all(output_dir)?;
+    let mut written = vec![];
+    let pot_path = Path::new(output_dir).join(POT_FILE_NAME);
+    atomic_write(&pot_path, to_po(None, &po_messages(dictionary_dir, None)?))?;
+    written.push(pot_path.to_string_lossy().into_owned());
+    for language in &languages {
+        let path = resolve_output_path(
+            output_template,
+            &template_variables(output_dir, language, "messages"),
+        )?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let messages = po_messages(dictionary_dir, Some(language))?;
+        atomic_write(&path, to_po(Some(language), &messages))?;
+        written.push(path.to_string_lossy().into_owned());
+        if compile {
+            let path = path.with_extension("mo");
+            atomic_write(&path, compile_mo(language, &messages))?;
+            written.push(path.to_string_lossy().into_owned());
+        }
+    }
+    Ok(written)
+}
+
+fn quoted(line: &str, number: usize) -> Result<String, BuildSystemErrors> {
+    line.trim()
+        .strip_prefix('"')
+        .and_then(|line| line.strip_suffix('"'))
+        .map(unescape)
+        .ok_or_else(|| {
+            BuildSystemErrors::InvalidPo(format!("строка {}: ожидалась строка в кавычках", number))
+        })
+}
+
+#[doc = "Разбирает каталог PO. Возвращает язык из заголовка (если он указан) и сообщения без заголовка"]
+pub fn parse_po(content: &str) -> Result<(Option<String>, Vec<PoMessage>), BuildSystemErrors> {
+    // Поле, в которое дописываются строки-продолжения
+    enum Field {
+        Context,
+        Id,
+        IdPlural,
+        String(usize),
+    }
+    let mut messages: Vec<PoMessage> = vec![];
+    let mut current = PoMessage::default();
+    let mut field: Option<Field> = None;
+    let mut has_strings = false;
+    let mut pending_comments: Vec<String> = vec![];
+    let mut pending_fuzzy = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#~") {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix("#.") {
+            pending_comments.push(comment.trim().to_owned());
+            continue;
+        }
+        if let Some(flags) = line.strip_prefix("#,") {
+            pending_fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('"') {
+            let text = quoted(line, number)?;
+            match field {
+                Some(Field::Context) => current
+                    .context
+                    .get_or_insert_with(String::new)
+                    .push_str(&text),
+                Some(Field::Id) => current.id.push_str(&text),
+                Some(Field::IdPlural) => current
+                    .id_plural
+                    .get_or_insert_with(String::new)
+                    .push_str(&text),
+                Some(Field::String(index)) => current.strings[index].push_str(&text),
+                None => {
+                    return Err(BuildSystemErrors::InvalidPo(format!(
+                        "строка {}: строка вне сообщения",
+                        number
+                    )))
+                }
+            }
+            continue;
+        }
+        let (keyword, value) = line.split_once(char::is_whitespace).ok_or_else(|| {
+            BuildSystemErrors::InvalidPo(format!("строка {}: некорректная запись", number))
+        })?;
+        let value = quoted(value, number)?;
+        if (keyword == "msgctxt" || keyword == "msgid") && has_strings {
+            messages.push(std::mem::take(&mut current));
+            has_strings = false;
+        }
+        if keyword == "msgctxt" || (keyword == "msgid" && current.context.is_none()) {
+            current.comments = std::mem::take(&mut pending_comments);
+            current.fuzzy = std::mem::take(&mut pending_fuzzy);
+        }
+        field = Some(match keyword {
+            "msgctxt" => {
+                current.context = Some(value);
+                Field::Context
+            }
+            "msgid" => {
+                current.id = value;
+                Field::Id
+            }
+            "msgid_plural" => {
+                current.id_plural = Some(value);
+                Field::IdPlural
+            }
+            "msgstr" => {
+                has_strings = true;
+                current.strings = vec![value];
+                Field::String(0)
+            }
+            _ => {
+                let index = keyword
+                    .strip_prefix("msgstr[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .ok_or_else(|| {
+                        BuildSystemErrors::InvalidPo(format!(
+                            "строка {}: неизвестное ключевое слово {}",
+                            number, keyword
+                        ))
+                    })?;
+                has_strings = true;
+                if current.strings.len() <= index {
+                    current.strings.resize(index + 1, String::new());
+                }
+                current.strings[index] = value;
+                Field::String(index)
+            }
+        });
+    }
+    if has_strings {
+        messages.push(current);
+    }
+
+    let mut language = None;
+    if let Some(header) = messages
+        .iter()
+        .position(|message| message.id.is_empty() && message.context.is_none())
+    {
+        let header = messages.remove(header);
+        language = header
+            .strings
+            .first()
+            .into_iter()
+            .flat_map(|string| string.lines())
+            .find_map(|line| line.strip_prefix("Language:"))
+            .map(|language| language.trim().to_owned())
+            .filter(|language| !language.is_empty());
+    }
+    Ok((language, messages))
+}
+
+#[doc = "Загружает каталог PO в репозиторий. Язык берется из заголовка Language, а если его нет - из названия файла. Сообщения с флагом fuzzy и пустые переводы пропускаются. Возвращает количество измененных переводов (каждая форма множественного числа считается отдельно)"]
+pub fn import_po(dictionary_dir: &str, path: &str) -> Result<usize, BuildSystemErrors> {
+    let (language, messages) = parse_po(&fs::read_to_string(path)?)?;
+    let Some(language) = language.or_else(|| {
+        Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+    }) else {
+        return Err(BuildSystemErrors::InvalidPo(
+            "не удалось определить язык каталога".to_owned(),
+        ));
+    };
+    let mut rows = vec![];
+    for message in messages.into_iter().filter(|message| !message.fuzzy) {
+        // msgctxt хранит ключ словаря, если он отличается от исходной фразы
+        let key = message.context.unwrap_or(message.id);
+        let tags: Vec<String> = match message.id_plural {
+            Some(_) => plural_categories(&language)
+                .iter()
+                .map(|category| format!("{}_{}", key, category))
+                .collect(),
+            None => vec![key],
+        };
+        for (tag, text) in tags.into_iter().zip(message.strings) {
+            rows.push(TableRow {
+                tag,
+                translations: [(language.clone(), text)].into(),
+                ..Default::default()
+            });
+        }
+    }
+    let table = TranslationTable {
+        languages: vec![language],
+        rows,
+    };
+    Ok(table.merge_into_repository(dictionary_dir)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_translate::read_translated_dictionary;
+
+    #[test]
+    fn test_plural_forms_match_language_categories() {
+        for language in [
+            "ja", "en", "fr", "ru", "pl", "cs", "sk", "lt", "hr", "ro", "he", "lv", "sl", "ga", "ar", "cy",
+        ] {
+            let header = plural_forms_header(language).unwrap();
+            let nplurals = format!("nplurals={};", plural_categories(language).len());
+            assert!(header.starts_with(&nplurals), "{}: {}", language, header);
+        }
+        assert_eq!(
+            plural_forms_header("pl-PL"),
+            Some("nplurals=4; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<12 || n%100>14) ? 1 : 2);")
+        );
+        assert_eq!(
+            plural_forms_header("cs"),
+            Some("nplurals=4; plural=(n==1 ? 0 : n>=2 && n<=4 ? 1 : 3);")
+        );
+        assert_eq!(plural_forms_header("pt"), None);
+        assert!(!catalog_header(Some("pt")).contains("Plural-Forms"));
+    }
+
+    #[test]
+    fn test_po_build_and_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Сказать \"да\"", "comment": "Кнопка"}, {"phrase": "яблоки", "plural_forms": {"one": "{{count}} яблоко", "other": "{{count}} яблок"}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Сказать \"да\"": {"value": "Say \"yes\"", "status": "machine"}, "яблоки": {"value": "", "plural_forms": {"one": "", "other": ""}}}"#,
+        )
+        .unwrap();
+
+        let output = dir.path().join("po");
+        let written = build_for_gettext(
+            repo,
+            output.to_str().unwrap(),
+            None,
+            &BuildOptions::default(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(written.len(), 2);
+        let pot = fs::read_to_string(output.join(POT_FILE_NAME)).unwrap();
+        assert!(pot.contains("#. Кнопка\nmsgid \"Сказать \\\"да\\\"\"\nmsgstr \"\""));
+        let po = fs::read_to_string(output.join("en.po")).unwrap();
+        assert!(po.contains("\"Plural-Forms: nplurals=2; plural=(n != 1);\\n\""));
+        assert!(po.contains("#, fuzzy\nmsgid \"Сказать \\\"да\\\"\"\nmsgstr \"Say \\\"yes\\\"\""));
+        assert!(po.contains("msgctxt \"яблоки\"\nmsgid \"{{count}} яблоко\"\nmsgid_plural \"{{count}} яблок\"\nmsgstr[0] \"\"\nmsgstr[1] \"\""));
+
+        let translated = po
+            .replace("msgstr[0] \"\"", "msgstr[0] \"{{count}} apple\"")
+            .replace("msgstr[1] \"\"", "msgstr[1] \"{{count}} apples\"")
+            .replace("msgstr \"Say \\\"yes\\\"\"", "msgstr \"Say\"\n\"yes\"");
+        fs::write(output.join("en.po"), translated).unwrap();
+        assert_eq!(
+            import_po(repo, output.join("en.po").to_str().unwrap()).unwrap(),
+            2
+        );
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        let forms = dictionary["яблоки"].plural_forms.as_ref().unwrap();
+        assert_eq!(forms["other"], "{{count}} apples");
+        // Сообщение с флагом fuzzy не импортируется
+        assert_eq!(dictionary["Сказать \"да\""].value, "Say \"yes\"");
+    }
+
+    #[test]
+    fn test_mo_catalog_layout() {
+        let messages = vec![
+            PoMessage {
+                id: "Привет".to_owned(),
+                strings: vec!["Hello".to_owned()],
+                ..Default::default()
+            },
+            PoMessage {
+                context: Some("яблоки".to_owned()),
+                id: "яблоко".to_owned(),
+                id_plural: Some("яблок".to_owned()),
+                strings: vec!["apple".to_owned(), "apples".to_owned()],
+                ..Default::default()
+            },
+            PoMessage {
+                id: "Пока".to_owned(),
+                strings: vec!["Bye".to_owned()],
+                fuzzy: true,
+                ..Default::default()
+            },
+        ];
+        let mo = compile_mo("en", &messages);
+        let word = |offset: usize| u32::from_le_bytes(mo[offset..offset + 4].try_into().unwrap());
+        let string = |table: u32, index: u32| {
+            let entry = (table + index * 8) as usize;
+            let (length, offset) = (word(entry) as usize, word(entry + 4) as usize);
+            String::from_utf8(mo[offset..offset + length].to_vec()).unwrap()
+        };
+        assert_eq!(word(0), 0x950412de);
+        // Заголовок и два сообщения: fuzzy не компилируется
+        assert_eq!(word(8), 3);
+        let (originals, translations) = (word(12), word(16));
+        assert_eq!(string(originals, 0), "");
+        assert!(string(translations, 0).contains("Plural-Forms: nplurals=2"));
+        assert_eq!(string(originals, 1), "Привет");
+        assert_eq!(string(translations, 1), "Hello");
+        assert_eq!(string(originals, 2), "яблоки\u{4}яблоко\0яблок");
+        assert_eq!(string(translations, 2), "apple\0apples");
+    }
+
+    #[test]
+    fn test_catalogs_follow_output_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Да"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-de.json"), r#"{"Да": "Ja"}"#).unwrap();
+
+        let output = dir.path().join("locale");
+        let options = BuildOptions {
+            output_template: Some("{output_dir}/{lang}/LC_MESSAGES/{namespace}.po".to_owned()),
+            ..Default::default()
+        };
+        build_for_gettext(repo, output.to_str().unwrap(), None, &options, true).unwrap();
+        assert!(output.join(POT_FILE_NAME).exists());
+        let catalog = output.join("de").join("LC_MESSAGES");
+        assert!(fs::read_to_string(catalog.join("messages.po"))
+            .unwrap()
+            .contains("msgstr \"Ja\""));
+        assert!(catalog.join("messages.mo").exists());
+    }
+
+    #[test]
+    fn test_po_continuation_lines_and_header() {
+        let (language, messages) = parse_po(
+            "msgid \"\"\nmsgstr \"\"\n\"Language: de\\n\"\n\n#. Приветствие\nmsgid \"При\"\n\"вет\"\nmsgstr \"Hal\"\n\"lo\\n\"\n",
+        )
+        .unwrap();
+        assert_eq!(language.as_deref(), Some("de"));
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, "Привет");
+        assert_eq!(messages[0].strings, vec!["Hallo\n"]);
+        assert_eq!(messages[0].comments, vec!["Приветствие"]);
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::i18next_integration::DEFAULT_NAMESPACE;
+use crate::dictionary_format::{read_dictionary_file, DictionaryFormat};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, namespace_directory};
+use crate::language::LanguageCode;
+use crate::parser::get_basic_dictionary;
+use crate::static_translate::write_translated_dictionary;
+use crate::types::{BaseEntry, DictionaryEntry, EntryStatus, TranslatedDictionary};
+
+#[doc = "Суффиксы форм множественного числа в ключах i18next (key_one, key_other)"]
+pub const PLURAL_SUFFIXES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+#[doc = "Сообщения одного пространства имен: язык -> (ключ -> текст)"]
+pub type LanguageMessages = BTreeMap<String, BTreeMap<String, String>>;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Результат импорта одного пространства имен"]
+pub struct ImportedRepository {
+    /// Директория созданного репозитория
+    pub directory: String,
+    /// Количество записей базового словаря
+    pub entries: usize,
+    /// Языки созданных дочерних словарей
+    pub languages: Vec<String>,
+}
+
+#[doc = "Разворачивает вложенные объекты словаря в ключи с точками: {\"a\": {\"b\": \"x\"}} -> a.b"]
+pub fn flatten_messages(value: &Value, prefix: &str, messages: &mut BTreeMap<String, String>) {
+    let join = |key: &str| match prefix.is_empty() {
+        true => key.to_owned(),
+        false => format!("{}.{}", prefix, key),
+    };
+    match value {
+        Value::Object(children) => {
+            for (key, child) in children {
+                flatten_messages(child, &join(key), messages);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten_messages(item, &join(&index.to_string()), messages);
+            }
+        }
+        Value::String(text) => {
+            messages.insert(prefix.to_owned(), text.to_owned());
+        }
+        Value::Null => {}
+        other => {
+            messages.insert(prefix.to_owned(), other.to_string());
+        }
+    }
+}
+
+fn read_messages(path: &Path) -> Result<BTreeMap<String, String>, BuildSystemErrors> {
+    let value: Value = read_dictionary_file(path)?;
+    let mut messages = BTreeMap::new();
+    flatten_messages(&value, "", &mut messages);
+    Ok(messages)
+}
+
+fn is_language(name: &str) -> bool {
+    LanguageCode::new(name).is_ok()
+}
+
+#[doc = "Читает файлы i18next: <dir>/<lang>/<namespace>.json, или <dir>/<lang>.json (так же хранят словари ngx-translate и vue-i18n). Возвращает сообщения по пространствам имен"]
+pub fn read_i18next_directory(
+    input_dir: &str,
+) -> Result<BTreeMap<String, LanguageMessages>, BuildSystemErrors> {
+    let mut namespaces: BTreeMap<String, LanguageMessages> = BTreeMap::new();
+    for item in fs::read_dir(input_dir)? {
+        let path = item?.path();
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if path.is_dir() && is_language(name) {
+            for file in fs::read_dir(&path)? {
+                let file = file?.path();
+                let Some(namespace) = file.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                if file
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+                {
+                    namespaces
+                        .entry(namespace.to_owned())
+                        .or_default()
+                        .insert(name.to_owned(), read_messages(&file)?);
+                }
+            }
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+            && is_language(name)
+        {
+            namespaces
+                .entry(DEFAULT_NAMESPACE.to_owned())
+                .or_default()
+                .insert(name.to_owned(), read_messages(&path)?);
+        }
+    }
+    Ok(namespaces)
+}
+
+#[doc = "Отделяет суффикс формы множественного числа: apples_one -> (apples, one)"]
+pub fn split_plural_suffix(key: &str) -> Option<(&str, &str)> {
+    let (stem, suffix) = key.rsplit_once('_')?;
+    (!stem.is_empty() && PLURAL_SUFFIXES.contains(&suffix)).then_some((stem, suffix))
+}
+
+#[doc = "Строит базовый словарь и дочерние словари из сообщений. Ключи с суффиксами форм (_one, _other...) объединяются в одну запись с формами множественного числа, а ключи, которых нет в базовом языке, добавляются с ключом вместо фразы"]
+pub fn build_repository(
+    basic_language: &str,
+    messages: &LanguageMessages,
+) -> Result<(Vec<BaseEntry>, BTreeMap<String, TranslatedDictionary>), BuildSystemErrors> {
+    let Some(base_messages) = messages.get(basic_language) else {
+        return Err(BuildSystemErrors::InvalidImport(format!(
+            "нет файлов на языке базового словаря {}",
+            basic_language
+        )));
+    };
+    let mut all_keys: Vec<&String> = base_messages.keys().collect();
+    for language_messages in messages.values() {
+        for key in language_messages.keys() {
+            if !base_messages.contains_key(key) && !all_keys.contains(&key) {
+                all_keys.push(key);
+            }
+        }
+    }
+    // Ключ записи -> формы множественного числа (пустой для обычной фразы)
+    let mut grouped: Vec<(String, Vec<(String, String)>)> = vec![];
+    for key in all_keys {
+        let (tag, form) = match split_plural_suffix(key) {
+            Some((stem, form)) => (stem.to_owned(), form.to_owned()),
+            None => (key.to_owned(), String::new()),
+        };
+        match grouped.iter_mut().find(|(existing, _)| *existing == tag) {
+            Some((_, forms)) => forms.push((form, key.to_owned())),
+            None => grouped.push((tag, vec![(form, key.to_owned())])),
+        }
+    }
+
+    let mut entries = vec![];
+    let mut dictionaries: BTreeMap<String, TranslatedDictionary> = messages
+        .keys()
+        .filter(|language| *language != basic_language)
+        .map(|language| (language.to_owned(), TranslatedDictionary::new()))
+        .collect();
+    for (tag, forms) in grouped {
+        let is_plural = forms.iter().all(|(form, _)| !form.is_empty());
+        let source = |key: &str| {
+            base_messages
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| key.to_owned())
+        };
+        let mut entry = match is_plural {
+            true => {
+                let plural_forms: BTreeMap<String, String> = forms
+                    .iter()
+                    .map(|(form, key)| (form.to_owned(), source(key)))
+                    .collect();
+                BaseEntry {
+                    phrase: plural_forms
+                        .get("other")
+                        .or_else(|| plural_forms.values().next())
+                        .cloned()
+                        .unwrap_or_default(),
+                    plural_forms: Some(plural_forms),
+                    ..Default::default()
+                }
+            }
+            // Ключ с суффиксом, у которого нет других форм (например, item_one без item_other), остается обычной фразой
+            false => BaseEntry::new(source(&forms[0].1)),
+        };
+        let tag = match is_plural {
+            true => tag,
+            false => forms[0].1.to_owned(),
+        };
+        if entry.phrase != tag || entry.plural_forms.is_some() {
+            entry.key = Some(tag.clone());
+        }
+        for (language, dictionary) in dictionaries.iter_mut() {
+            let mut translated = DictionaryEntry::from_base(&entry, basic_language, language);
+            let language_messages = &messages[language];
+            match translated.plural_forms.as_mut() {
+                Some(plural_forms) => {
+                    for (form, value) in plural_forms.iter_mut() {
+                        let key = format!("{}_{}", tag, form);
+                        *value = language_messages.get(&key).cloned().unwrap_or_default();
+                    }
+                }
+                None => {
+                    translated.value = language_messages.get(&tag).cloned().unwrap_or_default();
+                }
+            }
+            let has_translation = !translated.value.is_empty()
+                || translated
+                    .plural_forms
+                    .as_ref()
+                    .is_some_and(|forms| forms.values().any(|value| !value.is_empty()));
+            if has_translation {
+                translated.status = EntryStatus::Translated;
+            }
+            dictionary.insert(tag.clone(), translated);
+        }
+        entries.push(entry);
+    }
+    Ok((entries, dictionaries))
+}
+
+#[doc = "Создает репозиторий словарей из файлов существующего проекта на i18next. Если пространств имен несколько, каждое становится поддиректорией репозитория. Существующие базовые словари не перезаписываются"]
+pub fn import_i18next(
+    input_dir: &str,
+    dictionary_dir: &str,
+    basic_language: &str,
+    format: DictionaryFormat,
+) -> Result<Vec<ImportedRepository>, BuildSystemErrors> {
+    let basic_language = LanguageCode::new(basic_language)?.to_string();
+    let namespaces = read_i18next_directory(input_dir)?;
+    if namespaces.is_empty() {
+        return Err(BuildSystemErrors::InvalidImport(format!(
+            "в {} не найдены файлы <язык>.json или <язык>/<пространство имен>.json",
+            input_dir
+        )));
+    }
+    let single = namespaces.len() == 1;
+    let mut imported = vec![];
+    for (namespace, messages) in &namespaces {
+        let directory = match single {
+            true => dictionary_dir.to_owned(),
+            false => namespace_directory(dictionary_dir, namespace),
+        };
+        if get_basic_dictionary(&directory).is_ok() {
+            return Err(BuildSystemErrors::InvalidImport(format!(
+                "в {} уже есть базовый словарь",
+                directory
+            )));
+        }
+        let (entries, dictionaries) = build_repository(&basic_language, messages)?;
+        fs::create_dir_all(&directory)?;
+        atomic_write(
+            Path::new(&directory).join(format!(
+                "dictionary-{}.base.{}",
+                basic_language,
+                format.extension()
+            )),
+            format.serialize(&entries)?,
+        )?;
+        for (language, dictionary) in &dictionaries {
+            write_translated_dictionary(&directory, language, dictionary)?;
+        }
+        imported.push(ImportedRepository {
+            directory,
+            entries: entries.len(),
+            languages: dictionaries.into_keys().collect(),
+        });
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_translate::{parse_static_basic_entries, read_translated_dictionary};
+
+    #[test]
+    fn test_i18next_project_is_imported() {
+        let dir = tempfile::tempdir().unwrap();
+        let locales = dir.path().join("locales");
+        for (language, content) in [
+            (
+                "en",
+                r#"{"checkout": {"title": "Checkout"}, "Hello": "Hello", "apples_one": "{{count}} apple", "apples_other": "{{count}} apples"}"#,
+            ),
+            (
+                "ru",
+                r#"{"checkout": {"title": "Оформление"}, "apples_one": "{{count}} яблоко", "apples_few": "{{count}} яблока", "extra": "Лишний"}"#,
+            ),
+        ] {
+            fs::create_dir_all(locales.join(language)).unwrap();
+            fs::write(locales.join(language).join("translation.json"), content).unwrap();
+        }
+        let repo = dir.path().join("dictionaries");
+        let repo = repo.to_str().unwrap();
+
+        let imported = import_i18next(
+            locales.to_str().unwrap(),
+            repo,
+            "en",
+            DictionaryFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(imported[0].entries, 4);
+        assert_eq!(imported[0].languages, vec!["ru"]);
+
+        let entries = parse_static_basic_entries(repo).unwrap();
+        assert_eq!(entries[0].tag(), "Hello");
+        assert!(entries[0].is_plain());
+        assert_eq!(entries[1].tag(), "apples");
+        assert_eq!(entries[1].phrase, "{{count}} apples");
+        assert_eq!(entries[2].tag(), "checkout.title");
+        assert_eq!(entries[2].phrase, "Checkout");
+        assert_eq!(entries[3].phrase, "extra");
+
+        let ru = read_translated_dictionary(repo, "ru").unwrap();
+        assert_eq!(ru["checkout.title"].value, "Оформление");
+        assert_eq!(ru["checkout.title"].status, EntryStatus::Translated);
+        assert_eq!(ru["Hello"].status, EntryStatus::Untranslated);
+        let forms = ru["apples"].plural_forms.as_ref().unwrap();
+        assert_eq!(forms["few"], "{{count}} яблока");
+        assert_eq!(forms["many"], "");
+
+        assert!(matches!(
+            import_i18next(
+                locales.to_str().unwrap(),
+                repo,
+                "en",
+                DictionaryFormat::Json
+            ),
+            Err(BuildSystemErrors::InvalidImport(_))
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+
+use serde_json::{json, Map, Value};
+
+use super::templating::{resolve_output_path, template_variables};
+use super::{
+    convert_placeholders, key_path, nest_entries, words_with_fallback, BuildOptions, BuildReport,
+};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::{plural_categories, Word};
+
+#[doc = "Шаблон пути итоговых файлов Laravel по умолчанию (output_dir обычно lang)"]
+pub const DEFAULT_LARAVEL_TEMPLATE: &str = "{output_dir}/{lang}/messages.php";
+
+#[doc = "Локаль в формате Laravel: pt-BR -> pt_BR"]
+pub fn laravel_locale(language: &str) -> String {
+    language.replace('-', "_")
+}
+
+#[doc = "Строка PHP в одинарных кавычках"]
+pub fn php_string(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[doc = "Сообщения Laravel для языка: ключи с точками разворачиваются во вложенные массивы, переменные {{name}} переводятся в :name, а формы множественного числа объединяются через | для trans_choice"]
+pub fn laravel_messages(
+    language: &str,
+    words: &[Word],
+) -> Result<Map<String, Value>, BuildSystemErrors> {
+    let mut messages: BTreeMap<String, BTreeMap<&str, String>> = BTreeMap::new();
+    for word in words {
+        let mut tag = word.tag.clone();
+        if let Some(variant) = &word.variant {
+            tag = format!("{}_{}", tag, variant);
+        }
+        let message = convert_placeholders(&word.word, |name| format!(":{}", name));
+        messages
+            .entry(tag)
+            .or_default()
+            .insert(word.plural_form.as_deref().unwrap_or_default(), message);
+    }
+    nest_entries(messages.into_iter().map(|(tag, mut forms)| {
+        let message = match forms.remove("") {
+            Some(message) => message,
+            None => plural_categories(language)
+                .iter()
+                .filter_map(|category| forms.get(category).cloned())
+                .collect::<Vec<String>>()
+                .join("|"),
+        };
+        (key_path(&tag), json!(message))
+    }))
+}
+
+fn write_php_array(php: &mut String, messages: &Map<String, Value>, depth: usize) {
+    let indent = "    ".repeat(depth + 1);
+    php.push_str("[\n");
+    for (key, value) in messages {
+        let _ = write!(php, "{}{} => ", indent, php_string(key));
+        match value {
+            Value::Object(children) => write_php_array(php, children, depth + 1),
+            Value::String(text) => php.push_str(&php_string(text)),
+            other => php.push_str(&php_string(&other.to_string())),
+        }
+        php.push_str(",\n");
+    }
+    let _ = write!(php, "{}]", "    ".repeat(depth));
+}
+
+#[doc = "Формирует файл PHP, возвращающий массив сообщений"]
+pub fn to_php(messages: &Map<String, Value>) -> String {
+    let mut php = String::from("<?php\n\nreturn ");
+    write_php_array(&mut php, messages, 0);
+    php.push_str(";\n");
+    php
+}
+
+#[doc = "Собирает файлы lang/<lang>/messages.php для Laravel. Недостающие переводы берутся по цепочке запасных локалей"]
+pub fn build_for_laravel(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_LARAVEL_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, &laravel_locale(language), "messages"),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(output_path, to_php(&laravel_messages(language, &words)?))?;
+        report.inherited.insert(language.to_owned(), inherited);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_laravel_php_array() {
+        let words = vec![
+            Word::new(
+                "Welcome, {{name}}".into(),
+                "auth.welcome".into(),
+                "en".into(),
+            ),
+            Word::new("It's a \\ test".into(), "quote".into(), "en".into()),
+            Word::new("{{count}} apple".into(), "apples".into(), "en".into())
+                .with_plural_form(Some("one".into())),
+            Word::new("{{count}} apples".into(), "apples".into(), "en".into())
+                .with_plural_form(Some("other".into())),
+        ];
+        let php = to_php(&laravel_messages("en", &words).unwrap());
+        assert_eq!(
+            php,
+            "<?php\n\nreturn [\n    'apples' => ':count apple|:count apples',\n    'auth' => [\n        'welcome' => 'Welcome, :name',\n    ],\n    'quote' => 'It\\'s a \\\\ test',\n];\n"
+        );
+    }
+}
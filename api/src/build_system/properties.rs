@@ -0,0 +1,153 @@
+use std::fmt::Write;
+use std::fs;
+use std::str::FromStr;
+
+use super::templating::{resolve_output_path, template_variables};
+use super::{words_with_fallback, BuildOptions, BuildReport};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::Word;
+
+#[doc = "Шаблон пути итоговых файлов .properties по умолчанию"]
+pub const DEFAULT_PROPERTIES_TEMPLATE: &str = "{output_dir}/messages_{lang}.properties";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[doc = "Кодировка файлов .properties"]
+pub enum PropertiesEncoding {
+    /// ISO-8859-1: символы вне ASCII записываются как \uXXXX (как native2ascii), файл читается любой версией Java
+    #[default]
+    Iso8859_1,
+    /// UTF-8 без экранирования, для Java 9+ и Spring с default-encoding UTF-8
+    Utf8,
+}
+
+impl FromStr for PropertiesEncoding {
+    type Err = String;
+
+    fn from_str(encoding: &str) -> Result<PropertiesEncoding, String> {
+        match encoding.to_lowercase().as_str() {
+            "iso-8859-1" | "latin1" | "escaped" => Ok(PropertiesEncoding::Iso8859_1),
+            "utf-8" | "utf8" => Ok(PropertiesEncoding::Utf8),
+            _ => Err(format!(
+                "Неподдерживаемая кодировка {}, доступны iso-8859-1 и utf-8",
+                encoding
+            )),
+        }
+    }
+}
+
+#[doc = "Локаль в формате Java: pt-BR -> pt_BR"]
+pub fn java_locale(language: &str) -> String {
+    language.replace('-', "_")
+}
+
+// Экранирует ключ или значение по правилам java.util.Properties
+fn escape(text: &str, is_key: bool, encoding: PropertiesEncoding) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (index, c) in text.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            // Пробелы в ключе и в начале значения иначе считаются разделителем
+            ' ' if is_key || index == 0 => escaped.push_str("\\ "),
+            _ => push_encoded(&mut escaped, c, encoding),
+        }
+    }
+    escaped
+}
+
+fn push_encoded(text: &mut String, c: char, encoding: PropertiesEncoding) {
+    if encoding == PropertiesEncoding::Utf8 || (' '..='~').contains(&c) {
+        text.push(c);
+        return;
+    }
+    let mut units = [0u16; 2];
+    for unit in c.encode_utf16(&mut units) {
+        let _ = write!(text, "\\u{:04X}", unit);
+    }
+}
+
+#[doc = "Формирует содержимое файла .properties с ключами в лексикографическом порядке, как и в остальных целях сборки"]
+pub fn to_properties(words: &[Word], encoding: PropertiesEncoding) -> String {
+    let mut words: Vec<&Word> = words.iter().collect();
+    words.sort_by_key(|word| word.suffixed_tag());
+    let mut properties = String::new();
+    for word in words {
+        for note in [&word.context, &word.comment].into_iter().flatten() {
+            properties.push_str("# ");
+            for c in note.chars() {
+                push_encoded(&mut properties, if c == '\n' { ' ' } else { c }, encoding);
+            }
+            properties.push('\n');
+        }
+        let _ = writeln!(
+            properties,
+            "{}={}",
+            escape(&word.suffixed_tag(), true, encoding),
+            escape(&word.word, false, encoding)
+        );
+    }
+    properties
+}
+
+#[doc = "Собирает файлы messages_<lang>.properties для Spring и других JVM-фреймворков. Недостающие переводы берутся по цепочке запасных локалей"]
+pub fn build_for_properties(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+    encoding: PropertiesEncoding,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_PROPERTIES_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, &java_locale(language), "messages"),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(output_path, to_properties(&words, encoding))?;
+        report.inherited.insert(language.to_owned(), inherited);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_properties_encodings_and_order() {
+        let words = vec![
+            Word::new("Привет мир".into(), "hello".into(), "ru".into())
+                .with_comment(Some("Главная".into())),
+            Word::new(" a=b".into(), "b key".into(), "ru".into()),
+        ];
+
+        let utf8 = to_properties(&words, PropertiesEncoding::Utf8);
+        assert_eq!(utf8, "b\\ key=\\ a\\=b\n# Главная\nhello=Привет мир\n");
+
+        let escaped = to_properties(&words, PropertiesEncoding::Iso8859_1);
+        assert!(escaped.is_ascii());
+        assert!(escaped.starts_with("b\\ key=\\ a\\=b\n"));
+        assert!(escaped
+            .contains("hello=\\u041F\\u0440\\u0438\\u0432\\u0435\\u0442 \\u043C\\u0438\\u0440\n"));
+    }
+}
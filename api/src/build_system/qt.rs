@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+
+use quick_xml::escape::escape;
+
+use super::templating::{resolve_output_path, template_variables};
+use super::BuildOptions;
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_existing_dictionary,
+};
+use crate::types::{plural_categories, EntryStatus};
+
+#[doc = "Шаблон пути итоговых файлов Qt Linguist по умолчанию"]
+pub const DEFAULT_QT_TEMPLATE: &str = "{output_dir}/{lang}.ts";
+
+#[doc = "Контекст Qt для записей без контекста"]
+pub const DEFAULT_QT_CONTEXT: &str = "dms";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Сообщение Qt Linguist внутри контекста"]
+pub struct QtMessage {
+    pub source: String,
+    /// Ключ словаря, если он отличается от исходной фразы (comment в .ts)
+    pub disambiguation: Option<String>,
+    /// Комментарий для переводчиков (extracomment в .ts)
+    pub comment: Option<String>,
+    /// Перевод или формы множественного числа по порядку категорий языка
+    pub translations: Vec<String>,
+    /// Сообщение с формами множественного числа (numerus="yes")
+    pub numerus: bool,
+    /// Перевод отсутствует или требует проверки
+    pub unfinished: bool,
+}
+
+impl QtMessage {
+    #[doc = "Ключ словаря сообщения: disambiguation, а если его нет - исходная фраза"]
+    pub fn key(&self) -> &str {
+        self.disambiguation.as_deref().unwrap_or(&self.source)
+    }
+}
+
+#[doc = "Локаль в формате Qt: pt-BR -> pt_BR"]
+pub fn qt_locale(language: &str) -> String {
+    language.replace('-', "_")
+}
+
+// В сообщениях с формами множественного числа Qt подставляет число вместо %n
+fn numerus_text(text: &str) -> String {
+    text.replace("{{count}}", "%n")
+}
+
+#[doc = "Сообщения перевода на язык, сгруппированные по контексту записей и упорядоченные по ключу словаря. Записи без контекста попадают в контекст dms"]
+pub fn qt_contexts(
+    dictionary_dir: &str,
+    language: &str,
+) -> Result<BTreeMap<String, Vec<QtMessage>>, BuildSystemErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let dictionary = read_existing_dictionary(dictionary_dir, language)?;
+    let translations: HashMap<String, String> = dictionary
+        .iter()
+        .flat_map(|(tag, entry)| entry.words(tag, language))
+        .map(|word| (word.suffixed_tag(), word.word))
+        .collect();
+    let translation = |key: &str| translations.get(key).cloned().unwrap_or_default();
+    let disambiguation_for = |key: &str, source: &str| (key != source).then(|| key.to_owned());
+
+    let mut contexts: BTreeMap<String, Vec<QtMessage>> = BTreeMap::new();
+    for entry in parse_static_basic_entries(dictionary_dir)? {
+        let machine = dictionary
+            .get(entry.tag())
+            .is_some_and(|translated| translated.status == EntryStatus::Machine);
+        let messages = contexts
+            .entry(
+                entry
+                    .context
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_QT_CONTEXT.to_owned()),
+            )
+            .or_default();
+        if let Some(forms) = &entry.plural_forms {
+            let source = numerus_text(forms.get("other").unwrap_or(&entry.phrase));
+            let translations: Vec<String> = plural_categories(language)
+                .iter()
+                .map(|category| {
+                    numerus_text(&translation(&format!("{}_{}", entry.tag(), category)))
+                })
+                .collect();
+            messages.push(QtMessage {
+                disambiguation: disambiguation_for(entry.tag(), &source),
+                source,
+                comment: entry.comment.clone(),
+                unfinished: machine || translations.iter().any(String::is_empty),
+                translations,
+                numerus: true,
+            });
+            continue;
+        }
+        for word in entry.words_for_translation(&basic_language, language) {
+            let key = word.suffixed_tag();
+            let translated = translation(&key);
+            messages.push(QtMessage {
+                disambiguation: disambiguation_for(&key, &word.word),
+                source: word.word,
+                comment: entry.comment.clone(),
+                unfinished: machine || translated.is_empty(),
+                translations: vec![translated],
+                numerus: false,
+            });
+        }
+    }
+    for messages in contexts.values_mut() {
+        messages.sort_by(|a, b| a.key().cmp(b.key()));
+    }
+    Ok(contexts)
+}
+
+#[doc = "Формирует файл Qt Linguist (.ts) с переводами на один язык"]
+pub fn to_ts(
+    source_language: &str,
+    language: &str,
+    contexts: &BTreeMap<String, Vec<QtMessage>>,
+) -> String {
+    let mut ts = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE TS>\n");
+    let _ = writeln!(
+        ts,
+        "<TS version=\"2.1\" language=\"{}\" sourcelanguage=\"{}\">",
+        escape(&qt_locale(language)),
+        escape(&qt_locale(source_language))
+    );
+    for (context, messages) in contexts {
+        let _ = writeln!(ts, "<context>\n    <name>{}</name>", escape(context));
+        for message in messages {
+            ts.push_str(if message.numerus {
+                "    <message numerus=\"yes\">\n"
+            } else {
+                "    <message>\n"
+            });
+            let _ = writeln!(ts, "        <source>{}</source>", escape(&message.source));
+            if let Some(disambiguation) = &message.disambiguation {
+                let _ = writeln!(ts, "        <comment>{}</comment>", escape(disambiguation));
+            }
+            if let Some(comment) = &message.comment {
+                let _ = writeln!(
+                    ts,
+                    "        <extracomment>{}</extracomment>",
+                    escape(comment)
+                );
+            }
+            let kind = if message.unfinished {
+                " type=\"unfinished\""
+            } else {
+                ""
+            };
+            if message.numerus {
+                let _ = writeln!(ts, "        <translation{}>", kind);
+                for form in &message.translations {
+                    let _ = writeln!(
+                        ts,
+                        "            <numerusform>{}</numerusform>",
+                        escape(form)
+                    );
+                }
+                ts.push_str("        </translation>\n");
+            } else {
+                let translation = message
+                    .translations
+                    .first()
+                    .map(String::as_str)
+                    .unwrap_or("");
+                let _ = writeln!(
+                    ts,
+                    "        <translation{}>{}</translation>",
+                    kind,
+                    escape(translation)
+                );
+            }
+            ts.push_str("    </message>\n");
+        }
+        ts.push_str("</context>\n");
+    }
+    ts.push_str("</TS>\n");
+    ts
+}
+
+#[doc = "Собирает файлы {output_dir}/<язык>.ts (или по шаблону пути из параметров) для Qt Linguist и lrelease. Возвращает пути созданных файлов"]
+pub fn build_for_qt(
+    dictionary_dir: &str,
+    output_dir: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<Vec<String>, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_QT_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let source_language = get_basic_language(dictionary_dir)?;
+    let mut written = vec![];
+    for language in &languages {
+        let contexts = qt_contexts(dictionary_dir, language)?;
+        let path = resolve_output_path(
+            output_template,
+            &template_variables(output_dir, &qt_locale(language), "messages"),
+        )?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(&path, to_ts(&source_language, language, &contexts))?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ts_groups_messages_by_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Вход", "context": "LoginDialog", "comment": "кнопка"}, "Выход", {"phrase": "яблоки", "plural_forms": {"one": "{{count}} яблоко", "other": "{{count}} яблок"}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in", "Выход": {"value": "Exit", "status": "machine"}, "яблоки": {"value": "", "plural_forms": {"one": "{{count}} apple", "other": "{{count}} apples"}}}"#,
+        )
+        .unwrap();
+
+        let output = dir.path().join("ts");
+        let written =
+            build_for_qt(repo, output.to_str().unwrap(), None, &BuildOptions::default()).unwrap();
+        let ts = fs::read_to_string(&written[0]).unwrap();
+        assert!(ts.contains("<TS version=\"2.1\" language=\"en\" sourcelanguage=\"ru\">"));
+        assert!(ts.contains(
+            "<name>LoginDialog</name>\n    <message>\n        <source>Вход</source>\n        <extracomment>кнопка</extracomment>\n        <translation>Sign in</translation>"
+        ));
+        assert!(ts.contains("<translation type=\"unfinished\">Exit</translation>"));
+        assert!(ts.contains("<source>%n яблок</source>\n        <comment>яблоки</comment>"));
+        assert!(ts.contains(
+            "<numerusform>%n apple</numerusform>\n            <numerusform>%n apples</numerusform>"
+        ));
+    }
+}
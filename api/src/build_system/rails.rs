@@ -0,0 +1,103 @@
+use std::fs;
+
+use serde_json::{json, Value};
+
+use super::templating::{resolve_output_path, template_variables};
+use super::{
+    convert_placeholders, key_path, nest_entries, words_with_fallback, BuildOptions, BuildReport,
+};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::Word;
+
+#[doc = "Шаблон пути итоговых файлов ruby-i18n по умолчанию (output_dir обычно config/locales)"]
+pub const DEFAULT_RAILS_TEMPLATE: &str = "{output_dir}/{lang}.yml";
+
+#[doc = "Переводит переменные i18next {{name}} в формат ruby-i18n %{name}"]
+pub fn to_rails_placeholders(text: &str) -> String {
+    convert_placeholders(text, |name| format!("%{{{}}}", name))
+}
+
+#[doc = "Формирует словарь ruby-i18n: язык в корне, ключи с точками разворачиваются во вложенные, а формы множественного числа (one, other...) становятся дочерними ключами фразы"]
+pub fn rails_document(language: &str, words: &[Word]) -> Result<Value, BuildSystemErrors> {
+    let messages = nest_entries(words.iter().map(|word| {
+        let mut tag = word.tag.clone();
+        if let Some(variant) = &word.variant {
+            tag = format!("{}_{}", tag, variant);
+        }
+        let mut path = key_path(&tag);
+        if let Some(plural_form) = &word.plural_form {
+            path.push(plural_form.to_owned());
+        }
+        (path, json!(to_rails_placeholders(&word.word)))
+    }))?;
+    Ok(json!({ language: messages }))
+}
+
+#[doc = "Собирает файлы config/locales/<lang>.yml для Rails и ruby-i18n. Недостающие переводы берутся по цепочке запасных локалей"]
+pub fn build_for_rails(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_RAILS_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, language, "messages"),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let document = rails_document(language, &words)?;
+        atomic_write(output_path, serde_yaml::to_string(&document)?)?;
+        report.inherited.insert(language.to_owned(), inherited);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rails_document_is_nested() {
+        let words = vec![
+            Word::new("Pay".into(), "checkout.payment.title".into(), "en".into()),
+            Word::new("Hello, {{name}}".into(), "greeting".into(), "en".into()),
+            Word::new(
+                "Bye. See you".into(),
+                "Пока. До встречи".into(),
+                "en".into(),
+            ),
+            Word::new("{{count}} apple".into(), "cart.apples".into(), "en".into())
+                .with_plural_form(Some("one".into())),
+            Word::new("{{count}} apples".into(), "cart.apples".into(), "en".into())
+                .with_plural_form(Some("other".into())),
+        ];
+        let document = rails_document("en", &words).unwrap();
+        assert_eq!(document["en"]["checkout"]["payment"]["title"], "Pay");
+        assert_eq!(document["en"]["greeting"], "Hello, %{name}");
+        assert_eq!(document["en"]["Пока. До встречи"], "Bye. See you");
+        assert_eq!(document["en"]["cart"]["apples"]["other"], "%{count} apples");
+
+        let collision = [
+            Word::new("Checkout".into(), "checkout".into(), "en".into()),
+            Word::new("Pay".into(), "checkout.pay".into(), "en".into()),
+        ];
+        assert!(matches!(
+            rails_document("en", &collision),
+            Err(BuildSystemErrors::KeyCollision(_))
+        ));
+    }
+}
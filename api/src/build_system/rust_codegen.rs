@@ -0,0 +1,134 @@
+use std::fmt::Write;
+use std::fs;
+
+use super::templating::{resolve_output_path, shared_template_variables};
+use super::{words_with_fallback, BuildOptions};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::Word;
+
+#[doc = "Название сгенерированного модуля Rust"]
+pub const RUST_MODULE_NAME: &str = "translations.rs";
+
+#[doc = "Шаблон пути модуля Rust по умолчанию. Модуль общий для всех языков, поэтому {lang} в шаблоне недоступна"]
+pub const DEFAULT_RUST_TEMPLATE: &str = "{output_dir}/{namespace}.rs";
+
+#[doc = "Подключает модуль, сгенерированный build_rust_module в build.rs, из OUT_DIR: include_dms!() или include_dms!(\"файл.rs\")"]
+#[macro_export]
+macro_rules! include_dms {
+    () => {
+        include!(concat!(env!("OUT_DIR"), "/translations.rs"));
+    };
+    ($file:literal) => {
+        include!(concat!(env!("OUT_DIR"), "/", $file));
+    };
+}
+
+#[doc = "Имя функции поиска для языка: pt-BR -> lang_pt_br"]
+pub fn language_function(language: &str) -> String {
+    let name: String = language
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect();
+    format!("lang_{}", name)
+}
+
+#[doc = "Генерирует модуль Rust с функцией translate(language, key) на match. Пустые переводы не попадают в модуль, чтобы приложение могло подставить свой запасной вариант"]
+pub fn to_rust_module(dictionaries: &[(String, Vec<Word>)]) -> String {
+    let mut code = String::from("// Сгенерировано dms build rust, не редактируйте вручную\n\n");
+    let _ = writeln!(
+        code,
+        "pub const LANGUAGES: &[&str] = &[{}];\n",
+        dictionaries
+            .iter()
+            .map(|(language, _)| format!("{:?}", language))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+    code.push_str("/// Перевод ключа (с суффиксами формы множественного числа и варианта) на язык\npub fn translate(language: &str, key: &str) -> Option<&'static str> {\n    match language {\n");
+    for (language, _) in dictionaries {
+        let _ = writeln!(
+            code,
+            "        {:?} => {}(key),",
+            language,
+            language_function(language)
+        );
+    }
+    code.push_str("        _ => None,\n    }\n}\n");
+    for (language, words) in dictionaries {
+        let _ = write!(
+            code,
+            "\nfn {}(key: &str) -> Option<&'static str> {{\n    match key {{\n",
+            language_function(language)
+        );
+        for word in words.iter().filter(|word| !word.word.is_empty()) {
+            let _ = writeln!(
+                code,
+                "        {:?} => Some({:?}),",
+                word.suffixed_tag(),
+                word.word
+            );
+        }
+        code.push_str("        _ => None,\n    }\n}\n");
+    }
+    code
+}
+
+#[doc = "Собирает модуль translations.rs (или по шаблону пути из параметров) для встраивания переводов в приложение на Rust при компиляции. Недостающие переводы берутся по цепочке запасных локалей. Возвращает путь созданного файла"]
+pub fn build_rust_module(
+    dictionary_dir: &str,
+    output_dir: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<String, BuildSystemErrors> {
+    let mut languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    languages.sort();
+    let mut dictionaries = vec![];
+    for language in languages {
+        let (words, _) = words_with_fallback(dictionary_dir, &language, options)?;
+        dictionaries.push((language, words));
+    }
+    let path = resolve_output_path(
+        options
+            .output_template
+            .as_deref()
+            .unwrap_or(DEFAULT_RUST_TEMPLATE),
+        &shared_template_variables(output_dir, "translations"),
+    )?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write(&path, to_rust_module(&dictionaries))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_module_uses_match_lookup() {
+        let dictionaries = vec![(
+            "pt-BR".to_owned(),
+            vec![
+                Word::new(
+                    "Olá, \"{{name}}\"".into(),
+                    "greeting".into(),
+                    "pt-BR".into(),
+                ),
+                Word::new("".into(), "missing".into(), "pt-BR".into()),
+            ],
+        )];
+        let code = to_rust_module(&dictionaries);
+        assert!(code.contains("pub const LANGUAGES: &[&str] = &[\"pt-BR\"];"));
+        assert!(code.contains("        \"pt-BR\" => lang_pt_br(key),\n"));
+        assert!(code.contains("        \"greeting\" => Some(\"Olá, \\\"{{name}}\\\"\"),\n"));
+        assert!(!code.contains("missing"));
+    }
+}
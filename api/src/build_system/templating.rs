@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::errors::errors::BuildSystemErrors;
+
+#[doc = "Переменные, которые можно использовать в шаблоне пути итогового файла"]
+pub const TEMPLATE_VARIABLES: [&str; 3] = ["output_dir", "lang", "namespace"];
+
+#[doc = "Собирает значения переменных шаблона для конкретного итогового файла"]
+pub fn template_variables<'a>(
+    output_dir: &'a str,
+    lang: &'a str,
+    namespace: &'a str,
+) -> HashMap<&'static str, &'a str> {
+    HashMap::from([
+        ("output_dir", output_dir),
+        ("lang", lang),
+        ("namespace", namespace),
+    ])
+}
+
+#[doc = "Значения переменных шаблона для файла, общего для всех языков (типы, модуль Rust): переменная {lang} в таком шаблоне недоступна"]
+pub fn shared_template_variables<'a>(
+    output_dir: &'a str,
+    namespace: &'a str,
+) -> HashMap<&'static str, &'a str> {
+    HashMap::from([("output_dir", output_dir), ("namespace", namespace)])
+}
+
+#[doc = "Подставляет переменные в шаблон пути вида {output_dir}/{lang}/{namespace}.json"]
+pub fn resolve_output_path(
+    template: &str,
+    variables: &HashMap<&str, &str>,
+) -> Result<PathBuf, BuildSystemErrors> {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| BuildSystemErrors::TemplateError(template.to_owned()))?;
+        let name = &rest[start + 1..start + end];
+        let value = variables
+            .get(name)
+            .ok_or_else(|| BuildSystemErrors::UnknownTemplateVariable(name.to_owned()))?;
+        result.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(PathBuf::from(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_is_resolved() {
+        let variables = template_variables("build", "pt-BR", "common");
+        let path = resolve_output_path("{output_dir}/{namespace}/{lang}.json", &variables).unwrap();
+        assert_eq!(path, PathBuf::from("build/common/pt-BR.json"));
+    }
+
+    #[test]
+    fn test_unknown_variable_is_rejected() {
+        let variables = template_variables("build", "en", "translation");
+        let result = resolve_output_path("{output_dir}/{language}.json", &variables);
+        assert!(matches!(
+            result,
+            Err(BuildSystemErrors::UnknownTemplateVariable(name)) if name == "language"
+        ));
+        assert!(matches!(
+            resolve_output_path("{output_dir/x.json", &variables),
+            Err(BuildSystemErrors::TemplateError(_))
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+
+use super::templating::{resolve_output_path, shared_template_variables};
+use super::BuildOptions;
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::atomic_write;
+use crate::qa::placeholders;
+use crate::static_translate::{get_basic_language, parse_static_basic_entries};
+
+#[doc = "Название файла с типами переводов"]
+pub const TYPES_FILE_NAME: &str = "translations.d.ts";
+
+#[doc = "Шаблон пути файла с типами по умолчанию. Файл общий для всех языков, поэтому {lang} в шаблоне недоступна"]
+pub const DEFAULT_TYPES_TEMPLATE: &str = "{output_dir}/{namespace}.d.ts";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Ключ базового словаря с переменными подстановки"]
+pub struct TypedKey {
+    /// Ключ в том виде, в котором он передается в t(): без суффиксов форм множественного числа
+    pub key: String,
+    /// Ключи итогового словаря: с суффиксами форм множественного числа и вариантов
+    pub resource_keys: Vec<String>,
+    /// Переменные подстановки: имя -> тип TypeScript
+    pub params: BTreeMap<String, String>,
+}
+
+// Переменная {{name}}, {name} или ${name} и ее тип. Для {{user.name}} i18next ожидает объект user
+fn parameter(placeholder: &str) -> Option<(String, String)> {
+    let name = placeholder.trim_start_matches('$').trim_matches(['{', '}']);
+    if name.is_empty() || placeholder.starts_with('%') {
+        return None;
+    }
+    match name.split_once('.') {
+        Some((object, _)) => Some((object.to_owned(), "Record<string, unknown>".to_owned())),
+        None => Some((name.to_owned(), "string | number".to_owned())),
+    }
+}
+
+#[doc = "Собирает ключи базового словаря с переменными подстановки. Для фраз с формами множественного числа добавляется count: number"]
+pub fn typed_keys(dictionary_dir: &str) -> Result<Vec<TypedKey>, BuildSystemErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let mut keys: BTreeMap<String, TypedKey> = BTreeMap::new();
+    for entry in parse_static_basic_entries(dictionary_dir)? {
+        for word in entry.words_for_translation(&basic_language, &basic_language) {
+            let key = match &word.variant {
+                Some(variant) => format!("{}_{}", word.tag, variant),
+                None => word.tag.clone(),
+            };
+            let typed = keys.entry(key.clone()).or_insert_with(|| TypedKey {
+                key,
+                ..Default::default()
+            });
+            typed.resource_keys.push(word.suffixed_tag());
+            typed.params.extend(
+                placeholders(&word.word)
+                    .iter()
+                    .filter_map(|found| parameter(found)),
+            );
+            if word.plural_form.is_some() {
+                typed.params.insert("count".to_owned(), "number".to_owned());
+            }
+        }
+    }
+    Ok(keys.into_values().collect())
+}
+
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+#[doc = "Формирует файл объявлений TypeScript: тип TranslationKey со всеми ключами и TranslationParams с переменными каждого ключа. С i18next_resources дополнительно расширяется CustomTypeOptions i18next"]
+pub fn to_type_definitions(keys: &[TypedKey], i18next_resources: bool) -> String {
+    let mut ts = String::from("// Сгенерировано dms build types, не редактируйте вручную\n");
+    if i18next_resources {
+        ts.push_str("import \"i18next\";\n");
+    }
+    ts.push_str("\nexport interface TranslationParams {\n");
+    for key in keys {
+        let params = match key.params.is_empty() {
+            true => "Record<string, never>".to_owned(),
+            false => format!(
+                "{{ {} }}",
+                key.params
+                    .iter()
+                    .map(|(name, kind)| format!("{}: {};", quoted(name), kind))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+        };
+        let _ = writeln!(ts, "  {}: {};", quoted(&key.key), params);
+    }
+    ts.push_str("}\n\nexport type TranslationKey = keyof TranslationParams;\n");
+    if i18next_resources {
+        ts.push_str("\ndeclare module \"i18next\" {\n  interface CustomTypeOptions {\n    defaultNS: \"translation\";\n    resources: {\n      translation: {\n");
+        for resource_key in keys.iter().flat_map(|key| &key.resource_keys) {
+            let _ = writeln!(ts, "        {}: string;", quoted(resource_key));
+        }
+        ts.push_str("      };\n    };\n  }\n}\n");
+    }
+    ts
+}
+
+#[doc = "Генерирует translations.d.ts (или файл по шаблону пути из параметров) из базового словаря. Возвращает путь созданного файла"]
+pub fn build_type_definitions(
+    dictionary_dir: &str,
+    output_dir: &str,
+    options: &BuildOptions,
+    i18next_resources: bool,
+) -> Result<String, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_TYPES_TEMPLATE);
+    let keys = typed_keys(dictionary_dir)?;
+    let path = resolve_output_path(
+        output_template,
+        &shared_template_variables(output_dir, "translations"),
+    )?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write(&path, to_type_definitions(&keys, i18next_resources))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_definitions_list_keys_and_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.base.json"),
+            r#"["Hello, {{name}}", "Done", {"phrase": "apples", "plural_forms": {"one": "{{count}} apple", "other": "{{count}} apples"}}]"#,
+        )
+        .unwrap();
+
+        let ts = to_type_definitions(&typed_keys(repo).unwrap(), true);
+        assert!(ts.contains("  \"Hello, {{name}}\": { \"name\": string | number; };\n"));
+        assert!(ts.contains("  \"Done\": Record<string, never>;\n"));
+        assert!(ts.contains("  \"apples\": { \"count\": number; };\n"));
+        assert!(ts.contains("        \"apples_one\": string;\n        \"apples_other\": string;\n"));
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::{json, Map, Value};
+
+use super::templating::{resolve_output_path, template_variables};
+use super::{
+    convert_placeholders, key_path, nest_entries, to_json_output, words_with_fallback,
+    BuildOptions, BuildReport,
+};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::{plural_categories, Word};
+
+#[doc = "Шаблон пути итоговых словарей vue-i18n по умолчанию"]
+pub const DEFAULT_VUE_I18N_TEMPLATE: &str = "{output_dir}/{lang}.json";
+
+#[doc = "Название модуля со всеми языками (без расширения)"]
+pub const MESSAGES_MODULE_NAME: &str = "messages";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[doc = "Язык модуля, в который собираются сообщения всех языков"]
+pub enum ModuleLanguage {
+    JavaScript,
+    TypeScript,
+}
+
+impl ModuleLanguage {
+    #[doc = "Расширение файла модуля"]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ModuleLanguage::JavaScript => "js",
+            ModuleLanguage::TypeScript => "ts",
+        }
+    }
+}
+
+impl FromStr for ModuleLanguage {
+    type Err = String;
+
+    fn from_str(language: &str) -> Result<ModuleLanguage, String> {
+        match language {
+            "js" => Ok(ModuleLanguage::JavaScript),
+            "ts" => Ok(ModuleLanguage::TypeScript),
+            _ => Err(format!(
+                "Неподдерживаемый модуль {}, доступны js и ts",
+                language
+            )),
+        }
+    }
+}
+
+// Экранирует специальные символы синтаксиса сообщений vue-i18n и переводит переменные {{name}} в {name}
+fn vue_message(text: &str) -> String {
+    let escaped = text.replace('@', "{'@'}").replace('|', "{'|'}");
+    convert_placeholders(&escaped, |name| format!("{{{}}}", name))
+}
+
+#[doc = "Сообщения vue-i18n для языка: ключи с точками разворачиваются во вложенные объекты, а формы множественного числа объединяются через | в порядке категорий языка"]
+pub fn vue_messages(
+    language: &str,
+    words: &[Word],
+) -> Result<Map<String, Value>, BuildSystemErrors> {
+    let mut messages: BTreeMap<String, BTreeMap<&str, String>> = BTreeMap::new();
+    for word in words {
+        let mut tag = word.tag.clone();
+        if let Some(variant) = &word.variant {
+            tag = format!("{}_{}", tag, variant);
+        }
+        let form = word.plural_form.as_deref().unwrap_or_default();
+        messages
+            .entry(tag)
+            .or_default()
+            .insert(form, vue_message(&word.word));
+    }
+    nest_entries(messages.into_iter().map(|(tag, mut forms)| {
+        let message = match forms.remove("") {
+            Some(message) => message,
+            None => plural_categories(language)
+                .iter()
+                .filter_map(|category| forms.get(category).cloned())
+                .collect::<Vec<String>>()
+                .join(" | "),
+        };
+        (key_path(&tag), json!(message))
+    }))
+}
+
+#[doc = "Формирует модуль JavaScript или TypeScript с сообщениями всех языков"]
+pub fn to_module(
+    messages: &Map<String, Value>,
+    options: &BuildOptions,
+) -> Result<String, BuildSystemErrors> {
+    Ok(format!(
+        "export const messages = {};\n\nexport default messages;\n",
+        to_json_output(messages, options)?
+    ))
+}
+
+#[doc = "Собирает словари для vue-i18n: по файлу JSON на каждый язык, или один модуль messages.js/messages.ts со всеми языками. Недостающие переводы берутся по цепочке запасных локалей"]
+pub fn build_for_vue_i18n(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+    module: Option<ModuleLanguage>,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_VUE_I18N_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    let mut all_messages = Map::new();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let messages = vue_messages(language, &words)?;
+        report.inherited.insert(language.to_owned(), inherited);
+        if module.is_some() {
+            all_messages.insert(language.to_owned(), Value::Object(messages));
+            continue;
+        }
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, language, MESSAGES_MODULE_NAME),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(output_path, to_json_output(&messages, options)?)?;
+    }
+    if let Some(module) = module {
+        fs::create_dir_all(output_directory)?;
+        let output_path = Path::new(output_directory).join(format!(
+            "{}.{}",
+            MESSAGES_MODULE_NAME,
+            module.extension()
+        ));
+        atomic_write(output_path, to_module(&all_messages, options)?)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vue_messages_are_nested_with_plural_choices() {
+        let words = vec![
+            Word::new(
+                "Привет, {{name}}".into(),
+                "home.greeting".into(),
+                "ru".into(),
+            ),
+            Word::new("почта@example.com".into(), "home.email".into(), "ru".into()),
+            Word::new("{{count}} яблоко".into(), "apples".into(), "ru".into())
+                .with_plural_form(Some("one".into())),
+            Word::new("{{count}} яблока".into(), "apples".into(), "ru".into())
+                .with_plural_form(Some("few".into())),
+            Word::new("{{count}} яблок".into(), "apples".into(), "ru".into())
+                .with_plural_form(Some("many".into())),
+        ];
+        let messages = vue_messages("ru", &words).unwrap();
+        assert_eq!(messages["home"]["greeting"], "Привет, {name}");
+        assert_eq!(messages["home"]["email"], "почта{'@'}example.com");
+        assert_eq!(
+            messages["apples"],
+            "{count} яблоко | {count} яблока | {count} яблок"
+        );
+
+        let module = to_module(
+            &Map::from_iter([("ru".to_owned(), Value::Object(messages))]),
+            &BuildOptions::default(),
+        )
+        .unwrap();
+        assert!(module.starts_with("export const messages = {\n  \"ru\": {"));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde_json::{json, Map, Value};
+
+use super::templating::{resolve_output_path, template_variables};
+use super::{convert_placeholders, to_json_output, words_with_fallback, BuildOptions, BuildReport};
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::types::{short_hash, Word};
+
+#[doc = "Шаблон пути итоговых файлов WebExtension по умолчанию"]
+pub const DEFAULT_WEBEXTENSION_TEMPLATE: &str = "{output_dir}/_locales/{lang}/messages.json";
+
+#[doc = "Локаль в формате WebExtension: pt-BR -> pt_BR"]
+pub fn webextension_locale(language: &str) -> String {
+    language.replace('-', "_")
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '@'
+}
+
+#[doc = "Имя сообщения WebExtension для ключа: допустимы только латинские буквы, цифры, _ и @. Точки и дефисы заменяются на _, а ключи с другими символами транслитерируются и получают суффикс с хешем, чтобы имена не совпадали"]
+pub fn message_name(key: &str) -> String {
+    let name = key.replace(['.', '-'], "_");
+    if !name.is_empty() && name.chars().all(is_name_char) {
+        return name;
+    }
+    let slug = slug::slugify(key).replace('-', "_");
+    match slug.is_empty() {
+        true => format!("m_{}", short_hash(key)),
+        false => format!("{}_{}", slug, short_hash(key)),
+    }
+}
+
+#[doc = "Сообщение WebExtension для слова: переменные {{name}} заменяются на $name$ и описываются в блоке placeholders ($1, $2...), а символ $ экранируется"]
+pub fn webextension_message(word: &Word) -> Value {
+    let mut names: Vec<String> = vec![];
+    let message = convert_placeholders(&word.word.replace('$', "$$"), |name| {
+        let name = name.replace('.', "_");
+        if !names.contains(&name) {
+            names.push(name.clone());
+        }
+        format!("${}$", name)
+    });
+    let mut message = json!({ "message": message });
+    let description: Vec<&str> = [&word.context, &word.comment]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    if !description.is_empty() {
+        message["description"] = json!(description.join("; "));
+    }
+    if !names.is_empty() {
+        message["placeholders"] = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                (
+                    name.to_lowercase(),
+                    json!({ "content": format!("${}", index + 1) }),
+                )
+            })
+            .collect::<Map<String, Value>>()
+            .into();
+    }
+    message
+}
+
+#[doc = "Собирает файлы _locales/<lang>/messages.json для браузерных расширений. Недостающие переводы берутся по цепочке запасных локалей"]
+pub fn build_for_webextension(
+    dictionary_dir: &str,
+    output_directory: &str,
+    languages: Option<Vec<String>>,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildSystemErrors> {
+    let output_template = options
+        .output_template
+        .as_deref()
+        .unwrap_or(DEFAULT_WEBEXTENSION_TEMPLATE);
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut report = BuildReport::default();
+    for language in &languages {
+        let (words, inherited) = words_with_fallback(dictionary_dir, language, options)?;
+        let output_path = resolve_output_path(
+            output_template,
+            &template_variables(output_directory, &webextension_locale(language), "messages"),
+        )?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let messages: BTreeMap<String, Value> = words
+            .iter()
+            .map(|word| {
+                (
+                    message_name(&word.suffixed_tag()),
+                    webextension_message(word),
+                )
+            })
+            .collect();
+        atomic_write(output_path, to_json_output(&messages, options)?)?;
+        report.inherited.insert(language.to_owned(), inherited);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webextension_message_with_placeholders() {
+        let word = Word::new(
+            "Hello, {{userName}}! You owe $5 to {{ friend }}".into(),
+            "popup.greeting".into(),
+            "en".into(),
+        )
+        .with_context(Some("Всплывающее окно".into()));
+        assert_eq!(message_name(&word.tag), "popup_greeting");
+        assert_eq!(
+            webextension_message(&word),
+            json!({
+                "message": "Hello, $userName$! You owe $$5 to $friend$",
+                "description": "Всплывающее окно",
+                "placeholders": {
+                    "username": {"content": "$1"},
+                    "friend": {"content": "$2"}
+                }
+            })
+        );
+
+        let name = message_name("Привет, мир");
+        assert!(name.starts_with("privet_mir_"));
+        assert!(name.chars().all(is_name_char));
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::errors::errors::BuildSystemErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::spreadsheet::{TableRow, TranslationTable};
+use crate::static_translate::{get_basic_language, pair_with_sources};
+use crate::types::EntryStatus;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[doc = "Версия формата XLIFF"]
+pub enum XliffVersion {
+    #[default]
+    V1_2,
+    V2_0,
+}
+
+impl FromStr for XliffVersion {
+    type Err = String;
+
+    fn from_str(version: &str) -> Result<XliffVersion, String> {
+        match version {
+            "1.2" => Ok(XliffVersion::V1_2),
+            "2.0" | "2" => Ok(XliffVersion::V2_0),
+            _ => Err(format!(
+                "Неподдерживаемая версия XLIFF {}, доступны 1.2 и 2.0",
+                version
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Единица перевода XLIFF: одна фраза (или форма множественного числа и вариант)"]
+pub struct XliffUnit {
+    /// Ключ с суффиксами формы и варианта, используется как id
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub status: EntryStatus,
+    /// Контекст и комментарий для переводчика
+    pub notes: Vec<String>,
+}
+
+fn state_v1_2(status: EntryStatus) -> &'static str {
+    match status {
+        EntryStatus::Untranslated => "needs-translation",
+        EntryStatus::Machine => "needs-review-translation",
+        EntryStatus::Translated => "translated",
+        EntryStatus::Reviewed => "signed-off",
+    }
+}
+
+fn state_v2_0(status: EntryStatus) -> &'static str {
+    match status {
+        EntryStatus::Untranslated => "initial",
+        EntryStatus::Machine | EntryStatus::Translated => "translated",
+        EntryStatus::Reviewed => "reviewed",
+    }
+}
+
+#[doc = "Формирует документ XLIFF с переводами на один язык"]
+pub fn to_xliff(
+    source_language: &str,
+    target_language: &str,
+    units: &[XliffUnit],
+    version: XliffVersion,
+) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    match version {
+        XliffVersion::V1_2 => {
+            let _ = writeln!(
+                xml,
+                "<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n  <file original=\"dictionary\" datatype=\"plaintext\" source-language=\"{}\" target-language=\"{}\">\n    <body>",
+                escape(source_language),
+                escape(target_language)
+            );
+            for unit in units {
+                let _ = writeln!(xml, "      <trans-unit id=\"{}\">", escape(&unit.id));
+                let _ = writeln!(xml, "        <source>{}</source>", escape(&unit.source));
+                let _ = writeln!(
+                    xml,
+                    "        <target state=\"{}\">{}</target>",
+                    state_v1_2(unit.status),
+                    escape(&unit.target)
+                );
+                for note in &unit.notes {
+                    let _ = writeln!(xml, "        <note>{}</note>", escape(note));
+                }
+                xml.push_str("      </trans-unit>\n");
+            }
+            xml.push_str("    </body>\n  </file>\n</xliff>\n");
+        }
+        XliffVersion::V2_0 => {
+            let _ = writeln!(
+                xml,
+                "<xliff version=\"2.0\" xmlns=\"urn:oasis:names:tc:xliff:document:2.0\" srcLang=\"{}\" trgLang=\"{}\">\n  <file id=\"dictionary\">",
+                escape(source_language),
+                escape(target_language)
+            );
+            for unit in units {
+                let _ = writeln!(xml, "    <unit id=\"{}\">", escape(&unit.id));
+                if !unit.notes.is_empty() {
+                    xml.push_str("      <notes>\n");
+                    for note in &unit.notes {
+                        let _ = writeln!(xml, "        <note>{}</note>", escape(note));
+                    }
+                    xml.push_str("      </notes>\n");
+                }
+                let _ = writeln!(
+                    xml,
+                    "      <segment state=\"{}\">\n        <source>{}</source>\n        <target>{}</target>\n      </segment>",
+                    state_v2_0(unit.status),
+                    escape(&unit.source),
+                    escape(&unit.target)
+                );
+                xml.push_str("    </unit>\n");
+            }
+            xml.push_str("  </file>\n</xliff>\n");
+        }
+    }
+    xml
+}
+
+#[doc = "Единицы перевода дочернего словаря вместе с исходными фразами, контекстом и комментариями, упорядоченные по id"]
+pub fn xliff_units(
+    dictionary_dir: &str,
+    language: &str,
+) -> Result<Vec<XliffUnit>, BuildSystemErrors> {
+    let mut units = vec![];
+    for paired in pair_with_sources(dictionary_dir, language)? {
+        for (source, translation) in paired.pairs {
+            // Контекст и комментарий дочернего словаря, а если их нет - из базового словаря
+            let notes = [
+                paired.entry.context.as_ref().or(source.context.as_ref()),
+                paired.entry.comment.as_ref().or(source.comment.as_ref()),
+            ]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+            units.push(XliffUnit {
+                id: translation.suffixed_tag(),
+                source: source.word,
+                target: translation.word,
+                status: paired.entry.status,
+                notes,
+            });
+        }
+    }
+    units.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(units)
+}
+
+#[doc = "Выгружает репозиторий в файлы {output_dir}/{lang}.xliff, по одному на каждый язык. Возвращает пути созданных файлов"]
+pub fn export_xliff(
+    dictionary_dir: &str,
+    output_dir: &str,
+    languages: Option<Vec<String>>,
+    version: XliffVersion,
+) -> Result<Vec<String>, BuildSystemErrors> {
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let source_language = get_basic_language(dictionary_dir)?;
+    fs::create_dir_all(output_dir)?;
+    let mut written = vec![];
+    for language in &languages {
+        let units = xliff_units(dictionary_dir, language)?;
+        let path = Path::new(output_dir).join(format!("{}.xliff", language));
+        atomic_write(&path, to_xliff(&source_language, language, &units, version))?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
+}
+
+fn attribute(
+    reader: &Reader<&[u8]>,
+    element: &BytesStart,
+    name: &str,
+) -> Result<Option<String>, BuildSystemErrors> {
+    Ok(match element.try_get_attribute(name)? {
+        Some(value) => Some(value.decode_and_unescape_value(reader)?.into_owned()),
+        None => None,
+    })
+}
+
+#[doc = "Разбирает документ XLIFF 1.2 или 2.0 в таблицу переводов. Id единиц перевода считаются ключами словаря, а язык берется из target-language (1.2) или trgLang (2.0)"]
+pub fn parse_xliff(content: &str) -> Result<TranslationTable, BuildSystemErrors> {
+    let mut reader = Reader::from_str(content);
+    let mut language: Option<String> = None;
+    let mut unit_id: Option<String> = None;
+    let mut target: Option<String> = None;
+    let mut targets: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    let mut languages: Vec<String> = vec![];
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"xliff" => {
+                    if let Some(trg_lang) = attribute(&reader, &element, "trgLang")? {
+                        language = Some(trg_lang);
+                    }
+                }
+                b"file" => {
+                    if let Some(target_language) = attribute(&reader, &element, "target-language")?
+                    {
+                        language = Some(target_language);
+                    }
+                }
+                b"trans-unit" | b"unit" => unit_id = attribute(&reader, &element, "id")?,
+                b"target" => target = Some(String::new()),
+                _ => {}
+            },
+            Event::Text(text) => {
+                if let Some(target) = target.as_mut() {
+                    target.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(target) = target.as_mut() {
+                    target.push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"target" => {
+                    let (Some(id), Some(text)) = (&unit_id, target.take()) else {
+                        continue;
+                    };
+                    let Some(language) = &language else {
+                        return Err(BuildSystemErrors::InvalidXliff(
+                            "не указан язык перевода (target-language или trgLang)".to_owned(),
+                        ));
+                    };
+                    if !languages.contains(language) {
+                        languages.push(language.to_owned());
+                    }
+                    // В XLIFF 2.0 перевод единицы может быть разбит на несколько сегментов
+                    targets
+                        .entry(id.to_owned())
+                        .or_default()
+                        .entry(language.to_owned())
+                        .or_default()
+                        .push_str(&text);
+                }
+                b"trans-unit" | b"unit" => unit_id = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(TranslationTable {
+        languages,
+        rows: targets
+            .into_iter()
+            .map(|(tag, translations)| TableRow {
+                tag,
+                translations,
+                ..Default::default()
+            })
+            .collect(),
+    })
+}
+
+#[doc = "Загружает переведенный файл XLIFF в репозиторий. Пустые переводы, неизвестные ключи и заблокированные записи пропускаются. Возвращает количество измененных записей"]
+pub fn import_xliff(dictionary_dir: &str, path: &str) -> Result<usize, BuildSystemErrors> {
+    let table = parse_xliff(&fs::read_to_string(path)?)?;
+    Ok(table.merge_into_repository(dictionary_dir)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_translate::read_translated_dictionary;
+
+    #[test]
+    fn test_xliff_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Вход", "context": "кнопка"}, "Открыть <файл> & выйти"]"#,
+        )
+        .unwrap();
+        let untranslated = r#"{"Вход": "", "Открыть <файл> & выйти": ""}"#;
+        fs::write(dir.path().join("dictionary-en.json"), untranslated).unwrap();
+
+        for version in [XliffVersion::V1_2, XliffVersion::V2_0] {
+            let output = dir.path().join("xliff");
+            let written = export_xliff(repo, output.to_str().unwrap(), None, version).unwrap();
+            let exported = fs::read_to_string(&written[0]).unwrap();
+            assert!(exported.contains("Открыть &lt;файл&gt; &amp; выйти"));
+            assert!(exported.contains("<note>кнопка</note>"));
+
+            // Переводчик заполняет target и возвращает файл
+            let translated = exported
+                .replace(
+                    "<target state=\"needs-translation\"></target>",
+                    "<target>Sign in</target>",
+                )
+                .replace("<target></target>", "<target>Sign in</target>");
+            fs::write(&written[0], translated).unwrap();
+            assert_eq!(import_xliff(repo, &written[0]).unwrap(), 2);
+            let dictionary = read_translated_dictionary(repo, "en").unwrap();
+            assert_eq!(dictionary["Вход"].value, "Sign in");
+            assert_eq!(dictionary["Вход"].status, EntryStatus::Translated);
+
+            fs::write(dir.path().join("dictionary-en.json"), untranslated).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_inline_markup_in_target_is_flattened() {
+        let content = r#"<xliff version="1.2"><file target-language="de"><body>
+            <trans-unit id="Привет"><source>Привет</source><target>Hallo <g id="1">Welt</g></target></trans-unit>
+        </body></file></xliff>"#;
+        let table = parse_xliff(content).unwrap();
+        assert_eq!(table.languages, vec!["de"]);
+        assert_eq!(table.rows[0].translations["de"], "Hallo Welt");
+    }
+}
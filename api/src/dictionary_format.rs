@@ -0,0 +1,202 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::atomic_write;
+use crate::language::LanguageCode;
+use crate::parser::get_basic_dictionary;
+
+#[doc = "Расширения файлов словарей, которые понимает утилита"]
+pub const DICTIONARY_EXTENSIONS: [&str; 3] = ["json", "yaml", "yml"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[doc = "Формат файлов словарей и конфига. Определяется по расширению файла"]
+pub enum DictionaryFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+#[doc = "Разбирает JSON, в котором допускаются комментарии (// и /* */) и висячие запятые (JSONC и JSON5). Сначала пробуется строгий JSON, а если разобрать не удалось - JSON5. При ошибке возвращается ошибка строгого JSON, потому что она понятнее"]
+pub fn parse_relaxed_json<T: DeserializeOwned>(content: &str) -> Result<T, serde_json::Error> {
+    match serde_json::from_str(content) {
+        Ok(value) => Ok(value),
+        Err(strict_error) => match json5::from_str::<serde_json::Value>(content) {
+            Ok(value) => serde_json::from_value(value),
+            Err(_) => Err(strict_error),
+        },
+    }
+}
+
+impl DictionaryFormat {
+    #[doc = "Определяет формат по расширению: .yaml и .yml - YAML, все остальное - JSON"]
+    pub fn from_path(path: impl AsRef<Path>) -> DictionaryFormat {
+        match path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("yaml") | Some("yml") => DictionaryFormat::Yaml,
+            _ => DictionaryFormat::Json,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DictionaryFormat::Json => "json",
+            DictionaryFormat::Yaml => "yaml",
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, StaticDictionaryErrors> {
+        match self {
+            DictionaryFormat::Json => Ok(parse_relaxed_json(content)?),
+            DictionaryFormat::Yaml => Ok(serde_yaml::from_str(content)?),
+        }
+    }
+
+    #[doc = "Сериализует значение с отступом в два пробела и переводом строки в конце файла"]
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String, StaticDictionaryErrors> {
+        match self {
+            DictionaryFormat::Json => Ok(serde_json::to_string_pretty(value)? + "\n"),
+            DictionaryFormat::Yaml => Ok(serde_yaml::to_string(value)?),
+        }
+    }
+}
+
+impl FromStr for DictionaryFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<DictionaryFormat, String> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(DictionaryFormat::Json),
+            "yaml" | "yml" => Ok(DictionaryFormat::Yaml),
+            _ => Err(format!(
+                "Неизвестный формат словарей {}, доступны json и yaml",
+                format
+            )),
+        }
+    }
+}
+
+impl Display for DictionaryFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+#[doc = "Читает файл словаря или конфига в формате, соответствующем расширению"]
+pub fn read_dictionary_file<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+) -> Result<T, StaticDictionaryErrors> {
+    let content = fs::read_to_string(path.as_ref())?;
+    DictionaryFormat::from_path(path).parse(&content)
+}
+
+#[doc = "Записывает файл словаря в формате, соответствующем расширению, перезаписывая существующий файл"]
+pub fn write_dictionary_file<T: Serialize>(
+    path: impl AsRef<Path>,
+    value: &T,
+) -> Result<(), StaticDictionaryErrors> {
+    let content = DictionaryFormat::from_path(path.as_ref()).serialize(value)?;
+    atomic_write(path, content)?;
+    Ok(())
+}
+
+#[doc = "Ищет в директории существующий файл с одним из расширений словарей"]
+pub fn find_with_dictionary_extension(directory: &str, stem: &str) -> Option<PathBuf> {
+    DICTIONARY_EXTENSIONS
+        .iter()
+        .map(|extension| Path::new(directory).join(format!("{}.{}", stem, extension)))
+        .find(|path| path.exists())
+}
+
+#[doc = "Возвращает путь к дочернему словарю. Язык проверяется и приводится к канонической форме, поэтому ни одна команда не создаст словарь с неизвестным кодом. Если словаря еще нет, он будет создан в формате базового словаря"]
+pub fn translated_dictionary_path(
+    dictionary_dir: &str,
+    language: &str,
+) -> Result<String, StaticDictionaryErrors> {
+    let stem = format!("dictionary-{}", LanguageCode::new(language)?);
+    if let Some(path) = find_with_dictionary_extension(dictionary_dir, &stem) {
+        return Ok(path.to_string_lossy().into_owned());
+    }
+    let format = get_basic_dictionary(dictionary_dir)
+        .map(DictionaryFormat::from_path)
+        .unwrap_or_default();
+    Ok(format!("{}/{}.{}", dictionary_dir, stem, format.extension()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_format_is_selected_by_extension() {
+        assert_eq!(
+            DictionaryFormat::from_path("dictionary-en.yml"),
+            DictionaryFormat::Yaml
+        );
+        assert_eq!(
+            DictionaryFormat::from_path("dictionaries/dictionary-en.json"),
+            DictionaryFormat::Json
+        );
+        assert_eq!("YAML".parse(), Ok(DictionaryFormat::Yaml));
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dictionary-en.yaml");
+        let dictionary = BTreeMap::from([("Привет".to_owned(), "Hello: \"world\"".to_owned())]);
+        write_dictionary_file(&path, &dictionary).unwrap();
+        let read: BTreeMap<String, String> = read_dictionary_file(&path).unwrap();
+        assert_eq!(read, dictionary);
+        assert_eq!(
+            find_with_dictionary_extension(dir.path().to_str().unwrap(), "dictionary-en"),
+            Some(path)
+        );
+    }
+
+    #[test]
+    fn test_json_with_comments_is_read_and_written_strict() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dictionary-en.json");
+        fs::write(
+            &path,
+            "{\n  // Главная страница\n  \"Привет\": \"Hello\", /* кнопка */\n  \"Пока\": \"Bye\",\n}\n",
+        )
+        .unwrap();
+        let dictionary: BTreeMap<String, String> = read_dictionary_file(&path).unwrap();
+        assert_eq!(dictionary["Пока"], "Bye");
+
+        write_dictionary_file(&path, &dictionary).unwrap();
+        let strict: BTreeMap<String, String> =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(strict, dictionary);
+        assert!(parse_relaxed_json::<BTreeMap<String, String>>("{\"a\": }").is_err());
+    }
+
+    #[test]
+    fn test_dictionary_paths_are_built_from_validated_languages() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), "[]").unwrap();
+        assert_eq!(
+            translated_dictionary_path(repo, "pt_br").unwrap(),
+            format!("{}/dictionary-pt-BR.json", repo)
+        );
+        assert!(matches!(
+            translated_dictionary_path(repo, "englsh"),
+            Err(StaticDictionaryErrors::UnknownLanguage(_))
+        ));
+        assert!(crate::parser::get_dictionary_language("dictionary-englsh.json").is_err());
+        assert!(crate::parser::get_dictionary_language("dictionary-pt_br.json").is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::file_system::{read_config_file, CONFIG_FILE_NAMES};
+use crate::language::validate_languages;
+use crate::parser::get_basic_dictionary;
+use crate::parser::types::ConfigFileParameters;
+use crate::validation::validate_repository;
+
+// Сколько ждать ответа хостинга переводчика
+const HOST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[doc = "Результат одной проверки dms doctor"]
+pub enum DiagnosticStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Проверка окружения или конфига с советом, как исправить проблему"]
+pub struct Diagnostic {
+    /// Что проверялось
+    pub check: String,
+    pub status: DiagnosticStatus,
+    pub message: String,
+    /// Что сделать, чтобы проблема исчезла
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Diagnostic {
+    fn ok(check: &str, message: String) -> Diagnostic {
+        Diagnostic {
+            check: check.to_owned(),
+            status: DiagnosticStatus::Ok,
+            message,
+            fix: None,
+        }
+    }
+
+    fn problem(status: DiagnosticStatus, check: &str, message: String, fix: String) -> Diagnostic {
+        Diagnostic {
+            check: check.to_owned(),
+            status,
+            message,
+            fix: Some(fix),
+        }
+    }
+}
+
+// Конфиг и проверка его схемы: обязательные поля, директории для сканирования, языки и порог уверенности
+fn check_config(config_path: Option<&str>) -> (Vec<Diagnostic>, Option<ConfigFileParameters>) {
+    let check = "Конфиг";
+    let path = match config_path {
+        Some(path) => path.to_owned(),
+        None => match CONFIG_FILE_NAMES
+            .iter()
+            .find(|name| Path::new(name).is_file())
+        {
+            Some(name) => name.to_string(),
+            None => {
+                let diagnostic = Diagnostic::problem(
+                    DiagnosticStatus::Error,
+                    check,
+                    format!("в текущей директории нет {}", CONFIG_FILE_NAMES.join(", ")),
+                    "создайте конфиг командой dms init config или передайте путь через --config"
+                        .to_owned(),
+                );
+                return (vec![diagnostic], None);
+            }
+        },
+    };
+    let config = match read_config_file(&path) {
+        Ok(config) => config,
+        Err(err) => {
+            let diagnostic = Diagnostic::problem(
+                DiagnosticStatus::Error,
+                check,
+                format!("не удалось прочитать {}: {}", path, err),
+                "исправьте синтаксис и проверьте, что указаны base, exclude, dictionary_repo, output_dir, include и manual_translate".to_owned(),
+            );
+            return (vec![diagnostic], None);
+        }
+    };
+    let mut diagnostics = vec![Diagnostic::ok(check, format!("{} прочитан", path))];
+    for root in config.base_directory.roots() {
+        if !Path::new(&root.path).is_dir() {
+            diagnostics.push(Diagnostic::problem(
+                DiagnosticStatus::Warning,
+                "Директории для сканирования",
+                format!("директории {} нет", root.path),
+                "исправьте base в конфиге или запускайте dms из корня проекта".to_owned(),
+            ));
+        }
+    }
+    if config.languages_configurations.is_empty() {
+        diagnostics.push(Diagnostic::problem(
+            DiagnosticStatus::Warning,
+            "Расширения файлов",
+            "в include нет ни одной группы расширений, scan не найдет файлов".to_owned(),
+            "добавьте в include группу, например {\"code\": {\"file_extensions\": [\"ts\", \"tsx\"]}}".to_owned(),
+        ));
+    }
+    if let Err(err) = validate_languages(&config.languages) {
+        diagnostics.push(Diagnostic::problem(
+            DiagnosticStatus::Error,
+            "Языки",
+            err.to_string(),
+            "укажите в languages коды языков или локалей, например en или pt-BR".to_owned(),
+        ));
+    }
+    (diagnostics, Some(config))
+}
+
+// Структура репозитория словарей: директория, базовый словарь и целостность дочерних словарей
+fn check_repository(name: &str, path: &str) -> Diagnostic {
+    let check = format!("Репозиторий словарей {}", name);
+    if !Path::new(path).is_dir() {
+        return Diagnostic::problem(
+            DiagnosticStatus::Error,
+            &check,
+            format!("директории {} нет", path),
+            format!(
+                "создайте репозиторий командой dms init {} <язык> или исправьте dictionary_repo",
+                path
+            ),
+        );
+    }
+    if let Err(err) = get_basic_dictionary(path) {
+        return Diagnostic::problem(
+            DiagnosticStatus::Error,
+            &check,
+            format!("в {} нет базового словаря: {}", path, err),
+            format!("создайте базовый словарь командой dms init {} <язык>", path),
+        );
+    }
+    match validate_repository(path) {
+        Ok(issues) if issues.is_empty() => Diagnostic::ok(&check, format!("{} в порядке", path)),
+        Ok(issues) => Diagnostic::problem(
+            DiagnosticStatus::Warning,
+            &check,
+            format!("найдено нарушений целостности: {}", issues.len()),
+            format!(
+                "посмотрите список командой dms validate {}, недостающие ключи добавит dms sync {}",
+                path, path
+            ),
+        ),
+        Err(err) => Diagnostic::problem(
+            DiagnosticStatus::Error,
+            &check,
+            format!("не удалось проверить {}: {}", path, err),
+            "проверьте права на чтение файлов репозитория".to_owned(),
+        ),
+    }
+}
+
+// Запрос к хостингу переводчика. Успешным считается любой ответ 2xx
+async fn ping_host(
+    check: &str,
+    request: reqwest::RequestBuilder,
+    url: &str,
+    fix: &str,
+) -> Diagnostic {
+    match request.timeout(HOST_TIMEOUT).send().await {
+        Ok(response) if response.status().is_success() => {
+            Diagnostic::ok(check, format!("{} отвечает", url))
+        }
+        Ok(response) => Diagnostic::problem(
+            DiagnosticStatus::Error,
+            check,
+            format!("{} ответил {}", url, response.status()),
+            fix.to_owned(),
+        ),
+        Err(err) => Diagnostic::problem(
+            DiagnosticStatus::Error,
+            check,
+            format!("{} недоступен: {}", url, err),
+            fix.to_owned(),
+        ),
+    }
+}
+
+// Хостинги переводчиков из translator: LibreTranslate по /languages, DeepL по /v2/usage с ключом из DEEPL_API_KEY
+async fn check_translators(config: &ConfigFileParameters) -> Vec<Diagnostic> {
+    let client = reqwest::Client::new();
+    let mut diagnostics = vec![];
+    let translator = &config.translator;
+    if let Some(host) = &translator.libretranslate_host {
+        let url = format!("{}/languages", host.trim_end_matches('/'));
+        diagnostics.push(
+            ping_host(
+                "LibreTranslate",
+                client.get(&url),
+                &url,
+                "запустите LibreTranslate или исправьте translator.libretranslate_host в конфиге",
+            )
+            .await,
+        );
+    }
+    if let Some(host) = &translator.deepl_host {
+        let url = format!("{}/v2/usage", host.trim_end_matches('/'));
+        diagnostics.push(match env::var("DEEPL_API_KEY") {
+            Ok(api_key) => {
+                let request = client
+                    .get(&url)
+                    .header("Authorization", format!("DeepL-Auth-Key {}", api_key));
+                ping_host(
+                    "DeepL",
+                    request,
+                    &url,
+                    "проверьте ключ в DEEPL_API_KEY и translator.deepl_host (для ключей бесплатного тарифа - https://api-free.deepl.com)",
+                )
+                .await
+            }
+            Err(_) => Diagnostic::problem(
+                DiagnosticStatus::Warning,
+                "DeepL",
+                "переменная DEEPL_API_KEY не задана, хостинг не проверен".to_owned(),
+                "задайте DEEPL_API_KEY или передавайте ключ через --api-key".to_owned(),
+            ),
+        });
+    }
+    diagnostics
+}
+
+#[doc = "Проверяет, что в директорию можно писать: создает и удаляет в ней временный файл. Если директории нет, проверяется ближайшая существующая родительская, в которой ее создаст сборка"]
+pub fn check_writable(directory: &str) -> Result<(), String> {
+    let mut existing = Path::new(directory);
+    while !existing.is_dir() {
+        existing = match existing.parent() {
+            Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+            Some(parent) => parent,
+            None => return Err(format!("не найдена директория для {}", directory)),
+        };
+    }
+    let probe = existing.join(format!(".dms-doctor-{}", process::id()));
+    fs::write(&probe, b"").map_err(|err| format!("{}: {}", existing.display(), err))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+// Права на запись в директорию сборки output_dir
+fn check_output_directory(config: &ConfigFileParameters) -> Diagnostic {
+    let check = "Директория сборки";
+    let output_dir = &config.output_dir;
+    match check_writable(output_dir) {
+        Ok(()) if Path::new(output_dir).is_dir() => {
+            Diagnostic::ok(check, format!("в {} можно писать", output_dir))
+        }
+        Ok(()) => Diagnostic::ok(check, format!("{} будет создана при сборке", output_dir)),
+        Err(err) => Diagnostic::problem(
+            DiagnosticStatus::Error,
+            check,
+            format!("в {} нельзя писать: {}", output_dir, err),
+            "выдайте права на запись или укажите другую output_dir в конфиге".to_owned(),
+        ),
+    }
+}
+
+#[doc = "Диагностика окружения: схема конфига (без пути - первый из CONFIG_FILE_NAMES в текущей директории), структура репозиториев словарей, доступность хостингов переводчиков из translator и права на запись в output_dir. Без network хостинги не проверяются"]
+pub async fn diagnose(config_path: Option<&str>, network: bool) -> Vec<Diagnostic> {
+    let (mut diagnostics, config) = check_config(config_path);
+    let Some(config) = config else {
+        return diagnostics;
+    };
+    for repository in config.dictionary_repo.repositories() {
+        diagnostics.push(check_repository(&repository.name, &repository.path));
+    }
+    if network {
+        diagnostics.extend(check_translators(&config).await);
+    }
+    diagnostics.push(check_output_directory(&config));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_diagnose_reports_missing_repository_and_output_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        let config = dir.path().join("config.dms.json");
+        fs::write(
+            &config,
+            serde_json::json!({
+                "base": format!("{}/src", root),
+                "exclude": [],
+                "dictionary_repo": format!("{}/dictionaries", root),
+                "output_dir": format!("{}/locales/build", root),
+                "include": [{"code": {"file_extensions": ["ts"]}}],
+                "manual_translate": [],
+                "languages": ["en", "xx"],
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let diagnostics = diagnose(config.to_str(), false).await;
+        let status = |check: &str| {
+            diagnostics
+                .iter()
+                .find(|diagnostic| diagnostic.check.starts_with(check))
+                .map(|diagnostic| diagnostic.status)
+        };
+        assert_eq!(status("Конфиг"), Some(DiagnosticStatus::Ok));
+        assert_eq!(status("Языки"), Some(DiagnosticStatus::Error));
+        assert_eq!(
+            status("Репозиторий словарей"),
+            Some(DiagnosticStatus::Error)
+        );
+        assert_eq!(status("Директория сборки"), Some(DiagnosticStatus::Ok));
+        assert_eq!(status("LibreTranslate"), None);
+
+        let missing = diagnose(Some(&format!("{}/missing.json", root)), false).await;
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].status, DiagnosticStatus::Error);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+
+// Номер копии, чтобы копии репозитория и директории сборки одного процесса не совпадали
+static SANDBOX_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[doc = "Как изменился бы файл"]
+pub enum PlannedChange {
+    Create,
+    Modify,
+    Remove,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[doc = "Файл, который команда изменила бы без пробного запуска"]
+pub struct PlannedWrite {
+    /// Путь в исходной директории
+    pub path: String,
+    pub change: PlannedChange,
+}
+
+#[doc = "Копия директории или файла во временном каталоге для пробного запуска: команда выполняется над копией, а изменения сравниваются с оригиналом. Копия удаляется вместе со структурой"]
+pub struct Sandbox {
+    original: PathBuf,
+    copy: PathBuf,
+    /// Временный каталог, в котором лежит копия
+    root: PathBuf,
+    /// Копия создана пустой: файлы, которых в ней нет, не считаются удаленными
+    overlay: bool,
+}
+
+// Содержимое всех файлов директории по относительным путям. Каталоги .git пропускаются. Файл читается под пустым путем
+fn read_tree(
+    root: &Path,
+    dir: &Path,
+    files: &mut BTreeMap<PathBuf, Vec<u8>>,
+) -> Result<(), StaticDictionaryErrors> {
+    if dir.is_file() {
+        files.insert(PathBuf::new(), fs::read(dir)?);
+        return Ok(());
+    }
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            read_tree(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_owned();
+            files.insert(relative, fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+impl Sandbox {
+    // Временный каталог и путь копии в нем с тем же именем, что и у оригинала
+    fn create(path: &str, overlay: bool) -> Result<Sandbox, StaticDictionaryErrors> {
+        let root = env::temp_dir().join(format!(
+            "dms-dry-run-{}-{}",
+            process::id(),
+            SANDBOX_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root)?;
+        let original = PathBuf::from(path);
+        let name = original
+            .file_name()
+            .map(|name| name.to_owned())
+            .unwrap_or_else(|| "copy".into());
+        let copy = root.join(name);
+        tracing::debug!("Копия {} для пробного запуска: {}", path, copy.display());
+        Ok(Sandbox {
+            original,
+            copy,
+            root,
+            overlay,
+        })
+    }
+
+    #[doc = "Копирует директорию во временный каталог. Если директории нет, копия создается пустой"]
+    pub fn new(directory: &str) -> Result<Sandbox, StaticDictionaryErrors> {
+        let sandbox = Sandbox::create(directory, false)?;
+        let mut files = BTreeMap::new();
+        read_tree(&sandbox.original, &sandbox.original, &mut files)?;
+        fs::create_dir_all(&sandbox.copy)?;
+        for (relative, content) in files {
+            let path = sandbox.copy.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(sandbox)
+    }
+
+    #[doc = "Копирует файл во временный каталог. Если файла нет, копии тоже нет, и команда создает ее сама"]
+    pub fn file(path: &str) -> Result<Sandbox, StaticDictionaryErrors> {
+        let sandbox = Sandbox::create(path, false)?;
+        if sandbox.original.is_file() {
+            fs::copy(&sandbox.original, &sandbox.copy)?;
+        }
+        Ok(sandbox)
+    }
+
+    #[doc = "Создает пустую копию директории, в которую команда только добавляет файлы. Содержимое оригинала не копируется, поэтому подходит и для больших директорий вроде текущей"]
+    pub fn overlay(directory: &str) -> Result<Sandbox, StaticDictionaryErrors> {
+        let sandbox = Sandbox::create(directory, true)?;
+        fs::create_dir_all(&sandbox.copy)?;
+        Ok(sandbox)
+    }
+
+    #[doc = "Путь к копии, который передается команде вместо исходной директории"]
+    pub fn path(&self) -> String {
+        self.copy.to_string_lossy().into_owned()
+    }
+
+    #[doc = "Файлы, которые команда создала, изменила или удалила в копии, с путями в исходной директории"]
+    pub fn changes(&self) -> Result<Vec<PlannedWrite>, StaticDictionaryErrors> {
+        let mut after = BTreeMap::new();
+        read_tree(&self.copy, &self.copy, &mut after)?;
+        let mut before = BTreeMap::new();
+        if self.overlay {
+            for relative in after.keys() {
+                let path = self.original.join(relative);
+                if path.is_file() {
+                    before.insert(relative.clone(), fs::read(path)?);
+                }
+            }
+        } else {
+            read_tree(&self.original, &self.original, &mut before)?;
+        }
+        let mut paths: Vec<&PathBuf> = before.keys().chain(after.keys()).collect();
+        paths.sort();
+        paths.dedup();
+        Ok(paths
+            .into_iter()
+            .filter_map(|relative| {
+                let change = match (before.get(relative), after.get(relative)) {
+                    (None, Some(_)) => PlannedChange::Create,
+                    (Some(_), None) => PlannedChange::Remove,
+                    (Some(old), Some(new)) if old != new => PlannedChange::Modify,
+                    _ => return None,
+                };
+                let path = match relative.as_os_str().is_empty() {
+                    true => self.original.clone(),
+                    false => self.original.join(relative),
+                };
+                Some(PlannedWrite {
+                    path: path.to_string_lossy().into_owned(),
+                    change,
+                })
+            })
+            .collect())
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_reports_changes_without_touching_original() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Вход"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-en.json"), "{}").unwrap();
+        fs::write(dir.path().join("dictionary-de.json"), "{}").unwrap();
+
+        let sandbox = Sandbox::new(dir.path().to_str().unwrap()).unwrap();
+        let copy = PathBuf::from(sandbox.path());
+        fs::write(copy.join("dictionary-en.json"), r#"{"Вход": "Sign in"}"#).unwrap();
+        fs::write(copy.join("dictionary-fr.json"), "{}").unwrap();
+        fs::remove_file(copy.join("dictionary-de.json")).unwrap();
+
+        let changes: Vec<(String, PlannedChange)> = sandbox
+            .changes()
+            .unwrap()
+            .into_iter()
+            .map(|write| {
+                let name = Path::new(&write.path).file_name().unwrap();
+                (name.to_string_lossy().into_owned(), write.change)
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("dictionary-de.json".to_owned(), PlannedChange::Remove),
+                ("dictionary-en.json".to_owned(), PlannedChange::Modify),
+                ("dictionary-fr.json".to_owned(), PlannedChange::Create),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap(),
+            "{}"
+        );
+        drop(sandbox);
+        assert!(!copy.exists());
+    }
+
+    #[test]
+    fn test_file_and_overlay_sandboxes() {
+        let dir = tempfile::tempdir().unwrap();
+        let table = dir.path().join("translations.csv");
+        fs::write(&table, "tag,ru\n").unwrap();
+
+        let sandbox = Sandbox::file(table.to_str().unwrap()).unwrap();
+        assert_eq!(fs::read_to_string(sandbox.path()).unwrap(), "tag,ru\n");
+        fs::write(sandbox.path(), "tag,ru,en\n").unwrap();
+        let changes = sandbox.changes().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, table.to_string_lossy());
+        assert_eq!(changes[0].change, PlannedChange::Modify);
+        assert_eq!(fs::read_to_string(&table).unwrap(), "tag,ru\n");
+
+        // Файлы оригинала не копируются в пустую копию и не считаются удаленными
+        let sandbox = Sandbox::overlay(dir.path().to_str().unwrap()).unwrap();
+        assert!(!Path::new(&sandbox.path()).join("translations.csv").exists());
+        fs::write(Path::new(&sandbox.path()).join("en-1.json"), "{}").unwrap();
+        let changes = sandbox.changes().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change, PlannedChange::Create);
+        assert!(!dir.path().join("en-1.json").exists());
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::normalization::normalize_phrase;
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_translated_dictionary,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Разные ключи с одинаковым текстом на одном языке"]
+pub struct DuplicateGroup {
+    pub language: String,
+    /// Группа из базового словаря: одинаковые исходные фразы. Иначе - одинаковые переводы
+    pub base: bool,
+    /// Текст первого ключа группы
+    pub text: String,
+    /// Ключи в порядке базового словаря. Первый из них предлагается оставить вместо остальных
+    pub tags: Vec<String>,
+}
+
+#[doc = "Текст для сравнения: без учета регистра, повторяющихся пробелов и знаков препинания в конце"]
+pub fn duplicate_key(text: &str) -> String {
+    normalize_phrase(text)
+        .trim_end_matches(['.', '!', '?', ':', ';', '…'])
+        .trim_end()
+        .to_lowercase()
+}
+
+// Группы ключей с одинаковым текстом в порядке первого ключа. Пустые тексты не сравниваются
+fn group_duplicates(
+    language: &str,
+    base: bool,
+    texts: impl Iterator<Item = (String, String)>,
+) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = vec![];
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (tag, text) in texts {
+        let key = duplicate_key(&text);
+        if key.is_empty() {
+            continue;
+        }
+        match positions.get(&key) {
+            Some(&position) => groups[position].tags.push(tag),
+            None => {
+                positions.insert(key, groups.len());
+                groups.push(DuplicateGroup {
+                    language: language.to_owned(),
+                    base,
+                    text,
+                    tags: vec![tag],
+                });
+            }
+        }
+    }
+    groups.retain(|group| group.tags.len() > 1);
+    groups
+}
+
+#[doc = "Находит разные ключи с одинаковыми исходными фразами и с одинаковыми переводами на указанные языки (или на все языки репозитория). Такие ключи обычно появляются при сканировании больших проектов и их можно объединить"]
+pub fn find_duplicates(
+    dictionary_dir: &str,
+    languages: Option<Vec<String>>,
+) -> Result<Vec<DuplicateGroup>, StaticDictionaryErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let base_entries = parse_static_basic_entries(dictionary_dir)?;
+    let mut groups = group_duplicates(
+        &basic_language,
+        true,
+        base_entries
+            .iter()
+            .map(|entry| (entry.tag().to_owned(), entry.phrase.clone())),
+    );
+    let languages = match languages {
+        Some(languages) => languages,
+        None => {
+            let mut languages = find_all_translated_languages(dictionary_dir)?;
+            languages.sort();
+            languages
+        }
+    };
+    for language in languages {
+        let dictionary = read_translated_dictionary(dictionary_dir, &language)?;
+        groups.extend(group_duplicates(
+            &language,
+            false,
+            base_entries.iter().filter_map(|entry| {
+                dictionary
+                    .get(entry.tag())
+                    .map(|translated| (entry.tag().to_owned(), translated.value.clone()))
+            }),
+        ));
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_duplicates_of_sources_and_translations() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Сохранить", "Сохранить.", {"phrase": "сохранить ", "key": "save_button"}, "Записать", "Отмена", "Закрыть"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Записать": "Save", "Сохранить": "Save!", "Отмена": "", "Закрыть": ""}"#,
+        )
+        .unwrap();
+
+        let groups = find_duplicates(repo, None).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].base);
+        assert_eq!(groups[0].text, "Сохранить");
+        assert_eq!(
+            groups[0].tags,
+            vec!["Сохранить", "Сохранить.", "save_button"]
+        );
+        assert_eq!((groups[1].language.as_str(), groups[1].base), ("en", false));
+        assert_eq!(groups[1].tags, vec!["Сохранить", "Записать"]);
+    }
+}
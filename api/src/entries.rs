@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::language::LanguageCode;
+use crate::source_locations::{locations_for, read_source_locations, SourceLocation};
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_existing_dictionary,
+    write_translated_dictionary,
+};
+use crate::types::{
+    ApiArgs, BaseEntry, DictionaryEntry, EntryStatus, Suggestion, TranslatorApi, TranslatorApis,
+};
+use crate::web_api::TranslatorBackend;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Ключ базового словаря в списке ключей"]
+pub struct KeySummary {
+    pub tag: String,
+    pub phrase: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Переведены ли значение и все формы на язык из запроса. Без языка не заполняется
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translated: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Запись со всеми переводами: то, что нужно редактору перевода"]
+pub struct EntryView {
+    pub tag: String,
+    pub basic_language: String,
+    /// Запись базового словаря
+    pub source: BaseEntry,
+    /// Язык -> запись дочернего словаря. Языки, в словаре которых записи нет, не попадают
+    pub translations: BTreeMap<String, DictionaryEntry>,
+    /// Места использования из последнего сканирования
+    pub locations: Vec<SourceLocation>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[doc = "Изменение перевода записи. Поля, которые не переданы, не меняются"]
+pub struct EntryUpdate {
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub plural_forms: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub variants: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// Статус после изменения. По умолчанию translated: перевод выполнен вручную
+    #[serde(default)]
+    pub status: Option<EntryStatus>,
+}
+
+// Запись базового словаря по тегу
+fn find_base_entry(dictionary_dir: &str, tag: &str) -> Result<BaseEntry, StaticDictionaryErrors> {
+    parse_static_basic_entries(dictionary_dir)?
+        .into_iter()
+        .find(|entry| entry.tag() == tag)
+        .ok_or_else(|| StaticDictionaryErrors::EntryNotFound(tag.to_owned()))
+}
+
+#[doc = "Ключи базового словаря в порядке записи. С языком у каждого ключа отмечено, переведен ли он, а с untranslated возвращаются только непереведенные"]
+pub fn list_keys(
+    dictionary_dir: &str,
+    language: Option<&str>,
+    untranslated: bool,
+) -> Result<Vec<KeySummary>, StaticDictionaryErrors> {
+    let dictionary = match language {
+        Some(language) => Some(read_existing_dictionary(
+            dictionary_dir,
+            LanguageCode::new(language)?.as_str(),
+        )?),
+        None => None,
+    };
+    Ok(parse_static_basic_entries(dictionary_dir)?
+        .into_iter()
+        .map(|entry| KeySummary {
+            translated: dictionary.as_ref().map(|dictionary| {
+                dictionary
+                    .get(entry.tag())
+                    .is_some_and(DictionaryEntry::is_translated)
+            }),
+            tag: entry.tag().to_owned(),
+            phrase: entry.phrase,
+            context: entry.context,
+        })
+        .filter(|key| !untranslated || key.translated == Some(false))
+        .collect())
+}
+
+#[doc = "Запись базового словаря с переводами на все языки и местами использования"]
+pub fn entry_view(dictionary_dir: &str, tag: &str) -> Result<EntryView, StaticDictionaryErrors> {
+    let source = find_base_entry(dictionary_dir, tag)?;
+    let mut translations = BTreeMap::new();
+    for language in find_all_translated_languages(dictionary_dir)? {
+        if let Some(entry) = read_existing_dictionary(dictionary_dir, &language)?.remove(tag) {
+            translations.insert(language, entry);
+        }
+    }
+    let locations = read_source_locations(dictionary_dir)?;
+    Ok(EntryView {
+        tag: tag.to_owned(),
+        basic_language: get_basic_language(dictionary_dir)?,
+        locations: locations_for(&locations, tag).to_vec(),
+        source,
+        translations,
+    })
+}
+
+#[doc = "Записывает перевод ключа на язык. Запись, которой нет в дочернем словаре, создается, хеш исходной фразы обновляется, чтобы перевод не считался устаревшим, а предложение автопереводчика убирается. Возвращает запись после изменения"]
+pub fn update_entry(
+    dictionary_dir: &str,
+    tag: &str,
+    language: &str,
+    update: EntryUpdate,
+) -> Result<DictionaryEntry, StaticDictionaryErrors> {
+    let language = LanguageCode::new(language)?;
+    let base = find_base_entry(dictionary_dir, tag)?;
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let mut dictionary = read_existing_dictionary(dictionary_dir, language.as_str())?;
+    let entry = dictionary
+        .entry(tag.to_owned())
+        .or_insert_with(|| DictionaryEntry::from_base(&base, &basic_language, language.as_str()));
+    if let Some(value) = update.value {
+        entry.value = value;
+    }
+    if let Some(plural_forms) = update.plural_forms {
+        entry.plural_forms = Some(plural_forms);
+    }
+    if let Some(variants) = update.variants {
+        entry.variants = Some(variants);
+    }
+    if let Some(comment) = update.comment {
+        entry.comment = (!comment.is_empty()).then_some(comment);
+    }
+    entry.status = update.status.unwrap_or(EntryStatus::Translated);
+    // Перевод проверен человеком, и ждущее утверждения предложение автопереводчика больше не нужно
+    entry.suggestion = None;
+    entry.source_hash = Some(base.source_hash());
+    let updated = entry.clone();
+    write_translated_dictionary(dictionary_dir, language.as_str(), &dictionary)?;
+    Ok(updated)
+}
+
+#[doc = "Машинный перевод записи на язык для редактора перевода: фраза, формы множественного числа и варианты переводятся выбранным переводчиком. В словарь ничего не записывается, предложение становится переводом только после update_entry"]
+pub async fn suggest_translation(
+    dictionary_dir: &str,
+    tag: &str,
+    language: &str,
+    translator_api: TranslatorApis,
+    api_args: ApiArgs,
+) -> Result<Suggestion, StaticDictionaryErrors> {
+    let language = LanguageCode::new(language)?;
+    let base = find_base_entry(dictionary_dir, tag)?;
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let translator = TranslatorBackend::new(translator_api, api_args);
+    let mut entry = DictionaryEntry::from_base(&base, &basic_language, language.as_str());
+    for word in base.words_for_translation(&basic_language, language.as_str()) {
+        let word = translator
+            .translate_word_with_tag(word, language.as_str().to_owned())
+            .await?;
+        entry.set_word(&word);
+    }
+    Ok(entry.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_update_entry_and_list_untranslated_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", {"phrase": "Выход", "context": "Кнопка в меню"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in"}"#,
+        )
+        .unwrap();
+
+        let untranslated = list_keys(repo, Some("en"), true).unwrap();
+        assert_eq!(untranslated.len(), 1);
+        assert_eq!(untranslated[0].tag, "Выход");
+        assert_eq!(untranslated[0].context.as_deref(), Some("Кнопка в меню"));
+
+        let update = EntryUpdate {
+            value: Some("Sign out".to_owned()),
+            ..Default::default()
+        };
+        let updated = update_entry(repo, "Выход", "en", update).unwrap();
+        assert_eq!(updated.status, EntryStatus::Translated);
+        assert!(list_keys(repo, Some("en"), true).unwrap().is_empty());
+
+        let view = entry_view(repo, "Выход").unwrap();
+        assert_eq!(view.translations["en"].value, "Sign out");
+        assert!(matches!(
+            update_entry(repo, "Корзина", "en", EntryUpdate::default()),
+            Err(StaticDictionaryErrors::EntryNotFound(_))
+        ));
+    }
+}
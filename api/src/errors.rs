@@ -12,6 +12,12 @@ pub mod errors {
         #[error("Не удалось спарсить JSON файл словаря")]
         /// Обертка для типа serde_json::Error
         JSONParsingError(#[from] serde_json::Error),
+        #[error("Не удалось спарсить YAML файл словаря")]
+        /// Обертка для типа serde_yaml::Error
+        YAMLParsingError(#[from] serde_yaml::Error),
+        #[error("Не удалось спарсить TOML файл конфига")]
+        /// Обертка для типа toml::de::Error
+        TOMLParsingError(#[from] toml::de::Error),
         #[error("Произошла ошибка при переводе слова в API")]
         /// Обертка для типа reqwest::Error
         APIError(#[from] reqwest::Error),
@@ -24,6 +30,60 @@ pub mod errors {
         /// Обертка для ошибок при работе с регулярными выражениями
         #[error("Ошибка при работе с регулярными выражениями")]
         RegexError(#[from] regex::Error),
+        /// Код языка не найден в ISO 639-1 и ISO 639-2
+        #[error("Неизвестный язык '{0}'")]
+        UnknownLanguage(String),
+        /// Некорректные письменность, регион или вариант в теге локали
+        #[error("Некорректный тег локали '{0}'")]
+        InvalidLocale(String),
+        /// Обертка для ошибок чтения и записи CSV
+        #[error("Ошибка при работе с CSV: {0}")]
+        CSVError(#[from] csv::Error),
+        /// Таблица переводов не соответствует ожидаемому формату
+        #[error("Некорректная таблица переводов: {0}")]
+        InvalidTable(String),
+        /// Обертка для ошибок записи XLSX
+        #[error("Ошибка при записи XLSX: {0}")]
+        XlsxWriteError(#[from] rust_xlsxwriter::XlsxError),
+        /// Обертка для ошибок чтения XLSX
+        #[error("Ошибка при чтении XLSX: {0}")]
+        XlsxReadError(#[from] calamine::XlsxError),
+        /// Некорректный glob-шаблон в include_files или exclude конфига
+        #[error("Некорректный шаблон файлов: {0}")]
+        GlobError(#[from] globset::Error),
+        /// Обертка для ошибок отслеживания изменений файлов
+        #[error("Не удалось отслеживать изменения файлов: {0}")]
+        WatchError(#[from] notify::Error),
+        /// Значение в конфиге не поддерживается
+        #[error("Некорректный конфиг: {0}")]
+        InvalidConfig(String),
+        /// В базовом словаре нет записи с указанным тегом
+        #[error("Запись с тегом '{0}' не найдена в базовом словаре")]
+        EntryNotFound(String),
+        /// Со стратегией тегов manual у фразы нет тега
+        #[error("У фразы '{0}' в строке {1} нет тега: стратегия manual требует аннотацию dms-key или тег в вызове t(\"тег\", \"фраза\")")]
+        MissingKey(String, usize),
+        /// Тег уже занят другой записью базового словаря
+        #[error("Тег '{0}' уже используется в базовом словаре")]
+        DuplicateTag(String),
+        /// Репозитории словарей нельзя объединить
+        #[error("Репозитории словарей несовместимы: {0}")]
+        IncompatibleRepositories(String),
+        /// Резервная копия для отката не найдена
+        #[error("Резервная копия не найдена: {0}")]
+        BackupNotFound(String),
+        /// Репозиторий словарей изменяется другим процессом
+        #[error("Репозиторий словарей заблокирован: {0}")]
+        RepositoryLocked(String),
+        /// Пакет переводов не подходит к репозиторию
+        #[error("Некорректный пакет переводов: {0}")]
+        InvalidPackage(String),
+        /// Для языка нет словаря проверки орфографии в формате hunspell
+        #[error("Словарь проверки орфографии не найден: {0}")]
+        SpellingDictionaryNotFound(String),
+        /// Релиз словарей не найден, уже существует или изменен после создания
+        #[error("Некорректный релиз словарей: {0}")]
+        InvalidRelease(String),
     }
 
     #[derive(Error, Debug)]
@@ -37,5 +97,38 @@ pub mod errors {
         StaticDictionaryError(#[from] StaticDictionaryErrors),
         #[error("Произошла ошибка при работе с JSON")]
         JSONError(#[from] serde_json::Error),
+        #[error("Некорректный шаблон пути {0}: не закрыта фигурная скобка")]
+        TemplateError(String),
+        #[error("Неизвестная переменная {{{0}}} в шаблоне пути")]
+        UnknownTemplateVariable(String),
+        #[error("Произошла ошибка при работе с XML: {0}")]
+        XMLError(#[from] quick_xml::Error),
+        #[error("Некорректный файл XLIFF: {0}")]
+        InvalidXliff(String),
+        #[error("Некорректный каталог PO: {0}")]
+        InvalidPo(String),
+        #[error("Ключ {0} конфликтует с другим ключом: префикс не может быть одновременно фразой и родителем")]
+        KeyCollision(String),
+        #[error("Пространство имен {0} не найдено в репозитории словарей")]
+        NamespaceNotFound(String),
+        #[error("Не удалось импортировать файлы: {0}")]
+        InvalidImport(String),
+        #[error("Произошла ошибка при работе с YAML")]
+        YAMLError(#[from] serde_yaml::Error),
+    }
+
+    #[derive(Error, Debug)]
+    #[doc = "Ошибки при составлении отчетов об изменениях в словарях"]
+    pub enum ReportErrors {
+        #[error("Произошла ошибка при работе с файловой системой")]
+        IOError(#[from] io::Error),
+        #[error("Произошла ошибка в системе статических словарей")]
+        StaticDictionaryError(#[from] StaticDictionaryErrors),
+        /// Ошибка при вызове git (например, несуществующая ревизия)
+        #[error("Ошибка git: {0}")]
+        GitError(String),
+        /// Ошибка при отправке отчета в GitHub
+        #[error("Произошла ошибка при отправке отчета в GitHub")]
+        APIError(#[from] reqwest::Error),
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::dictionary_format::DictionaryFormat;
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::{atomic_write, find_all_translated_dictionaries};
+use crate::parser::get_basic_dictionary;
+use crate::types::{BaseEntry, DictionaryEntry, TranslatedDictionary};
+
+fn nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+fn nfc_values(values: Option<BTreeMap<String, String>>) -> Option<BTreeMap<String, String>> {
+    values.map(|values| {
+        values
+            .into_iter()
+            .map(|(name, text)| (name, nfc(&text)))
+            .collect()
+    })
+}
+
+// В отличие от normalize_entry пробелы не меняются: форматирование не должно менять теги и текст
+fn format_base_entry(entry: BaseEntry) -> BaseEntry {
+    BaseEntry {
+        phrase: nfc(&entry.phrase),
+        key: entry.key.as_deref().map(nfc),
+        plural_forms: nfc_values(entry.plural_forms),
+        context: entry.context.as_deref().map(nfc),
+        variants: nfc_values(entry.variants),
+        comment: entry.comment.as_deref().map(nfc),
+        ..entry
+    }
+}
+
+fn format_translated_entry(entry: DictionaryEntry) -> DictionaryEntry {
+    DictionaryEntry {
+        value: nfc(&entry.value),
+        context: entry.context.as_deref().map(nfc),
+        plural_forms: nfc_values(entry.plural_forms),
+        variants: nfc_values(entry.variants),
+        comment: entry.comment.as_deref().map(nfc),
+        ..entry
+    }
+}
+
+#[doc = "Приводит файлы словарей репозитория к единому виду: Unicode в форме NFC, отступ в два пробела, перевод строки в конце файла и ключи дочерних словарей по алфавиту. С sort_base записи базового словаря тоже сортируются по тегам. С check файлы не изменяются. Возвращает имена файлов, которые были или были бы изменены"]
+pub fn format_repository(
+    dictionary_dir: &str,
+    sort_base: bool,
+    check: bool,
+) -> Result<Vec<String>, StaticDictionaryErrors> {
+    let mut formatted: Vec<(String, String)> = vec![];
+
+    let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
+    let format = DictionaryFormat::from_path(&basic_dictionary);
+    let content = fs::read_to_string(Path::new(dictionary_dir).join(&basic_dictionary))?;
+    let mut entries: Vec<BaseEntry> = format
+        .parse::<Vec<BaseEntry>>(&content)?
+        .into_iter()
+        .map(format_base_entry)
+        .collect();
+    if sort_base {
+        entries.sort_by(|left, right| left.tag().cmp(right.tag()));
+    }
+    formatted.push((basic_dictionary, format.serialize(&entries)?));
+
+    let mut translated_dictionaries = find_all_translated_dictionaries(dictionary_dir)?;
+    translated_dictionaries.sort();
+    for filename in translated_dictionaries {
+        let format = DictionaryFormat::from_path(&filename);
+        let content = fs::read_to_string(Path::new(dictionary_dir).join(&filename))?;
+        let dictionary: TranslatedDictionary = format
+            .parse::<TranslatedDictionary>(&content)?
+            .into_iter()
+            .map(|(tag, entry)| (nfc(&tag), format_translated_entry(entry)))
+            .collect();
+        formatted.push((filename, format.serialize(&dictionary)?));
+    }
+
+    let mut changed = vec![];
+    for (filename, content) in formatted {
+        let path = Path::new(dictionary_dir).join(&filename);
+        if fs::read_to_string(&path)? == content {
+            continue;
+        }
+        if !check {
+            atomic_write(&path, content)?;
+        }
+        changed.push(filename);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sorts_and_normalizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            "[\"Мои\u{306} профиль\", \"Вход\"]",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            "{\"Мои\u{306} профиль\": \"Profile\",\n    \"Вход\": \"Sign in\"}",
+        )
+        .unwrap();
+
+        let changed = format_repository(repo, false, true).unwrap();
+        assert_eq!(
+            changed,
+            vec!["dictionary-ru.base.json", "dictionary-en.json"]
+        );
+        assert!(fs::read_to_string(dir.path().join("dictionary-en.json"))
+            .unwrap()
+            .contains('\u{306}'));
+
+        format_repository(repo, true, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dictionary-ru.base.json")).unwrap(),
+            "[\n  \"Вход\",\n  \"Мой профиль\"\n]\n"
+        );
+        let translated = fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap();
+        assert!(translated.starts_with("{\n  \"Вход\": {"));
+        assert!(translated.contains("\"Мой профиль\""));
+        assert!(translated.ends_with("}\n"));
+        assert!(format_repository(repo, true, true).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::errors::errors::StaticDictionaryErrors;
+
+#[doc = "Глоссарий в корне репозитория словарей: термин исходного языка -> язык -> утвержденный перевод термина"]
+pub const GLOSSARY_FILE: &str = "glossary.json";
+
+#[derive(Debug, Clone)]
+#[doc = "Термин глоссария с утвержденными переводами"]
+pub struct GlossaryTerm {
+    pub term: String,
+    /// Язык -> утвержденный перевод
+    pub translations: BTreeMap<String, String>,
+    // Термин целым словом без учета регистра
+    pattern: Regex,
+}
+
+#[derive(Debug, Clone, Default)]
+#[doc = "Глоссарий терминов, которые должны переводиться одинаково во всех фразах"]
+pub struct Glossary {
+    pub terms: Vec<GlossaryTerm>,
+}
+
+// Граница слова проверяется только у букв и цифр, чтобы термины вроде "C++" или "Wi-Fi" тоже находились
+fn term_pattern(term: &str) -> Result<Regex, regex::Error> {
+    let start = match term.starts_with(char::is_alphanumeric) {
+        true => r"(?:^|[^\w])",
+        false => "",
+    };
+    let end = match term.ends_with(char::is_alphanumeric) {
+        true => r"(?:$|[^\w])",
+        false => "",
+    };
+    Regex::new(&format!("(?i){}{}{}", start, regex::escape(term), end))
+}
+
+impl Glossary {
+    #[doc = "Разбирает глоссарий из JSON вида {\"Корзина\": {\"en\": \"Cart\", \"de\": \"Warenkorb\"}}"]
+    pub fn parse(content: &str) -> Result<Glossary, StaticDictionaryErrors> {
+        let entries: BTreeMap<String, BTreeMap<String, String>> = serde_json::from_str(content)?;
+        let mut terms = vec![];
+        for (term, translations) in entries {
+            let term = term.trim().to_owned();
+            if term.is_empty() {
+                continue;
+            }
+            terms.push(GlossaryTerm {
+                pattern: term_pattern(&term)?,
+                term,
+                translations,
+            });
+        }
+        Ok(Glossary { terms })
+    }
+
+    #[doc = "Читает глоссарий из файла"]
+    pub fn load(path: impl AsRef<Path>) -> Result<Glossary, StaticDictionaryErrors> {
+        Glossary::parse(&fs::read_to_string(path)?)
+    }
+
+    #[doc = "Глоссарий из GLOSSARY_FILE репозитория словарей. Если файла нет, возвращается None"]
+    pub fn read(dictionary_dir: &str) -> Result<Option<Glossary>, StaticDictionaryErrors> {
+        let path = Path::new(dictionary_dir).join(GLOSSARY_FILE);
+        match path.exists() {
+            true => Ok(Some(Glossary::load(path)?)),
+            false => Ok(None),
+        }
+    }
+
+    #[doc = "Термины, которые встречаются в исходной фразе, но в переводе на язык нет их утвержденного перевода. Возвращает пары (термин, утвержденный перевод). Перевод ищется без учета регистра"]
+    pub fn violations(
+        &self,
+        source: &str,
+        translation: &str,
+        language: &str,
+    ) -> Vec<(String, String)> {
+        if translation.is_empty() {
+            return vec![];
+        }
+        let translation = translation.to_lowercase();
+        self.terms
+            .iter()
+            .filter(|term| term.pattern.is_match(source))
+            .filter_map(|term| {
+                let approved = term.translations.get(language)?;
+                (!translation.contains(&approved.to_lowercase()))
+                    .then(|| (term.term.clone(), approved.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glossary_terms_must_use_approved_translations() {
+        let glossary = Glossary::parse(
+            r#"{"Корзина": {"en": "Cart", "de": "Warenkorb"}, "Wi-Fi": {"en": "Wi-Fi"}}"#,
+        )
+        .unwrap();
+        assert!(glossary
+            .violations("Корзина пуста", "Your cart is empty", "en")
+            .is_empty());
+        assert_eq!(
+            glossary.violations("Корзина пуста", "Your basket is empty", "en"),
+            vec![("Корзина".to_owned(), "Cart".to_owned())]
+        );
+        assert!(glossary
+            .violations("Корзинах нет", "No baskets", "en")
+            .is_empty());
+        assert!(glossary.violations("Корзина", "Kosik", "cs").is_empty());
+        assert_eq!(
+            glossary.violations("Сеть Wi-Fi", "Wireless network", "en"),
+            vec![("Wi-Fi".to_owned(), "Wi-Fi".to_owned())]
+        );
+        assert!(glossary.violations("Корзина", "", "de").is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backup::utc_timestamp;
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_translated_dictionary,
+};
+use crate::types::Word;
+
+#[doc = "Журнал изменений значений внутри репозитория словарей: одна запись JSON на строку, записи только добавляются"]
+pub const HISTORY_FILE: &str = ".dms/history.jsonl";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[doc = "Изменение значения одного ключа на одном языке"]
+pub struct HistoryRecord {
+    /// Время изменения в UTC
+    pub time: String,
+    /// Имя пользователя из git config user.name или операционной системы
+    pub user: String,
+    /// Команда dms, которая изменила значение (scan, translate-auto, import...)
+    pub operation: String,
+    pub language: String,
+    pub tag: String,
+    /// Вариант и форма множественного числа, если изменилась не основная строка записи
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<String>,
+    /// Значение до изменения. Нет у добавленных ключей
+    pub before: Option<String>,
+    /// Значение после изменения. Нет у удаленных ключей
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Значения всех словарей репозитория: язык -> (тег и форма -> текст)"]
+pub struct RepositoryValues(BTreeMap<String, BTreeMap<(String, Option<String>), String>>);
+
+// Ключ значения: тег и суффикс формы и варианта
+fn value_key(word: &Word) -> (String, Option<String>) {
+    let suffixed = word.suffixed_tag();
+    let form = suffixed[word.tag.len()..].trim_start_matches('_');
+    (
+        word.tag.clone(),
+        (!form.is_empty()).then(|| form.to_owned()),
+    )
+}
+
+impl RepositoryValues {
+    #[doc = "Читает исходные фразы базового словаря и переводы всех дочерних словарей"]
+    pub fn read(dictionary_dir: &str) -> Result<RepositoryValues, StaticDictionaryErrors> {
+        let mut values = BTreeMap::new();
+        let basic_language = get_basic_language(dictionary_dir)?;
+        let base = parse_static_basic_entries(dictionary_dir)?
+            .iter()
+            .flat_map(|entry| entry.words_for_translation(&basic_language, &basic_language))
+            .map(|word| (value_key(&word), word.word))
+            .collect();
+        values.insert(basic_language, base);
+        for language in find_all_translated_languages(dictionary_dir)? {
+            let translated = read_translated_dictionary(dictionary_dir, &language)?
+                .iter()
+                .flat_map(|(tag, entry)| entry.words(tag, &language))
+                .map(|word| (value_key(&word), word.word))
+                .collect();
+            values.insert(language, translated);
+        }
+        Ok(RepositoryValues(values))
+    }
+
+    #[doc = "Записи журнала для значений, которые отличаются от other. time, user и operation не заполнены"]
+    pub fn changes_to(&self, other: &RepositoryValues) -> Vec<HistoryRecord> {
+        let empty = BTreeMap::new();
+        let mut languages: Vec<&String> = self.0.keys().chain(other.0.keys()).collect();
+        languages.sort();
+        languages.dedup();
+        let mut records = vec![];
+        for language in languages {
+            let before = self.0.get(language).unwrap_or(&empty);
+            let after = other.0.get(language).unwrap_or(&empty);
+            let mut keys: Vec<&(String, Option<String>)> =
+                before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let (old, new) = (before.get(key), after.get(key));
+                if old == new {
+                    continue;
+                }
+                records.push(HistoryRecord {
+                    time: String::new(),
+                    user: String::new(),
+                    operation: String::new(),
+                    language: language.to_owned(),
+                    tag: key.0.clone(),
+                    form: key.1.clone(),
+                    before: old.cloned(),
+                    after: new.cloned(),
+                });
+            }
+        }
+        records
+    }
+}
+
+#[doc = "Автор изменений: git config user.name в репозитории словарей, иначе пользователь операционной системы"]
+pub fn current_user(dictionary_dir: &str) -> String {
+    Command::new("git")
+        .args(["config", "user.name"])
+        .current_dir(dictionary_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .or_else(|| env::var("USER").ok())
+        .or_else(|| env::var("USERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_owned())
+}
+
+#[doc = "Сравнивает значения словарей с состоянием до операции и добавляет изменения в журнал. Возвращает записанные записи"]
+pub fn record_changes(
+    dictionary_dir: &str,
+    operation: &str,
+    before: &RepositoryValues,
+) -> Result<Vec<HistoryRecord>, StaticDictionaryErrors> {
+    let mut records = before.changes_to(&RepositoryValues::read(dictionary_dir)?);
+    if records.is_empty() {
+        return Ok(records);
+    }
+    let time = utc_timestamp(SystemTime::now());
+    let user = current_user(dictionary_dir);
+    let path = Path::new(dictionary_dir).join(HISTORY_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut lines = String::new();
+    for record in &mut records {
+        record.time = time.clone();
+        record.user = user.clone();
+        record.operation = operation.to_owned();
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+    tracing::debug!("Изменений для журнала истории: {}", records.len());
+    // Все записи операции дописываются одной записью в файл
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(lines.as_bytes())?;
+    Ok(records)
+}
+
+#[doc = "Читает журнал изменений от старых записей к новым. С tag возвращаются только записи этого ключа"]
+pub fn read_history(
+    dictionary_dir: &str,
+    tag: Option<&str>,
+) -> Result<Vec<HistoryRecord>, StaticDictionaryErrors> {
+    let path = Path::new(dictionary_dir).join(HISTORY_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut records = vec![];
+    for line in fs::read_to_string(path)?.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: HistoryRecord = serde_json::from_str(line)?;
+        if tag.is_none_or(|tag| record.tag == tag) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_records_changed_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выход"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "", "Выход": "Exit"}"#,
+        )
+        .unwrap();
+
+        let before = RepositoryValues::read(repo).unwrap();
+        assert!(record_changes(repo, "sync", &before).unwrap().is_empty());
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in", "Выход": "Log out"}"#,
+        )
+        .unwrap();
+        record_changes(repo, "translate-auto", &before).unwrap();
+
+        let before = RepositoryValues::read(repo).unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выход", "Корзина"]"#,
+        )
+        .unwrap();
+        record_changes(repo, "scan", &before).unwrap();
+
+        let history = read_history(repo, None).unwrap();
+        assert_eq!(history.len(), 3);
+        let sign_in = read_history(repo, Some("Вход")).unwrap();
+        assert_eq!(sign_in.len(), 1);
+        assert_eq!(sign_in[0].operation, "translate-auto");
+        assert_eq!(sign_in[0].language, "en");
+        assert_eq!(
+            (sign_in[0].before.as_deref(), sign_in[0].after.as_deref()),
+            (Some(""), Some("Sign in"))
+        );
+        let added = &history[2];
+        assert_eq!(
+            (added.language.as_str(), added.tag.as_str()),
+            ("ru", "Корзина")
+        );
+        assert_eq!(added.before, None);
+    }
+}
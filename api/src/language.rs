@@ -0,0 +1,249 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::errors::StaticDictionaryErrors;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[doc = "Проверенный тег локали BCP-47: язык ISO 639-1 или ISO 639-2 с необязательными письменностью, регионом и вариантами (en, eng, pt-BR, zh-Hans-CN). Хранится в канонической форме: язык в нижнем регистре, письменность с заглавной буквы, регион в верхнем регистре. Аргументы CLI разбираются сразу в LanguageCode, а языки из конфига, HTTP-запросов dms serve и пакетов проверяются на границе с файлами: путь дочернего словаря (translated_dictionary_path) и язык найденного файла (get_dictionary_language) строятся только из проверенного кода, поэтому словарь с неизвестным языком не создается и не читается ни одной командой"]
+pub struct LanguageCode(String);
+
+impl LanguageCode {
+    #[doc = "Проверяет и нормализует тег локали. Вместо дефиса допускается подчеркивание (pt_BR)"]
+    pub fn new(code: &str) -> Result<LanguageCode, StaticDictionaryErrors> {
+        let mut subtags = code.trim().split(['-', '_']);
+        let language = subtags.next().unwrap_or_default().to_lowercase();
+        if !is_known_language(&language) {
+            return Err(StaticDictionaryErrors::UnknownLanguage(code.to_owned()));
+        }
+        let mut canonical = language;
+        // Подтеги идут строго по порядку: письменность, регион, варианты
+        let mut position = 0;
+        for subtag in subtags {
+            let (normalized, next_position) = if position == 0 && is_script(subtag) {
+                let (first, rest) = subtag.split_at(1);
+                (first.to_uppercase() + &rest.to_lowercase(), 1)
+            } else if position <= 1 && is_region(subtag) {
+                (subtag.to_uppercase(), 2)
+            } else if is_variant(subtag) {
+                (subtag.to_lowercase(), 2)
+            } else {
+                return Err(StaticDictionaryErrors::InvalidLocale(code.to_owned()));
+            };
+            canonical.push('-');
+            canonical.push_str(&normalized);
+            position = next_position;
+        }
+        Ok(LanguageCode(canonical))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[doc = "Код языка без письменности и региона"]
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or(&self.0)
+    }
+
+    #[doc = "Письменность (Hans, Latn), если она указана"]
+    pub fn script(&self) -> Option<&str> {
+        self.0.split('-').skip(1).find(|subtag| is_script(subtag))
+    }
+
+    #[doc = "Регион, если он указан"]
+    pub fn region(&self) -> Option<&str> {
+        self.0.split('-').skip(1).find(|subtag| is_region(subtag))
+    }
+
+    #[doc = "Более общая локаль без последнего подтега: pt-BR -> pt, zh-Hans-CN -> zh-Hans"]
+    pub fn parent(&self) -> Option<LanguageCode> {
+        self.0
+            .rsplit_once('-')
+            .map(|(parent, _)| LanguageCode(parent.to_owned()))
+    }
+
+    #[doc = "Цепочка более общих локалей от ближайшей к языку без уточнений, не включая саму локаль"]
+    pub fn parents(&self) -> Vec<LanguageCode> {
+        std::iter::successors(self.parent(), LanguageCode::parent).collect()
+    }
+}
+
+#[doc = "Проверяет список кодов языков и возвращает их в канонической форме"]
+pub fn validate_languages(languages: &[String]) -> Result<Vec<String>, StaticDictionaryErrors> {
+    languages
+        .iter()
+        .map(|language| LanguageCode::new(language).map(String::from))
+        .collect()
+}
+
+fn is_known_language(language: &str) -> bool {
+    match language.len() {
+        2 => ISO_639_1.binary_search(&language).is_ok(),
+        3 => ISO_639_2.binary_search(&language).is_ok(),
+        _ => false,
+    }
+}
+
+// Письменность - четырехбуквенный код ISO 15924
+fn is_script(subtag: &str) -> bool {
+    subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+// Регион - двухбуквенный код ISO 3166-1 или трехзначный код UN M.49 (419 - Латинская Америка)
+fn is_region(subtag: &str) -> bool {
+    (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+        || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+}
+
+// Вариант - 5-8 букв и цифр или 4 символа, начинающихся с цифры (1996)
+fn is_variant(subtag: &str) -> bool {
+    subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        && ((5..=8).contains(&subtag.len())
+            || (subtag.len() == 4 && subtag.starts_with(|c: char| c.is_ascii_digit())))
+}
+
+impl FromStr for LanguageCode {
+    type Err = StaticDictionaryErrors;
+
+    fn from_str(code: &str) -> Result<LanguageCode, StaticDictionaryErrors> {
+        LanguageCode::new(code)
+    }
+}
+
+impl TryFrom<String> for LanguageCode {
+    type Error = StaticDictionaryErrors;
+
+    fn try_from(code: String) -> Result<LanguageCode, StaticDictionaryErrors> {
+        LanguageCode::new(&code)
+    }
+}
+
+impl From<LanguageCode> for String {
+    fn from(code: LanguageCode) -> String {
+        code.0
+    }
+}
+
+impl AsRef<str> for LanguageCode {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for LanguageCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[doc = "Двухбуквенные коды языков ISO 639-1"]
+pub const ISO_639_1: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+#[doc = "Трехбуквенные коды языков ISO 639-2 (библиографические и терминологические)"]
+pub const ISO_639_2: &[&str] = &[
+    "aar", "abk", "ace", "ady", "afr", "ain", "aka", "alb", "ale", "alt", "amh", "ang", "ara",
+    "arc", "arg", "arm", "arn", "asm", "ast", "ava", "ave", "awa", "aym", "aze", "bak", "bam",
+    "ban", "baq", "bej", "bel", "bem", "ben", "bho", "bih", "bik", "bin", "bis", "bla", "bod",
+    "bos", "bre", "bua", "bug", "bul", "bur", "byn", "cad", "car", "cat", "ceb", "ces", "cha",
+    "chb", "che", "chg", "chi", "chk", "chm", "chn", "cho", "chp", "chr", "chu", "chv", "chy",
+    "cop", "cor", "cos", "cre", "crh", "csb", "cym", "cze", "dak", "dan", "dar", "del", "den",
+    "deu", "dgr", "din", "div", "doi", "dsb", "dua", "dut", "dyu", "dzo", "efi", "egy", "eka",
+    "ell", "elx", "eng", "enm", "epo", "est", "eus", "ewe", "ewo", "fan", "fao", "fas", "fat",
+    "fij", "fil", "fin", "fon", "fra", "fre", "fro", "fry", "ful", "fur", "gaa", "gay", "gba",
+    "geo", "ger", "gez", "gil", "gla", "gle", "glg", "glv", "gmh", "goh", "gon", "gor", "got",
+    "grb", "grc", "gre", "grn", "gsw", "guj", "gwi", "hai", "hat", "hau", "haw", "heb", "her",
+    "hil", "hin", "hit", "hmn", "hmo", "hrv", "hsb", "hun", "hup", "hye", "iba", "ibo", "ice",
+    "ido", "iii", "iku", "ile", "ilo", "ina", "ind", "inh", "ipk", "isl", "ita", "jav", "jbo",
+    "jpn", "jpr", "jrb", "kaa", "kab", "kac", "kal", "kam", "kan", "kas", "kat", "kau", "kaw",
+    "kaz", "kbd", "kha", "khm", "kho", "kik", "kin", "kir", "kmb", "kok", "kom", "kon", "kor",
+    "kos", "kpe", "krc", "krl", "kru", "kua", "kum", "kur", "kut", "lad", "lah", "lam", "lao",
+    "lat", "lav", "lez", "lim", "lin", "lit", "lol", "loz", "ltz", "lua", "lub", "lug", "lui",
+    "lun", "luo", "lus", "mac", "mad", "mag", "mah", "mai", "mak", "mal", "man", "mao", "mar",
+    "mas", "may", "mdf", "mdr", "men", "mga", "mic", "min", "mis", "mkd", "mlg", "mlt", "mnc",
+    "mni", "moh", "mon", "mos", "mri", "msa", "mul", "mus", "mwl", "mwr", "mya", "myv", "nap",
+    "nau", "nav", "nbl", "nde", "ndo", "nds", "nep", "new", "nia", "niu", "nld", "nno", "nob",
+    "nog", "non", "nor", "nqo", "nso", "nub", "nwc", "nya", "nym", "nyn", "nyo", "nzi", "oci",
+    "oji", "ori", "orm", "osa", "oss", "ota", "pag", "pal", "pam", "pan", "pap", "pau", "peo",
+    "per", "phn", "pli", "pol", "pon", "por", "pro", "pus", "que", "raj", "rap", "rar", "roh",
+    "rom", "ron", "rum", "run", "rup", "rus", "sad", "sag", "sah", "sam", "san", "sas", "sat",
+    "scn", "sco", "sel", "sga", "shn", "sid", "sin", "slk", "slo", "slv", "sma", "sme", "smj",
+    "smn", "smo", "sms", "sna", "snd", "snk", "sog", "som", "sot", "spa", "sqi", "srd", "srn",
+    "srp", "srr", "ssw", "suk", "sun", "sus", "sux", "swa", "swe", "syc", "syr", "tah", "tam",
+    "tat", "tel", "tem", "ter", "tet", "tgk", "tgl", "tha", "tib", "tig", "tir", "tiv", "tkl",
+    "tlh", "tli", "tmh", "tog", "ton", "tpi", "tsi", "tsn", "tso", "tuk", "tum", "tur", "tvl",
+    "twi", "tyv", "udm", "uga", "uig", "ukr", "umb", "und", "urd", "uzb", "vai", "ven", "vie",
+    "vol", "vot", "wal", "war", "was", "wel", "wln", "wol", "xal", "xho", "yao", "yap", "yid",
+    "yor", "yue", "zap", "zbl", "zen", "zgh", "zha", "zho", "zul", "zun", "zxx", "zza",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_codes_are_validated_and_canonicalized() {
+        assert_eq!(LanguageCode::new("en").unwrap().as_str(), "en");
+        assert_eq!(LanguageCode::new("eng").unwrap().as_str(), "eng");
+        let brazilian = LanguageCode::new("PT_br").unwrap();
+        assert_eq!(brazilian.as_str(), "pt-BR");
+        assert_eq!(brazilian.language(), "pt");
+        assert_eq!(brazilian.region(), Some("BR"));
+        assert_eq!(LanguageCode::new("es-419").unwrap().as_str(), "es-419");
+    }
+
+    #[test]
+    fn test_bcp47_tags_and_parents() {
+        let chinese = LanguageCode::new("zh-hans-cn").unwrap();
+        assert_eq!(chinese.as_str(), "zh-Hans-CN");
+        assert_eq!(chinese.script(), Some("Hans"));
+        assert_eq!(chinese.region(), Some("CN"));
+        let parents: Vec<String> = chinese.parents().into_iter().map(String::from).collect();
+        assert_eq!(parents, vec!["zh-Hans", "zh"]);
+        assert_eq!(
+            LanguageCode::new("sl-rozaj-1994").unwrap().as_str(),
+            "sl-rozaj-1994"
+        );
+        assert_eq!(LanguageCode::new("de").unwrap().parent(), None);
+    }
+
+    #[test]
+    fn test_unknown_languages_are_rejected() {
+        let error = LanguageCode::new("englsh").unwrap_err();
+        assert_eq!(error.to_string(), "Неизвестный язык 'englsh'");
+        assert!(matches!(
+            LanguageCode::new("xx"),
+            Err(StaticDictionaryErrors::UnknownLanguage(_))
+        ));
+        assert!(matches!(
+            LanguageCode::new("en-USA"),
+            Err(StaticDictionaryErrors::InvalidLocale(_))
+        ));
+        assert!(matches!(
+            LanguageCode::new("pt-BR-Latn"),
+            Err(StaticDictionaryErrors::InvalidLocale(_))
+        ));
+        assert!(validate_languages(&["de".to_owned(), "englsh".to_owned()]).is_err());
+    }
+
+    #[test]
+    fn test_code_tables_are_sorted() {
+        assert!(ISO_639_1.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ISO_639_2.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -2,13 +2,78 @@
 #![allow(async_fn_in_trait)]
 
 pub mod errors;
+#[doc = "Отчеты об изменениях в репозитории словарей (например, для описания pull request)"]
+pub mod report;
+#[doc = "Проверки качества переводов"]
+pub mod qa;
+#[doc = "Проверка целостности репозитория словарей: недостающие, лишние, пустые и повторяющиеся ключи"]
+pub mod validation;
+#[doc = "Защищенные сегменты фраз, которые не отправляются на машинный перевод"]
+pub mod notranslate;
+#[doc = "Нормализация Unicode и пробелов во фразах"]
+pub mod normalization;
+#[doc = "Коды языков ISO 639 и теги локалей BCP-47"]
+pub mod language;
+#[doc = "Проверка машинных переводов человеком"]
+pub mod review;
+#[doc = "Форматы файлов словарей и конфига: JSON и YAML"]
+pub mod dictionary_format;
+#[doc = "Экспорт и импорт переводов в таблицы для работы в Excel и Google Sheets"]
+pub mod spreadsheet;
+#[doc = "Кеш хешей файлов для повторного сканирования только измененных файлов"]
+pub mod scan_cache;
+#[doc = "Чтение исходных файлов проекта: пропуск двоичных файлов и перекодирование в UTF-8"]
+pub mod source_encoding;
+#[doc = "Места в исходном коде, где найдены фразы базового словаря"]
+pub mod source_locations;
+#[doc = "Поиск текста интерфейса в разметке HTML, XML, Vue и Svelte"]
+pub mod markup_extraction;
+#[doc = "Поиск записей по тегам, исходным фразам и переводам"]
+pub mod search;
+#[doc = "Слияние двух репозиториев словарей"]
+pub mod merge;
+#[doc = "Единое оформление файлов словарей: сортировка ключей, отступы и нормализация Unicode"]
+pub mod formatting;
+#[doc = "Статистика объема текста по языкам: слова, символы, переменные подстановки"]
+pub mod statistics;
+#[doc = "Резервные копии репозитория словарей перед операциями, которые перезаписывают переводы"]
+pub mod backup;
+#[doc = "Блокировка репозитория словарей от одновременного изменения несколькими процессами"]
+pub mod repository_lock;
+#[doc = "Пакеты переводов для внешних переводчиков: выгрузка непереведенных записей и загрузка результата"]
+pub mod package;
+#[doc = "Проверка орфографии переводов по словарям hunspell"]
+pub mod spelling;
+#[doc = "Глоссарий терминов с утвержденными переводами"]
+pub mod glossary;
+#[doc = "Поиск разных ключей с одинаковыми исходными фразами или переводами"]
+pub mod duplicates;
+#[doc = "Журнал изменений значений ключей командами dms"]
+pub mod history;
+#[doc = "Релизы словарей: неизменяемые снимки репозитория для воспроизводимой сборки"]
+pub mod release;
+#[doc = "События о ходе долгих операций для индикаторов прогресса"]
+pub mod progress;
+#[doc = "Пробный запуск команд над копией репозитория без изменения файлов"]
+pub mod dry_run;
+#[doc = "Аннотации GitHub Actions для проблем в словарях"]
+pub mod annotations;
+#[doc = "Диагностика окружения и конфига для dms doctor"]
+pub mod doctor;
+#[doc = "Чтение и изменение отдельных записей для локального сервера и интеграций с редакторами"]
+pub mod entries;
+#[cfg(feature = "tree-sitter")]
+#[doc = "Поиск фраз в JS/TS/JSX/TSX по синтаксическому дереву tree-sitter"]
+pub mod tree_sitter_extraction;
 
 #[doc = "Типы данных, которые используются во всех частях API"]
 pub mod types {
     use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
     use std::fmt::Display;
 
     use crate::errors::errors::StaticDictionaryErrors;
+    pub use crate::language::LanguageCode;
 
     #[doc = "Треит, который должны реализовывать все структуры, используемые для обращения к API переводчиков"]
     pub trait TranslatorApi {
@@ -19,12 +84,439 @@ pub mod types {
         ) -> Result<Word, StaticDictionaryErrors>;
     }
 
-    #[derive(Serialize, Deserialize, Default, Clone, Debug)]
+    #[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
     #[doc = "Промежуточная модель между JSON-словарями и API"]
     pub struct Word {
         pub word: String,
         pub tag: String,
         pub language: String,
+        /// Форма множественного числа (one, few, many, other...), если слово является одной из форм
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub plural_form: Option<String>,
+        /// Контекст фразы для переводчика (например, "кнопка на странице оплаты")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub context: Option<String>,
+        /// Вариант фразы по грамматическому роду (male, female...), если слово является одним из вариантов
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub variant: Option<String>,
+        /// Комментарий для переводчиков из словаря
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub comment: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+    #[serde(remote = "Self")]
+    #[doc = "Запись базового словаря. В файле хранится либо строкой, либо объектом с дополнительными полями"]
+    pub struct BaseEntry {
+        /// Фраза на языке базового словаря
+        pub phrase: String,
+        /// Тег записи. Если не указан, тегом считается сама фраза
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub key: Option<String>,
+        /// Формы множественного числа на языке базового словаря
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub plural_forms: Option<BTreeMap<String, String>>,
+        /// Контекст фразы, который передается переводчикам и в комментарии итоговых файлов
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub context: Option<String>,
+        /// Варианты фразы в зависимости от грамматического рода (male, female...), в i18next становятся контекстами
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub variants: Option<BTreeMap<String, String>>,
+        /// Комментарий для переводчиков. Переносится в дочерние словари и в форматы, которые поддерживают комментарии
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub comment: Option<String>,
+        /// Имена переменных подстановки во фразе, найденные при сканировании. Проверки качества требуют их в каждом переводе
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub placeholders: Option<Vec<String>>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    #[doc = "Представление записи базового словаря в файле"]
+    enum BaseEntryRepr {
+        Phrase(String),
+        Entry(#[serde(with = "BaseEntry")] BaseEntry),
+    }
+
+    impl<'de> Deserialize<'de> for BaseEntry {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(match BaseEntryRepr::deserialize(deserializer)? {
+                BaseEntryRepr::Phrase(phrase) => BaseEntry::new(phrase),
+                BaseEntryRepr::Entry(entry) => entry,
+            })
+        }
+    }
+
+    impl Serialize for BaseEntry {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if self.is_plain() {
+                serializer.serialize_str(&self.phrase)
+            } else {
+                BaseEntry::serialize(self, serializer)
+            }
+        }
+    }
+
+    impl BaseEntry {
+        pub fn new(phrase: String) -> BaseEntry {
+            BaseEntry {
+                phrase,
+                ..Default::default()
+            }
+        }
+
+        #[doc = "Создает запись с тегом, выбранным по стратегии генерации ключей"]
+        pub fn with_key_strategy(phrase: String, strategy: KeyStrategy) -> BaseEntry {
+            BaseEntry {
+                key: strategy.key_for(&phrase),
+                ..BaseEntry::new(phrase)
+            }
+        }
+
+        #[inline]
+        #[doc = "Тег записи, под которым она хранится в дочерних словарях и итоговых файлах"]
+        pub fn tag(&self) -> &str {
+            self.key.as_deref().unwrap_or(&self.phrase)
+        }
+
+        #[inline]
+        #[doc = "Запись без дополнительных полей, которая хранится в словаре обычной строкой"]
+        pub fn is_plain(&self) -> bool {
+            *self == BaseEntry::new(self.phrase.clone())
+        }
+
+        #[doc = "Возвращает тексты, которые нужно перевести для каждой формы множественного числа целевого языка. Для форм, отсутствующих в базовом словаре, используется форма other"]
+        pub fn plural_sources(&self, target_language: &str) -> Option<Vec<(String, String)>> {
+            let forms = self.plural_forms.as_ref()?;
+            Some(
+                plural_categories(target_language)
+                    .iter()
+                    .map(|category| {
+                        let source = forms
+                            .get(*category)
+                            .or_else(|| forms.get("other"))
+                            .unwrap_or(&self.phrase);
+                        (category.to_string(), source.to_owned())
+                    })
+                    .collect(),
+            )
+        }
+
+        #[doc = "Хеш исходного текста записи вместе с формами и вариантами. Меняется при любом изменении исходной фразы"]
+        pub fn source_hash(&self) -> String {
+            let mut source = self.phrase.clone();
+            for (name, text) in self.plural_forms.iter().chain(self.variants.iter()).flatten() {
+                source.push('\n');
+                source.push_str(name);
+                source.push('=');
+                source.push_str(text);
+            }
+            short_hash(&source)
+        }
+
+        #[doc = "Разворачивает запись в слова для перевода: по одному на каждую форму множественного числа и вариант, либо одно слово для обычной фразы"]
+        pub fn words_for_translation(
+            &self,
+            source_language: &str,
+            target_language: &str,
+        ) -> Vec<Word> {
+            let word = |text: &str| {
+                Word::new(
+                    text.to_owned(),
+                    self.tag().to_owned(),
+                    source_language.to_owned(),
+                )
+                .with_context(self.context.clone())
+                .with_comment(self.comment.clone())
+            };
+            let mut words: Vec<Word> = vec![];
+            if let Some(sources) = self.plural_sources(target_language) {
+                words.extend(
+                    sources
+                        .into_iter()
+                        .map(|(form, source)| word(&source).with_plural_form(Some(form))),
+                );
+            }
+            if let Some(variants) = &self.variants {
+                words.extend(
+                    variants
+                        .iter()
+                        .map(|(variant, source)| word(source).with_variant(Some(variant.to_owned()))),
+                );
+            }
+            if words.is_empty() {
+                words.push(word(&self.phrase));
+            }
+            words
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    #[doc = "Стратегия генерации тегов для новых фраз базового словаря"]
+    pub enum KeyStrategy {
+        /// Тегом является сама фраза
+        #[default]
+        Phrase,
+        /// Транслитерированная фраза в виде slug (dobro-pozhalovat)
+        Slug,
+        /// Короткий хеш SHA-256 от фразы
+        Hash,
+        /// Тег задается вручную аннотацией dms-key или в вызове t("тег", "фраза"), фраза без тега считается ошибкой
+        Manual,
+    }
+
+    #[doc = "Максимальная длина тега, сгенерированного из фразы"]
+    pub const MAX_GENERATED_KEY_LENGTH: usize = 48;
+
+    impl KeyStrategy {
+        #[doc = "Генерирует тег для фразы. Для стратегии Phrase возвращает None, так как тегом служит сама фраза, а для Manual - None, так как тег задается только вручную"]
+        pub fn key_for(&self, phrase: &str) -> Option<String> {
+            match self {
+                KeyStrategy::Phrase | KeyStrategy::Manual => None,
+                KeyStrategy::Slug => {
+                    let mut key = slug::slugify(phrase);
+                    if key.len() > MAX_GENERATED_KEY_LENGTH {
+                        key.truncate(MAX_GENERATED_KEY_LENGTH);
+                        key = key.trim_end_matches('-').to_owned();
+                    }
+                    Some(key)
+                }
+                KeyStrategy::Hash => Some(short_hash(phrase)),
+            }
+        }
+    }
+
+    #[doc = "Короткий (12 символов) шестнадцатеричный хеш SHA-256 от текста"]
+    pub fn short_hash(text: &str) -> String {
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(text.as_bytes());
+        digest[..6].iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    #[serde(rename_all = "lowercase")]
+    #[doc = "Статус перевода записи в дочернем словаре"]
+    pub enum EntryStatus {
+        /// Перевода еще нет
+        #[default]
+        Untranslated,
+        /// Перевод выполнен автопереводчиком и не проверен человеком
+        Machine,
+        /// Перевод выполнен или отредактирован вручную
+        Translated,
+        /// Машинный перевод проверен и одобрен
+        Reviewed,
+    }
+
+    #[doc = "Дочерний словарь: тег -> запись"]
+    pub type TranslatedDictionary = BTreeMap<String, DictionaryEntry>;
+
+    #[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+    #[serde(remote = "Self")]
+    #[doc = "Запись дочернего словаря в формате v2. Записи формата v1 (тег -> строка) читаются как значение без метаданных"]
+    pub struct DictionaryEntry {
+        /// Перевод фразы
+        #[serde(default)]
+        pub value: String,
+        /// Статус перевода
+        #[serde(default)]
+        pub status: EntryStatus,
+        /// Контекст фразы из базового словаря
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub context: Option<String>,
+        /// Переводы форм множественного числа
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub plural_forms: Option<BTreeMap<String, String>>,
+        /// Переводы вариантов фразы по грамматическому роду
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub variants: Option<BTreeMap<String, String>>,
+        /// Хеш исходной фразы, с которой был сделан перевод. Позволяет находить устаревшие переводы
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub source_hash: Option<String>,
+        /// Комментарий для переводчиков: из базового словаря или добавленный в дочерний словарь
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub comment: Option<String>,
+        /// Заблокированный перевод не меняется автопереводом, генерацией и массовыми операциями
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pub locked: bool,
+        /// Пакет переводов, из которого загружен перевод
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub package: Option<String>,
+        /// Машинный перевод, который ждет утверждения и не попадает в сборку
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub suggestion: Option<Box<Suggestion>>,
+    }
+
+    #[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+    #[doc = "Предложенный автопереводчиком перевод записи. Становится значением только после проверки человеком"]
+    pub struct Suggestion {
+        #[serde(default)]
+        pub value: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub plural_forms: Option<BTreeMap<String, String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub variants: Option<BTreeMap<String, String>>,
+    }
+
+    impl From<DictionaryEntry> for Suggestion {
+        fn from(entry: DictionaryEntry) -> Suggestion {
+            Suggestion {
+                value: entry.value,
+                plural_forms: entry.plural_forms,
+                variants: entry.variants,
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    #[doc = "Представление записи дочернего словаря в файле: строка (v1) или объект (v2)"]
+    enum DictionaryEntryRepr {
+        V1(String),
+        V2(#[serde(with = "DictionaryEntry")] DictionaryEntry),
+    }
+
+    impl<'de> Deserialize<'de> for DictionaryEntry {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(match DictionaryEntryRepr::deserialize(deserializer)? {
+                DictionaryEntryRepr::V1(value) => DictionaryEntry::from_value(value),
+                DictionaryEntryRepr::V2(entry) => entry,
+            })
+        }
+    }
+
+    impl Serialize for DictionaryEntry {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            DictionaryEntry::serialize(self, serializer)
+        }
+    }
+
+    impl DictionaryEntry {
+        #[doc = "Создает запись из значения формата v1"]
+        pub fn from_value(value: String) -> DictionaryEntry {
+            DictionaryEntry {
+                status: if value.is_empty() {
+                    EntryStatus::Untranslated
+                } else {
+                    EntryStatus::Translated
+                },
+                value,
+                ..Default::default()
+            }
+        }
+
+        #[doc = "Создает пустую запись для перевода записи базового словаря на язык"]
+        pub fn from_base(entry: &BaseEntry, source_language: &str, target_language: &str) -> DictionaryEntry {
+            let mut result = DictionaryEntry {
+                context: entry.context.clone(),
+                comment: entry.comment.clone(),
+                source_hash: Some(entry.source_hash()),
+                ..Default::default()
+            };
+            for word in entry.words_for_translation(source_language, target_language) {
+                result.set_word(&Word { word: "".to_owned(), ..word });
+            }
+            result
+        }
+
+        #[doc = "Записывает переведенное слово в значение, форму множественного числа или вариант записи"]
+        pub fn set_word(&mut self, word: &Word) {
+            match (&word.plural_form, &word.variant) {
+                (Some(form), _) => {
+                    self.plural_forms
+                        .get_or_insert_with(BTreeMap::new)
+                        .insert(form.to_owned(), word.word.clone());
+                }
+                (None, Some(variant)) => {
+                    self.variants
+                        .get_or_insert_with(BTreeMap::new)
+                        .insert(variant.to_owned(), word.word.clone());
+                }
+                (None, None) => self.value = word.word.clone(),
+            }
+        }
+
+        #[doc = "Разворачивает запись в слова: по одному на каждую форму множественного числа и вариант, либо одно слово со значением"]
+        pub fn words(&self, tag: &str, language: &str) -> Vec<Word> {
+            let word = |text: &str| {
+                Word::new(text.to_owned(), tag.to_owned(), language.to_owned())
+                    .with_context(self.context.clone())
+                    .with_comment(self.comment.clone())
+            };
+            let mut words: Vec<Word> = vec![];
+            for (form, text) in self.plural_forms.iter().flatten() {
+                words.push(word(text).with_plural_form(Some(form.to_owned())));
+            }
+            for (variant, text) in self.variants.iter().flatten() {
+                words.push(word(text).with_variant(Some(variant.to_owned())));
+            }
+            if words.is_empty() {
+                words.push(word(&self.value));
+            }
+            words
+        }
+
+        #[doc = "Разворачивает предложенный перевод записи в слова так же, как words"]
+        pub fn suggested_words(&self, tag: &str, language: &str) -> Vec<Word> {
+            let Some(suggestion) = &self.suggestion else {
+                return vec![];
+            };
+            DictionaryEntry {
+                value: suggestion.value.clone(),
+                plural_forms: suggestion.plural_forms.clone(),
+                variants: suggestion.variants.clone(),
+                context: self.context.clone(),
+                comment: self.comment.clone(),
+                ..Default::default()
+            }
+            .words(tag, language)
+        }
+
+        #[doc = "Переносит предложенный перевод в значение и формы записи. Возвращает false, если предложения нет"]
+        pub fn promote_suggestion(&mut self) -> bool {
+            let Some(suggestion) = self.suggestion.take().map(|suggestion| *suggestion) else {
+                return false;
+            };
+            self.value = suggestion.value;
+            for (form, text) in suggestion.plural_forms.into_iter().flatten() {
+                self.plural_forms.get_or_insert_with(BTreeMap::new).insert(form, text);
+            }
+            for (variant, text) in suggestion.variants.into_iter().flatten() {
+                self.variants.get_or_insert_with(BTreeMap::new).insert(variant, text);
+            }
+            true
+        }
+
+        #[doc = "Переведены ли значение и все формы записи"]
+        pub fn is_translated(&self) -> bool {
+            self.words("", "").iter().all(|word| !word.word.is_empty())
+        }
+
+        #[doc = "Перевод сделан с исходной фразы, которая с тех пор изменилась"]
+        pub fn is_outdated(&self, base: &BaseEntry) -> bool {
+            self.is_translated()
+                && self
+                    .source_hash
+                    .as_ref()
+                    .is_some_and(|hash| hash != &base.source_hash())
+        }
+    }
+
+    #[doc = "Возвращает категории множественного числа CLDR для языка (используются i18next в суффиксах ключей)"]
+    pub fn plural_categories(language: &str) -> &'static [&'static str] {
+        let language = language.split(['-', '_']).next().unwrap_or(language);
+        match language {
+            "ru" | "uk" | "be" | "pl" | "lt" | "cs" | "sk" => &["one", "few", "many", "other"],
+            "ar" | "cy" => &["zero", "one", "two", "few", "many", "other"],
+            "ga" => &["one", "two", "few", "many", "other"],
+            "sl" => &["one", "two", "few", "other"],
+            "he" => &["one", "two", "other"],
+            "ro" | "bs" | "hr" | "sr" => &["one", "few", "other"],
+            "lv" => &["zero", "one", "other"],
+            "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" => &["other"],
+            _ => &["one", "other"],
+        }
     }
 
     #[doc = "Варианты API переводчиков для передачи в функции автоматических переводчиков"]
@@ -41,11 +533,23 @@ pub mod types {
     pub struct ApiArgs {
         pub api_key: Option<String>,
         pub host: String,
+        /// Записывать машинные переводы в поле suggestion, не меняя значения записей
+        pub suggestions: bool,
     }
 
     impl ApiArgs {
         pub fn new(api_key: Option<String>, host: String) -> ApiArgs {
-            ApiArgs { api_key, host }
+            ApiArgs {
+                api_key,
+                host,
+                suggestions: false,
+            }
+        }
+
+        #[doc = "Включает режим предложений: автоперевод записывается в suggestion и ждет утверждения в dms review"]
+        pub fn with_suggestions(mut self, suggestions: bool) -> ApiArgs {
+            self.suggestions = suggestions;
+            self
         }
     }
 
@@ -55,7 +559,45 @@ pub mod types {
                 word,
                 tag,
                 language: lang,
+                plural_form: None,
+                context: None,
+                variant: None,
+                comment: None,
+            }
+        }
+
+        #[doc = "Добавляет к слову комментарий для переводчиков"]
+        pub fn with_comment(mut self, comment: Option<String>) -> Word {
+            self.comment = comment;
+            self
+        }
+
+        #[doc = "Помечает слово как один из вариантов фразы по грамматическому роду"]
+        pub fn with_variant(mut self, variant: Option<String>) -> Word {
+            self.variant = variant;
+            self
+        }
+
+        #[doc = "Добавляет к слову контекст для переводчика"]
+        pub fn with_context(mut self, context: Option<String>) -> Word {
+            self.context = context;
+            self
+        }
+
+        #[doc = "Помечает слово как одну из форм множественного числа"]
+        pub fn with_plural_form(mut self, plural_form: Option<String>) -> Word {
+            self.plural_form = plural_form;
+            self
+        }
+
+        #[doc = "Возвращает ключ для итогового словаря с суффиксами варианта и формы множественного числа (key_male, key_one)"]
+        pub fn suffixed_tag(&self) -> String {
+            let mut tag = self.tag.clone();
+            for suffix in [&self.variant, &self.plural_form].into_iter().flatten() {
+                tag.push('_');
+                tag.push_str(suffix);
             }
+            tag
         }
         #[inline]
         #[doc = "Сериализует модель в JSON"]
@@ -84,9 +626,11 @@ pub mod types {
 pub mod web_api {
     use std::collections::HashMap;
 
+    use crate::notranslate::protect;
     use crate::errors::errors::StaticDictionaryErrors;
     use crate::types::TranslatorApi;
     use crate::types::Word;
+    use crate::types::{ApiArgs, TranslatorApis};
 
     use serde::Deserialize;
     use serde::Serialize;
@@ -130,6 +674,16 @@ pub mod web_api {
         }
     }
 
+    #[doc = "Достает из ответа LibreTranslate переведенный текст без JSON-экранирования. Уверенность LibreTranslate возвращает только для определения языка (detectedLanguage), а не для перевода, поэтому она не используется"]
+    pub fn parse_libre_translate_response(response: &str) -> Result<String, StaticDictionaryErrors> {
+        let translated_word: HashMap<String, Value> = serde_json::from_str(response)?;
+        Ok(translated_word
+            .get("translatedText")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned())
+    }
+
     impl TranslatorApi for LibreTranslateApi {
         async fn translate_word_with_tag(
             &self,
@@ -150,12 +704,130 @@ pub mod web_api {
                 .await?
                 .text()
                 .await?;
-            let translated_word: HashMap<String, Value> = serde_json::from_str(&result)?;
-            Ok(Word::new(
-                translated_word["translatedText"].to_string(),
-                word.tag,
-                target_language,
-            ))
+            let translated_text = parse_libre_translate_response(&result)?;
+            Ok(Word::new(translated_text, word.tag, target_language)
+            .with_plural_form(word.plural_form)
+            .with_variant(word.variant)
+            .with_context(word.context))
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    #[doc = "Структура для работы с API DeepL"]
+    pub struct DeepLApi {
+        pub host: String,
+        pub api_key: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[doc = "Модель запроса к DeepL"]
+    struct DeepLJsonRequest {
+        pub text: Vec<String>,
+        pub source_lang: String,
+        pub target_lang: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub context: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[doc = "Модель ответа DeepL"]
+    struct DeepLJsonResponse {
+        pub translations: Vec<DeepLTranslation>,
+    }
+
+    #[derive(Deserialize)]
+    struct DeepLTranslation {
+        pub text: String,
+    }
+
+    impl DeepLApi {
+        pub fn new(host: String, api_key: String) -> DeepLApi {
+            DeepLApi { host, api_key }
+        }
+    }
+
+    impl TranslatorApi for DeepLApi {
+        async fn translate_word_with_tag(
+            &self,
+            word: Word,
+            target_language: String,
+        ) -> Result<Word, StaticDictionaryErrors> {
+            let client = reqwest::Client::new();
+            let json_data = DeepLJsonRequest {
+                text: vec![word.word],
+                source_lang: word.language.to_uppercase(),
+                target_lang: target_language.to_uppercase(),
+                context: word.context.clone(),
+            };
+            let response = client
+                .post(format!("{}/v2/translate", self.host))
+                .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+                .json(&json_data)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<DeepLJsonResponse>()
+                .await?;
+            let translated = response
+                .translations
+                .into_iter()
+                .next()
+                .map(|translation| translation.text)
+                .unwrap_or_default();
+            Ok(Word::new(translated, word.tag, target_language)
+                .with_plural_form(word.plural_form)
+                .with_variant(word.variant)
+                .with_context(word.context))
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    #[doc = "Выбранный API переводчика. Позволяет запускать перевод в tokio::spawn без обобщенных типов"]
+    pub enum TranslatorBackend {
+        LibreTranslate(LibreTranslateApi),
+        DeepL(DeepLApi),
+    }
+
+    impl TranslatorBackend {
+        #[doc = "Клиент выбранного API переводчика с хостингом и ключом из аргументов"]
+        pub fn new(translator_api: TranslatorApis, api_args: ApiArgs) -> TranslatorBackend {
+            match translator_api {
+                TranslatorApis::LibreTranslate => {
+                    TranslatorBackend::LibreTranslate(LibreTranslateApi::new(api_args.host))
+                }
+                TranslatorApis::DeepL => TranslatorBackend::DeepL(DeepLApi::new(
+                    api_args.host,
+                    api_args.api_key.unwrap_or_default(),
+                )),
+                TranslatorApis::Yandex => todo!(),
+            }
+        }
+    }
+
+    impl TranslatorApi for TranslatorBackend {
+        async fn translate_word_with_tag(
+            &self,
+            word: Word,
+            target_language: String,
+        ) -> Result<Word, StaticDictionaryErrors> {
+            // Защищенные сегменты заменяются метками до отправки переводчику и возвращаются после
+            let protected = protect(&word.word);
+            let word = Word {
+                word: protected.text.clone(),
+                ..word
+            };
+            let translated = match self {
+                TranslatorBackend::LibreTranslate(api) => {
+                    api.translate_word_with_tag(word, target_language).await
+                }
+                TranslatorBackend::DeepL(api) => {
+                    api.translate_word_with_tag(word, target_language).await
+                }
+            }?;
+            Ok(Word {
+                word: protected.restore(&translated.word),
+                ..translated
+            })
         }
     }
 }
@@ -164,26 +836,48 @@ pub mod web_api {
 //TODO: Вынести функции, используемые только в preprocess в отдельный модуль
 pub mod parser {
     use std::{
-        collections::HashMap,
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
         env, fs,
-        io::{self, BufRead},
-        sync::{Arc, Mutex},
+        io,
+        path::{Path, PathBuf},
+        sync::{mpsc, OnceLock},
+        time::Duration,
     };
 
+    use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+    use ignore::WalkBuilder;
+    use notify::{RecursiveMode, Watcher};
     use regex::Regex;
 
     use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
     use serde::de::Error;
+    use serde::Serialize;
     use types::ConfigFileParameters;
 
     use crate::{
-        errors::errors::StaticDictionaryErrors, file_system::{get_file_extension, parse_config},
-        static_translate::update_basic_dictionary, types::Word,
+        dictionary_format::{
+            parse_relaxed_json, read_dictionary_file, write_dictionary_file, DictionaryFormat,
+            DICTIONARY_EXTENSIONS,
+        },
+        errors::errors::StaticDictionaryErrors, file_system::{atomic_write, get_file_extension, parse_config},
+        language::LanguageCode,
+        normalization::{normalize_new_entries, normalize_phrase},
+        parser::types::{ExtractorKind, LanguageConfiguration},
+        progress::{NoProgress, Progress, SCAN_STAGE},
+        qa::placeholder_names,
+        scan_cache::{content_hash, settings_hash, ScanCache},
+        source_encoding::{decode_source, read_source_file, SourceEncoding},
+        source_locations::{
+            location_path, read_source_locations, replace_file_locations, retain_files,
+            write_source_locations, SourceLocations,
+        },
+        static_translate::{matching_entry, merge_basic_entries, parse_static_basic_entries},
+        types::{BaseEntry, KeyStrategy, Word},
     };
 
     #[doc = "Считывает JSON из словаря"]
     pub fn read_json_dictionary(file_name: &str) -> Result<serde_json::Value, serde_json::Error> {
-        serde_json::from_str(&fs::read_to_string(file_name).unwrap())
+        parse_relaxed_json(&fs::read_to_string(file_name).unwrap())
     }
 
     #[doc = "Парсит список тегов из JSON словаря"]
@@ -208,7 +902,14 @@ pub mod parser {
         for file in dictionary_list_dir {
             if let Ok(entry) = file {
                 let filename = entry.file_name().into_string().unwrap();
-                if filename.contains(&("dictionary-".to_owned() + lang)) {
+                // Сравнение по полному имени, чтобы для pt не находился словарь pt-BR
+                let stem = filename
+                    .rsplit_once('.')
+                    .filter(|(_, extension)| DICTIONARY_EXTENSIONS.contains(extension))
+                    .map(|(stem, _)| stem);
+                if stem == Some(&format!("dictionary-{}", lang))
+                    || stem == Some(&format!("dictionary-{}.base", lang))
+                {
                     return Ok(filename);
                 }
             }
@@ -236,17 +937,17 @@ pub mod parser {
         Err(StaticDictionaryErrors::BasicDictionaryNotFound)
     }
 
-    #[doc = "Возвращает язык файла словаря"]
-    pub fn get_dictionary_language(dictionary_name: &str) -> Result<String, ()> {
-        let pattern = Regex::new(r"^dictionary-(.+?)(?:\.base)?\.json$").unwrap();
-        if let Some(captures) = pattern.captures(dictionary_name) {
-            if let Some(language) = captures.get(1) {
-                return Ok(language.as_str().to_owned());
-            } else {
-                Err(())
-            }
-        } else {
-            Err(())
+    #[doc = "Возвращает язык файла словаря. Файлы с неизвестным или неканоническим кодом языка (dictionary-englsh.json, dictionary-pt_br.json) словарями не считаются, как и при их создании"]
+    pub fn get_dictionary_language(dictionary_name: &str) -> Result<LanguageCode, ()> {
+        let pattern = Regex::new(r"^dictionary-(.+?)(?:\.base)?\.(?:json|ya?ml)$").unwrap();
+        let language = pattern
+            .captures(dictionary_name)
+            .and_then(|captures| captures.get(1))
+            .ok_or(())?
+            .as_str();
+        match LanguageCode::new(language) {
+            Ok(code) if code.as_str() == language => Ok(code),
+            _ => Err(()),
         }
     }
 
@@ -257,7 +958,7 @@ pub mod parser {
     ) -> Result<Vec<Word>, StaticDictionaryErrors> {
         let filename = get_dictionary_by_lang(dictionary_dir, language)?;
         let path = format!("{}/", dictionary_dir.to_owned()) + &filename;
-        let json = read_json_dictionary(&path)?;
+        let json: serde_json::Value = read_dictionary_file(&path)?;
         let json_clone = json.clone();
         let keys = get_tags_from_dictionary(json)?;
         Ok(keys
@@ -265,7 +966,11 @@ pub mod parser {
             .map(|tag| {
                 let tag_data = json_clone.get(tag).unwrap();
                 Word::new(
-                    tag_data.get("word").unwrap().to_string(),
+                    tag_data
+                        .get("word")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or_default()
+                        .to_owned(),
                     tag.to_owned(),
                     language.to_owned(),
                 )
@@ -273,634 +978,4861 @@ pub mod parser {
             .collect::<Vec<Word>>())
     }
 
+    #[doc = "Кавычки фраз между regexp-start и regexp-end, если для расширения не заданы другие"]
+    pub const DEFAULT_STRING_DELIMITER: &str = "\"";
+
     #[doc = "Составляет регулярное выражение для получения всех фраз из файла для базовго словаря"]
     #[inline]
     pub fn generate_regex(
         regex_start: Vec<String>,
         regex_end: Vec<String>,
+    ) -> Result<Regex, StaticDictionaryErrors> {
+        generate_delimited_regex(regex_start, regex_end, DEFAULT_STRING_DELIMITER)
+    }
+
+    #[doc = "Составляет регулярное выражение для фраз между началом и концу строки, заключенных в указанные кавычки (', \" или `)"]
+    pub fn generate_delimited_regex(
+        regex_start: Vec<String>,
+        regex_end: Vec<String>,
+        delimiter: &str,
     ) -> Result<Regex, StaticDictionaryErrors> {
         let start_pattern = regex_start.join("|");
         let end_pattern = regex_end.join("|");
+        let delimiter = regex::escape(delimiter);
         let pattern = format!(
-            r#"({})"(.*?)"({})"#,
+            r#"(?s)({}){delimiter}(.*?){delimiter}({})"#,
             regex::escape(&start_pattern),
             regex::escape(&end_pattern)
         );
         Ok(Regex::new(&pattern)?)
     }
 
-    #[doc = "Сканирует файлы на наличие строк для добавления в базовый словарь"]
-    pub fn scan_files_for_phrases(
-        config_path: Option<String>,
-    ) -> Result<(), StaticDictionaryErrors> {
-        let config = parse_config(config_path)?;
-        println!("{:?}", config.exclude_files);
-        let exclude_files_patterns: Vec<Regex> = config
-            .exclude_files
-            .par_iter()
-            .map(|exclude| {
-                Regex::new(*&exclude).expect(&format!("Ошибка: неправильный паттерн {}", exclude))
+    #[doc = "Составляет регулярное выражение для первого строкового аргумента вызовов функций (t, i18n.t, $t). Аргумент может быть в одинарных или двойных кавычках, а остальные аргументы и вложенные скобки не важны. Если вторым аргументом идет строка, как в t(\"checkout.title\", \"Оформить заказ\"), первый аргумент считается тегом, а второй - фразой"]
+    pub fn generate_call_regex(function_names: &[String]) -> Result<Regex, StaticDictionaryErrors> {
+        let names: Vec<String> = function_names
+            .iter()
+            .map(|name| regex::escape(name))
+            .collect();
+        Ok(Regex::new(&call_pattern(&names))?)
+    }
+
+    // Перед именем функции не должно быть части другого идентификатора, чтобы t( не находилось в split(
+    fn call_pattern(names: &[String]) -> String {
+        format!(
+            r#"(?:^|[^\w$])(?:{})\s*\(\s*{}(?:\s*,\s*{})?"#,
+            names.join("|"),
+            string_argument(""),
+            string_argument("default_")
+        )
+    }
+
+    // Строковый аргумент в двойных, одинарных или обратных кавычках с группами <prefix>double, <prefix>single и <prefix>template
+    fn string_argument(prefix: &str) -> String {
+        format!(
+            r#"(?:"(?P<{prefix}double>(?:[^"\\]|\\.)*)"|'(?P<{prefix}single>(?:[^'\\]|\\.)*)'|`(?P<{prefix}template>(?:[^`\\]|\\.)*)`)"#
+        )
+    }
+
+    // Строковый литерал Rust внутри макроса: обычный или сырой (r"...", r#"..."#). Перед ним может идти другой аргумент, как загрузчик в fl!(LOADER, "id")
+    fn macro_pattern(names: &[String]) -> String {
+        let raw_strings: Vec<String> = (0..=3)
+            .map(|hashes| {
+                let hashes = "#".repeat(hashes);
+                format!(r#"r{hashes}"(?P<raw{}>(?s:.*?))"{hashes}"#, hashes.len())
             })
             .collect();
-        println!("{:?}", exclude_files_patterns);
-        let include_files_patterns: Arc<Mutex<HashMap<String, Regex>>> =
-            Arc::new(Mutex::new(HashMap::new()));
-        config.languages_configurations.par_iter().for_each(|conf| {
-            let local_patterns = Arc::clone(&include_files_patterns);
-            for (_, configurations) in conf {
-                let pattern_start = configurations.string_start.clone();
-                let pattern_end = configurations.string_end.clone();
-                let pattern =
-                    generate_regex(pattern_start, pattern_end).expect("Не удалось создать паттерн");
-                configurations
-                    .file_extensions
-                    .par_iter()
-                    .for_each(|extension| {
-                        let mut patterns = local_patterns.lock().unwrap();
-                        patterns.insert(extension.to_owned(), pattern.clone());
-                    })
-            }
-        });
-        let base_directory_containments = fs::read_dir(config.base_directory.clone())?;
-        for file in base_directory_containments {
-            match file {
-                Ok(file_entry) => {
-                    let exclude_patterns = exclude_files_patterns.clone();
-                    let filename = file_entry.file_name().into_string().unwrap();
-                    let include_patterns = Arc::clone(&include_files_patterns);
-                    if exclude_patterns.len() == 0 {
-                        if !filename.starts_with(".") {
-                            let file_extension = get_file_extension(&filename).expect(&format!(
-                                "Произошла ошибка при прочтении файла {}",
-                                filename
-                            ));
-                            println!("Working with {}", filename);
-                            if include_patterns
-                                .lock()
-                                .unwrap()
-                                .contains_key(&format!(".{}", file_extension))
-                            {
-                                let phrases = get_phrases_from_file(
-                                    &format!("{}/{}", config.base_directory.clone(), filename),
-                                    include_patterns
-                                        .lock()
-                                        .unwrap()
-                                        .get(&format!(".{}", file_extension))
-                                        .unwrap()
-                                        .clone(),
-                                )?;
-                                update_basic_dictionary(&config.dictionary_repo, phrases)?;
-                            }
-                        }
-                    } else {
-                        for pattern in exclude_patterns {
-                            if !pattern.is_match(&filename) && !filename.starts_with(".") {
-                                let file_extension = get_file_extension(&filename).expect(
-                                    &format!("Произошла ошибка при прочтении файла {}", filename),
-                                );
-                                println!("Working with {}", filename);
-                                if include_patterns
-                                    .lock()
-                                    .unwrap()
-                                    .contains_key(&format!(".{}", file_extension))
-                                {
-                                    let phrases = get_phrases_from_file(
-                                        &format!("{}/{}", config.base_directory.clone(), filename),
-                                        include_patterns
-                                            .lock()
-                                            .unwrap()
-                                            .get(&format!(".{}", file_extension))
-                                            .unwrap()
-                                            .clone(),
-                                    )?;
-                                    update_basic_dictionary(&config.dictionary_repo, phrases)?;
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(err) => {
-                    println!("{}", err);
-                    return Err(StaticDictionaryErrors::IOError(err));
-                }
-            }
+        format!(
+            r#"(?:^|[^\w$])(?:{})!\s*[(\[{{]\s*(?:[\w.:&]+\s*,\s*)?(?:"(?P<rust>(?:[^"\\]|\\.)*)"|{})"#,
+            names.join("|"),
+            raw_strings.join("|")
+        )
+    }
+
+    #[doc = "Регулярное выражение для фраз одного языка из конфига: по началу и концу строки (regexp-start и regexp-end), по вызовам функций (call_patterns) и по макросам Rust (macro_patterns)"]
+    pub fn language_regex(
+        configuration: &LanguageConfiguration,
+    ) -> Result<Regex, StaticDictionaryErrors> {
+        delimited_language_regex(configuration, DEFAULT_STRING_DELIMITER)
+    }
+
+    #[doc = "Регулярное выражение для фраз в файлах с расширением: как language_regex, но фразы между regexp-start и regexp-end берутся в кавычках, заданных для расширения в delimiters"]
+    pub fn extension_regex(
+        configuration: &LanguageConfiguration,
+        extension: &str,
+    ) -> Result<Regex, StaticDictionaryErrors> {
+        delimited_language_regex(configuration, configuration.delimiter_for(extension))
+    }
+
+    fn delimited_language_regex(
+        configuration: &LanguageConfiguration,
+        delimiter: &str,
+    ) -> Result<Regex, StaticDictionaryErrors> {
+        let escape = |names: &[String]| -> Vec<String> {
+            names.iter().map(|name| regex::escape(name)).collect()
+        };
+        let mut alternatives = vec![];
+        // Выражение по началу и концу идет первым, чтобы фраза в нем оставалась во второй группе
+        if !configuration.string_start.is_empty() && !configuration.string_end.is_empty() {
+            let delimiters = generate_delimited_regex(
+                configuration.string_start.clone(),
+                configuration.string_end.clone(),
+                delimiter,
+            )?;
+            alternatives.push(delimiters.as_str().to_owned());
+        }
+        if !configuration.call_patterns.is_empty() {
+            alternatives.push(call_pattern(&escape(&configuration.call_patterns)));
+        }
+        if !configuration.macro_patterns.is_empty() {
+            alternatives.push(macro_pattern(&escape(&configuration.macro_patterns)));
+        }
+        match alternatives.is_empty() {
+            true => generate_delimited_regex(
+                configuration.string_start.clone(),
+                configuration.string_end.clone(),
+                delimiter,
+            ),
+            false => Ok(Regex::new(&alternatives.join("|"))?),
         }
-        Ok(())
     }
 
-    #[doc = "Ищет в файле фразы для добавления в базовый словарь"]
-    pub fn get_phrases_from_file(
-        filepath: &str,
-        pattern: Regex,
-    ) -> Result<Vec<String>, StaticDictionaryErrors> {
-        let file = fs::File::open(filepath)?;
-        let reader = io::BufReader::new(file);
-        let mut results = Vec::new();
+    #[doc = "Фраза из шаблонной строки JavaScript: статический текст сохраняется, ${name} и ${user.name} становятся переменными {{name}} и {{user.name}}, а остальные выражения - нумерованными переменными {{0}}, {{1}}"]
+    pub fn template_phrase(template: &str) -> String {
+        static SUBSTITUTION: OnceLock<Regex> = OnceLock::new();
+        let mut index = 0;
+        let phrase = SUBSTITUTION
+            .get_or_init(|| Regex::new(r"\$\{([^}]*)\}").unwrap())
+            .replace_all(template, |captures: &regex::Captures| {
+                let expression = captures[1].trim();
+                let is_path = !expression.is_empty()
+                    && expression.split('.').all(|part| {
+                        !part.is_empty()
+                            && part.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+                    });
+                let name = match is_path {
+                    true => expression.to_owned(),
+                    false => index.to_string(),
+                };
+                index += 1;
+                format!("{{{{{}}}}}", name)
+            });
+        phrase.replace("\\`", "`").replace("\\$", "$")
+    }
 
-        for line in reader.lines() {
-            let line = line?;
-            for cap in pattern.captures_iter(&line) {
-                if let Some(matched) = cap.get(2) {
-                    results.push(matched.as_str().to_string());
+    // Раскрывает \", \' и \\ в строковом аргументе
+    fn unescape_argument(argument: &str) -> String {
+        let mut phrase = String::with_capacity(argument.len());
+        let mut chars = argument.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some(next @ ('"' | '\'' | '\\'))) => {
+                    phrase.push(next);
+                    chars.next();
                 }
+                _ => phrase.push(c),
             }
         }
-        Ok(results)
+        phrase
     }
 
-    #[doc = "Типы данных в парсере"]
-    pub mod types {
-        use std::collections::HashMap;
+    #[doc = "Фраза из совпадения: аргумент вызова функции или макроса (с раскрытыми \\\", \\' и \\\\), сырая строка Rust как есть или вторая группа выражения по началу и концу строки. Если у вызова есть строковый второй аргумент, фразой считается он"]
+    pub fn captured_phrase(captures: &regex::Captures) -> Option<String> {
+        if let Some(argument) = ["default_double", "default_single"]
+            .into_iter()
+            .find_map(|name| captures.name(name))
+        {
+            return Some(unescape_argument(argument.as_str()));
+        }
+        if let Some(template) = captures.name("default_template") {
+            return Some(template_phrase(template.as_str()));
+        }
+        if let Some(argument) = ["double", "single", "rust"]
+            .into_iter()
+            .find_map(|name| captures.name(name))
+        {
+            return Some(unescape_argument(argument.as_str()));
+        }
+        if let Some(template) = captures.name("template") {
+            return Some(template_phrase(template.as_str()));
+        }
+        if let Some(raw) = ["raw0", "raw1", "raw2", "raw3"]
+            .into_iter()
+            .find_map(|name| captures.name(name))
+        {
+            return Some(raw.as_str().to_owned());
+        }
+        captures.get(2).map(|matched| matched.as_str().to_owned())
+    }
 
-        use serde::{Deserialize, Serialize};
+    #[doc = "Тег из совпадения: первый аргумент вызова вида t(\"checkout.title\", \"Оформить заказ\"), если вторым аргументом идет фраза"]
+    pub fn captured_key(captures: &regex::Captures) -> Option<String> {
+        ["default_double", "default_single", "default_template"]
+            .into_iter()
+            .find_map(|name| captures.name(name))?;
+        ["double", "single", "template"]
+            .into_iter()
+            .find_map(|name| captures.name(name))
+            .map(|key| unescape_argument(key.as_str()))
+            .filter(|key| !key.trim().is_empty())
+    }
 
-        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-        #[doc = "Конфиг для настройки параметров парсера"]
-        pub struct ConfigFileParameters {
-            /// Директория проекта, в котором нужно сканировать файлы
-            #[serde(rename = "base")]
-            pub base_directory: String,
-            /// Список путей, которые нужно игнорировать
-            #[serde(rename = "exclude")]
-            pub exclude_files: Vec<String>,
-            /// Репозиторий словарей
-            #[serde(rename = "dictionary_repo")]
-            pub dictionary_repo: String,
-            /// Директория, куда будут собираться итоговые словари
-            #[serde(rename = "output_dir")]
-            pub output_dir: String,
+    #[derive(Debug, Clone)]
+    #[doc = "Способ поиска фраз в файлах с определенным расширением"]
+    pub enum PhraseExtractor {
+        /// Регулярное выражение по строкам файла (regexp-start, regexp-end и call_patterns)
+        Regex(Regex),
+        /// Синтаксическое дерево tree-sitter: аргументы вызовов функций перевода и текст JSX
+        #[cfg(feature = "tree-sitter")]
+        TreeSitter {
+            language: tree_sitter::Language,
+            call_names: Vec<String>,
+        },
+        /// Разметка HTML, XML, Vue или Svelte: текстовые узлы и атрибуты, а также вызовы функций перевода, если заданы call_patterns
+        Markup {
+            attributes: Vec<String>,
+            calls: Option<Regex>,
+        },
+    }
+
+    impl PhraseExtractor {
+        #[doc = "Ищет фразы в файле и создает для них записи базового словаря вместе с номерами строк"]
+        pub fn extract(
+            &self,
+            filepath: &str,
+            key_strategy: KeyStrategy,
+        ) -> Result<Vec<(BaseEntry, usize)>, StaticDictionaryErrors> {
+            self.extract_from_content(&fs::read_to_string(filepath)?, key_strategy)
+        }
+
+        #[doc = "Ищет фразы в уже прочитанном содержимом файла, как extract"]
+        pub fn extract_from_content(
+            &self,
+            content: &str,
+            key_strategy: KeyStrategy,
+        ) -> Result<Vec<(BaseEntry, usize)>, StaticDictionaryErrors> {
+            match self {
+                PhraseExtractor::Regex(pattern) => {
+                    located_entries_from_content(content, pattern, key_strategy)
+                }
+                #[cfg(feature = "tree-sitter")]
+                PhraseExtractor::TreeSitter {
+                    language,
+                    call_names,
+                } => {
+                    let found = crate::tree_sitter_extraction::extract_phrases(
+                        content, language, call_names,
+                    )?;
+                    let found = found
+                        .into_iter()
+                        .map(|(phrase, line)| (phrase, line, None))
+                        .collect();
+                    located_entries(content, found, key_strategy)
+                }
+                PhraseExtractor::Markup { attributes, calls } => {
+                    let mut found: Vec<(String, usize, Option<String>)> =
+                        crate::markup_extraction::extract_markup_phrases(content, attributes)
+                            .into_iter()
+                            .map(|(phrase, line)| (phrase, line, None))
+                            .collect();
+                    if let Some(calls) = calls {
+                        found.extend(regex_phrases(content, calls));
+                        found.sort_by_key(|(_, line, _)| *line);
+                    }
+                    located_entries(content, found, key_strategy)
+                }
+            }
+        }
+    }
+
+    fn configuration_extractors(
+        configuration: &LanguageConfiguration,
+    ) -> Result<Vec<(String, PhraseExtractor)>, StaticDictionaryErrors> {
+        let configuration = &configuration.with_preset();
+        let extensions = configuration
+            .file_extensions
+            .iter()
+            .map(|extension| extension.trim_start_matches('.').to_owned());
+        match configuration.extractor {
+            ExtractorKind::Regex => {
+                // Расширения с одинаковыми кавычками используют одно выражение
+                let mut patterns: HashMap<&str, Regex> = HashMap::new();
+                extensions
+                    .map(|extension| {
+                        let delimiter = configuration.delimiter_for(&extension);
+                        let pattern = match patterns.get(delimiter) {
+                            Some(pattern) => pattern.clone(),
+                            None => {
+                                let pattern = extension_regex(configuration, &extension)?;
+                                patterns.insert(delimiter, pattern.clone());
+                                pattern
+                            }
+                        };
+                        Ok((extension, PhraseExtractor::Regex(pattern)))
+                    })
+                    .collect()
+            }
+            #[cfg(feature = "tree-sitter")]
+            ExtractorKind::TreeSitter => extensions
+                .map(|extension| {
+                    match crate::tree_sitter_extraction::grammar_for_extension(&extension) {
+                        Some(language) => Ok((
+                            extension,
+                            PhraseExtractor::TreeSitter {
+                                language,
+                                call_names: configuration.call_patterns.clone(),
+                            },
+                        )),
+                        None => Err(StaticDictionaryErrors::InvalidConfig(format!(
+                            "tree-sitter поддерживает только файлы js, jsx, ts и tsx, а не {}",
+                            extension
+                        ))),
+                    }
+                })
+                .collect(),
+            ExtractorKind::Html => {
+                let attributes = match configuration.attributes.is_empty() {
+                    true => crate::markup_extraction::DEFAULT_TEXT_ATTRIBUTES
+                        .map(String::from)
+                        .to_vec(),
+                    false => configuration.attributes.clone(),
+                };
+                let calls = match configuration.call_patterns.is_empty() {
+                    true => None,
+                    false => Some(generate_call_regex(&configuration.call_patterns)?),
+                };
+                Ok(extensions
+                    .map(|extension| {
+                        let extractor = PhraseExtractor::Markup {
+                            attributes: attributes.clone(),
+                            calls: calls.clone(),
+                        };
+                        (extension, extractor)
+                    })
+                    .collect())
+            }
+            #[cfg(not(feature = "tree-sitter"))]
+            ExtractorKind::TreeSitter => Err(StaticDictionaryErrors::InvalidConfig(
+                "dms собран без поддержки tree-sitter, пересоберите его с --features tree-sitter"
+                    .to_owned(),
+            )),
+        }
+    }
+
+    #[doc = "Составляет способы поиска фраз по расширениям файлов из конфига (расширения можно указывать с точкой и без)"]
+    pub fn scan_extractors(
+        config: &ConfigFileParameters,
+    ) -> Result<HashMap<String, PhraseExtractor>, StaticDictionaryErrors> {
+        let mut extractors = HashMap::new();
+        for configurations in &config.languages_configurations {
+            for configuration in configurations.values() {
+                extractors.extend(configuration_extractors(configuration)?);
+            }
+        }
+        Ok(extractors)
+    }
+
+    #[derive(Debug, Clone)]
+    #[doc = "Фильтр файлов проекта по glob-шаблонам include_files и exclude. Шаблоны без / (node_modules, *.min.js) относятся к имени на любом уровне, остальные - к пути относительно base"]
+    pub struct PathFilter {
+        include: Option<GlobSet>,
+        exclude: GlobSet,
+    }
+
+    // Признаки регулярного выражения, которые не встречаются в glob-шаблонах и обычных путях
+    fn looks_like_regex(pattern: &str) -> bool {
+        pattern.starts_with('^')
+            || pattern.ends_with('$')
+            || pattern.contains('|')
+            || [".+", "\\.", "\\d", "\\w", "\\s", "\\/"]
+                .iter()
+                .any(|token| pattern.contains(token))
+    }
+
+    impl PathFilter {
+        fn glob_set<'a>(
+            patterns: impl IntoIterator<Item = &'a String>,
+        ) -> Result<GlobSet, StaticDictionaryErrors> {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                // Раньше exclude задавался регулярными выражениями: такие шаблоны молча перестали бы работать
+                if looks_like_regex(pattern) {
+                    return Err(StaticDictionaryErrors::InvalidConfig(format!(
+                        "шаблон {} похож на регулярное выражение, а include_files и exclude задаются glob-шаблонами (например \"*.min.js\" или \"dist/**\")",
+                        pattern
+                    )));
+                }
+                let pattern = pattern.trim_start_matches("./");
+                let mut variants = vec![pattern.to_owned()];
+                if !pattern.contains('/') {
+                    variants.push(format!("**/{}", pattern));
+                }
+                // node_modules/** исключает и саму директорию, чтобы не обходить ее
+                if let Some(directory) = pattern.strip_suffix("/**") {
+                    variants.push(directory.to_owned());
+                }
+                for variant in variants {
+                    builder.add(GlobBuilder::new(&variant).literal_separator(true).build()?);
+                }
+            }
+            Ok(builder.build()?)
+        }
+
+        #[doc = "Составляет фильтр из шаблонов конфига. Пустой список include_files разрешает все файлы"]
+        pub fn new(
+            include: &[String],
+            exclude: &[String],
+        ) -> Result<PathFilter, StaticDictionaryErrors> {
+            Ok(PathFilter {
+                include: match include.is_empty() {
+                    true => None,
+                    false => Some(PathFilter::glob_set(include)?),
+                },
+                exclude: PathFilter::glob_set(exclude)?,
+            })
+        }
+
+        #[doc = "Путь относительно base исключен шаблонами exclude"]
+        pub fn is_excluded(&self, relative_path: &str) -> bool {
+            self.exclude.is_match(relative_path)
+        }
+
+        #[doc = "Файл по пути относительно base подходит под include_files и не исключен"]
+        pub fn is_included(&self, relative_path: &str) -> bool {
+            !self.is_excluded(relative_path)
+                && self
+                    .include
+                    .as_ref()
+                    .is_none_or(|include| include.is_match(relative_path))
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[doc = "Параметры обхода директории проекта при сканировании"]
+    pub struct WalkOptions {
+        /// Максимальная глубина вложенности директорий (0 - только файлы в base)
+        pub max_depth: Option<usize>,
+        /// Пропускать файлы из .gitignore, .ignore и глобального gitignore
+        pub respect_gitignore: bool,
+        /// Переходить по символическим ссылкам на файлы и директории
+        pub follow_symlinks: bool,
+    }
+
+    impl Default for WalkOptions {
+        fn default() -> Self {
+            WalkOptions {
+                max_depth: None,
+                respect_gitignore: true,
+                follow_symlinks: false,
+            }
+        }
+    }
+
+    impl From<&ConfigFileParameters> for WalkOptions {
+        fn from(config: &ConfigFileParameters) -> Self {
+            WalkOptions {
+                max_depth: config.max_depth,
+                respect_gitignore: config.respect_gitignore,
+                follow_symlinks: config.follow_symlinks,
+            }
+        }
+    }
+
+    // Ссылка, которая ведет в одну из родительских директорий, образует цикл
+    fn is_symlink_loop(err: &ignore::Error) -> bool {
+        match err {
+            ignore::Error::Loop { .. } => true,
+            ignore::Error::WithPath { err, .. }
+            | ignore::Error::WithDepth { err, .. }
+            | ignore::Error::WithLineNumber { err, .. } => is_symlink_loop(err),
+            _ => false,
+        }
+    }
+
+    #[doc = "Рекурсивно обходит директорию проекта и возвращает файлы с нужными расширениями. Скрытые файлы и директории пропускаются, исключенные директории не обходятся, а файлы из .gitignore и .ignore пропускаются, если это не отключено в параметрах. По символическим ссылкам обход идет только с follow_symlinks: циклы пропускаются, а файл, доступный по нескольким путям, возвращается один раз"]
+    pub fn collect_source_files(
+        base_directory: &str,
+        filter: &PathFilter,
+        extensions: &HashMap<String, PhraseExtractor>,
+        options: WalkOptions,
+    ) -> Result<Vec<PathBuf>, StaticDictionaryErrors> {
+        let base = PathBuf::from(base_directory);
+        let relative_path = {
+            let base = base.clone();
+            move |path: &Path| {
+                path.strip_prefix(&base)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            }
+        };
+        let directory_filter = filter.clone();
+        let directory_path = relative_path.clone();
+        let walker = WalkBuilder::new(&base)
+            .standard_filters(options.respect_gitignore)
+            .hidden(true)
+            // .gitignore учитывается и в проектах, которые не являются git-репозиторием
+            .require_git(false)
+            .max_depth(options.max_depth.map(|depth| depth + 1))
+            .follow_links(options.follow_symlinks)
+            .filter_entry(move |entry| {
+                entry.depth() == 0
+                    || !entry.file_type().is_some_and(|file_type| file_type.is_dir())
+                    || !directory_filter.is_excluded(&directory_path(entry.path()))
+            })
+            .build();
+        let mut files = vec![];
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) if is_symlink_loop(&err) => continue,
+                Err(err) => {
+                    return Err(match err.into_io_error() {
+                        Some(err) => err,
+                        None => io::Error::other("Не удалось прочитать файл .gitignore"),
+                    }
+                    .into())
+                }
+            };
+            let path = entry.path();
+            if entry.file_type().is_some_and(|file_type| file_type.is_file())
+                && filter.is_included(&relative_path(path))
+                && get_file_extension(&entry.file_name().to_string_lossy())
+                    .is_some_and(|extension| extensions.contains_key(extension))
+            {
+                files.push(path.to_path_buf());
+            }
+        }
+        files.sort();
+        if options.follow_symlinks {
+            let mut seen = HashSet::new();
+            files.retain(|file| {
+                seen.insert(fs::canonicalize(file).unwrap_or_else(|_| file.clone()))
+            });
+        }
+        Ok(files)
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize)]
+    #[doc = "Результат сканирования одного файла"]
+    pub struct FileScanReport {
+        pub file: String,
+        /// Теги фраз, которые добавлены в базовый словарь при этом сканировании
+        pub added: Vec<String>,
+        /// Теги найденных фраз, которые уже были в базовом словаре
+        pub existing: Vec<String>,
+        /// Теги, которые раньше встречались в файле, а теперь нет. Кандидаты на удаление, если не используются в других файлах
+        pub removed: Vec<String>,
+        /// Файл пропущен: он двоичный или его не удалось декодировать в заданной кодировке
+        pub unreadable: bool,
+    }
+
+    impl FileScanReport {
+        #[inline]
+        #[doc = "Количество разных фраз, найденных в файле"]
+        pub fn found(&self) -> usize {
+            self.added.len() + self.existing.len()
+        }
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize)]
+    #[doc = "Отчет о сканировании проекта: новые, уже известные и пропавшие из исходного кода фразы по файлам"]
+    pub struct ScanReport {
+        /// Просканированные файлы
+        pub files: Vec<FileScanReport>,
+        /// Количество файлов, пропущенных, потому что они не изменились с прошлого сканирования
+        pub skipped: usize,
+    }
+
+    impl ScanReport {
+        #[doc = "Количество фраз, добавленных в базовый словарь"]
+        pub fn added(&self) -> usize {
+            self.files.iter().map(|file| file.added.len()).sum()
+        }
+
+        #[doc = "Количество найденных фраз, которые уже были в базовом словаре"]
+        pub fn existing(&self) -> usize {
+            self.files.iter().map(|file| file.existing.len()).sum()
+        }
+
+        #[doc = "Количество тегов, которые пропали из файлов с прошлого сканирования"]
+        pub fn removed(&self) -> usize {
+            self.files.iter().map(|file| file.removed.len()).sum()
+        }
+
+        #[doc = "Файлы, пропущенные из-за двоичного содержимого или неизвестной кодировки"]
+        pub fn unreadable(&self) -> Vec<&str> {
+            self.files
+                .iter()
+                .filter(|file| file.unreadable)
+                .map(|file| file.file.as_str())
+                .collect()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    #[doc = "Запись, которая была бы добавлена в базовый словарь, и место, где фраза впервые встретилась"]
+    pub struct PreviewEntry {
+        pub entry: BaseEntry,
+        pub file: String,
+        pub line: usize,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize)]
+    #[doc = "Результат пробного сканирования: что было бы добавлено в базовые словари, без записи в репозиторий"]
+    pub struct ScanPreview {
+        /// Новые записи по директориям словарей в порядке появления в файлах
+        pub added: BTreeMap<String, Vec<PreviewEntry>>,
+        /// Количество просканированных файлов
+        pub scanned: usize,
+        /// Двоичные файлы и файлы в неизвестной кодировке
+        pub unreadable: Vec<String>,
+    }
+
+    impl ScanPreview {
+        #[doc = "Количество записей, которые были бы добавлены во все базовые словари"]
+        pub fn added_count(&self) -> usize {
+            self.added.values().map(Vec::len).sum()
+        }
+    }
+
+    #[doc = "Сканирует все файлы проекта, как scan_files_for_phrases, но ничего не записывает: ни базовые словари, ни места использования, ни кеш. Возвращает записи, которые были бы добавлены в каждый базовый словарь"]
+    pub fn preview_scan_files(
+        config_path: Option<String>,
+    ) -> Result<ScanPreview, StaticDictionaryErrors> {
+        let config = parse_config(config_path)?;
+        let filter = PathFilter::new(&config.include_files, &config.exclude_files)?;
+        let include_files_patterns = scan_extractors(&config)?;
+        let sources = scan_source_files(&config, &filter, &include_files_patterns)?;
+        let cache = ScanCache::default();
+        let jobs: Vec<(&str, &PathBuf)> = sources
+            .iter()
+            .flat_map(|(dictionary_dir, files)| {
+                files.iter().map(move |file| (dictionary_dir.as_str(), file))
+            })
+            .collect();
+        let extracted: Vec<Result<Option<ExtractedFile>, StaticDictionaryErrors>> = jobs
+            .par_iter()
+            .map(|(_, file)| extract_changed_file(&config, &include_files_patterns, &cache, file))
+            .collect();
+
+        let mut preview = ScanPreview::default();
+        // Базовые словари в памяти: фраза из нескольких файлов добавляется один раз
+        let mut base_entries: HashMap<&str, Vec<BaseEntry>> = HashMap::new();
+        for ((dictionary_dir, _), extracted) in jobs.iter().zip(extracted) {
+            let Some(extracted) = extracted? else {
+                continue;
+            };
+            preview.scanned += 1;
+            let file = location_path(&extracted.file);
+            let Some(located) = extracted.located else {
+                preview.unreadable.push(file);
+                continue;
+            };
+            if !base_entries.contains_key(dictionary_dir) {
+                // Базовый словарь пространства имен может быть еще не создан
+                let existing = match get_basic_dictionary(dictionary_dir) {
+                    Ok(_) => parse_static_basic_entries(dictionary_dir)?,
+                    Err(_) => vec![],
+                };
+                base_entries.insert(dictionary_dir, existing);
+            }
+            let base = base_entries.entry(dictionary_dir).or_default();
+            for (entry, line) in located {
+                let entry = match config.normalize_phrases {
+                    true => normalize_new_entries(base, vec![entry]).remove(0),
+                    false => entry,
+                };
+                if matching_entry(base, &entry).is_some() {
+                    continue;
+                }
+                merge_basic_entries(base, vec![entry]);
+                let entry = base.last().unwrap().clone();
+                preview
+                    .added
+                    .entry(dictionary_dir.to_string())
+                    .or_default()
+                    .push(PreviewEntry {
+                        entry,
+                        file: file.clone(),
+                        line,
+                    });
+            }
+        }
+        Ok(preview)
+    }
+
+    #[doc = "Сканирует файлы проекта (включая поддиректории) на наличие строк для добавления в базовый словарь. С кешем пропускаются файлы, которые не изменились с прошлого сканирования"]
+    pub fn scan_files_for_phrases(
+        config_path: Option<String>,
+        use_cache: bool,
+    ) -> Result<ScanReport, StaticDictionaryErrors> {
+        scan_files_for_phrases_with_progress(config_path, use_cache, &NoProgress)
+    }
+
+    #[doc = "Сканирует файлы проекта, как scan_files_for_phrases, и сообщает в progress о каждом разобранном файле на этапе SCAN_STAGE"]
+    pub fn scan_files_for_phrases_with_progress(
+        config_path: Option<String>,
+        use_cache: bool,
+        progress: &dyn Progress,
+    ) -> Result<ScanReport, StaticDictionaryErrors> {
+        let config = parse_config(config_path)?;
+        let filter = PathFilter::new(&config.include_files, &config.exclude_files)?;
+        let include_files_patterns = scan_extractors(&config)?;
+        let sources = collect_scan_sources(&config, &filter, &include_files_patterns)?;
+        let mut cache = load_scan_cache(&config, use_cache);
+        let jobs: Vec<(&str, &PathBuf)> = sources
+            .iter()
+            .flat_map(|(dictionary_dir, files)| {
+                files.iter().map(move |file| (dictionary_dir.as_str(), file))
+            })
+            .collect();
+        tracing::info!("Файлов для сканирования: {}", jobs.len());
+        progress.start(SCAN_STAGE, jobs.len());
+        // Файлы читаются и разбираются параллельно, а записываются в словари по порядку
+        let extracted: Vec<Result<Option<ExtractedFile>, StaticDictionaryErrors>> = jobs
+            .par_iter()
+            .map(|(_, file)| {
+                let extracted = extract_changed_file(&config, &include_files_patterns, &cache, file);
+                progress.advance(SCAN_STAGE);
+                extracted
+            })
+            .collect();
+        progress.finish(SCAN_STAGE);
+        let mut dictionaries = BTreeMap::new();
+        for dictionary_dir in sources.keys() {
+            let dictionary = ScannedDictionary::load(dictionary_dir)?;
+            dictionaries.insert(dictionary_dir.as_str(), dictionary);
+        }
+        let mut report = ScanReport::default();
+        let mut hashes = vec![];
+        for ((dictionary_dir, file), extracted) in jobs.iter().zip(extracted) {
+            let Some(extracted) = extracted? else {
+                tracing::trace!("{} не изменился с прошлого сканирования", file.display());
+                report.skipped += 1;
+                continue;
+            };
+            tracing::debug!("Просканирован {}", file.display());
+            hashes.push((extracted.file.to_string_lossy().into_owned(), extracted.hash.clone()));
+            if let Some(dictionary) = dictionaries.get_mut(dictionary_dir) {
+                report.files.push(dictionary.record(&config, extracted));
+            }
+        }
+        // Каждый базовый словарь записывается один раз, после того как разобраны все файлы
+        for (dictionary_dir, dictionary) in &mut dictionaries {
+            dictionary.retain_files(&sources[*dictionary_dir]);
+            dictionary.save()?;
+        }
+        for (key, hash) in hashes {
+            cache.update(&key, hash);
+        }
+        save_scan_cache(&config, &mut cache, &sources)?;
+        tracing::info!(
+            "Просканировано файлов: {}, пропущено без изменений: {}",
+            report.files.len(),
+            report.skipped
+        );
+        Ok(report)
+    }
+
+    #[doc = "Файлы проекта из всех директорий base, сгруппированные по директориям словарей, в которые попадают их фразы. Базовый словарь пространства имен создается на языке и в формате базового словаря репозитория, если его еще нет"]
+    pub fn collect_scan_sources(
+        config: &ConfigFileParameters,
+        filter: &PathFilter,
+        extractors: &HashMap<String, PhraseExtractor>,
+    ) -> Result<BTreeMap<String, Vec<PathBuf>>, StaticDictionaryErrors> {
+        for root in config.base_directory.roots() {
+            if root.namespace.is_some() {
+                let dictionary_repo = config.dictionary_repo.path_for(root.repo.as_deref())?;
+                create_namespace_dictionary(&dictionary_repo, &root.dictionary_dir(&dictionary_repo))?;
+            }
+        }
+        scan_source_files(config, filter, extractors)
+    }
+
+    // Файлы проекта по директориям словарей, без создания словарей пространств имен
+    fn scan_source_files(
+        config: &ConfigFileParameters,
+        filter: &PathFilter,
+        extractors: &HashMap<String, PhraseExtractor>,
+    ) -> Result<BTreeMap<String, Vec<PathBuf>>, StaticDictionaryErrors> {
+        let mut sources: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        for root in config.base_directory.roots() {
+            let dictionary_repo = config.dictionary_repo.path_for(root.repo.as_deref())?;
+            let dictionary_dir = root.dictionary_dir(&dictionary_repo);
+            let files =
+                collect_source_files(&root.path, filter, extractors, WalkOptions::from(config))?;
+            sources.entry(dictionary_dir).or_default().extend(files);
+        }
+        Ok(sources)
+    }
+
+    fn create_namespace_dictionary(
+        dictionary_repo: &str,
+        dictionary_dir: &str,
+    ) -> Result<(), StaticDictionaryErrors> {
+        if Path::new(dictionary_dir).is_dir() && get_basic_dictionary(dictionary_dir).is_ok() {
+            return Ok(());
+        }
+        let basic_dictionary = get_basic_dictionary(dictionary_repo)?;
+        let format = DictionaryFormat::from_path(&basic_dictionary);
+        fs::create_dir_all(dictionary_dir)?;
+        atomic_write(
+            Path::new(dictionary_dir).join(basic_dictionary),
+            format.serialize(&Vec::<BaseEntry>::new())?,
+        )?;
+        Ok(())
+    }
+
+    fn load_scan_cache(config: &ConfigFileParameters, use_cache: bool) -> ScanCache {
+        let settings = settings_hash(config);
+        match use_cache {
+            true => ScanCache::load(&config.scan_cache, &settings),
+            false => ScanCache {
+                settings,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn save_scan_cache(
+        config: &ConfigFileParameters,
+        cache: &mut ScanCache,
+        sources: &BTreeMap<String, Vec<PathBuf>>,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let paths: Vec<String> = sources
+            .values()
+            .flatten()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect();
+        cache.retain_files(&paths);
+        cache.save(&config.scan_cache)
+    }
+
+    // Удаляет места использования в файлах, которых больше нет в проекте
+    fn retain_source_locations(
+        sources: &BTreeMap<String, Vec<PathBuf>>,
+    ) -> Result<(), StaticDictionaryErrors> {
+        for (dictionary_dir, files) in sources {
+            let mut locations = read_source_locations(dictionary_dir)?;
+            let paths: Vec<String> = files.iter().map(|file| location_path(file)).collect();
+            retain_files(&mut locations, &paths);
+            write_source_locations(dictionary_dir, &locations)?;
+        }
+        Ok(())
+    }
+
+    #[doc = "Сканирует файл, если его содержимое изменилось с прошлого сканирования, и запоминает новый хеш. Возвращает None, если файл пропущен"]
+    pub fn scan_cached_file(
+        config: &ConfigFileParameters,
+        dictionary_dir: &str,
+        patterns: &HashMap<String, PhraseExtractor>,
+        cache: &mut ScanCache,
+        file: &Path,
+    ) -> Result<Option<FileScanReport>, StaticDictionaryErrors> {
+        let Some(extracted) = extract_changed_file(config, patterns, cache, file)? else {
+            return Ok(None);
+        };
+        let (key, hash) = (extracted.file.to_string_lossy().into_owned(), extracted.hash.clone());
+        let found = record_extracted_file(config, dictionary_dir, extracted)?;
+        cache.update(&key, hash);
+        Ok(Some(found))
+    }
+
+    #[doc = "Ищет фразы в одном файле проекта, добавляет их в базовый словарь директории словарей и запоминает, на каких строках они найдены"]
+    pub fn scan_file(
+        config: &ConfigFileParameters,
+        dictionary_dir: &str,
+        patterns: &HashMap<String, PhraseExtractor>,
+        file: &Path,
+    ) -> Result<FileScanReport, StaticDictionaryErrors> {
+        let extracted = extract_file(config, patterns, file, fs::read(file)?)?;
+        record_extracted_file(config, dictionary_dir, extracted)
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[doc = "Фразы, найденные в одном файле, до записи в репозиторий словарей"]
+    pub struct ExtractedFile {
+        pub file: PathBuf,
+        /// Хеш содержимого файла для кеша сканирования
+        pub hash: String,
+        /// Фразы с номерами строк. None, если файл двоичный или его не удалось декодировать
+        pub located: Option<Vec<(BaseEntry, usize)>>,
+    }
+
+    #[doc = "Читает файл и ищет в нем фразы, если он изменился с прошлого сканирования. Ничего не записывает, поэтому файлы можно разбирать параллельно"]
+    pub fn extract_changed_file(
+        config: &ConfigFileParameters,
+        patterns: &HashMap<String, PhraseExtractor>,
+        cache: &ScanCache,
+        file: &Path,
+    ) -> Result<Option<ExtractedFile>, StaticDictionaryErrors> {
+        let content = fs::read(file)?;
+        if cache.is_unchanged(&file.to_string_lossy(), &content_hash(&content)) {
+            return Ok(None);
+        }
+        extract_file(config, patterns, file, content).map(Some)
+    }
+
+    fn extract_file(
+        config: &ConfigFileParameters,
+        patterns: &HashMap<String, PhraseExtractor>,
+        file: &Path,
+        content: Vec<u8>,
+    ) -> Result<ExtractedFile, StaticDictionaryErrors> {
+        let hash = content_hash(&content);
+        let extractor = file
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| patterns.get(extension));
+        let located = match (extractor, decode_source(&content, config.encoding)) {
+            (Some(extractor), Some(text)) => Some(
+                extractor
+                    .extract_from_content(&text, config.key_strategy)?
+                    .into_iter()
+                    .filter(|(entry, _)| config.filters.accepts(&entry.phrase))
+                    .collect(),
+            ),
+            (None, _) => Some(vec![]),
+            (_, None) => None,
+        };
+        Ok(ExtractedFile {
+            file: file.to_path_buf(),
+            hash,
+            located,
+        })
+    }
+
+    #[doc = "Добавляет найденные в файле фразы в базовый словарь директории словарей и запоминает, на каких строках они найдены"]
+    pub fn record_extracted_file(
+        config: &ConfigFileParameters,
+        dictionary_dir: &str,
+        extracted: ExtractedFile,
+    ) -> Result<FileScanReport, StaticDictionaryErrors> {
+        let mut dictionary = ScannedDictionary::load(dictionary_dir)?;
+        let report = dictionary.record(config, extracted);
+        dictionary.save()?;
+        Ok(report)
+    }
+
+    #[derive(Debug, Clone)]
+    #[doc = "Базовый словарь и места использования фраз одной директории словарей в памяти. Фразы из всех файлов сканирования накапливаются в нем и записываются в репозиторий один раз"]
+    pub struct ScannedDictionary {
+        dictionary_dir: String,
+        entries: Vec<BaseEntry>,
+        locations: SourceLocations,
+        /// Базовый словарь изменился с момента чтения
+        changed: bool,
+    }
+
+    impl ScannedDictionary {
+        #[doc = "Читает базовый словарь и места использования"]
+        pub fn load(dictionary_dir: &str) -> Result<ScannedDictionary, StaticDictionaryErrors> {
+            Ok(ScannedDictionary {
+                dictionary_dir: dictionary_dir.to_owned(),
+                entries: parse_static_basic_entries(dictionary_dir)?,
+                locations: read_source_locations(dictionary_dir)?,
+                changed: false,
+            })
+        }
+
+        #[doc = "Добавляет найденные в файле фразы в базовый словарь и заменяет места использования из этого файла. Ничего не записывает"]
+        pub fn record(
+            &mut self,
+            config: &ConfigFileParameters,
+            extracted: ExtractedFile,
+        ) -> FileScanReport {
+            let path = location_path(&extracted.file);
+            let mut report = FileScanReport {
+                file: path.clone(),
+                ..Default::default()
+            };
+            let Some(located) = extracted.located else {
+                report.unreadable = true;
+                return report;
+            };
+            let known: HashSet<String> = self
+                .entries
+                .iter()
+                .map(|entry| entry.tag().to_owned())
+                .collect();
+            let located: Vec<(BaseEntry, usize)> = located
+                .into_iter()
+                .map(|(entry, line)| match config.normalize_phrases {
+                    true => (normalize_new_entries(&self.entries, vec![entry]).remove(0), line),
+                    false => (entry, line),
+                })
+                .collect();
+            self.changed |= merge_basic_entries(
+                &mut self.entries,
+                located.iter().map(|(entry, _)| entry.clone()).collect(),
+            );
+
+            // Тег берется из базового словаря: новая фраза могла совпасть с уже существующей записью
+            let tags: Vec<(String, usize)> = located
+                .into_iter()
+                .filter_map(|(entry, line)| {
+                    matching_entry(&self.entries, &entry)
+                        .map(|index| (self.entries[index].tag().to_owned(), line))
+                })
+                .collect();
+            for (tag, _) in &tags {
+                let list = match known.contains(tag) {
+                    true => &mut report.existing,
+                    false => &mut report.added,
+                };
+                if !list.contains(tag) {
+                    list.push(tag.to_owned());
+                }
+            }
+            report.removed = self
+                .locations
+                .iter()
+                .filter(|(tag, used)| {
+                    used.iter().any(|location| location.file == path)
+                        && !tags.iter().any(|(found, _)| found == *tag)
+                })
+                .map(|(tag, _)| tag.to_owned())
+                .collect();
+            replace_file_locations(&mut self.locations, &path, tags);
+            report
+        }
+
+        #[doc = "Удаляет места использования в файлах, которых больше нет среди файлов проекта"]
+        pub fn retain_files(&mut self, files: &[PathBuf]) {
+            let paths: Vec<String> = files.iter().map(|file| location_path(file)).collect();
+            retain_files(&mut self.locations, &paths);
+        }
+
+        #[doc = "Записывает базовый словарь, если в него добавились фразы, и места использования"]
+        pub fn save(&self) -> Result<(), StaticDictionaryErrors> {
+            if self.changed {
+                let basic_dictionary = get_basic_dictionary(&self.dictionary_dir)?;
+                write_dictionary_file(
+                    format!("{}/{}", self.dictionary_dir, basic_dictionary),
+                    &self.entries,
+                )?;
+            }
+            write_source_locations(&self.dictionary_dir, &self.locations)
+        }
+    }
+
+    #[doc = "Сканирует проект, а затем следит за изменениями файлов и добавляет новые фразы в базовый словарь, пересканируя только измененные файлы. Результат сканирования каждого файла передается в on_scan. Работает, пока не будет прерван"]
+    pub fn watch_files_for_phrases(
+        config_path: Option<String>,
+        use_cache: bool,
+        on_scan: impl FnMut(&Path, Result<FileScanReport, StaticDictionaryErrors>),
+    ) -> Result<(), StaticDictionaryErrors> {
+        watch_scan_batches(
+            config_path,
+            use_cache,
+            Duration::from_millis(200),
+            on_scan,
+            |_| {},
+        )
+    }
+
+    #[doc = "То же, что watch_files_for_phrases, но события изменения файлов объединяются, пока между ними проходит меньше debounce. После первого сканирования и после каждого пересканирования в on_batch передаются директории словарей с тегами фраз, добавленных в их базовые словари (может быть пустым)"]
+    pub fn watch_scan_batches(
+        config_path: Option<String>,
+        use_cache: bool,
+        debounce: Duration,
+        mut on_scan: impl FnMut(&Path, Result<FileScanReport, StaticDictionaryErrors>),
+        mut on_batch: impl FnMut(&BTreeMap<String, BTreeSet<String>>),
+    ) -> Result<(), StaticDictionaryErrors> {
+        let config = parse_config(config_path)?;
+        let filter = PathFilter::new(&config.include_files, &config.exclude_files)?;
+        let patterns = scan_extractors(&config)?;
+        let mut cache = load_scan_cache(&config, use_cache);
+        let mut added = BTreeMap::new();
+        let mut scan = |cache: &mut ScanCache,
+                        added: &mut BTreeMap<String, BTreeSet<String>>,
+                        dictionary_dir: &str,
+                        file: &Path| {
+            match scan_cached_file(&config, dictionary_dir, &patterns, cache, file) {
+                Ok(Some(found)) => {
+                    if !found.added.is_empty() {
+                        added
+                            .entry(dictionary_dir.to_owned())
+                            .or_default()
+                            .extend(found.added.iter().cloned());
+                    }
+                    on_scan(file, Ok(found))
+                }
+                Ok(None) => {}
+                Err(err) => on_scan(file, Err(err)),
+            }
+        };
+        let sources = collect_scan_sources(&config, &filter, &patterns)?;
+        for (dictionary_dir, files) in &sources {
+            for file in files {
+                scan(&mut cache, &mut added, dictionary_dir, file);
+            }
+        }
+        retain_source_locations(&sources)?;
+        save_scan_cache(&config, &mut cache, &sources)?;
+        on_batch(&added);
+
+        // Запись базового словаря тоже вызывает событие, поэтому файлы репозиториев не сканируются
+        let dictionary_repos = config
+            .dictionary_repo
+            .repositories()
+            .iter()
+            .map(|repository| fs::canonicalize(&repository.path))
+            .collect::<Result<Vec<PathBuf>, _>>()?;
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        for root in config.base_directory.roots() {
+            watcher.watch(Path::new(&root.path), RecursiveMode::Recursive)?;
+        }
+        let changed_paths = |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.kind.is_create() || event.kind.is_modify() => event.paths,
+            _ => vec![],
+        };
+        while let Ok(event) = receiver.recv() {
+            let mut changed: HashSet<PathBuf> = changed_paths(event).into_iter().collect();
+            // Редакторы сохраняют файл несколькими событиями подряд, они объединяются в одно сканирование
+            while let Ok(event) = receiver.recv_timeout(debounce) {
+                changed.extend(changed_paths(event));
+            }
+            if changed.is_empty() {
+                continue;
+            }
+            added.clear();
+            let sources = collect_scan_sources(&config, &filter, &patterns)?;
+            for (dictionary_dir, files) in &sources {
+                for file in files {
+                    let Ok(canonical) = fs::canonicalize(file) else {
+                        continue;
+                    };
+                    let in_repository = dictionary_repos.iter().any(|repo| canonical.starts_with(repo));
+                    if changed.contains(&canonical) && !in_repository {
+                        scan(&mut cache, &mut added, dictionary_dir, file);
+                    }
+                }
+            }
+            retain_source_locations(&sources)?;
+            save_scan_cache(&config, &mut cache, &sources)?;
+            on_batch(&added);
+        }
+        Ok(())
+    }
+
+    #[doc = "Следит за файлами словарей репозитория, включая поддиректории пространств имен, и вызывает on_change после каждой серии изменений. Служебные файлы .dms, резервные копии и locations.json изменениями не считаются. Работает, пока не будет прерван"]
+    pub fn watch_repository(
+        dictionary_dir: &str,
+        debounce: Duration,
+        mut on_change: impl FnMut(),
+    ) -> Result<(), StaticDictionaryErrors> {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(Path::new(dictionary_dir), RecursiveMode::Recursive)?;
+        let is_dictionary = |path: &PathBuf| {
+            !path.components().any(|component| component.as_os_str() == ".dms")
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| get_dictionary_language(name).is_ok())
+        };
+        let dictionary_changed = |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() => {
+                event.paths.iter().any(is_dictionary)
+            }
+            _ => false,
+        };
+        while let Ok(event) = receiver.recv() {
+            let mut changed = dictionary_changed(event);
+            // Запись словаря через временный файл и правки нескольких словарей подряд объединяются в одну пересборку
+            while let Ok(event) = receiver.recv_timeout(debounce) {
+                changed |= dictionary_changed(event);
+            }
+            if changed {
+                on_change();
+            }
+        }
+        Ok(())
+    }
+
+    #[doc = "Находит записи базовых словарей, фразы и теги которых больше не встречаются ни в одном файле, который в них сканируется. Возвращает записи по директориям словарей, фразы из manual_translate не считаются неиспользуемыми"]
+    pub fn find_unused_entries(
+        config: &ConfigFileParameters,
+    ) -> Result<BTreeMap<String, Vec<BaseEntry>>, StaticDictionaryErrors> {
+        let filter = PathFilter::new(&config.include_files, &config.exclude_files)?;
+        let patterns = scan_extractors(config)?;
+        let mut unused_entries = BTreeMap::new();
+        for (dictionary_dir, files) in collect_scan_sources(config, &filter, &patterns)? {
+            let mut phrases = HashSet::new();
+            let mut tags = HashSet::new();
+            let extracted = files
+                .par_iter()
+                .map(|file| extract_file(config, &patterns, file, fs::read(file)?))
+                .collect::<Result<Vec<ExtractedFile>, StaticDictionaryErrors>>()?;
+            for (entry, _) in extracted.into_iter().flat_map(|file| file.located).flatten() {
+                tags.insert(entry.tag().to_owned());
+                phrases.insert(entry.phrase);
+            }
+            // Существующие записи не нормализуются при записи, поэтому с найденными фразами сравнивается их нормализованная форма
+            let used = |phrase: &str| match config.normalize_phrases {
+                true => {
+                    phrases.contains(phrase)
+                        || phrases
+                            .iter()
+                            .any(|found| normalize_phrase(found) == normalize_phrase(phrase))
+                }
+                false => phrases.contains(phrase),
+            };
+            let unused: Vec<BaseEntry> = parse_static_basic_entries(&dictionary_dir)?
+                .into_iter()
+                .filter(|entry| {
+                    !used(&entry.phrase)
+                        && !tags.contains(entry.tag())
+                        && !config.manual_translate_words.contains(&entry.phrase)
+                })
+                .collect();
+            if !unused.is_empty() {
+                unused_entries.insert(dictionary_dir, unused);
+            }
+        }
+        Ok(unused_entries)
+    }
+
+    #[doc = "Ищет в файле фразы для добавления в базовый словарь. Двоичные файлы и файлы не в UTF-8 пропускаются"]
+    pub fn get_phrases_from_file(
+        filepath: &str,
+        pattern: Regex,
+    ) -> Result<Vec<String>, StaticDictionaryErrors> {
+        let Some(content) = read_source_file(filepath, SourceEncoding::Utf8)? else {
+            return Ok(vec![]);
+        };
+        let mut results = Vec::new();
+
+        for line in content.lines() {
+            for cap in pattern.captures_iter(line) {
+                if let Some(matched) = cap.get(2) {
+                    results.push(matched.as_str().to_string());
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    #[doc = "Ищет в файле фразы и создает для них записи базового словаря с тегами по выбранной стратегии. Аннотация dms-key: <тег> в строке задает тег вручную"]
+    pub fn get_entries_from_file(
+        filepath: &str,
+        pattern: Regex,
+        key_strategy: KeyStrategy,
+    ) -> Result<Vec<BaseEntry>, StaticDictionaryErrors> {
+        Ok(get_located_entries_from_file(filepath, pattern, key_strategy)?
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect())
+    }
+
+    #[doc = "Ищет в файле фразы, как get_entries_from_file, и возвращает их вместе с номерами строк (начиная с 1). Выражение применяется ко всему файлу, поэтому фразы могут занимать несколько строк"]
+    pub fn get_located_entries_from_file(
+        filepath: &str,
+        pattern: Regex,
+        key_strategy: KeyStrategy,
+    ) -> Result<Vec<(BaseEntry, usize)>, StaticDictionaryErrors> {
+        let content = fs::read_to_string(filepath)?;
+        located_entries_from_content(&content, &pattern, key_strategy)
+    }
+
+    fn located_entries_from_content(
+        content: &str,
+        pattern: &Regex,
+        key_strategy: KeyStrategy,
+    ) -> Result<Vec<(BaseEntry, usize)>, StaticDictionaryErrors> {
+        located_entries(content, regex_phrases(content, pattern), key_strategy)
+    }
+
+    // Фразы из совпадений выражения с номерами строк и тегами из вызовов t("тег", "фраза")
+    fn regex_phrases(content: &str, pattern: &Regex) -> Vec<(String, usize, Option<String>)> {
+        pattern
+            .captures_iter(content)
+            .filter_map(|captures| {
+                let matched = captures.get(0)?;
+                // Совпадение может начинаться с переноса строки перед вызовом функции
+                let start = matched.end() - matched.as_str().trim_start().len();
+                let line = content[..start].matches('\n').count() + 1;
+                captured_phrase(&captures).map(|phrase| (phrase, line, captured_key(&captures)))
+            })
+            .collect()
+    }
+
+    #[doc = "Комментарий, который исключает фразы следующей строки из сканирования"]
+    pub const IGNORE_NEXT_LINE_ANNOTATION: &str = "dms-ignore-next-line";
+    #[doc = "Комментарий, который исключает фразы своей строки из сканирования"]
+    pub const IGNORE_LINE_ANNOTATION: &str = "dms-ignore-line";
+
+    // Создает записи для найденных фраз. Тег из вызова t("тег", "фраза") или аннотация dms-key: <тег>
+    // в строке задает тег вручную, dms-context: <текст> в строке или строкой выше - контекст для переводчиков,
+    // а dms-ignore-line и dms-ignore-next-line исключают фразы из сканирования. Со стратегией manual фраза без тега - ошибка
+    fn located_entries(
+        content: &str,
+        found: Vec<(String, usize, Option<String>)>,
+        key_strategy: KeyStrategy,
+    ) -> Result<Vec<(BaseEntry, usize)>, StaticDictionaryErrors> {
+        let annotation = Regex::new(r"dms-key:\s*([\w.\-]+)").unwrap();
+        let context_annotation = Regex::new(r"dms-context:\s*(.*?)\s*(?:\*/|-->|$)").unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        let line_text = |line: usize| lines.get(line.wrapping_sub(1)).copied().unwrap_or("");
+        let mut results = Vec::new();
+        for (phrase, line, explicit_key) in &found {
+            if line_text(*line).contains(IGNORE_LINE_ANNOTATION)
+                || line_text(line - 1).contains(IGNORE_NEXT_LINE_ANNOTATION)
+            {
+                continue;
+            }
+            let manual_key = annotation
+                .captures(line_text(*line))
+                .and_then(|captures| captures.get(1))
+                .map(|key| key.as_str().to_owned());
+            // Аннотация строкой выше относится к этой фразе, только если в той строке нет своих фраз
+            let previous_has_phrases = found.iter().any(|(_, other, _)| *other == line - 1);
+            let context = [Some(*line), (!previous_has_phrases).then(|| line - 1)]
+                .into_iter()
+                .flatten()
+                .find_map(|line| context_annotation.captures(line_text(line)))
+                .map(|captures| captures[1].to_owned())
+                .filter(|context| !context.is_empty());
+            // Ручной тег однозначно относится к фразе, только если она в строке одна
+            let single_phrase = found.iter().filter(|(_, other, _)| other == line).count() == 1;
+            let mut entry = BaseEntry::with_key_strategy(phrase.to_owned(), key_strategy);
+            if let Some(key) = explicit_key {
+                entry.key = Some(key.to_owned());
+            } else if let (true, Some(key)) = (single_phrase, manual_key) {
+                entry.key = Some(key);
+            } else if key_strategy == KeyStrategy::Manual {
+                return Err(StaticDictionaryErrors::MissingKey(phrase.to_owned(), *line));
+            }
+            entry.context = context;
+            let names = placeholder_names(phrase);
+            if !names.is_empty() {
+                entry.placeholders = Some(names);
+            }
+            results.push((entry, *line));
+        }
+        Ok(results)
+    }
+
+    #[doc = "Типы данных в парсере"]
+    pub mod types {
+        use std::collections::{BTreeMap, HashMap};
+        use std::path::Path;
+
+        use regex::Regex;
+        use serde::{Deserialize, Serialize};
+
+        use crate::errors::errors::StaticDictionaryErrors;
+        use crate::file_system::namespace_directory;
+        use crate::source_encoding::SourceEncoding;
+        use crate::types::KeyStrategy;
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[doc = "Конфиг для настройки параметров парсера"]
+        pub struct ConfigFileParameters {
+            /// Директории проекта, в которых нужно сканировать файлы: одна, список или пакеты монорепозитория
+            #[serde(rename = "base")]
+            pub base_directory: BaseDirectories,
+            /// Glob-шаблоны путей, которые нужно игнорировать (node_modules, src/**/*.test.ts)
+            #[serde(rename = "exclude")]
+            pub exclude_files: Vec<String>,
+            /// Glob-шаблоны файлов, которые нужно сканировать (src/**/*.tsx). Если не указаны, сканируются все файлы с расширениями из include
+            #[serde(default)]
+            pub include_files: Vec<String>,
+            /// Репозиторий словарей: путь, список путей или имя -> путь для проектов, которые разделяют переводы по областям
+            #[serde(rename = "dictionary_repo")]
+            pub dictionary_repo: DictionaryRepositories,
+            /// Директория, куда будут собираться итоговые словари
+            #[serde(rename = "output_dir")]
+            pub output_dir: String,
             /// Конфигурации для языков
             #[serde(rename = "include")]
             pub languages_configurations: Vec<HashMap<String, LanguageConfiguration>>,
             /// Фразы, которые не должны переводиться автоматически, только в ручную
             #[serde(rename = "manual_translate")]
-            pub manual_translate_words: Vec<String>
+            pub manual_translate_words: Vec<String>,
+            /// Стратегия генерации тегов для новых фраз (phrase, slug, hash, manual)
+            #[serde(default)]
+            pub key_strategy: KeyStrategy,
+            /// Нормализовать фразы (NFC, пробелы по краям и повторяющиеся пробелы) при сканировании и записи базового словаря
+            #[serde(default)]
+            pub normalize_phrases: bool,
+            /// Цепочки запасных локалей для сборки, например "fr-CA": ["fr", "en"]
+            #[serde(default)]
+            pub fallbacks: BTreeMap<String, Vec<String>>,
+            /// Разворачивать ключи с точками (checkout.payment.title) во вложенные объекты при сборке (сейчас для i18next и ngx-translate)
+            #[serde(default)]
+            pub nested_keys: bool,
+            /// Пространства имен (common, checkout...): поддиректории репозитория словарей со своими базовым и дочерними словарями, которые переводятся независимо
+            #[serde(default)]
+            pub namespaces: Vec<String>,
+            /// Максимальная глубина вложенности директорий при сканировании (0 - только base). Без ограничения, если не указана
+            #[serde(default)]
+            pub max_depth: Option<usize>,
+            /// Пропускать при сканировании файлы из .gitignore и .ignore (включено по умолчанию)
+            #[serde(default = "default_respect_gitignore")]
+            pub respect_gitignore: bool,
+            /// Переходить при сканировании по символическим ссылкам (выключено по умолчанию). Циклы из ссылок пропускаются
+            #[serde(default)]
+            pub follow_symlinks: bool,
+            /// Файл кеша сканирования с хешами просканированных файлов
+            #[serde(default = "default_scan_cache")]
+            pub scan_cache: String,
+            /// Кодировка исходных файлов не в UTF-8: auto (определяется по содержимому) или название (windows-1251, shift_jis). По умолчанию такие файлы пропускаются
+            #[serde(default)]
+            pub encoding: SourceEncoding,
+            /// Фильтры, которые отбрасывают найденные строки, не похожие на фразы интерфейса
+            #[serde(default)]
+            pub filters: PhraseFilters,
+            /// Языки перевода по умолчанию для команд, которым языки не переданы в командной строке
+            #[serde(default)]
+            pub languages: Vec<String>,
+            /// Настройки автопереводчиков по умолчанию для translate auto
+            #[serde(default)]
+            pub translator: TranslatorSettings,
+        }
+
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[doc = "Настройки автопереводчиков из конфига. Ключ DeepL API в конфиг не записывается, он берется из DEEPL_API_KEY"]
+        pub struct TranslatorSettings {
+            /// Хостинг LibreTranslate
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub libretranslate_host: Option<String>,
+            /// Хостинг DeepL API
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub deepl_host: Option<String>,
+        }
+
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[doc = "Фильтры найденных при сканировании строк. По умолчанию выключены"]
+        pub struct PhraseFilters {
+            /// Минимальная длина фразы в символах без пробелов по краям
+            #[serde(default)]
+            pub min_length: usize,
+            /// Пропускать строки без букв: числа, CSS-значения, знаки препинания
+            #[serde(default)]
+            pub require_letters: bool,
+            /// Пропускать константы из латинских заглавных букв, цифр и _ (API_KEY, GET)
+            #[serde(default)]
+            pub ignore_constants: bool,
+            /// Регулярные выражения: строки, в которых они находят совпадение, пропускаются
+            #[serde(default)]
+            pub reject: Vec<RejectPattern>,
+        }
+
+        impl PhraseFilters {
+            #[doc = "Проходит ли найденная строка все фильтры"]
+            pub fn accepts(&self, phrase: &str) -> bool {
+                let trimmed = phrase.trim();
+                let is_constant = trimmed.chars().count() > 1
+                    && trimmed.chars().any(|c| c.is_ascii_uppercase())
+                    && trimmed
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+                trimmed.chars().count() >= self.min_length
+                    && (!self.require_letters || trimmed.chars().any(char::is_alphabetic))
+                    && !(self.ignore_constants && is_constant)
+                    && !self.reject.iter().any(|pattern| pattern.0.is_match(phrase))
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        #[doc = "Регулярное выражение из фильтров сканирования. Проверяется при чтении конфига"]
+        pub struct RejectPattern(pub Regex);
+
+        impl PartialEq for RejectPattern {
+            fn eq(&self, other: &RejectPattern) -> bool {
+                self.0.as_str() == other.0.as_str()
+            }
+        }
+
+        impl Serialize for RejectPattern {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.0.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for RejectPattern {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let pattern = String::deserialize(deserializer)?;
+                Regex::new(&pattern)
+                    .map(RejectPattern)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+
+        fn default_respect_gitignore() -> bool {
+            true
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[doc = "Директория проекта для сканирования и пространство имен, в базовый словарь которого попадают ее фразы"]
+        pub struct SourceRoot {
+            pub path: String,
+            /// Поддиректория репозитория словарей. Без нее фразы попадают в корень репозитория
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub namespace: Option<String>,
+            /// Имя репозитория словарей из dictionary_repo. Без него фразы попадают в первый репозиторий
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub repo: Option<String>,
+        }
+
+        impl SourceRoot {
+            #[doc = "Директория словарей, в которую записываются фразы из этой директории проекта"]
+            pub fn dictionary_dir(&self, dictionary_repo: &str) -> String {
+                match &self.namespace {
+                    Some(namespace) => namespace_directory(dictionary_repo, namespace),
+                    None => dictionary_repo.to_owned(),
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[serde(untagged)]
+        #[doc = "Директория в списке base: путь строкой или объект с path и namespace"]
+        pub enum SourceDirectory {
+            Path(String),
+            Root(SourceRoot),
+        }
+
+        impl SourceDirectory {
+            fn to_root(&self, default_namespace: Option<&str>) -> SourceRoot {
+                match self {
+                    SourceDirectory::Path(path) => SourceRoot {
+                        path: path.to_owned(),
+                        namespace: default_namespace.map(str::to_owned),
+                        repo: None,
+                    },
+                    SourceDirectory::Root(root) => SourceRoot {
+                        namespace: root
+                            .namespace
+                            .clone()
+                            .or_else(|| default_namespace.map(str::to_owned)),
+                        ..root.clone()
+                    },
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[serde(untagged)]
+        #[doc = "Параметр base: одна директория, список директорий или пакеты монорепозитория"]
+        pub enum BaseDirectories {
+            Single(String),
+            List(Vec<SourceDirectory>),
+            /// Имя пакета -> директория. Если пространство имен не указано явно, им становится имя пакета
+            Packages(BTreeMap<String, SourceDirectory>),
+        }
+
+        impl BaseDirectories {
+            #[doc = "Все директории для сканирования вместе с их пространствами имен"]
+            pub fn roots(&self) -> Vec<SourceRoot> {
+                match self {
+                    BaseDirectories::Single(path) => vec![SourceRoot {
+                        path: path.to_owned(),
+                        namespace: None,
+                        repo: None,
+                    }],
+                    BaseDirectories::List(directories) => directories
+                        .iter()
+                        .map(|directory| directory.to_root(None))
+                        .collect(),
+                    BaseDirectories::Packages(packages) => packages
+                        .iter()
+                        .map(|(package, directory)| directory.to_root(Some(package)))
+                        .collect(),
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize)]
+        #[doc = "Репозиторий словарей из параметра dictionary_repo"]
+        pub struct DictionaryRepository {
+            /// Имя для --repo: ключ в конфиге или последняя часть пути
+            pub name: String,
+            pub path: String,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[serde(untagged)]
+        #[doc = "Параметр dictionary_repo: один репозиторий, список репозиториев или имя -> путь"]
+        pub enum DictionaryRepositories {
+            Single(String),
+            List(Vec<String>),
+            Named(BTreeMap<String, String>),
+        }
+
+        impl DictionaryRepositories {
+            #[doc = "Все репозитории в порядке из конфига (именованные - по алфавиту)"]
+            pub fn repositories(&self) -> Vec<DictionaryRepository> {
+                let named = |path: &String| DictionaryRepository {
+                    name: Path::new(path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.to_owned()),
+                    path: path.to_owned(),
+                };
+                match self {
+                    DictionaryRepositories::Single(path) => vec![named(path)],
+                    DictionaryRepositories::List(paths) => paths.iter().map(named).collect(),
+                    DictionaryRepositories::Named(repositories) => repositories
+                        .iter()
+                        .map(|(name, path)| DictionaryRepository {
+                            name: name.to_owned(),
+                            path: path.to_owned(),
+                        })
+                        .collect(),
+                }
+            }
+
+            #[doc = "Первый репозиторий: в него попадают фразы при сканировании, если в base не указан другой"]
+            pub fn primary(&self) -> &str {
+                match self {
+                    DictionaryRepositories::Single(path) => path,
+                    DictionaryRepositories::List(paths) => {
+                        paths.first().map(String::as_str).unwrap_or_default()
+                    }
+                    DictionaryRepositories::Named(repositories) => repositories
+                        .values()
+                        .next()
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                }
+            }
+
+            #[doc = "Репозиторий с указанным именем или все репозитории, если имя не передано"]
+            pub fn select(
+                &self,
+                name: Option<&str>,
+            ) -> Result<Vec<DictionaryRepository>, StaticDictionaryErrors> {
+                let repositories = self.repositories();
+                let Some(name) = name else {
+                    return Ok(repositories);
+                };
+                let names: Vec<String> = repositories
+                    .iter()
+                    .map(|repository| repository.name.clone())
+                    .collect();
+                match repositories
+                    .into_iter()
+                    .find(|repository| repository.name == name)
+                {
+                    Some(repository) => Ok(vec![repository]),
+                    None => Err(StaticDictionaryErrors::InvalidConfig(format!(
+                        "репозиторий словарей {} не найден в dictionary_repo, доступны: {}",
+                        name,
+                        names.join(", ")
+                    ))),
+                }
+            }
+
+            #[doc = "Путь к репозиторию с указанным именем или к первому репозиторию"]
+            pub fn path_for(&self, name: Option<&str>) -> Result<String, StaticDictionaryErrors> {
+                match name {
+                    Some(name) => Ok(self.select(Some(name))?.remove(0).path),
+                    None => Ok(self.primary().to_owned()),
+                }
+            }
+        }
+
+        fn default_scan_cache() -> String {
+            crate::scan_cache::DEFAULT_SCAN_CACHE_PATH.to_owned()
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[doc = "Настройки парсинга: настройки для каждого конкретного языка, файлы которого будут парсится"]
+        pub struct LanguageConfiguration {
+            /// Расширения файлов, которые нужно проверять для конкретного языка
+            #[serde(rename = "ext", default)]
+            pub file_extensions: Vec<String>,
+            /// Начало строки
+            #[serde(rename = "regexp-start", default)]
+            pub string_start: Vec<String>,
+            /// Конец строки
+            #[serde(rename = "regexp-end", default)]
+            pub string_end: Vec<String>,
+            /// Функции, из вызовов которых берется первый строковый аргумент (t, i18n.t, $t)
+            #[serde(default)]
+            pub call_patterns: Vec<String>,
+            /// Макросы Rust, из которых берется строковый литерал, в том числе сырой (t!, fl!)
+            #[serde(default)]
+            pub macro_patterns: Vec<String>,
+            /// Способ поиска фраз: regex (по умолчанию), tree-sitter для JS/TS/JSX/TSX или html для разметки
+            #[serde(default)]
+            pub extractor: ExtractorKind,
+            /// Готовые настройки для языка, незаполненные поля берутся из них
+            #[serde(default)]
+            pub preset: Option<LanguagePreset>,
+            /// Атрибуты с текстом интерфейса для extractor html, по умолчанию placeholder, title, alt и aria-label
+            #[serde(default, skip_serializing_if = "Vec::is_empty")]
+            pub attributes: Vec<String>,
+            /// Кавычки фраз между regexp-start и regexp-end для отдельных расширений ("py": "'"), по умолчанию "
+            #[serde(rename = "delimiters", default, skip_serializing_if = "BTreeMap::is_empty")]
+            pub string_delimiters: BTreeMap<String, String>,
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        #[doc = "Готовые настройки сканирования для языков программирования"]
+        pub enum LanguagePreset {
+            /// Файлы .rs и макросы t! (rust-i18n) и fl! (fluent)
+            Rust,
+        }
+
+        impl LanguageConfiguration {
+            #[doc = "Настройки с примененным пресетом: пустые расширения и макросы заполняются из него"]
+            pub fn with_preset(&self) -> LanguageConfiguration {
+                let mut configuration = self.clone();
+                match self.preset {
+                    Some(LanguagePreset::Rust) => {
+                        if configuration.file_extensions.is_empty() {
+                            configuration.file_extensions = vec!["rs".to_owned()];
+                        }
+                        if configuration.macro_patterns.is_empty() {
+                            configuration.macro_patterns = vec!["t".to_owned(), "fl".to_owned()];
+                        }
+                    }
+                    None => {}
+                }
+                configuration
+            }
+
+            #[doc = "Кавычки фраз для расширения (с точкой или без): из delimiters или \" по умолчанию"]
+            pub fn delimiter_for(&self, extension: &str) -> &str {
+                let extension = extension.trim_start_matches('.');
+                self.string_delimiters
+                    .iter()
+                    .find(|(configured, _)| configured.trim_start_matches('.') == extension)
+                    .map(|(_, delimiter)| delimiter.as_str())
+                    .unwrap_or(crate::parser::DEFAULT_STRING_DELIMITER)
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        #[doc = "Способ поиска фраз в файлах"]
+        pub enum ExtractorKind {
+            /// Регулярные выражения по строкам файла
+            #[default]
+            Regex,
+            /// Синтаксическое дерево tree-sitter (нужна сборка с feature tree-sitter)
+            TreeSitter,
+            /// Текстовые узлы и атрибуты разметки HTML, XML, Vue и Svelte
+            Html,
+        }
+
+        impl ConfigFileParameters {
+
+            #[doc = "Парсинг конфиг-файла в структуру"]
+            pub fn from_json(
+                json_content: &str,
+            ) -> Result<ConfigFileParameters, serde_json::Error> {
+                crate::dictionary_format::parse_relaxed_json(json_content)
+            }
+
+            #[doc = "Превращает структуру в JSON"]
+            pub fn into_json(&self) -> Result<String, serde_json::Error> {
+                serde_json::to_string(&self)
+            }
+        }
+    }
+}
+
+#[doc = "Функционал для генерации и парсинга static-словарей"]
+pub mod static_translate {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::join_all;
+    use globset::GlobBuilder;
+    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+    use regex::Regex;
+    use serde::{Deserialize, Serialize};
+
+    use crate::dictionary_format::{
+        read_dictionary_file, translated_dictionary_path, write_dictionary_file, DictionaryFormat,
+    };
+    use crate::errors::errors::StaticDictionaryErrors;
+    use crate::parser::get_basic_dictionary;
+    use crate::parser::get_dictionary_language;
+    use crate::file_system::{atomic_write, check_dictionary_exists, find_all_translated_languages};
+    use crate::source_locations::{
+        read_source_locations, rename_in_sources, write_source_locations,
+    };
+    use crate::language::validate_languages;
+    use crate::normalization::normalize_new_entries;
+    use crate::progress::{NoProgress, Progress};
+    use crate::types::ApiArgs;
+    use crate::types::{
+        BaseEntry, DictionaryEntry, EntryStatus, KeyStrategy, TranslatedDictionary, TranslatorApi,
+        TranslatorApis, Word,
+    };
+    use crate::web_api::TranslatorBackend;
+
+    #[doc = "Парсит список слов из базового словаря в Vec<Word>"]
+    pub fn parse_static_basic_dictionary(
+        dictionary_dir: &str,
+    ) -> Result<Vec<String>, StaticDictionaryErrors> {
+        Ok(parse_static_basic_entries(dictionary_dir)?
+            .into_iter()
+            .map(|entry| entry.phrase)
+            .collect())
+    }
+
+    #[doc = "Парсит записи базового словаря вместе с формами множественного числа"]
+    pub fn parse_static_basic_entries(
+        dictionary_dir: &str,
+    ) -> Result<Vec<BaseEntry>, StaticDictionaryErrors> {
+        let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
+        read_dictionary_file(format!("{}/{}", dictionary_dir, basic_dictionary))
+    }
+
+    #[doc = "Парсит дочерний словарь и возвращает вектор с структурами типа Word"]
+    pub fn parse_translated_dictionary(
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<Vec<Word>, StaticDictionaryErrors> {
+        let path = translated_dictionary_path(dictionary_dir, language)?;
+        let file_content = fs::read_to_string(&path)?;
+        parse_translated_dictionary_content(&file_content, language, DictionaryFormat::from_path(&path))
+    }
+
+    #[doc = "Парсит содержимое дочернего словаря (например, прочитанное из git) в вектор Word"]
+    pub fn parse_translated_dictionary_content(
+        file_content: &str,
+        language: &str,
+        format: DictionaryFormat,
+    ) -> Result<Vec<Word>, StaticDictionaryErrors> {
+        let dictionary: TranslatedDictionary = format.parse(file_content)?;
+        Ok(dictionary
+            .iter()
+            .flat_map(|(tag, entry)| entry.words(tag, language))
+            .collect())
+    }
+
+    #[doc = "Читает дочерний словарь в формате v1 или v2"]
+    pub fn read_translated_dictionary(
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<TranslatedDictionary, StaticDictionaryErrors> {
+        read_dictionary_file(translated_dictionary_path(dictionary_dir, language)?)
+    }
+
+    #[doc = "Записывает дочерний словарь в формате v2, перезаписывая существующий файл"]
+    pub fn write_translated_dictionary(
+        dictionary_dir: &str,
+        language: &str,
+        dictionary: &TranslatedDictionary,
+    ) -> Result<(), StaticDictionaryErrors> {
+        write_dictionary_file(translated_dictionary_path(dictionary_dir, language)?, dictionary)
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[doc = "Запись дочернего словаря вместе с исходными текстами из базового словаря"]
+    pub struct PairedEntry {
+        pub tag: String,
+        pub entry: DictionaryEntry,
+        /// Пары (исходный текст, перевод) для значения, каждой формы множественного числа и варианта
+        pub pairs: Vec<(Word, Word)>,
+        /// Переменные подстановки, записанные в базовом словаре
+        pub placeholders: Vec<String>,
+    }
+
+    #[doc = "Сопоставляет записи дочернего словаря с исходными фразами базового словаря"]
+    pub fn pair_with_sources(
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<Vec<PairedEntry>, StaticDictionaryErrors> {
+        let basic_language = get_basic_language(dictionary_dir)?;
+        let base_entries = parse_static_basic_entries(dictionary_dir)?;
+        let base_by_tag: HashMap<&str, &BaseEntry> = base_entries
+            .iter()
+            .map(|entry| (entry.tag(), entry))
+            .collect();
+        let dictionary = read_translated_dictionary(dictionary_dir, language)?;
+
+        Ok(dictionary
+            .into_iter()
+            .map(|(tag, entry)| {
+                let sources: HashMap<String, Word> = base_by_tag
+                    .get(tag.as_str())
+                    .map(|base| base.words_for_translation(&basic_language, language))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|word| (word.suffixed_tag(), word))
+                    .collect();
+                let pairs = entry
+                    .words(&tag, language)
+                    .into_iter()
+                    .map(|translation| {
+                        let source = sources
+                            .get(&translation.suffixed_tag())
+                            .cloned()
+                            .unwrap_or_else(|| {
+                                Word::new(String::new(), tag.clone(), basic_language.clone())
+                            });
+                        (source, translation)
+                    })
+                    .collect();
+                let placeholders = base_by_tag
+                    .get(tag.as_str())
+                    .and_then(|base| base.placeholders.clone())
+                    .unwrap_or_default();
+                PairedEntry {
+                    tag,
+                    entry,
+                    pairs,
+                    placeholders,
+                }
+            })
+            .collect())
+    }
+
+    #[doc = "Возвращает язык базового словаря"]
+    pub fn get_basic_language(dictionary_dir: &str) -> Result<String, StaticDictionaryErrors> {
+        get_dictionary_language(&get_basic_dictionary(dictionary_dir)?)
+            .map(String::from)
+            .map_err(|_| StaticDictionaryErrors::BasicDictionaryNotFound)
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[doc = "Состояние перевода на один язык"]
+    pub struct LanguageStatus {
+        pub language: String,
+        /// Ключей в базовом словаре
+        pub total: usize,
+        /// Ключей, у которых переведены значение и все формы
+        pub translated: usize,
+        /// Ключей без перевода или с непереведенными формами, в том числе отсутствующих в дочернем словаре
+        pub empty: usize,
+        /// Переведенных ключей, перевод которых выполнен автопереводчиком и не проверен
+        pub machine: usize,
+        /// Переведенных ключей, перевод которых выполнен или проверен человеком
+        pub human: usize,
+        /// Переведенных ключей, исходная фраза которых изменилась после перевода
+        #[serde(default)]
+        pub outdated: usize,
+    }
+
+    impl LanguageStatus {
+        #[doc = "Процент переведенных ключей"]
+        pub fn percentage(&self) -> f64 {
+            match self.total {
+                0 => 0.0,
+                total => self.translated as f64 * 100.0 / total as f64,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[doc = "Состояние перевода репозитория словарей по языкам"]
+    pub struct RepositoryStatus {
+        pub basic_language: String,
+        /// Ключей в базовом словаре
+        pub total: usize,
+        pub languages: Vec<LanguageStatus>,
+    }
+
+    #[doc = "Считает для каждого языка, сколько ключей базового словаря переведено, не переведено, переведено машинно и человеком, и сколько переводов устарело. Без списка языков учитываются все дочерние словари. Ключи дочерних словарей, которых нет в базовом, не учитываются"]
+    pub fn status(
+        dictionary_dir: &str,
+        languages: Option<Vec<String>>,
+    ) -> Result<RepositoryStatus, StaticDictionaryErrors> {
+        let languages = match languages {
+            Some(languages) => languages,
+            None => find_all_translated_languages(dictionary_dir)?,
+        };
+        let base_entries = parse_static_basic_entries(dictionary_dir)?;
+        let mut report = RepositoryStatus {
+            basic_language: get_basic_language(dictionary_dir)?,
+            total: base_entries.len(),
+            languages: vec![],
+        };
+        for language in languages {
+            let dictionary = read_existing_dictionary(dictionary_dir, &language)?;
+            let mut language_status = LanguageStatus {
+                language,
+                total: base_entries.len(),
+                ..Default::default()
+            };
+            for entry in &base_entries {
+                match dictionary.get(entry.tag()) {
+                    Some(translated) if translated.is_translated() => {
+                        language_status.translated += 1;
+                        if translated.is_outdated(entry) {
+                            language_status.outdated += 1;
+                        }
+                        match translated.status {
+                            EntryStatus::Machine => language_status.machine += 1,
+                            _ => language_status.human += 1,
+                        }
+                    }
+                    _ => language_status.empty += 1,
+                }
+            }
+            report.languages.push(language_status);
+        }
+        Ok(report)
+    }
+
+    #[doc = "Генерирует пустые статические словари из базового статического словаря"]
+    pub fn generate_empty_dictionaries_from_static_basic(
+        dictionary_dir: &str,
+        languages: Vec<String>,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let languages = validate_languages(&languages)?;
+        let mut basic_dictionary = parse_static_basic_entries(dictionary_dir)?;
+        basic_dictionary.dedup();
+        let basic_language = get_basic_language(dictionary_dir)?;
+
+        languages
+            .par_iter()
+            .try_for_each(|language| -> Result<(), StaticDictionaryErrors> {
+                let mut dictionary: TranslatedDictionary = basic_dictionary
+                    .iter()
+                    .map(|entry| {
+                        (
+                            entry.tag().to_owned(),
+                            DictionaryEntry::from_base(entry, &basic_language, language),
+                        )
+                    })
+                    .collect();
+                preserve_existing_entries(dictionary_dir, language, &mut dictionary)?;
+                write_translated_dictionary(dictionary_dir, language, &dictionary)
+            })
+    }
+
+    #[doc = "Читает дочерний словарь, если он существует, иначе возвращает пустой"]
+    pub fn read_existing_dictionary(
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<TranslatedDictionary, StaticDictionaryErrors> {
+        if !check_dictionary_exists(dictionary_dir, language) {
+            return Ok(TranslatedDictionary::new());
+        }
+        read_translated_dictionary(dictionary_dir, language)
+    }
+
+    #[doc = "Теги заблокированных записей дочернего словаря"]
+    pub fn locked_tags(
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<HashSet<String>, StaticDictionaryErrors> {
+        Ok(read_existing_dictionary(dictionary_dir, language)?
+            .into_iter()
+            .filter(|(_, entry)| entry.locked)
+            .map(|(tag, _)| tag)
+            .collect())
+    }
+
+    #[doc = "Переносит из существующего дочернего словаря в перезаписываемый комментарии и заблокированные записи, чтобы генерация и автоперевод их не теряли"]
+    pub fn preserve_existing_entries(
+        dictionary_dir: &str,
+        language: &str,
+        dictionary: &mut TranslatedDictionary,
+    ) -> Result<(), StaticDictionaryErrors> {
+        for (tag, existing) in read_existing_dictionary(dictionary_dir, language)? {
+            if existing.locked {
+                dictionary.insert(tag, existing);
+            } else if let (Some(entry), Some(comment)) = (dictionary.get_mut(&tag), existing.comment) {
+                entry.comment = Some(comment);
+            }
+        }
+        Ok(())
+    }
+
+    #[doc = "Блокирует или разблокирует записи дочерних словарей, теги которых подходят под один из шаблонов. Возвращает количество измененных записей"]
+    pub fn set_entries_locked(
+        dictionary_dir: &str,
+        languages: &[String],
+        patterns: &[Regex],
+        locked: bool,
+    ) -> Result<usize, StaticDictionaryErrors> {
+        let mut changed = 0;
+        for language in languages {
+            let mut dictionary = read_translated_dictionary(dictionary_dir, language)?;
+            for (tag, entry) in dictionary.iter_mut() {
+                if entry.locked != locked && patterns.iter().any(|pattern| pattern.is_match(tag)) {
+                    entry.locked = locked;
+                    changed += 1;
+                }
+            }
+            write_translated_dictionary(dictionary_dir, language, &dictionary)?;
+        }
+        Ok(changed)
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize)]
+    #[doc = "Ключи, добавленные в дочерние словари и удаленные из них при синхронизации, по языкам"]
+    pub struct SyncReport {
+        pub added: BTreeMap<String, Vec<String>>,
+        pub removed: BTreeMap<String, Vec<String>>,
+    }
+
+    impl SyncReport {
+        #[doc = "Изменила ли синхронизация хотя бы один словарь"]
+        pub fn is_empty(&self) -> bool {
+            self.added.values().all(Vec::is_empty) && self.removed.values().all(Vec::is_empty)
+        }
+    }
+
+    #[doc = "Переносит изменения базового словаря в существующие дочерние словари: добавляет новые ключи пустыми записями, а с prune удаляет ключи, которых больше нет в базовом. Существующие переводы не меняются, заблокированные записи не удаляются. Записываются только изменившиеся словари"]
+    pub fn sync_dictionaries(
+        dictionary_dir: &str,
+        prune: bool,
+    ) -> Result<SyncReport, StaticDictionaryErrors> {
+        let basic_language = get_basic_language(dictionary_dir)?;
+        let base_entries = parse_static_basic_entries(dictionary_dir)?;
+        let base_tags: HashSet<&str> = base_entries.iter().map(BaseEntry::tag).collect();
+        let mut report = SyncReport::default();
+        for language in find_all_translated_languages(dictionary_dir)? {
+            let mut dictionary = read_translated_dictionary(dictionary_dir, &language)?;
+            let mut added = vec![];
+            for entry in &base_entries {
+                if !dictionary.contains_key(entry.tag()) {
+                    dictionary.insert(
+                        entry.tag().to_owned(),
+                        DictionaryEntry::from_base(entry, &basic_language, &language),
+                    );
+                    added.push(entry.tag().to_owned());
+                }
+            }
+            let removed: Vec<String> = match prune {
+                true => dictionary
+                    .iter()
+                    .filter(|(tag, entry)| !entry.locked && !base_tags.contains(tag.as_str()))
+                    .map(|(tag, _)| tag.to_owned())
+                    .collect(),
+                false => vec![],
+            };
+            for tag in &removed {
+                dictionary.remove(tag);
+            }
+            if !added.is_empty() || !removed.is_empty() {
+                write_translated_dictionary(dictionary_dir, &language, &dictionary)?;
+            }
+            report.added.insert(language.to_owned(), added);
+            report.removed.insert(language, removed);
+        }
+        Ok(report)
+    }
+
+    #[doc = "Генериует статические словари на основе базового, а потом автоматически их переводит с помощью выбранного автопереводчика"]
+    // Когда я писал это, только двое знали что тут вообще творится - это я и Бог. Сейчас только Бог знает, что здесь происходит....
+    // А не, кажись я допер че я тут понаписал
+    pub async fn autotranslate_from_basic_dictionary(
+        dictionary_dir: &str,
+        target_languages: Vec<String>,
+        translator_api: TranslatorApis,
+        api_args: ApiArgs,
+    ) -> Result<(), StaticDictionaryErrors> {
+        autotranslate_with_progress(
+            dictionary_dir,
+            target_languages,
+            translator_api,
+            api_args,
+            Arc::new(NoProgress),
+        )
+        .await
+    }
+
+    // Фразы для отправки переводчику и заблокированные записи языка. Каждая форма множественного числа и каждый вариант фразы переводятся отдельно
+    fn autotranslate_words(
+        dictionary_dir: &str,
+        basic_dictionary: &[BaseEntry],
+        basic_language: &str,
+        language: &str,
+    ) -> Result<(Vec<Word>, HashSet<String>), StaticDictionaryErrors> {
+        let locked = locked_tags(dictionary_dir, language)?;
+        let words = basic_dictionary
+            .iter()
+            .filter(|entry| !locked.contains(entry.tag()))
+            .flat_map(|entry| entry.words_for_translation(basic_language, language))
+            .collect();
+        Ok((words, locked))
+    }
+
+    #[doc = "Сколько фраз автоперевод отправил бы переводчику для каждого языка. Ничего не переводит и не записывает"]
+    pub fn autotranslate_plan(
+        dictionary_dir: &str,
+        target_languages: Vec<String>,
+    ) -> Result<BTreeMap<String, usize>, StaticDictionaryErrors> {
+        let target_languages = validate_languages(&target_languages)?;
+        let mut basic_dictionary = parse_static_basic_entries(dictionary_dir)?;
+        basic_dictionary.dedup();
+        let basic_language = get_basic_language(dictionary_dir)?;
+        target_languages
+            .into_iter()
+            .map(|language| {
+                let (words, _) =
+                    autotranslate_words(dictionary_dir, &basic_dictionary, &basic_language, &language)?;
+                Ok((language, words.len()))
+            })
+            .collect()
+    }
+
+    #[doc = "Переводит словари, как autotranslate_from_basic_dictionary, и сообщает в progress о каждой переведенной фразе. Этап - язык перевода"]
+    pub async fn autotranslate_with_progress(
+        dictionary_dir: &str,
+        target_languages: Vec<String>,
+        translator_api: TranslatorApis,
+        api_args: ApiArgs,
+        progress: Arc<dyn Progress>,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let target_languages = validate_languages(&target_languages)?;
+        let mut basic_dictionary = parse_static_basic_entries(dictionary_dir)?;
+        basic_dictionary.dedup();
+        let basic_language = get_basic_language(dictionary_dir)?;
+
+        let mut words_by_language = vec![];
+        for target_language in target_languages {
+            let (words, locked) =
+                autotranslate_words(dictionary_dir, &basic_dictionary, &basic_language, &target_language)?;
+            tracing::info!(
+                "{}: фраз для перевода {}, заблокированных записей {}",
+                target_language,
+                words.len(),
+                locked.len()
+            );
+            words_by_language.push((target_language, words));
+        }
+
+        let dictionaries = machine_translate(
+            &basic_dictionary,
+            &basic_language,
+            words_by_language,
+            translator_api,
+            &api_args,
+            progress,
+        )
+        .await?;
+        for (language, mut dictionary) in dictionaries {
+            if api_args.suggestions {
+                write_suggestions(dictionary_dir, &language, dictionary)?;
+                continue;
+            }
+            preserve_existing_entries(dictionary_dir, &language, &mut dictionary)?;
+            write_translated_dictionary(dictionary_dir, &language, &dictionary)?;
+        }
+
+        Ok(())
+    }
+
+    #[doc = "Переводит только записи без перевода: новые фразы базового словаря и пустые записи дочерних словарей. С tags переводятся только записи с этими тегами. Машинные переводы добавляются в существующие словари, переведенные и заблокированные записи не меняются"]
+    pub async fn autotranslate_untranslated(
+        dictionary_dir: &str,
+        target_languages: Vec<String>,
+        translator_api: TranslatorApis,
+        api_args: ApiArgs,
+        tags: Option<&BTreeSet<String>>,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let target_languages = validate_languages(&target_languages)?;
+        let mut basic_dictionary = parse_static_basic_entries(dictionary_dir)?;
+        basic_dictionary.dedup();
+        let basic_language = get_basic_language(dictionary_dir)?;
+
+        let mut existing = HashMap::new();
+        let mut words_by_language = vec![];
+        for language in target_languages {
+            let dictionary = read_existing_dictionary(dictionary_dir, &language)?;
+            let words: Vec<Word> = basic_dictionary
+                .iter()
+                .filter(|entry| tags.is_none_or(|tags| tags.contains(entry.tag())))
+                .filter(|entry| {
+                    dictionary
+                        .get(entry.tag())
+                        .is_none_or(|entry| !entry.locked && !entry.is_translated())
+                })
+                .flat_map(|entry| entry.words_for_translation(&basic_language, &language))
+                .collect();
+            tracing::info!("{}: фраз без перевода {}", language, words.len());
+            words_by_language.push((language.clone(), words));
+            existing.insert(language, dictionary);
+        }
+
+        let dictionaries = machine_translate(
+            &basic_dictionary,
+            &basic_language,
+            words_by_language,
+            translator_api,
+            &api_args,
+            Arc::new(NoProgress),
+        )
+        .await?;
+        for (language, machine) in dictionaries {
+            if machine.is_empty() {
+                continue;
+            }
+            if api_args.suggestions {
+                write_suggestions(dictionary_dir, &language, machine)?;
+                continue;
+            }
+            let mut dictionary = existing.remove(&language).unwrap_or_default();
+            for (tag, mut entry) in machine {
+                if let Some(comment) = dictionary.get(&tag).and_then(|existing| existing.comment.clone()) {
+                    entry.comment = Some(comment);
+                }
+                dictionary.insert(tag, entry);
+            }
+            write_translated_dictionary(dictionary_dir, &language, &dictionary)?;
+        }
+
+        Ok(())
+    }
+
+    // Отправляет фразы переводчику и собирает из переводов записи дочерних словарей по языкам со статусом машинного перевода
+    async fn machine_translate(
+        basic_dictionary: &[BaseEntry],
+        basic_language: &str,
+        words_by_language: Vec<(String, Vec<Word>)>,
+        translator_api: TranslatorApis,
+        api_args: &ApiArgs,
+        progress: Arc<dyn Progress>,
+    ) -> Result<HashMap<String, TranslatedDictionary>, StaticDictionaryErrors> {
+        let entries_by_tag: HashMap<&str, &BaseEntry> = basic_dictionary
+            .iter()
+            .map(|entry| (entry.tag(), entry))
+            .collect();
+
+        let translator = Arc::new(TranslatorBackend::new(translator_api, api_args.clone()));
+
+        let mut tasks = vec![];
+        let mut dictionaries: HashMap<String, TranslatedDictionary> = HashMap::new();
+
+        for (target_language, words) in words_by_language {
+            progress.start(&target_language, words.len());
+            dictionaries.insert(target_language.clone(), TranslatedDictionary::new());
+
+            for word in words {
+                let translator = Arc::clone(&translator);
+                let progress = Arc::clone(&progress);
+                let target_language = target_language.to_string();
+
+                let task = tokio::spawn(async move {
+                    let result = translator
+                        .translate_word_with_tag(word, target_language.clone())
+                        .await;
+                    progress.advance(&target_language);
+                    result
+                });
+                tasks.push(task);
+            }
+        }
+
+        let results = join_all(tasks).await;
+        for language in dictionaries.keys() {
+            progress.finish(language);
+        }
+        for join_result in results {
+            let word = join_result.map_err(StaticDictionaryErrors::AsyncError)??;
+            tracing::debug!("[{}] {}: {}", word.language, word.suffixed_tag(), word.word);
+            let dictionary = dictionaries
+                .get_mut(&word.language)
+                .expect(&format!("Не найден ключ {}", word.tag));
+            let entry = dictionary.entry(word.tag.clone()).or_insert_with(|| {
+                match entries_by_tag.get(word.tag.as_str()) {
+                    Some(base_entry) => {
+                        DictionaryEntry::from_base(base_entry, basic_language, &word.language)
+                    }
+                    None => DictionaryEntry::default(),
+                }
+            });
+            entry.set_word(&word);
+            entry.status = EntryStatus::Machine;
+        }
+        Ok(dictionaries)
+    }
+
+    #[doc = "Записывает машинные переводы в дочерний словарь как предложения записей. Значения и статусы записей не меняются, заблокированные записи и пустые переводы (ниже порога уверенности) пропускаются"]
+    pub fn write_suggestions(
+        dictionary_dir: &str,
+        language: &str,
+        machine: TranslatedDictionary,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let mut dictionary = read_existing_dictionary(dictionary_dir, language)?;
+        for (tag, translated) in machine {
+            if !translated.is_translated() {
+                continue;
+            }
+            let entry = dictionary.entry(tag).or_insert_with(|| DictionaryEntry {
+                value: String::new(),
+                plural_forms: translated.plural_forms.as_ref().map(|forms| {
+                    forms.keys().map(|form| (form.to_owned(), String::new())).collect()
+                }),
+                variants: translated.variants.as_ref().map(|variants| {
+                    variants.keys().map(|variant| (variant.to_owned(), String::new())).collect()
+                }),
+                status: EntryStatus::Untranslated,
+                ..translated.clone()
+            });
+            if !entry.locked {
+                entry.suggestion = Some(Box::new(translated.into()));
+            }
+        }
+        write_translated_dictionary(dictionary_dir, language, &dictionary)
+    }
+
+    #[doc = "Добавляет новые фразы в базовый словарь"]
+    pub fn update_basic_dictionary(
+        dictionary_dir: &str,
+        words: Vec<String>,
+    ) -> Result<(), StaticDictionaryErrors> {
+        update_basic_dictionary_entries(
+            dictionary_dir,
+            words.into_iter().map(BaseEntry::new).collect(),
+            false,
+        )
+    }
+
+    #[doc = "Добавляет новые записи в базовый словарь. Записи с уже существующей фразой или тегом пропускаются. С normalize к канонической форме приводятся только новые фразы: существующие записи не меняются, чтобы не потерять их переводы"]
+    pub fn update_basic_dictionary_entries(
+        dictionary_dir: &str,
+        entries: Vec<BaseEntry>,
+        normalize: bool,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
+        let mut basic_dictionary_content = parse_static_basic_entries(dictionary_dir)?;
+        let entries = match normalize {
+            true => normalize_new_entries(&basic_dictionary_content, entries),
+            false => entries,
+        };
+        merge_basic_entries(&mut basic_dictionary_content, entries);
+        write_dictionary_file(
+            format!("{}/{}", dictionary_dir, basic_dictionary),
+            &basic_dictionary_content,
+        )
+    }
+
+    #[doc = "Добавляет новые записи к записям базового словаря в памяти. Записи с уже существующей фразой или заданным вручную тегом пропускаются, но дополняют существующую запись контекстом. Если сгенерированный тег новой фразы уже занят другой фразой, к нему добавляется номер (otmena-2). Возвращает true, если записи изменились"]
+    pub fn merge_basic_entries(basic_entries: &mut Vec<BaseEntry>, entries: Vec<BaseEntry>) -> bool {
+        let mut changed = false;
+        for mut new_entry in entries {
+            match matching_entry(basic_entries, &new_entry) {
+                // Контекст из аннотации в исходном коде дополняет запись, но не заменяет заданный вручную
+                Some(index) => {
+                    let existing = &mut basic_entries[index];
+                    if existing.context.is_none() && new_entry.context.is_some() {
+                        existing.context = new_entry.context;
+                        changed = true;
+                    }
+                }
+                None => {
+                    if let Some(key) = &new_entry.key {
+                        new_entry.key = Some(unique_key(basic_entries, key));
+                    }
+                    basic_entries.push(new_entry);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    #[doc = "Индекс записи базового словаря, которой соответствует найденная фраза: записи с той же фразой, а если ее нет - с тем же тегом. Сгенерированный из другой фразы тег не делает записи одной и той же"]
+    pub fn matching_entry(basic_entries: &[BaseEntry], entry: &BaseEntry) -> Option<usize> {
+        basic_entries
+            .iter()
+            .position(|existing| existing.phrase == entry.phrase)
+            .or_else(|| match has_generated_key(entry) {
+                true => None,
+                false => basic_entries
+                    .iter()
+                    .position(|existing| existing.tag() == entry.tag()),
+            })
+    }
+
+    // Тег записи получен из фразы стратегией slug или hash, а не задан вручную
+    fn has_generated_key(entry: &BaseEntry) -> bool {
+        entry.key.as_ref().is_some_and(|key| {
+            [KeyStrategy::Slug, KeyStrategy::Hash]
+                .iter()
+                .any(|strategy| strategy.key_for(&entry.phrase).as_ref() == Some(key))
+        })
+    }
+
+    // Тег, еще не занятый в базовом словаре: к занятому добавляется номер, начиная с 2
+    fn unique_key(basic_entries: &[BaseEntry], key: &str) -> String {
+        let taken = |candidate: &str| basic_entries.iter().any(|entry| entry.tag() == candidate);
+        if !taken(key) {
+            return key.to_owned();
+        }
+        (2..)
+            .map(|number| format!("{}-{}", key, number))
+            .find(|candidate| !taken(candidate))
+            .unwrap()
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize)]
+    #[doc = "Записи, удаленные из репозитория словарей"]
+    pub struct RemovalReport {
+        /// Удаленные теги базового словаря
+        pub tags: Vec<String>,
+        /// Количество удаленных записей по языкам дочерних словарей
+        pub languages: BTreeMap<String, usize>,
+    }
+
+    #[doc = "Удаляет записи с указанными тегами из базового словаря, всех дочерних словарей и мест использования. Возвращает количество удаленных записей базового словаря"]
+    pub fn remove_entries(
+        dictionary_dir: &str,
+        tags: &[String],
+    ) -> Result<usize, StaticDictionaryErrors> {
+        Ok(remove_tags(dictionary_dir, tags, false)?.tags.len())
+    }
+
+    #[doc = "Удаляет записи по тегам или glob-шаблонам (auth.*) из базового словаря, всех дочерних словарей и мест использования. Тег, который точно совпадает с аргументом, не считается шаблоном. Если аргументу не соответствует ни одна запись, ничего не удаляется. С dry_run только возвращает, что было бы удалено"]
+    pub fn remove_matching_entries(
+        dictionary_dir: &str,
+        patterns: &[String],
+        dry_run: bool,
+    ) -> Result<RemovalReport, StaticDictionaryErrors> {
+        let entries = parse_static_basic_entries(dictionary_dir)?;
+        let mut tags: Vec<String> = vec![];
+        for pattern in patterns {
+            let matched: Vec<&str> = match entries.iter().any(|entry| entry.tag() == pattern) {
+                true => vec![pattern.as_str()],
+                false => {
+                    let matcher = GlobBuilder::new(pattern).build()?.compile_matcher();
+                    entries
+                        .iter()
+                        .map(BaseEntry::tag)
+                        .filter(|tag| matcher.is_match(tag))
+                        .collect()
+                }
+            };
+            if matched.is_empty() {
+                return Err(StaticDictionaryErrors::EntryNotFound(pattern.to_owned()));
+            }
+            for tag in matched {
+                if !tags.iter().any(|known| known == tag) {
+                    tags.push(tag.to_owned());
+                }
+            }
+        }
+        remove_tags(dictionary_dir, &tags, dry_run)
+    }
+
+    // Сначала читает и изменяет в памяти все словари, и только если все они прочитаны без ошибок, записывает изменившиеся
+    fn remove_tags(
+        dictionary_dir: &str,
+        tags: &[String],
+        dry_run: bool,
+    ) -> Result<RemovalReport, StaticDictionaryErrors> {
+        let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
+        let mut entries = parse_static_basic_entries(dictionary_dir)?;
+        let mut report = RemovalReport {
+            tags: entries
+                .iter()
+                .map(|entry| entry.tag().to_owned())
+                .filter(|tag| tags.contains(tag))
+                .collect(),
+            ..Default::default()
+        };
+        entries.retain(|entry| !tags.iter().any(|tag| tag == entry.tag()));
+        let mut dictionaries = vec![];
+        for language in find_all_translated_languages(dictionary_dir)? {
+            let mut dictionary = read_translated_dictionary(dictionary_dir, &language)?;
+            let size = dictionary.len();
+            dictionary.retain(|tag, _| !tags.contains(tag));
+            if dictionary.len() != size {
+                report
+                    .languages
+                    .insert(language.to_owned(), size - dictionary.len());
+                dictionaries.push((language, dictionary));
+            }
+        }
+        let mut locations = read_source_locations(dictionary_dir)?;
+        let size = locations.len();
+        locations.retain(|tag, _| !tags.contains(tag));
+        if dry_run {
+            return Ok(report);
+        }
+
+        if !report.tags.is_empty() {
+            write_dictionary_file(format!("{}/{}", dictionary_dir, basic_dictionary), &entries)?;
+        }
+        for (language, dictionary) in &dictionaries {
+            write_translated_dictionary(dictionary_dir, language, dictionary)?;
+        }
+        if locations.len() != size {
+            write_source_locations(dictionary_dir, &locations)?;
+        }
+        Ok(report)
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize)]
+    #[doc = "Результат переименования тега: языки дочерних словарей и исходные файлы, в которых он изменен"]
+    pub struct RenameReport {
+        pub languages: Vec<String>,
+        pub source_files: Vec<String>,
+    }
+
+    #[doc = "Переименовывает тег записи в базовом словаре, всех дочерних словарях и местах использования. Исходная фраза и переводы сохраняются. С rewrite_sources тег заменяется и в вызовах на строках, где он был найден при сканировании. Все файлы сначала читаются и проверяются, и только потом записываются, поэтому при ошибке репозиторий не меняется"]
+    pub fn rename_entry(
+        dictionary_dir: &str,
+        old_tag: &str,
+        new_tag: &str,
+        rewrite_sources: bool,
+    ) -> Result<RenameReport, StaticDictionaryErrors> {
+        let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
+        let mut entries = parse_static_basic_entries(dictionary_dir)?;
+        let position = entries
+            .iter()
+            .position(|entry| entry.tag() == old_tag)
+            .ok_or_else(|| StaticDictionaryErrors::EntryNotFound(old_tag.to_owned()))?;
+        if entries.iter().any(|entry| entry.tag() == new_tag) {
+            return Err(StaticDictionaryErrors::DuplicateTag(new_tag.to_owned()));
+        }
+        let entry = &mut entries[position];
+        entry.key = (entry.phrase != new_tag).then(|| new_tag.to_owned());
+
+        let mut report = RenameReport::default();
+        let mut dictionaries = vec![];
+        for language in find_all_translated_languages(dictionary_dir)? {
+            let mut dictionary = read_translated_dictionary(dictionary_dir, &language)?;
+            // Запись с новым тегом без записи в базовом словаре иначе молча потеряла бы перевод
+            if dictionary.contains_key(new_tag) {
+                return Err(StaticDictionaryErrors::DuplicateTag(new_tag.to_owned()));
+            }
+            if let Some(entry) = dictionary.remove(old_tag) {
+                dictionary.insert(new_tag.to_owned(), entry);
+                report.languages.push(language.clone());
+                dictionaries.push((language, dictionary));
+            }
+        }
+        let mut locations = read_source_locations(dictionary_dir)?;
+        let tag_locations = locations.remove(old_tag);
+        let sources = match (&tag_locations, rewrite_sources) {
+            (Some(tag_locations), true) => rename_in_sources(tag_locations, old_tag, new_tag)?,
+            _ => vec![],
+        };
+
+        write_dictionary_file(format!("{}/{}", dictionary_dir, basic_dictionary), &entries)?;
+        for (language, dictionary) in &dictionaries {
+            write_translated_dictionary(dictionary_dir, language, dictionary)?;
+        }
+        for (file, content) in sources {
+            atomic_write(&file, content)?;
+            report.source_files.push(file);
+        }
+        if let Some(tag_locations) = tag_locations {
+            locations.insert(new_tag.to_owned(), tag_locations);
+            write_source_locations(dictionary_dir, &locations)?;
+        }
+        Ok(report)
+    }
+
+    #[doc = "Управляет синхронизацией фраз из конфига в базовый словарь"]
+    pub fn sync_manual_phrases(manual_phrases: Vec<String>, dictionary_dir: &str) -> Result<(), StaticDictionaryErrors> {
+        let basic_dictionary_content: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(parse_static_basic_dictionary(dictionary_dir)?));
+        manual_phrases
+            .par_iter()
+            .for_each(|phrase| {
+                let dictionary = Arc::clone(&basic_dictionary_content);
+                let mut mut_dictionary = dictionary.lock().expect("Произошла ошибка при синхронизации словарей");
+                if !mut_dictionary.contains(phrase) {
+                    mut_dictionary.push(phrase.to_owned());
+                }
+            });
+        Ok(())
+    }
+}
+
+#[doc = "Модуль с функциями для работы с репозиториями словарей"]
+pub mod file_system {
+    use std::{
+        ffi::OsStr,
+        fs::{self, File},
+        io::{self, Write},
+        path::Path,
+        env
+    };
+
+    use regex;
+
+    use crate::{
+        dictionary_format::{find_with_dictionary_extension, read_dictionary_file, DictionaryFormat},
+        errors::errors::{BuildSystemErrors, StaticDictionaryErrors},
+        language::LanguageCode,
+        parser::{
+            get_dictionary_language,
+            types::{ConfigFileParameters, DictionaryRepository, TranslatorSettings},
+        },
+        static_translate::{
+            parse_static_basic_entries, read_translated_dictionary, write_translated_dictionary,
+        },
+        types::BaseEntry,
+    };
+
+    #[doc = "Инициализирует новый репозиторий словарей. Формат базового словаря наследуют все дочерние словари"]
+    pub fn init_new_dictionary_system(
+        parent: Option<String>,
+        basic_language: String,
+        format: DictionaryFormat,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let basic_language = LanguageCode::new(&basic_language)?;
+        let path = match parent {
+            Some(path) => path,
+            None => std::env::current_dir()?.to_str().unwrap().to_owned(),
+        };
+        fs::create_dir_all(format!("{}/dictionaries", path))?;
+        let mut file = File::create_new(format!(
+            "{}/dictionaries/dictionary-{}.base.{}",
+            path,
+            basic_language,
+            format.extension()
+        ))?;
+        file.write_all(format.serialize(&Vec::<BaseEntry>::new())?.as_bytes())?;
+        Ok(())
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[doc = "Ответы для стартового конфига проекта (dms init config)"]
+    pub struct StarterConfig {
+        /// Директория исходного кода для сканирования
+        pub base_directory: String,
+        pub dictionary_repo: String,
+        pub output_dir: String,
+        /// Расширения сканируемых файлов без точки
+        pub extensions: Vec<String>,
+        /// Языки перевода по умолчанию
+        pub languages: Vec<String>,
+        pub translator: TranslatorSettings,
+    }
+
+    impl Default for StarterConfig {
+        fn default() -> StarterConfig {
+            StarterConfig {
+                base_directory: "./src".to_owned(),
+                dictionary_repo: "./dictionaries".to_owned(),
+                output_dir: "./locales".to_owned(),
+                extensions: ["js", "jsx", "ts", "tsx"].map(String::from).to_vec(),
+                languages: vec![],
+                translator: TranslatorSettings::default(),
+            }
+        }
+    }
+
+    #[doc = "Стартовый конфиг. Расширения разметки (html, vue, svelte) сканируются извлекателем html, .rs - пресетом rust, остальные - по вызовам t, i18n.t и $t"]
+    pub fn starter_config(
+        starter: &StarterConfig,
+    ) -> Result<serde_json::Value, StaticDictionaryErrors> {
+        let mut code = vec![];
+        let mut markup = vec![];
+        let mut include = serde_json::Map::new();
+        for extension in &starter.extensions {
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            match extension.as_str() {
+                "" => {}
+                "rs" => {
+                    include.insert("rust".to_owned(), serde_json::json!({"preset": "rust"}));
+                }
+                "html" | "vue" | "svelte" => markup.push(extension),
+                _ => code.push(extension),
+            }
+        }
+        if !code.is_empty() {
+            include.insert(
+                "code".to_owned(),
+                serde_json::json!({"ext": code, "call_patterns": ["t", "i18n.t", "$t"]}),
+            );
+        }
+        if !markup.is_empty() {
+            include.insert(
+                "markup".to_owned(),
+                serde_json::json!({"ext": markup, "extractor": "html", "call_patterns": ["$t", "t"]}),
+            );
+        }
+        let mut config = serde_json::json!({
+            "base": starter.base_directory,
+            "exclude": ["node_modules"],
+            "dictionary_repo": starter.dictionary_repo,
+            "output_dir": starter.output_dir,
+            "include": [include],
+            "manual_translate": [],
+            "languages": starter.languages,
+        });
+        if starter.translator != TranslatorSettings::default() {
+            config["translator"] = serde_json::to_value(&starter.translator)?;
+        }
+        // Конфиг разбирается обратно, чтобы стартовый файл гарантированно читался командами
+        serde_json::from_value::<ConfigFileParameters>(config.clone())?;
+        Ok(config)
+    }
+
+    #[doc = "Записывает стартовый конфиг в формате по расширению пути (json, yaml или toml). Существующий файл перезаписывается только с overwrite"]
+    pub fn write_starter_config(
+        path: &str,
+        starter: &StarterConfig,
+        overwrite: bool,
+    ) -> Result<(), StaticDictionaryErrors> {
+        let config = starter_config(starter)?;
+        let content = match get_file_extension(path) {
+            Some("toml") => toml::to_string_pretty(&config)
+                .map_err(|err| StaticDictionaryErrors::InvalidConfig(err.to_string()))?,
+            Some("yaml" | "yml") => serde_yaml::to_string(&config)?,
+            _ => serde_json::to_string_pretty(&config)? + "\n",
+        };
+        match overwrite {
+            true => atomic_write(path, content)?,
+            false => File::create_new(path)?.write_all(content.as_bytes())?,
+        }
+        Ok(())
+    }
+
+    #[doc = "Записывает файл через временный файл в той же директории и переименование, поэтому при сбое во время записи на диске остается прежняя версия файла целиком. Если путь - символическая ссылка, перезаписывается файл, на который она указывает"]
+    pub fn atomic_write(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> io::Result<()> {
+        let mut path = path.as_ref().to_path_buf();
+        if fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_symlink()) {
+            path = fs::canonicalize(&path)?;
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "путь без имени файла"))?;
+        let temporary_path = path.with_file_name(format!(
+            ".{}.{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id()
+        ));
+        let result = File::create(&temporary_path)
+            .and_then(|mut file| {
+                file.write_all(content.as_ref())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temporary_path, &path));
+        match &result {
+            Ok(_) => tracing::debug!("Записан файл {}", path.display()),
+            Err(_) => {
+                let _ = fs::remove_file(&temporary_path);
+            }
+        }
+        result
+    }
+
+    #[doc = "Проверяет наличие словаря определенного языка в репозитории"]
+    pub fn check_dictionary_exists(dictionary_path: &str, language: &str) -> bool {
+        find_with_dictionary_extension(dictionary_path, &format!("dictionary-{}", language)).is_some()
+    }
+
+    #[doc = "Возвращает список всех словарей в репозитории. Словари, которые являются символическими ссылками, учитываются только с follow_symlinks, и только если ссылка ведет на существующий файл"]
+    // TODO: Заменить на другой тип ошибки
+    pub fn find_all_dictionaries_in_repository(
+        dictionary_path: &str,
+        follow_symlinks: bool,
+    ) -> Result<Vec<String>, BuildSystemErrors> {
+        let paths = fs::read_dir(dictionary_path)?;
+        let pattern = regex::Regex::new(r"^dictionary-(.+?)(?:\.base)?\.(?:json|ya?ml)$")?;
+        let mut result: Vec<String> = vec![];
+        for file in paths {
+            match file {
+                Ok(path) => {
+                    let filename = path.file_name().into_string().unwrap();
+                    let is_symlink = path.file_type()?.is_symlink();
+                    if is_symlink && !(follow_symlinks && path.path().is_file()) {
+                        continue;
+                    }
+                    if pattern.is_match(&filename) {
+                        result.push(filename);
+                    }
+                }
+                Err(error) => return Err(BuildSystemErrors::IOError(error)),
+            }
+        }
+        Ok(result)
+    }
+
+    #[doc = "Находит все переведнные словари в репозитории, игнорируя базовый словарь"]
+    pub fn find_all_translated_dictionaries(
+        dictionary_path: &str,
+    ) -> Result<Vec<String>, StaticDictionaryErrors> {
+        let paths = fs::read_dir(dictionary_path)?;
+        let pattern = regex::Regex::new(
+            r"^dictionary-[A-Za-z]{2,3}(?:-[A-Za-z0-9]{1,8})*\.(?:json|ya?ml)$",
+        )?;
+        let mut result = vec![];
+        for file in paths {
+            match file {
+                Ok(path) => {
+                    let filename = path.file_name().into_string().unwrap();
+                    if pattern.is_match(&filename) {
+                        result.push(filename);
+                    }
+                }
+                Err(error) => return Err(StaticDictionaryErrors::IOError(error)),
+            }
+        }
+        return Ok(result);
+    }
+
+    #[doc = "Директория пространства имен внутри репозитория словарей"]
+    pub fn namespace_directory(dictionary_dir: &str, namespace: &str) -> String {
+        Path::new(dictionary_dir)
+            .join(namespace)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[doc = "Возвращает языки всех дочерних словарей репозитория"]
+    pub fn find_all_translated_languages(
+        dictionary_path: &str,
+    ) -> Result<Vec<String>, StaticDictionaryErrors> {
+        Ok(find_all_translated_dictionaries(dictionary_path)?
+            .iter()
+            .filter_map(|dictionary| get_dictionary_language(dictionary).ok())
+            .map(String::from)
+            .collect())
+    }
+
+    #[doc = "Обновляет дочерние словари репозитория до формата v2: добавляет статусы, контекст и хеши исходных фраз. Возвращает список обновленных словарей"]
+    pub fn migrate_repository(dictionary_path: &str) -> Result<Vec<String>, StaticDictionaryErrors> {
+        let base_entries = parse_static_basic_entries(dictionary_path)?;
+        let mut migrated = vec![];
+        for dictionary in find_all_translated_dictionaries(dictionary_path)? {
+            let language = get_dictionary_language(&dictionary)
+                .map(String::from)
+                .map_err(|_| StaticDictionaryErrors::BasicDictionaryNotFound)?;
+            let mut entries = read_translated_dictionary(dictionary_path, &language)?;
+            for base_entry in &base_entries {
+                if let Some(entry) = entries.get_mut(base_entry.tag()) {
+                    if entry.context.is_none() {
+                        entry.context = base_entry.context.clone();
+                    }
+                    if entry.comment.is_none() {
+                        entry.comment = base_entry.comment.clone();
+                    }
+                    if entry.source_hash.is_none() {
+                        entry.source_hash = Some(base_entry.source_hash());
+                    }
+                }
+            }
+            write_translated_dictionary(dictionary_path, &language, &entries)?;
+            migrated.push(dictionary);
+        }
+        Ok(migrated)
+    }
+
+    #[doc = "Считывает и парсит конфиг. Если путь до конфига не передан - пытается найти его в cwd"]
+    #[inline]
+    pub fn parse_config_file(
+        config_path: &str,
+    ) -> Result<ConfigFileParameters, StaticDictionaryErrors> {
+        let config_parsed = read_config_file(config_path);
+        match config_parsed {
+            Ok(conf) => return Ok(conf),
+            Err(err) => {
+                tracing::debug!("Не удалось прочитать конфиг {}: {:?}", config_path, err);
+                return Err(err);
+            }
+        }
+    }
+
+    #[doc = "Идиоматически верно возвращает расширение файла"]
+    #[inline]
+    pub fn get_file_extension(filename: &str) -> Option<&str> {
+        Path::new(filename).extension().and_then(OsStr::to_str)
+    }
+
+    #[doc = "Названия конфига в порядке поиска в cwd"]
+    pub const CONFIG_FILE_NAMES: [&str; 4] = [
+        "config.dms.toml",
+        "config.dms.json",
+        "config.dms.yaml",
+        "config.dms.yml",
+    ];
+
+    #[doc = "Читает конфиг в формате, соответствующем расширению: TOML, JSON или YAML"]
+    pub fn read_config_file(config_path: &str) -> Result<ConfigFileParameters, StaticDictionaryErrors> {
+        match get_file_extension(config_path) {
+            Some("toml") => Ok(toml::from_str(&fs::read_to_string(config_path)?)?),
+            _ => read_dictionary_file(config_path),
+        }
+    }
+
+    #[doc = "Парсинг конфига. Формат выбирается по расширению, без пути ищется первый из CONFIG_FILE_NAMES в cwd"]
+    pub fn parse_config(config_path: Option<String>) -> Result<ConfigFileParameters, StaticDictionaryErrors> {
+        let config_dir = match config_path {
+            Some(path) => path,
+            None => {
+                let cwd = env::current_dir()?;
+                let found = CONFIG_FILE_NAMES
+                    .iter()
+                    .map(|name| cwd.join(name))
+                    .find(|path| path.exists())
+                    .unwrap_or_else(|| cwd.join("config.dms.json"));
+                found.to_str().unwrap().to_owned()
+            }
+        };
+        tracing::debug!("Конфиг {}", config_dir);
+        read_config_file(&config_dir)
+    }
+
+    #[doc = "Репозитории словарей для команды. Путь к директории - это один репозиторий, путь к файлу конфига - все репозитории из dictionary_repo или только repo"]
+    pub fn resolve_repositories(
+        path: &str,
+        repo: Option<&str>,
+    ) -> Result<Vec<DictionaryRepository>, StaticDictionaryErrors> {
+        if !Path::new(path).is_file() {
+            return match repo {
+                Some(repo) => Err(StaticDictionaryErrors::InvalidConfig(format!(
+                    "{} не является конфигом, репозиторий {} из него выбрать нельзя",
+                    path, repo
+                ))),
+                None => Ok(vec![DictionaryRepository {
+                    name: path.to_owned(),
+                    path: path.to_owned(),
+                }]),
+            };
+        }
+        parse_config(Some(path.to_owned()))?.dictionary_repo.select(repo)
+    }
+}
+
+#[doc = "Модули и утилиты для сборки итоговых словарей"]
+pub mod build_system {
+    use std::collections::btree_map::Entry;
+    use std::collections::BTreeMap;
+    use std::sync::OnceLock;
+
+    use regex::{Captures, Regex};
+
+    use crate::errors::errors::BuildSystemErrors;
+    use crate::file_system::check_dictionary_exists;
+    use crate::language::LanguageCode;
+    use crate::static_translate::{
+        get_basic_language, parse_static_basic_entries, parse_translated_dictionary,
+    };
+    use crate::types::Word;
+
+    pub mod templating;
+    #[doc = "Обмен переводами с переводчиками в формате XLIFF 1.2 и 2.0"]
+    pub mod xliff;
+    #[doc = "Сборка каталогов gettext (POT и PO) и импорт переводов из PO"]
+    pub mod gettext;
+    #[doc = "Сборка файлов ARB для Flutter"]
+    pub mod arb;
+    #[doc = "Сборка файлов Qt Linguist (.ts)"]
+    pub mod qt;
+    #[doc = "Сборка файлов .properties для Java и Kotlin"]
+    pub mod properties;
+    #[doc = "Сборка словарей ruby-i18n (Rails) в формате YAML"]
+    pub mod rails;
+    #[doc = "Сборка словарей для vue-i18n"]
+    pub mod vue_i18n;
+    #[doc = "Сборка словарей для ngx-translate и встроенной интернационализации Angular (XLF)"]
+    pub mod angular;
+    #[doc = "Сборка файлов messages.json для браузерных расширений (WebExtension)"]
+    pub mod webextension;
+    #[doc = "Сборка языковых файлов PHP для Laravel"]
+    pub mod laravel;
+    #[doc = "Генерация объявлений типов TypeScript для ключей словаря"]
+    pub mod typescript;
+    #[doc = "Генерация модуля Rust для встраивания переводов при компиляции"]
+    pub mod rust_codegen;
+    #[doc = "Импорт файлов существующего проекта на i18next в новый репозиторий словарей"]
+    pub mod i18next_import;
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    #[doc = "Общие параметры сборки итоговых словарей для всех фреймворков"]
+    pub struct BuildOptions {
+        /// Шаблон пути итогового файла, например {output_dir}/{lang}/{namespace}.json
+        pub output_template: Option<String>,
+        /// Цепочки запасных локалей из конфига: локаль -> локали, из которых по порядку берутся недостающие переводы
+        pub fallbacks: BTreeMap<String, Vec<String>>,
+        /// Разворачивать ключи с точками во вложенные объекты
+        pub nested_keys: bool,
+        /// Пространства имен: поддиректории репозитория, которые собираются в отдельные файлы
+        pub namespaces: Vec<String>,
+        /// Записывать итоговые JSON в одну строку без отступов
+        pub minify: bool,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    #[doc = "Результат сборки итоговых словарей"]
+    pub struct BuildReport {
+        /// Унаследованные переводы: локаль -> (ключ -> локаль, из которой взят перевод)
+        pub inherited: BTreeMap<String, BTreeMap<String, String>>,
+    }
+
+    #[doc = "Цепочка запасных локалей: из конфига, а если ее там нет - более общие локали (pt-BR -> pt)"]
+    pub fn fallback_chain(language: &str, options: &BuildOptions) -> Vec<String> {
+        match options.fallbacks.get(language) {
+            Some(chain) => chain.clone(),
+            None => LanguageCode::new(language)
+                .map(|code| code.parents().into_iter().map(String::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    // Слова запасной локали: ее дочерний словарь, а для языка базового словаря - исходные фразы
+    fn fallback_words(
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<Vec<Word>, BuildSystemErrors> {
+        if check_dictionary_exists(dictionary_dir, language) {
+            return Ok(parse_translated_dictionary(dictionary_dir, language)?);
+        }
+        if get_basic_language(dictionary_dir)? == language {
+            return Ok(parse_static_basic_entries(dictionary_dir)?
+                .iter()
+                .flat_map(|entry| entry.words_for_translation(language, language))
+                .collect());
+        }
+        Ok(vec![])
+    }
+
+    #[doc = "Слова дочернего словаря для сборки. Отсутствующие и пустые ключи заполняются по цепочке запасных локалей. Возвращает слова и унаследованные ключи с локалью, из которой они взяты"]
+    pub fn words_with_fallback(
+        dictionary_dir: &str,
+        language: &str,
+        options: &BuildOptions,
+    ) -> Result<(Vec<Word>, BTreeMap<String, String>), BuildSystemErrors> {
+        let mut words: BTreeMap<String, Word> = parse_translated_dictionary(dictionary_dir, language)?
+            .into_iter()
+            .map(|word| (word.suffixed_tag(), word))
+            .collect();
+        let mut inherited = BTreeMap::new();
+        for fallback in fallback_chain(language, options) {
+            for word in fallback_words(dictionary_dir, &fallback)? {
+                if word.word.is_empty() {
+                    continue;
+                }
+                let key = word.suffixed_tag();
+                match words.entry(key.clone()) {
+                    Entry::Vacant(vacant) => {
+                        vacant.insert(Word {
+                            language: language.to_owned(),
+                            ..word
+                        });
+                    }
+                    Entry::Occupied(mut occupied) => {
+                        if !occupied.get().word.is_empty() {
+                            continue;
+                        }
+                        occupied.get_mut().word = word.word;
+                    }
+                }
+                inherited.insert(key, fallback.clone());
+            }
         }
+        Ok((words.into_values().collect(), inherited))
+    }
 
-        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-        #[doc = "Настройки парсинга: настройки для каждого конкретного языка, файлы которого будут парсится"]
-        pub struct LanguageConfiguration {
-            /// Расширения файлов, которые нужно проверять для конкретного языка
-            #[serde(rename = "ext")]
-            pub file_extensions: Vec<String>,
-            /// Начало строки
-            #[serde(rename = "regexp-start")]
-            pub string_start: Vec<String>,
-            /// Конец строки
-            #[serde(rename = "regexp-end")]
-            pub string_end: Vec<String>,
+    #[doc = "Путь ключа во вложенной структуре: checkout.payment.title -> [checkout, payment, title]. Фразы с пробелами вокруг точек и пустыми сегментами (\"Привет. Как дела?\") остаются одним ключом"]
+    pub fn key_path(key: &str) -> Vec<String> {
+        let segments: Vec<&str> = key.split('.').collect();
+        if segments.len() > 1
+            && segments
+                .iter()
+                .all(|segment| !segment.is_empty() && !segment.contains(char::is_whitespace))
+        {
+            return segments.into_iter().map(str::to_owned).collect();
         }
+        vec![key.to_owned()]
+    }
 
-        impl ConfigFileParameters {
+    #[doc = "Собирает вложенный объект из значений по путям ключей с ключами в лексикографическом порядке на каждом уровне. Если префикс одновременно является фразой и родителем других ключей, возвращает ошибку"]
+    pub fn nest_entries(
+        entries: impl IntoIterator<Item = (Vec<String>, serde_json::Value)>,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, BuildSystemErrors> {
+        let mut root = serde_json::Map::new();
+        for (path, value) in entries {
+            let Some((leaf, parents)) = path.split_last() else {
+                continue;
+            };
+            let mut node = &mut root;
+            for parent in parents {
+                node = match node
+                    .entry(parent.to_owned())
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()))
+                {
+                    serde_json::Value::Object(child) => child,
+                    _ => return Err(BuildSystemErrors::KeyCollision(path.join("."))),
+                };
+            }
+            if node.contains_key(leaf) {
+                return Err(BuildSystemErrors::KeyCollision(path.join(".")));
+            }
+            node.insert(leaf.to_owned(), value);
+        }
+        Ok(sorted_map(root))
+    }
 
-            #[doc = "Парсинг конфиг-файла в структуру"]
-            pub fn from_json(
-                json_content: &str,
-            ) -> Result<ConfigFileParameters, serde_json::Error> {
-                serde_json::from_str(json_content)
+    // Рекурсивно упорядочивает ключи объекта, даже если serde_json собран с preserve_order
+    fn sorted_map(
+        map: serde_json::Map<String, serde_json::Value>,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        map.into_iter()
+            .map(|(key, value)| (key, sorted_json(value)))
+            .collect::<BTreeMap<String, serde_json::Value>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn sorted_json(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => serde_json::Value::Object(sorted_map(map)),
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.into_iter().map(sorted_json).collect())
             }
+            other => other,
+        }
+    }
 
-            #[doc = "Превращает структуру в JSON"]
-            pub fn into_json(&self) -> Result<String, serde_json::Error> {
-                serde_json::to_string(&self)
+    #[doc = "Сериализует итоговый словарь в JSON с ключами в лексикографическом порядке: с отступами или в одну строку (minify), чтобы повторные сборки давали побайтно одинаковый результат"]
+    pub fn to_json_output(
+        value: &impl serde::Serialize,
+        options: &BuildOptions,
+    ) -> Result<String, BuildSystemErrors> {
+        let value = sorted_json(serde_json::to_value(value)?);
+        Ok(match options.minify {
+            true => serde_json::to_string(&value)?,
+            false => serde_json::to_string_pretty(&value)?,
+        })
+    }
+
+    #[doc = "Переводит переменные i18next {{name}} в формат другого фреймворка"]
+    pub fn convert_placeholders(text: &str, mut format: impl FnMut(&str) -> String) -> String {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN
+            .get_or_init(|| Regex::new(r"\{\{\s*([\w.]+)\s*\}\}").unwrap())
+            .replace_all(text, |captures: &Captures| format(&captures[1]))
+            .into_owned()
+    }
+
+    #[doc = "Интеграция с фреймворком i18next"]
+    pub mod i18next_integration {
+        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+        use std::collections::BTreeMap;
+
+        use super::templating::{resolve_output_path, template_variables};
+        use super::{
+            key_path, nest_entries, to_json_output, words_with_fallback, BuildOptions, BuildReport,
+        };
+        use crate::errors::errors::BuildSystemErrors;
+        use crate::file_system::{atomic_write, find_all_translated_dictionaries, namespace_directory};
+        use crate::parser::get_dictionary_language;
+        use std::fs;
+        use std::path::Path;
+
+        #[doc = "Шаблон пути итоговых словарей i18next по умолчанию"]
+        pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{output_dir}/{lang}/{namespace}.json";
+
+        #[doc = "Пространство имен i18next по умолчанию"]
+        pub const DEFAULT_NAMESPACE: &str = "translation";
+
+        #[doc = "Функция для сборки словарей из репозитория в итоговые словари для i18next. Если в параметрах указаны пространства имен, каждое собирается из своей поддиректории репозитория в <lang>/<namespace>.json"]
+        pub fn build_for_i18next(
+            dictionary_dir: &str,
+            output_directory: &str,
+            languages: Option<Vec<String>>,
+            options: &BuildOptions,
+        ) -> Result<BuildReport, BuildSystemErrors> {
+            if options.namespaces.is_empty() {
+                let inherited = build_namespace(
+                    dictionary_dir,
+                    output_directory,
+                    languages,
+                    options,
+                    DEFAULT_NAMESPACE,
+                )?;
+                return Ok(BuildReport { inherited });
             }
+            let mut report = BuildReport::default();
+            for namespace in &options.namespaces {
+                let namespace_dir = namespace_directory(dictionary_dir, namespace);
+                if !Path::new(&namespace_dir).is_dir() {
+                    return Err(BuildSystemErrors::NamespaceNotFound(namespace.to_owned()));
+                }
+                let inherited = build_namespace(
+                    &namespace_dir,
+                    output_directory,
+                    languages.clone(),
+                    options,
+                    namespace,
+                )?;
+                // Унаследованные ключи записываются в нотации i18next: namespace:key
+                for (language, keys) in inherited {
+                    report.inherited.entry(language).or_default().extend(
+                        keys.into_iter()
+                            .map(|(key, fallback)| (format!("{}:{}", namespace, key), fallback)),
+                    );
+                }
+            }
+            Ok(report)
+        }
+
+        fn build_namespace(
+            dictionary_dir: &str,
+            output_directory: &str,
+            languages: Option<Vec<String>>,
+            options: &BuildOptions,
+            namespace: &str,
+        ) -> Result<BTreeMap<String, BTreeMap<String, String>>, BuildSystemErrors> {
+            let output_template = options
+                .output_template
+                .as_deref()
+                .unwrap_or(DEFAULT_OUTPUT_TEMPLATE);
+            let languages = match languages {
+                Some(langs) => langs,
+                None => {
+                    let dictionaries = find_all_translated_dictionaries(dictionary_dir)?;
+                    dictionaries
+                        .par_iter()
+                        .map(|dictionary| String::from(get_dictionary_language(&dictionary).unwrap()))
+                        .collect()
+                }
+            };
+            let inherited = languages
+                .par_iter()
+                .map(|language| -> Result<(String, BTreeMap<String, String>), BuildSystemErrors> {
+                    let (dictionary_content, inherited) =
+                        words_with_fallback(dictionary_dir, language, options)?;
+                    let output_path = resolve_output_path(
+                        output_template,
+                        &template_variables(output_directory, language, namespace),
+                    )?;
+                    if let Some(parent) = output_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    let json_content = match options.nested_keys {
+                        true => nest_entries(dictionary_content.iter().map(|word| {
+                            (key_path(&word.suffixed_tag()), word.word.clone().into())
+                        }))?,
+                        false => dictionary_content
+                            .iter()
+                            .map(|word| (word.suffixed_tag(), word.word.clone().into()))
+                            .collect(),
+                    };
+                    atomic_write(output_path, to_json_output(&json_content, options)?)?;
+                    Ok((language.to_owned(), inherited))
+                })
+                .collect::<Result<BTreeMap<String, BTreeMap<String, String>>, BuildSystemErrors>>()?;
+            Ok(inherited)
         }
     }
 }
 
-#[doc = "Функционал для генерации и парсинга static-словарей"]
-pub mod static_translate {
-    use std::collections::HashMap;
-    use std::fs;
-    use std::{
-        fs::OpenOptions,
-        sync::{Arc, Mutex},
-    };
-
-    use futures::future::join_all;
-    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-    use serde_json::Value;
+#[cfg(test)]
+mod tests {
 
-    use crate::errors::errors::StaticDictionaryErrors;
+    use super::types::*;
     use crate::file_system::check_dictionary_exists;
+    use crate::file_system::find_all_dictionaries_in_repository;
+    use crate::file_system::find_all_translated_dictionaries;
     use crate::parser::get_basic_dictionary;
-    use crate::parser::get_dictionary_language;
-    use crate::types::ApiArgs;
-    use crate::types::{TranslatorApi, TranslatorApis, Word};
+    use crate::parser::get_dictionary_by_lang;
+    use crate::parser::get_tags_from_dictionary;
+    use crate::parser::read_json_dictionary;
+    use crate::static_translate::parse_static_basic_dictionary;
     use crate::web_api::LibreTranslateApi;
+    use crate::build_system::i18next_integration::build_for_i18next;
+    use crate::build_system::BuildOptions;
+    use crate::errors::errors::BuildSystemErrors;
+    use crate::static_translate::generate_empty_dictionaries_from_static_basic;
+    use crate::static_translate::parse_static_basic_entries;
+    use crate::static_translate::parse_translated_dictionary;
+    use crate::parser::{
+        collect_source_files, extension_regex, find_unused_entries, generate_call_regex,
+        generate_regex, get_entries_from_file, get_located_entries_from_file, language_regex,
+        preview_scan_files, scan_files_for_phrases,
+        types::{ConfigFileParameters, LanguageConfiguration, PhraseFilters},
+        ExtractedFile, PathFilter, PhraseExtractor, ScannedDictionary, WalkOptions,
+    };
+    use std::path::PathBuf;
+    use crate::static_translate::{remove_entries, remove_matching_entries, rename_entry};
+    use crate::static_translate::{status, sync_dictionaries};
+    use crate::file_system::migrate_repository;
+    use crate::static_translate::read_translated_dictionary;
+    use crate::static_translate::write_translated_dictionary;
+    use crate::static_translate::write_suggestions;
+    use crate::static_translate::update_basic_dictionary_entries;
+    use crate::static_translate::{locked_tags, set_entries_locked};
+    use crate::static_translate::{matching_entry, merge_basic_entries};
+    use crate::dictionary_format::DictionaryFormat;
+    use crate::source_locations::{read_source_locations, LOCATIONS_FILE};
+    use crate::file_system::{
+        find_all_translated_languages, init_new_dictionary_system, parse_config, read_config_file,
+        resolve_repositories,
+    };
+    use crate::web_api::parse_libre_translate_response;
+    use crate::static_translate::autotranslate_untranslated;
+    use crate::errors::errors::StaticDictionaryErrors;
+    use std::collections::{BTreeSet, HashMap};
+    use std::fs;
 
-    #[doc = "Парсит список слов из базового словаря в Vec<Word>"]
-    pub fn parse_static_basic_dictionary(
-        dictionary_dir: &str,
-    ) -> Result<Vec<String>, StaticDictionaryErrors> {
-        let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
-        let file_content = fs::read_to_string(format!("{}/{}", dictionary_dir, basic_dictionary))?;
-        let json_object: Value = serde_json::from_str(&file_content)?;
-        Ok(json_object
-            .as_array()
+    #[test]
+    fn test_base_entries_accept_strings_and_plural_objects() {
+        let json = r#"["Привет", {"phrase": "яблоки", "plural_forms": {"one": "{{count}} яблоко", "few": "{{count}} яблока", "many": "{{count}} яблок"}}]"#;
+        let entries: Vec<BaseEntry> = serde_json::from_str(json).unwrap();
+        assert!(entries[0].is_plain());
+        assert_eq!(entries[1].phrase, "яблоки");
+
+        let sources = entries[1].plural_sources("en").unwrap();
+        assert_eq!(sources[0], ("one".to_owned(), "{{count}} яблоко".to_owned()));
+        // Формы other нет в базовом словаре, поэтому используется сама фраза
+        assert_eq!(sources[1], ("other".to_owned(), "яблоки".to_owned()));
+
+        let serialized = serde_json::to_value(&entries).unwrap();
+        assert_eq!(serialized[0], "Привет");
+        assert_eq!(serialized[1]["plural_forms"]["few"], "{{count}} яблока");
+    }
+
+    #[test]
+    fn test_base_entry_context_round_trip() {
+        let json = r#"[{"phrase": "Оплатить", "context": "кнопка на странице оплаты"}]"#;
+        let entries: Vec<BaseEntry> = serde_json::from_str(json).unwrap();
+        assert!(!entries[0].is_plain());
+        assert_eq!(entries[0].context.as_deref(), Some("кнопка на странице оплаты"));
+        let serialized = serde_json::to_value(&entries).unwrap();
+        assert_eq!(serialized[0]["context"], "кнопка на странице оплаты");
+        assert!(serialized[0].get("plural_forms").is_none());
+    }
+
+    #[test]
+    fn test_key_strategies() {
+        assert_eq!(KeyStrategy::Phrase.key_for("Добро пожаловать"), None);
+        assert_eq!(
+            KeyStrategy::Slug.key_for("Добро пожаловать!").as_deref(),
+            Some("dobro-pozhalovat")
+        );
+        let hash = KeyStrategy::Hash.key_for("Добро пожаловать").unwrap();
+        assert_eq!(hash.len(), 12);
+        assert_eq!(KeyStrategy::Hash.key_for("Добро пожаловать").unwrap(), hash);
+        assert_eq!(KeyStrategy::Manual.key_for("Добро пожаловать"), None);
+    }
+
+    #[test]
+    fn test_generated_key_collisions_are_disambiguated() {
+        let entries = |phrases: &[&str]| -> Vec<BaseEntry> {
+            phrases
+                .iter()
+                .map(|phrase| BaseEntry::with_key_strategy(phrase.to_string(), KeyStrategy::Slug))
+                .collect()
+        };
+        let mut basic_entries = vec![];
+        assert!(merge_basic_entries(
+            &mut basic_entries,
+            entries(&["Отмена", "Отмена!", "Отмена?"])
+        ));
+        let tags: Vec<(&str, &str)> = basic_entries
+            .iter()
+            .map(|entry| (entry.tag(), entry.phrase.as_str()))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![("otmena", "Отмена"), ("otmena-2", "Отмена!"), ("otmena-3", "Отмена?")]
+        );
+        // Повторное сканирование находит записи по фразе и ничего не добавляет
+        assert!(!merge_basic_entries(&mut basic_entries, entries(&["Отмена?", "Отмена"])));
+        assert_eq!(basic_entries.len(), 3);
+
+        // Заданный вручную тег по-прежнему указывает на существующую запись
+        let mut explicit = BaseEntry::new("Отменить".to_owned());
+        explicit.key = Some("otmena".to_owned());
+        assert_eq!(matching_entry(&basic_entries, &explicit), Some(0));
+    }
+
+    #[test]
+    fn test_manual_key_strategy_requires_explicit_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("page.js");
+        let pattern = generate_regex(vec!["t(".to_owned()], vec![")".to_owned()]).unwrap();
+        fs::write(&source, "t(\"Оформить заказ\") // dms-key: checkout.title\n").unwrap();
+        let entries =
+            get_entries_from_file(source.to_str().unwrap(), pattern.clone(), KeyStrategy::Manual)
+                .unwrap();
+        assert_eq!(entries[0].tag(), "checkout.title");
+
+        fs::write(&source, "t(\"Оформить заказ\") // dms-key: checkout.title\nt(\"Отмена\")\n")
+            .unwrap();
+        let result = get_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Manual);
+        assert!(matches!(
+            result,
+            Err(StaticDictionaryErrors::MissingKey(phrase, 2)) if phrase == "Отмена"
+        ));
+    }
+
+    #[test]
+    fn test_scanned_entries_use_key_strategy_and_annotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("page.js");
+        fs::write(
+            &source,
+            "t(\"Оформить заказ\") // dms-key: checkout.title\nt(\"Отмена\")\n",
+        )
+        .unwrap();
+        let pattern = generate_regex(vec!["t(".to_owned()], vec![")".to_owned()]).unwrap();
+        let entries =
+            get_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Slug).unwrap();
+        assert_eq!(entries[0].tag(), "checkout.title");
+        assert_eq!(entries[1].tag(), "otmena");
+        let serialized = serde_json::to_value(&entries[1]).unwrap();
+        assert_eq!(serialized["phrase"], "Отмена");
+        assert_eq!(serialized["key"], "otmena");
+    }
+
+    #[test]
+    fn test_explicit_keys_from_call_arguments() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("page.ts");
+        fs::write(
+            &source,
+            concat!(
+                "t(\"checkout.title\", \"Оформить заказ\")\n",
+                "i18n.t('checkout.total', `Итого: ${sum}`, { sum })\n",
+                "t(\"Товаров: {{count}}\", { count })\n",
+            ),
+        )
+        .unwrap();
+        let pattern = generate_call_regex(&["t".to_owned(), "i18n.t".to_owned()]).unwrap();
+        let entries =
+            get_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Slug).unwrap();
+        let tagged: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|entry| (entry.tag(), entry.phrase.as_str()))
+            .collect();
+        assert_eq!(
+            tagged,
+            vec![
+                ("checkout.title", "Оформить заказ"),
+                ("checkout.total", "Итого: {{sum}}"),
+                ("tovarov-count", "Товаров: {{count}}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_files_are_collected_recursively() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "app.js",
+            "components/button/Button.js",
+            "components/styles.css",
+            "components/button/Button.test.js",
+            "node_modules/lib/index.js",
+            "generated/api.js",
+            ".cache/page.js",
+            "dist/bundle.js",
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "t(\"Привет\")").unwrap();
+        }
+        fs::write(dir.path().join(".gitignore"), "dist/\n").unwrap();
+        let base = dir.path().to_str().unwrap();
+        let extensions = HashMap::from([(
+            "js".to_owned(),
+            PhraseExtractor::Regex(
+                generate_regex(vec!["t(".to_owned()], vec![")".to_owned()]).unwrap(),
+            ),
+        )]);
+        let filter = PathFilter::new(
+            &[],
+            &[
+                "node_modules".to_owned(),
+                "generated/**".to_owned(),
+                "components/**/*.test.js".to_owned(),
+            ],
+        )
+        .unwrap();
+
+        let relative = |files: Vec<std::path::PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|file| {
+                    file.strip_prefix(base)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+        let files =
+            collect_source_files(base, &filter, &extensions, WalkOptions::default()).unwrap();
+        assert_eq!(relative(files), vec!["app.js", "components/button/Button.js"]);
+        let options = WalkOptions {
+            max_depth: Some(1),
+            respect_gitignore: false,
+            ..Default::default()
+        };
+        let files = collect_source_files(base, &filter, &extensions, options).unwrap();
+        assert_eq!(relative(files), vec!["app.js", "dist/bundle.js"]);
+
+        let filter = PathFilter::new(&["components/**".to_owned()], &[]).unwrap();
+        let files =
+            collect_source_files(base, &filter, &extensions, WalkOptions::default()).unwrap();
+        assert_eq!(
+            relative(files),
+            vec!["components/button/Button.js", "components/button/Button.test.js"]
+        );
+        assert!(matches!(
+            PathFilter::new(&["src/[".to_owned()], &[]),
+            Err(StaticDictionaryErrors::GlobError(_))
+        ));
+        for regex in ["^dist", ".*\\.min\\.js", "\\.test\\.ts$", "build|dist"] {
+            assert!(matches!(
+                PathFilter::new(&[], &[regex.to_owned()]),
+                Err(StaticDictionaryErrors::InvalidConfig(_))
+            ));
+        }
+        let globs = ["app/(auth)/**".to_owned(), "vite.config.*".to_owned()];
+        assert!(PathFilter::new(&[], &globs).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_write_replaces_file_behind_symlink() {
+        use crate::file_system::atomic_write;
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("shared")).unwrap();
+        let target = dir.path().join("shared/dictionary-en.json");
+        fs::write(&target, "{}").unwrap();
+        let link = dir.path().join("dictionary-en.json");
+        symlink(&target, &link).unwrap();
+
+        atomic_write(&link, "{\"Вход\": \"Sign in\"}").unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "{\"Вход\": \"Sign in\"}");
+        assert_eq!(fs::read_dir(dir.path().join("shared")).unwrap().count(), 1);
+        assert!(atomic_write(dir.path().join("missing/dictionary-de.json"), "{}").is_err());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_followed_only_when_enabled() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("app");
+        fs::create_dir_all(&base).unwrap();
+        fs::create_dir_all(dir.path().join("packages/ui")).unwrap();
+        fs::write(base.join("index.js"), "").unwrap();
+        fs::write(dir.path().join("packages/ui/button.js"), "").unwrap();
+        symlink(dir.path().join("packages/ui"), base.join("ui")).unwrap();
+        symlink(dir.path().join("packages/ui"), base.join("ui-copy")).unwrap();
+        symlink(&base, dir.path().join("packages/ui/back")).unwrap();
+        let base = base.to_str().unwrap();
+        let extensions = HashMap::from([(
+            "js".to_owned(),
+            PhraseExtractor::Regex(generate_call_regex(&["t".to_owned()]).unwrap()),
+        )]);
+        let filter = PathFilter::new(&[], &[]).unwrap();
+        let relative = |files: Vec<std::path::PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|file| file.strip_prefix(base).unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let files =
+            collect_source_files(base, &filter, &extensions, WalkOptions::default()).unwrap();
+        assert_eq!(relative(files), vec!["index.js"]);
+        let options = WalkOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let files = collect_source_files(base, &filter, &extensions, options).unwrap();
+        // Ссылка packages/ui/back ведет обратно в app и образует цикл
+        assert_eq!(relative(files), vec!["index.js", "ui/button.js"]);
+
+        let repo = dir.path().join("dict");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("dictionary-ru.base.json"), "[]").unwrap();
+        fs::write(dir.path().join("shared-en.json"), "{}").unwrap();
+        symlink(dir.path().join("shared-en.json"), repo.join("dictionary-en.json")).unwrap();
+        symlink(dir.path().join("missing.json"), repo.join("dictionary-de.json")).unwrap();
+        let repo = repo.to_str().unwrap();
+        assert_eq!(find_all_dictionaries_in_repository(repo, false).unwrap().len(), 1);
+        let mut found = find_all_dictionaries_in_repository(repo, true).unwrap();
+        found.sort();
+        assert_eq!(found, vec!["dictionary-en.json", "dictionary-ru.base.json"]);
+    }
+
+    #[test]
+    fn test_unchanged_files_are_skipped_with_scan_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap().replace('\\', "/");
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("dict")).unwrap();
+        fs::write(dir.path().join("src/app.js"), "t(\"Привет\")").unwrap();
+        let base_dictionary = dir.path().join("dict/dictionary-ru.base.json");
+        fs::write(&base_dictionary, "[]").unwrap();
+        let config_path = dir.path().join("config.dms.json");
+        fs::write(
+            &config_path,
+            format!(
+                r#"{{"base": "{root}/src", "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": [], "scan_cache": "{root}/.dms/scan-cache.json", "include": [{{"js": {{"ext": ["js"], "regexp-start": ["t("], "regexp-end": [")"]}}}}]}}"#
+            ),
+        )
+        .unwrap();
+        let config_path = Some(config_path.to_str().unwrap().to_owned());
+
+        let report = scan_files_for_phrases(config_path.clone(), true).unwrap();
+        assert_eq!(report.files[0].added, vec!["Привет"]);
+        assert_eq!((report.added(), report.existing(), report.skipped), (1, 0, 0));
+        assert_eq!(parse_static_basic_dictionary(&format!("{root}/dict")).unwrap(), vec!["Привет"]);
+        assert!(dir.path().join(".dms/scan-cache.json").exists());
+        let locations = read_source_locations(&format!("{root}/dict")).unwrap();
+        assert_eq!(
+            locations["Привет"][0].to_string(),
+            format!("{}/src/app.js:1", root.trim_start_matches("./"))
+        );
+
+        // Файл не изменился, поэтому удаленная из словаря фраза не возвращается
+        fs::write(&base_dictionary, "[]").unwrap();
+        let report = scan_files_for_phrases(config_path.clone(), true).unwrap();
+        assert_eq!((report.files.len(), report.skipped), (0, 1));
+        assert!(parse_static_basic_dictionary(&format!("{root}/dict")).unwrap().is_empty());
+
+        scan_files_for_phrases(config_path.clone(), false).unwrap();
+        assert_eq!(parse_static_basic_dictionary(&format!("{root}/dict")).unwrap(), vec!["Привет"]);
+
+        // Фраза уже в словаре, а пропавший из файла тег попадает в кандидаты на удаление
+        fs::write(dir.path().join("src/app.js"), "t(\"Привет\")\nt(\"Пока\")").unwrap();
+        scan_files_for_phrases(config_path.clone(), true).unwrap();
+        fs::write(dir.path().join("src/app.js"), "t(\"Привет\")").unwrap();
+        let report = scan_files_for_phrases(config_path, true).unwrap();
+        assert_eq!(report.files[0].existing, vec!["Привет"]);
+        assert_eq!(report.files[0].removed, vec!["Пока"]);
+    }
+
+    #[test]
+    fn test_unused_entries_are_pruned_from_all_dictionaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap().replace('\\', "/");
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("dict")).unwrap();
+        fs::write(dir.path().join("src/app.js"), "t(\"Привет\")\nt(\"menu.title\")").unwrap();
+        fs::write(
+            dir.path().join("dict/dictionary-ru.base.json"),
+            r#"["Привет", "Пока", "Справка", {"phrase": "Меню", "key": "menu.title"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dict/dictionary-en.json"),
+            r#"{"Привет": "Hello", "Пока": "Bye", "Справка": "Help", "menu.title": "Menu"}"#,
+        )
+        .unwrap();
+        let config = ConfigFileParameters::from_json(&format!(
+            r#"{{"base": "{root}/src", "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": ["Справка"], "include": [{{"js": {{"ext": ["js"], "regexp-start": ["t("], "regexp-end": [")"]}}}}]}}"#
+        ))
+        .unwrap();
+
+        let unused = find_unused_entries(&config).unwrap();
+        let tags: Vec<String> = unused[config.dictionary_repo.primary()]
+            .iter()
+            .map(|entry| entry.tag().to_owned())
+            .collect();
+        assert_eq!(tags, vec!["Пока"]);
+        assert_eq!(remove_entries(config.dictionary_repo.primary(), &tags).unwrap(), 1);
+        assert_eq!(
+            parse_static_basic_dictionary(config.dictionary_repo.primary()).unwrap(),
+            vec!["Привет", "Справка", "Меню"]
+        );
+        let en = read_translated_dictionary(config.dictionary_repo.primary(), "en").unwrap();
+        assert!(!en.contains_key("Пока"));
+        assert_eq!(en.len(), 3);
+    }
+
+    #[test]
+    fn test_phrase_filters_reject_noise() {
+        let filters: PhraseFilters = serde_json::from_str(
+            r#"{"min_length": 3, "require_letters": true, "ignore_constants": true, "reject": ["^#[0-9a-fA-F]{3,8}$", "^\\d+(px|rem|%)$"]}"#,
+        )
+        .unwrap();
+        for noise in ["px", "OK", "100%", "12px", "#fff", "API_KEY", "GET", "...", "2024"] {
+            assert!(!filters.accepts(noise), "{noise}");
+        }
+        for phrase in ["Войти", "ВНИМАНИЕ", "Save changes", "OK, got it"] {
+            assert!(filters.accepts(phrase), "{phrase}");
+        }
+        assert!(PhraseFilters::default().accepts("px"));
+        assert!(serde_json::from_str::<PhraseFilters>(r#"{"reject": ["("]}"#).is_err());
+    }
+
+    #[test]
+    fn test_binary_and_legacy_encoded_files_do_not_break_scanning() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap().replace('\\', "/");
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("dict")).unwrap();
+        fs::write(dir.path().join("dict/dictionary-ru.base.json"), "[]").unwrap();
+        fs::write(dir.path().join("src/app.js"), "t(\"Привет\")").unwrap();
+        fs::write(dir.path().join("src/logo.js"), b"\x89PNG\r\n\x1a\n\0\0t(\"x\")").unwrap();
+        let (legacy, _, _) = encoding_rs::WINDOWS_1251.encode("t(\"Старый модуль\")");
+        fs::write(dir.path().join("src/legacy.js"), legacy).unwrap();
+        let config_path = dir.path().join("config.dms.json");
+        let write_config = |encoding: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"{{"base": "{root}/src", "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": [], "scan_cache": "{root}/.dms/scan-cache.json", "encoding": "{encoding}", "include": [{{"js": {{"ext": ["js"], "call_patterns": ["t"]}}}}]}}"#
+                ),
+            )
+            .unwrap();
+        };
+        let config = Some(config_path.to_str().unwrap().to_owned());
+
+        write_config("utf-8");
+        let report = scan_files_for_phrases(config.clone(), true).unwrap();
+        assert_eq!(report.unreadable().len(), 2);
+        assert_eq!(parse_static_basic_dictionary(&format!("{root}/dict")).unwrap(), vec!["Привет"]);
+
+        // Смена кодировки меняет настройки кеша, поэтому файлы сканируются заново
+        write_config("windows-1251");
+        let report = scan_files_for_phrases(config, true).unwrap();
+        assert_eq!(report.unreadable(), vec![format!("{root}/src/logo.js")]);
+        assert_eq!(
+            parse_static_basic_dictionary(&format!("{root}/dict")).unwrap(),
+            vec!["Привет", "Старый модуль"]
+        );
+    }
+
+    #[test]
+    fn test_monorepo_packages_are_scanned_into_namespaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap().replace('\\', "/");
+        for (package, phrase) in [("web", "Главная"), ("admin", "Пользователи"), ("shared", "ОК")] {
+            fs::create_dir_all(dir.path().join(format!("apps/{package}"))).unwrap();
+            fs::write(
+                dir.path().join(format!("apps/{package}/index.js")),
+                format!("t(\"{phrase}\")"),
+            )
+            .unwrap();
+        }
+        fs::create_dir_all(dir.path().join("dict")).unwrap();
+        fs::write(dir.path().join("dict/dictionary-ru.base.yaml"), "[]").unwrap();
+        let config_path = dir.path().join("config.dms.json");
+        fs::write(
+            &config_path,
+            format!(
+                r#"{{"base": {{"web": "{root}/apps/web", "admin": {{"path": "{root}/apps/admin", "namespace": "backoffice"}}}}, "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": [], "scan_cache": "{root}/.dms/scan-cache.json", "include": [{{"js": {{"ext": ["js"], "call_patterns": ["t"]}}}}]}}"#
+            ),
+        )
+        .unwrap();
+        let report = scan_files_for_phrases(Some(config_path.to_str().unwrap().to_owned()), true)
+            .unwrap();
+        assert_eq!(report.added(), 2);
+        assert_eq!(
+            parse_static_basic_dictionary(&format!("{root}/dict/web")).unwrap(),
+            vec!["Главная"]
+        );
+        assert_eq!(
+            parse_static_basic_dictionary(&format!("{root}/dict/backoffice")).unwrap(),
+            vec!["Пользователи"]
+        );
+        assert!(dir.path().join("dict/web/dictionary-ru.base.yaml").exists());
+        assert!(parse_static_basic_dictionary(&format!("{root}/dict"))
             .unwrap()
-            .par_iter()
-            .map(|v| v.as_str().unwrap().to_owned())
-            .collect::<Vec<String>>())
+            .is_empty());
+
+        let list: ConfigFileParameters = ConfigFileParameters::from_json(&format!(
+            r#"{{"base": ["{root}/apps/web", {{"path": "{root}/apps/shared", "namespace": "common"}}], "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": [], "include": []}}"#
+        ))
+        .unwrap();
+        let roots = list.base_directory.roots();
+        assert_eq!(roots[0].namespace, None);
+        assert_eq!(
+            roots[1].dictionary_dir("dict"),
+            std::path::Path::new("dict").join("common").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_scan_preview_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap().replace('\\', "/");
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.js"), "t(\"Вход\")\nt(\"Выход\")").unwrap();
+        fs::write(dir.path().join("src/b.js"), "t(\"Выход\")\nt(\"Новая\")").unwrap();
+        fs::create_dir_all(dir.path().join("dict")).unwrap();
+        let base = r#"["Вход"]"#;
+        fs::write(dir.path().join("dict/dictionary-ru.base.json"), base).unwrap();
+        let config_path = dir.path().join("config.dms.json");
+        fs::write(
+            &config_path,
+            format!(
+                r#"{{"base": ["{root}/src", {{"path": "{root}/src", "namespace": "common"}}], "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": [], "scan_cache": "{root}/.dms/scan-cache.json", "include": [{{"js": {{"ext": ["js"], "call_patterns": ["t"]}}}}]}}"#
+            ),
+        )
+        .unwrap();
+        let preview = preview_scan_files(Some(config_path.to_str().unwrap().to_owned())).unwrap();
+        assert_eq!(preview.scanned, 4);
+        let added = |dictionary_dir: &str| -> Vec<(String, String, usize)> {
+            preview.added[dictionary_dir]
+                .iter()
+                .map(|added| {
+                    let file = added.file.rsplit('/').next().unwrap().to_owned();
+                    (added.entry.phrase.clone(), file, added.line)
+                })
+                .collect()
+        };
+        assert_eq!(
+            added(&format!("{root}/dict")),
+            vec![
+                ("Выход".to_owned(), "a.js".to_owned(), 2),
+                ("Новая".to_owned(), "b.js".to_owned(), 2)
+            ]
+        );
+        assert_eq!(added(&format!("{root}/dict/common")).len(), 3);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dict/dictionary-ru.base.json")).unwrap(),
+            base
+        );
+        assert!(!dir.path().join("dict/common").exists());
+        assert!(!dir.path().join("dict/locations.json").exists());
+        assert!(!dir.path().join(".dms").exists());
+    }
+
+    #[test]
+    fn test_scanned_files_are_written_to_base_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let base = r#"["Вход"]"#;
+        fs::write(dir.path().join("dictionary-ru.base.json"), base).unwrap();
+        let config = ConfigFileParameters::from_json(&format!(
+            r#"{{"base": "src", "exclude": [], "dictionary_repo": "{}", "output_dir": "out", "manual_translate": [], "include": []}}"#,
+            repo.replace('\\', "/")
+        ))
+        .unwrap();
+        let extracted = |file: &str, phrases: &[&str]| ExtractedFile {
+            file: PathBuf::from(file),
+            hash: String::new(),
+            located: Some(
+                phrases
+                    .iter()
+                    .enumerate()
+                    .map(|(line, phrase)| (BaseEntry::new((*phrase).to_owned()), line + 1))
+                    .collect(),
+            ),
+        };
+
+        let mut dictionary = ScannedDictionary::load(repo).unwrap();
+        let first = dictionary.record(&config, extracted("src/a.js", &["Вход", "Выход"]));
+        let second = dictionary.record(&config, extracted("src/b.js", &["Выход", "Новая"]));
+        assert_eq!((first.added, first.existing), (vec!["Выход".into()], vec!["Вход".into()]));
+        assert_eq!((second.added, second.existing), (vec!["Новая".into()], vec!["Выход".into()]));
+        // До save репозиторий не меняется
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dictionary-ru.base.json")).unwrap(),
+            base
+        );
+        assert!(!dir.path().join("locations.json").exists());
+
+        dictionary.save().unwrap();
+        assert_eq!(
+            parse_static_basic_dictionary(repo).unwrap(),
+            vec!["Вход", "Выход", "Новая"]
+        );
+        assert_eq!(read_source_locations(repo).unwrap()["Выход"].len(), 2);
+    }
+
+    #[test]
+    fn test_html_extractor_scans_vue_templates_and_script_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap().replace('\\', "/");
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/Search.vue"),
+            concat!(
+                "<template>\n",
+                "  <input placeholder=\"Найти товар\" title=\"не атрибут из списка\">\n",
+                "  <button>Искать</button> <!-- dms-context: кнопка поиска -->\n",
+                "</template>\n",
+                "<script>\n",
+                "export default { computed: { empty() { return this.$t('Ничего не найдено') } } }\n",
+                "</script>\n",
+            ),
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("dict")).unwrap();
+        fs::write(dir.path().join("dict/dictionary-ru.base.json"), "[]").unwrap();
+        let config_path = dir.path().join("config.dms.json");
+        fs::write(
+            &config_path,
+            format!(
+                r#"{{"base": "{root}/src", "exclude": [], "dictionary_repo": "{root}/dict", "output_dir": "{root}/out", "manual_translate": [], "scan_cache": "{root}/.dms/scan-cache.json", "include": [{{"vue": {{"ext": ["vue"], "extractor": "html", "attributes": ["placeholder"], "call_patterns": ["$t"]}}}}]}}"#
+            ),
+        )
+        .unwrap();
+        scan_files_for_phrases(Some(config_path.to_str().unwrap().to_owned()), false).unwrap();
+        let entries = parse_static_basic_entries(&format!("{root}/dict")).unwrap();
+        let phrases: Vec<&str> = entries.iter().map(|entry| entry.phrase.as_str()).collect();
+        assert_eq!(phrases, vec!["Найти товар", "Искать", "Ничего не найдено"]);
+        assert_eq!(entries[1].context.as_deref(), Some("кнопка поиска"));
+    }
+
+    #[test]
+    fn test_status_counts_translation_states() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выход", "Отмена", {"phrase": "яблоки", "plural_forms": {"one": "{{count}} яблоко", "other": "{{count}} яблок"}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": {"value": "Sign in", "status": "machine"}, "Выход": {"value": "Exit", "status": "reviewed"}, "Отмена": "", "яблоки": {"value": "", "plural_forms": {"one": "{{count}} apple", "other": ""}}, "Лишний": "Extra"}"#,
+        )
+        .unwrap();
+
+        let report = status(repo, None).unwrap();
+        assert_eq!(report.basic_language, "ru");
+        assert_eq!(report.total, 4);
+        let en = &report.languages[0];
+        assert_eq!((en.translated, en.empty, en.machine, en.human), (2, 2, 1, 1));
+        assert_eq!(en.percentage(), 50.0);
+
+        let report = status(repo, Some(vec!["de".to_owned()])).unwrap();
+        assert_eq!((report.languages[0].empty, report.languages[0].percentage()), (4, 0.0));
+    }
+
+    #[test]
+    fn test_sync_adds_new_keys_and_prunes_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выход"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in", "Старый": "Old", "Закреплен": {"value": "Pinned", "locked": true}}"#,
+        )
+        .unwrap();
+
+        let report = sync_dictionaries(repo, false).unwrap();
+        assert_eq!(report.added["en"], vec!["Выход".to_owned()]);
+        assert!(report.removed["en"].is_empty());
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary["Вход"].value, "Sign in");
+        assert_eq!(dictionary["Выход"].value, "");
+        assert!(dictionary.contains_key("Старый"));
+
+        let report = sync_dictionaries(repo, true).unwrap();
+        assert!(report.added["en"].is_empty());
+        assert_eq!(report.removed["en"], vec!["Старый".to_owned()]);
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert!(!dictionary.contains_key("Старый"));
+        assert!(dictionary.contains_key("Закреплен"));
+        assert!(sync_dictionaries(repo, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rename_entry_across_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let source = dir.path().join("login.ts");
+        fs::write(&source, "t(\"auth.login\", \"Вход\");\nconst auth = 'auth.login.hint';\n").unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Вход", "key": "auth.login"}, "Выход"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"auth.login": {"value": "Sign in", "status": "reviewed"}, "Выход": "Exit"}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(LOCATIONS_FILE),
+            format!(
+                r#"{{"auth.login": [{{"file": "{}", "line": 1}}]}}"#,
+                source.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            rename_entry(repo, "auth.login", "Выход", false),
+            Err(StaticDictionaryErrors::DuplicateTag(_))
+        ));
+        assert!(matches!(
+            rename_entry(repo, "auth.logout", "auth.exit", false),
+            Err(StaticDictionaryErrors::EntryNotFound(_))
+        ));
+
+        let report = rename_entry(repo, "auth.login", "auth.sign_in", true).unwrap();
+        assert_eq!(report.languages, vec!["en".to_owned()]);
+        assert_eq!(report.source_files.len(), 1);
+        let entries = parse_static_basic_entries(repo).unwrap();
+        assert_eq!((entries[0].tag(), entries[0].phrase.as_str()), ("auth.sign_in", "Вход"));
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary["auth.sign_in"].value, "Sign in");
+        assert!(!dictionary.contains_key("auth.login"));
+        assert!(read_source_locations(repo).unwrap().contains_key("auth.sign_in"));
+        assert_eq!(
+            fs::read_to_string(&source).unwrap(),
+            "t(\"auth.sign_in\", \"Вход\");\nconst auth = 'auth.login.hint';\n"
+        );
+
+        rename_entry(repo, "Выход", "auth.logout", false).unwrap();
+        rename_entry(repo, "auth.logout", "Выход", false).unwrap();
+        assert_eq!(parse_static_basic_entries(repo).unwrap()[1].key, None);
+    }
+
+    #[test]
+    fn test_failed_rename_leaves_repository_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let base = r#"[{"phrase": "Вход", "key": "auth.login"}]"#;
+        let translated = r#"{"auth.login": "Sign in", "auth.sign_in": "Log in"}"#;
+        fs::write(dir.path().join("dictionary-ru.base.json"), base).unwrap();
+        fs::write(dir.path().join("dictionary-en.json"), translated).unwrap();
+        assert!(matches!(
+            rename_entry(repo, "auth.login", "auth.sign_in", false),
+            Err(StaticDictionaryErrors::DuplicateTag(_))
+        ));
+
+        fs::write(dir.path().join("dictionary-en.json"), r#"{"auth.login": "Sign in"}"#).unwrap();
+        let missing = dir.path().join("removed.ts");
+        fs::write(
+            dir.path().join(LOCATIONS_FILE),
+            format!(
+                r#"{{"auth.login": [{{"file": "{}", "line": 1}}]}}"#,
+                missing.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+        assert!(rename_entry(repo, "auth.login", "auth.sign_in", true).is_err());
+        assert_eq!(fs::read_to_string(dir.path().join("dictionary-ru.base.json")).unwrap(), base);
+        assert!(read_translated_dictionary(repo, "en").unwrap().contains_key("auth.login"));
+        assert!(read_source_locations(repo).unwrap().contains_key("auth.login"));
+    }
+
+    #[test]
+    fn test_remove_entries_by_glob_with_dry_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Вход", "key": "auth.login"}, {"phrase": "Выход", "key": "auth.logout"}, "Вы уверены?", "Вы уверены!"]"#,
+        )
+        .unwrap();
+        let translated = r#"{"auth.login": "Sign in", "auth.logout": "Sign out", "Вы уверены?": "Sure?", "Вы уверены!": "Sure!"}"#;
+        fs::write(dir.path().join("dictionary-en.json"), translated).unwrap();
+
+        let patterns = vec!["auth.*".to_owned(), "Вы уверены?".to_owned()];
+        let report = remove_matching_entries(repo, &patterns, true).unwrap();
+        assert_eq!(report.tags, vec!["auth.login", "auth.logout", "Вы уверены?"]);
+        assert_eq!(report.languages["en"], 3);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap(),
+            translated
+        );
+
+        assert!(matches!(
+            remove_matching_entries(repo, &["auth.*".to_owned(), "missing".to_owned()], false),
+            Err(StaticDictionaryErrors::EntryNotFound(_))
+        ));
+        assert_eq!(parse_static_basic_entries(repo).unwrap().len(), 4);
+
+        remove_matching_entries(repo, &patterns, false).unwrap();
+        let entries = parse_static_basic_entries(repo).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].tag(), "Вы уверены!");
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary.keys().collect::<Vec<_>>(), vec!["Вы уверены!"]);
+    }
+
+    #[test]
+    fn test_call_patterns_extract_first_string_argument() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("page.ts");
+        fs::write(
+            &source,
+            concat!(
+                "const title = t('Оформить заказ');\n",
+                "i18n.t(\"Товаров: {{count}}\", { count: items.filter((item) => item.ok).length })\n",
+                "text.split(\"не фраза\"); format(t( 'Скажи \\'привет\\'' ))\n",
+                "legacy(\"По старому\")\n",
+            ),
+        )
+        .unwrap();
+        let configuration: LanguageConfiguration = serde_json::from_str(
+            r#"{"ext": ["ts"], "call_patterns": ["t", "i18n.t"], "regexp-start": ["legacy("], "regexp-end": [")"]}"#,
+        )
+        .unwrap();
+        let pattern = language_regex(&configuration).unwrap();
+        let phrases: Vec<String> =
+            get_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Phrase)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.phrase)
+                .collect();
+        assert_eq!(
+            phrases,
+            vec!["Оформить заказ", "Товаров: {{count}}", "Скажи 'привет'", "По старому"]
+        );
     }
 
-    #[doc = "Парсит дочерний словарь и возвращает вектор с структурами типа Word"]
-    pub fn parse_translated_dictionary(
-        dictionary_dir: &str,
-        language: &str,
-    ) -> Result<Vec<Word>, StaticDictionaryErrors> {
-        let file_content =
-            fs::read_to_string(format!("{}/dictionary-{}.json", dictionary_dir, language))?;
-        let json_object: Value = serde_json::from_str(&file_content)?;
-        let dictionary = json_object.as_object().unwrap();
-        let mut result: Vec<Word> = vec![];
-        for (tag, word) in dictionary {
-            result.push(Word::new(
-                word.to_string(),
-                tag.to_owned(),
-                language.to_owned(),
-            ));
+    #[test]
+    fn test_delimiters_per_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let configuration: LanguageConfiguration = serde_json::from_str(
+            r#"{"ext": ["py", "js", "rs"], "regexp-start": ["_("], "regexp-end": [")"], "delimiters": {"py": "'", ".js": "`"}}"#,
+        )
+        .unwrap();
+        let sources = [
+            ("app.py", "_('Сохранить') + _(\"не фраза\")", "Сохранить"),
+            ("app.js", "_(`Открыть`) + _('не фраза')", "Открыть"),
+            ("app.rs", "_(\"Закрыть\") + _('н')", "Закрыть"),
+        ];
+        for (name, content, expected) in sources {
+            let source = dir.path().join(name);
+            fs::write(&source, content).unwrap();
+            let extension = name.rsplit('.').next().unwrap();
+            let pattern = extension_regex(&configuration, extension).unwrap();
+            let phrases: Vec<String> =
+                get_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Phrase)
+                    .unwrap()
+                    .into_iter()
+                    .map(|entry| entry.phrase)
+                    .collect();
+            assert_eq!(phrases, vec![expected]);
         }
+    }
 
-        Ok(result)
+    #[test]
+    fn test_rust_preset_extracts_macro_literals() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("main.rs");
+        fs::write(
+            &source,
+            concat!(
+                "println!(\"{}\", t!(\"Привет, мир\"));\n",
+                "let label = fl!(LOADER, \"menu-title\");\n",
+                "let quoted = t!(r#\"Скажи \"да\"\"#, name = user);\n",
+                "let escaped = t![\"Путь C:\\\\temp\"];\n",
+                "format!(\"не фраза\");\n",
+            ),
+        )
+        .unwrap();
+        let configuration: LanguageConfiguration =
+            serde_json::from_str(r#"{"preset": "rust"}"#).unwrap();
+        let configuration = configuration.with_preset();
+        assert_eq!(configuration.file_extensions, vec!["rs"]);
+        let pattern = language_regex(&configuration).unwrap();
+        let phrases: Vec<String> =
+            get_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Phrase)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.phrase)
+                .collect();
+        assert_eq!(
+            phrases,
+            vec!["Привет, мир", "menu-title", "Скажи \"да\"", "Путь C:\\temp"]
+        );
     }
 
-    #[doc = "Генерирует пустые статические словари из базового статического словаря"]
-    pub fn generate_empty_dictionaries_from_static_basic(
-        dictionary_dir: &str,
-        languages: Vec<String>,
-    ) -> Result<(), StaticDictionaryErrors> {
-        let mut basic_dictionary = parse_static_basic_dictionary(dictionary_dir)?;
-        basic_dictionary.dedup();
-        let words = Arc::new(
-            basic_dictionary
-                .par_iter()
-                .map(|word| {
-                    Word::new(
-                        word.to_owned(),
-                        word.to_owned(),
-                        get_dictionary_language(&get_basic_dictionary(dictionary_dir).unwrap())
-                            .unwrap(),
-                    )
-                    .to_owned()
-                })
-                .collect::<Vec<Word>>(),
+    #[test]
+    fn test_multiline_and_template_literals_are_extracted() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("page.js");
+        fs::write(
+            &source,
+            concat!(
+                "const a = 1;\n",
+                "t(\n  \"Длинная фраза, \\\n  перенесенная\"\n)\n",
+                "t(`Привет, ${user.name}!\nУ вас ${count} писем и ${items.length * 2} задач`)\n",
+                "legacy(\"Первая\nвторая\")\n",
+            ),
+        )
+        .unwrap();
+        let configuration: LanguageConfiguration = serde_json::from_str(
+            r#"{"ext": ["js"], "call_patterns": ["t"], "regexp-start": ["legacy("], "regexp-end": [")"]}"#,
+        )
+        .unwrap();
+        let pattern = language_regex(&configuration).unwrap();
+        let located =
+            get_located_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Phrase)
+                .unwrap();
+        let phrases: Vec<(&str, usize)> = located
+            .iter()
+            .map(|(entry, line)| (entry.phrase.as_str(), *line))
+            .collect();
+        assert_eq!(
+            phrases,
+            vec![
+                ("Длинная фраза, \\\n  перенесенная", 2),
+                ("Привет, {{user.name}}!\nУ вас {{count}} писем и {{2}} задач", 6),
+                ("Первая\nвторая", 8),
+            ]
         );
+    }
 
-        languages.par_iter().for_each(|language| {
-            if check_dictionary_exists(dictionary_dir, language) {
-                fs::remove_file(format!("{}/dictionary-{}.json", dictionary_dir, language))
-                    .expect(&format!("Произошла ошибка при попытке удаления существующего словаря dictionary-{}.json", language));
-            }
-            let file =
-                fs::File::create_new(format!("{}/dictionary-{}.json", dictionary_dir, language))
-                    .expect(&format!(
-                        "Произошла ошибка при попытке создать файл словаря dictionary-{}.json",
-                        language
-                    ));
-            let json_object = Arc::new(Mutex::new(serde_json::json!({})));
-            let words = Arc::clone(&words);
-            words.par_iter().for_each(|word| {
-                let mut json_object = json_object.lock().unwrap();
-                json_object[word.clone().word] = "".into();
-            });
-            serde_json::to_writer_pretty(&file, &*json_object.lock().unwrap()).unwrap();
-        });
-        Ok(())
+    #[test]
+    fn test_ignore_and_context_annotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("login.jsx");
+        fs::write(
+            &source,
+            concat!(
+                "// dms-ignore-next-line
+",
+                "t(\"debug-only\")\n",
+                "t(\"log key\") // dms-ignore-line\n",
+                "// dms-context: label on login button\n",
+                "t(\"Войти\")\n",
+                "<b>{t(\"Выйти\")}</b> {/* dms-context: header menu */}\n",
+                "t(\"Без контекста\")\n",
+            ),
+        )
+        .unwrap();
+        let configuration: LanguageConfiguration =
+            serde_json::from_str(r#"{"ext": ["jsx"], "call_patterns": ["t"]}"#).unwrap();
+        let pattern = language_regex(&configuration).unwrap();
+        let located =
+            get_located_entries_from_file(source.to_str().unwrap(), pattern, KeyStrategy::Phrase)
+                .unwrap();
+        let entries: Vec<(&str, Option<&str>)> = located
+            .iter()
+            .map(|(entry, _)| (entry.phrase.as_str(), entry.context.as_deref()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("Войти", Some("label on login button")),
+                ("Выйти", Some("header menu")),
+                ("Без контекста", None),
+            ]
+        );
     }
 
-    #[doc = "Генериует статические словари на основе базового, а потом автоматически их переводит с помощью выбранного автопереводчика"]
-    // Когда я писал это, только двое знали что тут вообще творится - это я и Бог. Сейчас только Бог знает, что здесь происходит....
-    // А не, кажись я допер че я тут понаписал
-    pub async fn autotranslate_from_basic_dictionary(
-        dictionary_dir: &str,
-        target_languages: Vec<String>,
-        translator_api: TranslatorApis,
-        api_args: ApiArgs,
-    ) -> Result<(), StaticDictionaryErrors> {
-        let mut basic_dictionary = parse_static_basic_dictionary(dictionary_dir)?;
-        basic_dictionary.dedup();
-        let words = Arc::new(
-            basic_dictionary
-                .par_iter()
-                .map(|word| {
-                    Word::new(
-                        word.to_owned(),
-                        word.to_owned(),
-                        get_dictionary_language(&get_basic_dictionary(dictionary_dir).unwrap())
-                            .unwrap(),
-                    )
-                    .to_owned()
-                })
-                .collect::<Vec<Word>>(),
+    #[test]
+    fn test_v1_dictionaries_are_read_and_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Привет", {"phrase": "Оплатить", "context": "кнопка"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Привет": "Hello", "Оплатить": ""}"#,
+        )
+        .unwrap();
+
+        let words = parse_translated_dictionary(repo, "en").unwrap();
+        assert!(words.iter().any(|word| word.word == "Hello"));
+
+        assert_eq!(
+            migrate_repository(repo).unwrap(),
+            vec!["dictionary-en.json".to_owned()]
         );
+        let migrated = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(migrated["Привет"].value, "Hello");
+        assert_eq!(migrated["Привет"].status, EntryStatus::Translated);
+        assert_eq!(migrated["Оплатить"].status, EntryStatus::Untranslated);
+        assert_eq!(migrated["Оплатить"].context.as_deref(), Some("кнопка"));
+        assert!(migrated["Привет"].source_hash.is_some());
 
-        let translator = Arc::new(match translator_api {
-            TranslatorApis::LibreTranslate => LibreTranslateApi::new(api_args.host),
-            TranslatorApis::DeepL => todo!(),
-            TranslatorApis::Yandex => todo!(),
-        });
+        let raw: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(raw["Привет"]["value"], "Hello");
+        assert_eq!(raw["Привет"]["status"], "translated");
+    }
 
-        let mut tasks = vec![];
+    #[test]
+    fn test_i18next_build_emits_plural_suffixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("dictionaries");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(
+            repo.join("dictionary-ru.base.json"),
+            r#"["Привет", {"phrase": "файлы", "plural_forms": {"one": "файл", "other": "файлы"}}]"#,
+        )
+        .unwrap();
+        let repo = repo.to_str().unwrap();
+        generate_empty_dictionaries_from_static_basic(repo, vec!["en".to_owned()]).unwrap();
+        assert_eq!(parse_static_basic_entries(repo).unwrap().len(), 2);
 
-        for target_language in target_languages.clone() {
-            let words = Arc::clone(&words);
-            let translator = Arc::clone(&translator);
+        let output = dir.path().join("output");
+        build_for_i18next(
+            repo,
+            output.to_str().unwrap(),
+            Some(vec!["en".to_owned()]),
+            &BuildOptions::default(),
+        )
+        .unwrap();
+        let built: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output.join("en/translation.json")).unwrap(),
+        )
+        .unwrap();
+        assert!(built.get("Привет").is_some());
+        assert!(built.get("файлы_one").is_some());
+        assert!(built.get("файлы_other").is_some());
+        assert!(built.get("файлы").is_none());
+    }
 
-            for word in &*words.clone() {
-                let word = word.clone();
-                let translator = Arc::clone(&translator);
-                let target_language = target_language.to_string();
+    #[test]
+    fn test_variants_are_generated_and_suffixed() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "пригласил", "variants": {"male": "Он пригласил вас", "female": "Она пригласила вас"}}]"#,
+        )
+        .unwrap();
+        generate_empty_dictionaries_from_static_basic(repo, vec!["en".to_owned()]).unwrap();
+        let words = parse_translated_dictionary(repo, "en").unwrap();
+        let mut tags: Vec<String> = words.iter().map(Word::suffixed_tag).collect();
+        tags.sort();
+        assert_eq!(tags, vec!["пригласил_female", "пригласил_male"]);
+        assert_eq!(
+            Word::new("".to_owned(), "friend".to_owned(), "en".to_owned())
+                .with_variant(Some("male".to_owned()))
+                .with_plural_form(Some("one".to_owned()))
+                .suffixed_tag(),
+            "friend_male_one"
+        );
+    }
 
-                let task = tokio::spawn(async move {
-                    translator
-                        .translate_word_with_tag(word, target_language)
-                        .await
-                });
-                tasks.push(task);
-            }
+    #[test]
+    fn test_machine_translations_are_written_as_suggestions() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Привет", "Пока"]"#).unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Привет": {"value": "Hello", "status": "reviewed"}}"#,
+        )
+        .unwrap();
+        let mut machine = TranslatedDictionary::new();
+        for (tag, text) in [("Привет", "Hi"), ("Пока", "Bye")] {
+            let mut entry = DictionaryEntry::default();
+            entry.set_word(&Word::new(text.to_owned(), tag.to_owned(), "en".to_owned()));
+            entry.status = EntryStatus::Machine;
+            machine.insert(tag.to_owned(), entry);
         }
+        write_suggestions(repo, "en", machine).unwrap();
 
-        let results = join_all(tasks).await;
-        let mut words_with_languages_hashmap: HashMap<String, Vec<Word>> = HashMap::new();
-        target_languages.clone().iter().for_each(|language| {
-            words_with_languages_hashmap.insert(language.to_owned(), vec![]);
-        });
-        for join_result in results {
-            match join_result {
-                Ok(request_result) => {
-                    let word = request_result?;
-                    words_with_languages_hashmap
-                        .get_mut(&word.language)
-                        .expect(&format!("Не найден ключ {}", word.tag))
-                        .push(word.clone());
-                }
-                Err(err) => return Err(StaticDictionaryErrors::AsyncError(err)),
-            }
-        }
-
-        for (language, words) in &words_with_languages_hashmap {
-            if check_dictionary_exists(dictionary_dir, language) {
-                fs::remove_file(format!("{}/dictionary-{}.json", dictionary_dir, language))?;
-            }
-            let file =
-                fs::File::create_new(format!("{}/dictionary-{}.json", dictionary_dir, language))
-                    .expect(&format!(
-                        "Произошла ошибка при попытке создать файл словаря dictionary-{}.json",
-                        language
-                    ));
-            let json_object = Arc::new(Mutex::new(serde_json::json!({})));
-            let words = Arc::new(words);
-            words.par_iter().for_each(|word| {
-                let mut json_object = json_object.lock().unwrap();
-                json_object[word.clone().tag] = word.word.replace("\"", "").clone().into();
-            });
-            serde_json::to_writer_pretty(&file, &*json_object.lock().unwrap())?;
-        }
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary["Привет"].value, "Hello");
+        assert_eq!(dictionary["Привет"].status, EntryStatus::Reviewed);
+        assert_eq!(dictionary["Привет"].suggestion.as_ref().unwrap().value, "Hi");
+        assert_eq!(dictionary["Пока"].value, "");
+        assert_eq!(dictionary["Пока"].status, EntryStatus::Untranslated);
+        assert_eq!(
+            dictionary["Пока"].suggested_words("Пока", "en")[0].word,
+            "Bye"
+        );
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_autotranslate_untranslated_keeps_translated_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Да", "Нет"]"#).unwrap();
+        let translated = r#"{"Да": {"value": "Yes", "status": "reviewed"}}"#;
+        fs::write(dir.path().join("dictionary-en.json"), translated).unwrap();
+        // Переводчик недоступен: успешный результат значит, что запросов к нему не было
+        let api_args = ApiArgs::new(None, "http://127.0.0.1:9".to_owned());
+        let tags = BTreeSet::from(["Да".to_owned()]);
+        autotranslate_untranslated(
+            repo,
+            vec!["en".to_owned()],
+            TranslatorApis::LibreTranslate,
+            api_args.clone(),
+            Some(&tags),
+        )
+        .await
+        .unwrap();
+        let content = fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap();
+        assert_eq!(content, translated);
+
+        let result = autotranslate_untranslated(
+            repo,
+            vec!["en".to_owned()],
+            TranslatorApis::LibreTranslate,
+            api_args,
+            None,
+        )
+        .await;
+        assert!(result.is_err());
+        let content = fs::read_to_string(dir.path().join("dictionary-en.json")).unwrap();
+        assert_eq!(content, translated);
     }
 
-    #[doc = "Добавляет новые фразы в базовый словарь"]
-    pub fn update_basic_dictionary(
-        dictionary_dir: &str,
-        words: Vec<String>,
-    ) -> Result<(), StaticDictionaryErrors> {
-        let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
-        let mut basic_dictionary_content = parse_static_basic_dictionary(dictionary_dir)?;
+    const TRICKY_VALUES: [&str; 4] = [
+        r#"Он сказал "привет""#,
+        "It's a \"quoted\" word",
+        r"C:\Users\dms \n not a newline",
+        "日本語と emoji 🎉 и кириллица",
+    ];
 
-        for word in words {
-            if !basic_dictionary_content.contains(&word) {
-                basic_dictionary_content.push(word);
-            }
+    #[test]
+    fn test_libre_translate_response_is_unescaped() {
+        for value in TRICKY_VALUES {
+            let response = serde_json::json!({ "translatedText": value }).to_string();
+            let text = parse_libre_translate_response(&response).unwrap();
+            assert_eq!(text, value);
         }
-        let json_object: Value = serde_json::json!(basic_dictionary_content);
-        let file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(format!("{}/{}", dictionary_dir, basic_dictionary))?;
-        serde_json::to_writer_pretty(&file, &json_object)?;
-        Ok(())
     }
 
-    #[doc = "Управляет синхронизацией фраз из конфига в базовый словарь"]
-    pub fn sync_manual_phrases(manual_phrases: Vec<String>, dictionary_dir: &str) -> Result<(), StaticDictionaryErrors> {
-        let basic_dictionary_content: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(parse_static_basic_dictionary(dictionary_dir)?));
-        manual_phrases
-            .par_iter()
-            .for_each(|phrase| {
-                let dictionary = Arc::clone(&basic_dictionary_content);
-                let mut mut_dictionary = dictionary.lock().expect("Произошла ошибка при синхронизации словарей");
-                if !mut_dictionary.contains(phrase) {
-                    mut_dictionary.push(phrase.to_owned());
-                }
-            });
-        Ok(())
+    #[test]
+    fn test_quotes_round_trip_through_dictionary_and_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let mut dictionary = TranslatedDictionary::new();
+        for (index, value) in TRICKY_VALUES.iter().enumerate() {
+            dictionary.insert(
+                format!("key{}", index),
+                DictionaryEntry {
+                    value: value.to_string(),
+                    status: EntryStatus::Machine,
+                    ..Default::default()
+                },
+            );
+        }
+        write_translated_dictionary(repo, "en", &dictionary).unwrap();
+        assert_eq!(read_translated_dictionary(repo, "en").unwrap(), dictionary);
+
+        let output = dir.path().join("build");
+        build_for_i18next(
+            repo,
+            output.to_str().unwrap(),
+            Some(vec!["en".to_owned()]),
+            &BuildOptions::default(),
+        )
+        .unwrap();
+        let built: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output.join("en").join("translation.json")).unwrap(),
+        )
+        .unwrap();
+        for (index, value) in TRICKY_VALUES.iter().enumerate() {
+            assert_eq!(built[format!("key{}", index)], *value);
+        }
     }
-}
 
-#[doc = "Модуль с функциями для работы с репозиториями словарей"]
-pub mod file_system {
-    use std::{
-        ffi::OsStr,
-        fs::{self, File},
-        path::Path,
-        env
-    };
+    #[test]
+    fn test_normalized_update_merges_identical_phrases() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Мой  профиль "]"#).unwrap();
+        let scanned = vec![
+            BaseEntry::new("Мои\u{306} профиль".to_owned()),
+            BaseEntry::new(" Выход ".to_owned()),
+        ];
+        update_basic_dictionary_entries(repo, scanned, true).unwrap();
+        let entries = parse_static_basic_entries(repo).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                BaseEntry::new("Мой  профиль ".to_owned()),
+                BaseEntry::new("Выход".to_owned()),
+            ]
+        );
+    }
 
-    use regex;
+    #[test]
+    fn test_regional_dictionaries_are_found_and_fall_back_to_language() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Да", "Нет", "Отмена"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-pt.json"), r#"{"Да": "Sim", "Нет": "Não", "Отмена": "Cancelar"}"#).unwrap();
+        fs::write(dir.path().join("dictionary-pt-BR.json"), r#"{"Да": "Sim!", "Нет": ""}"#).unwrap();
 
-    use crate::{
-        errors::errors::{BuildSystemErrors, StaticDictionaryErrors},
-        parser::types::ConfigFileParameters,
-    };
+        let mut dictionaries = find_all_translated_dictionaries(repo).unwrap();
+        dictionaries.sort();
+        assert_eq!(dictionaries, vec!["dictionary-pt-BR.json", "dictionary-pt.json"]);
+        assert_eq!(get_dictionary_by_lang(repo, "pt").unwrap(), "dictionary-pt.json");
 
-    #[doc = "Инициализирует новый репозиторий словарей"]
-    pub fn init_new_dictionary_system(
-        parent: Option<String>,
-        basic_language: String,
-    ) -> Result<(), StaticDictionaryErrors> {
-        match parent {
-            Some(path) => {
-                fs::create_dir_all(format!("{}/dictionaries", path))?;
-                let file = File::create_new(format!(
-                    "{}/dictionaries/dictionary-{}.base.json",
-                    path, basic_language
-                ))?;
-                let json_object = serde_json::json!([]);
-                serde_json::to_writer_pretty(&file, &json_object)?;
-            }
-            None => {
-                let path = std::env::current_dir()?.to_str().unwrap().to_owned();
-                fs::create_dir_all(format!("{}/dictionaries", path))?;
-                let file = File::create_new(format!(
-                    "{}/dictionaries/dictionary-{}.base.json",
-                    &path, basic_language
-                ))?;
-                let json_object = serde_json::json!([]);
-                serde_json::to_writer_pretty(&file, &json_object)?;
-            }
-        }
-        Ok(())
+        let output = dir.path().join("build");
+        build_for_i18next(repo, output.to_str().unwrap(), None, &BuildOptions::default()).unwrap();
+        let built: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output.join("pt-BR").join("translation.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(built, serde_json::json!({"Да": "Sim!", "Нет": "Não", "Отмена": "Cancelar"}));
     }
 
-    #[doc = "Проверяет наличие словаря определенного языка в репозитории"]
-    pub fn check_dictionary_exists(dictionary_path: &str, language: &str) -> bool {
-        Path::new(&format!("{}/dictionary-{}.json", dictionary_path, language)).exists()
+    #[test]
+    fn test_configured_fallback_chain_reports_inherited_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-en.base.json"), r#"["Yes", "No", "Cancel"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-fr.json"), r#"{"Yes": "Oui", "No": ""}"#).unwrap();
+        fs::write(dir.path().join("dictionary-fr-CA.json"), r#"{"Yes": "Ouais"}"#).unwrap();
+        let options = BuildOptions {
+            fallbacks: [("fr-CA".to_owned(), vec!["fr".to_owned(), "en".to_owned()])].into(),
+            ..Default::default()
+        };
+
+        let output = dir.path().join("build");
+        let report = build_for_i18next(
+            repo,
+            output.to_str().unwrap(),
+            Some(vec!["fr-CA".to_owned()]),
+            &options,
+        )
+        .unwrap();
+        let built: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output.join("fr-CA").join("translation.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(built, serde_json::json!({"Yes": "Ouais", "No": "No", "Cancel": "Cancel"}));
+        assert_eq!(
+            report.inherited["fr-CA"],
+            [("Cancel".to_owned(), "en".to_owned()), ("No".to_owned(), "en".to_owned())].into()
+        );
     }
 
-    #[doc = "Возвращает список всех словарей в репозитории"]
-    // TODO: Заменить на другой тип ошибки
-    pub fn find_all_dictionaries_in_repository(
-        dictionary_path: &str,
-    ) -> Result<Vec<String>, BuildSystemErrors> {
-        let paths = fs::read_dir(dictionary_path)?;
-        let pattern = regex::Regex::new(r"^dictionary-(.+?)(?:\.base)?\.json$")?;
-        let mut result: Vec<String> = vec![];
-        for file in paths {
-            match file {
-                Ok(path) => {
-                    let filename = path.file_name().into_string().unwrap();
-                    if pattern.is_match(&filename) {
-                        result.push(filename);
-                    }
-                    return Ok(result);
-                }
-                Err(error) => return Err(BuildSystemErrors::IOError(error)),
-            }
-        }
-        Ok(result)
+    #[test]
+    fn test_nested_keys_are_expanded_with_collision_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.base.json"),
+            r#"["checkout.payment.title", "checkout.payment.button", "Hello. Welcome"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-de.json"),
+            r#"{"checkout.payment.title": "Zahlung", "checkout.payment.button": "", "Hello. Welcome": ""}"#,
+        )
+        .unwrap();
+        let options = BuildOptions {
+            nested_keys: true,
+            ..Default::default()
+        };
+
+        let output = dir.path().join("build");
+        build_for_i18next(repo, output.to_str().unwrap(), None, &options).unwrap();
+        let built: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output.join("de").join("translation.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            built,
+            serde_json::json!({"checkout": {"payment": {"title": "Zahlung", "button": ""}}, "Hello. Welcome": ""})
+        );
+
+        fs::write(dir.path().join("dictionary-en.base.json"), r#"["checkout", "checkout.title"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-de.json"), r#"{"checkout": "Kasse", "checkout.title": ""}"#).unwrap();
+        let output = dir.path().join("collision");
+        assert!(matches!(
+            build_for_i18next(repo, output.to_str().unwrap(), None, &options),
+            Err(BuildSystemErrors::KeyCollision(key)) if key == "checkout.title"
+        ));
     }
 
-    #[doc = "Находит все переведнные словари в репозитории, игнорируя базовый словарь"]
-    pub fn find_all_translated_dictionaries(
-        dictionary_path: &str,
-    ) -> Result<Vec<String>, StaticDictionaryErrors> {
-        let paths = fs::read_dir(dictionary_path)?;
-        let pattern = regex::Regex::new(r"^dictionary-[a-z]{2}\.json$")?;
-        let mut result = vec![];
-        for file in paths {
-            match file {
-                Ok(path) => {
-                    let filename = path.file_name().into_string().unwrap();
-                    if pattern.is_match(&filename) {
-                        result.push(filename);
-                    }
-                }
-                Err(error) => return Err(StaticDictionaryErrors::IOError(error)),
-            }
+    #[test]
+    fn test_namespaces_build_into_separate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        for (namespace, base, translated) in [
+            ("common", r#"["Да"]"#, r#"{"Да": "Yes"}"#),
+            ("checkout", r#"["Оплатить"]"#, r#"{"Оплатить": "Pay"}"#),
+        ] {
+            let namespace_dir = dir.path().join(namespace);
+            fs::create_dir(&namespace_dir).unwrap();
+            fs::write(namespace_dir.join("dictionary-ru.base.json"), base).unwrap();
+            fs::write(namespace_dir.join("dictionary-en.json"), translated).unwrap();
         }
-        return Ok(result);
+        let mut options = BuildOptions {
+            namespaces: vec!["common".to_owned(), "checkout".to_owned()],
+            ..Default::default()
+        };
+
+        let output = dir.path().join("build");
+        build_for_i18next(repo, output.to_str().unwrap(), None, &options).unwrap();
+        let read = |namespace: &str| -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(output.join("en").join(format!("{}.json", namespace))).unwrap()).unwrap()
+        };
+        assert_eq!(read("common"), serde_json::json!({"Да": "Yes"}));
+        assert_eq!(read("checkout"), serde_json::json!({"Оплатить": "Pay"}));
+
+        options.namespaces.push("profile".to_owned());
+        assert!(matches!(
+            build_for_i18next(repo, dir.path().join("other").to_str().unwrap(), None, &options),
+            Err(BuildSystemErrors::NamespaceNotFound(namespace)) if namespace == "profile"
+        ));
     }
 
-    #[doc = "Считывает и парсит конфиг. Если путь до конфига не передан - пытается найти его в cwd"]
-    #[inline]
-    pub fn parse_config_file(
-        config_path: &str,
-    ) -> Result<ConfigFileParameters, StaticDictionaryErrors> {
-        let file_content = fs::read_to_string(config_path)?;
-        let config_parsed = ConfigFileParameters::from_json(&file_content);
-        match config_parsed {
-            Ok(conf) => return Ok(conf),
-            Err(err) => {
-                println!("{:?}", err);
-                return Err(StaticDictionaryErrors::JSONParsingError(err));
-            }
+    #[test]
+    fn test_builds_are_sorted_and_can_be_minified() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Я", "Б", "А"]"#).unwrap();
+        fs::write(dir.path().join("dictionary-en.json"), r#"{"Я": "I", "Б": "B", "А": "A"}"#).unwrap();
+        let mut options = BuildOptions::default();
+
+        let mut outputs = vec![];
+        for attempt in ["first", "second"] {
+            let output = dir.path().join(attempt);
+            build_for_i18next(repo, output.to_str().unwrap(), None, &options).unwrap();
+            outputs.push(fs::read_to_string(output.join("en").join("translation.json")).unwrap());
         }
+        assert_eq!(outputs[0], outputs[1]);
+        assert_eq!(outputs[0], "{\n  \"А\": \"A\",\n  \"Б\": \"B\",\n  \"Я\": \"I\"\n}");
+
+        options.minify = true;
+        let output = dir.path().join("minified");
+        build_for_i18next(repo, output.to_str().unwrap(), None, &options).unwrap();
+        assert_eq!(
+            fs::read_to_string(output.join("en").join("translation.json")).unwrap(),
+            r#"{"А":"A","Б":"B","Я":"I"}"#
+        );
     }
 
-    #[doc = "Идиоматически верно возвращает расширение файла"]
-    #[inline]
-    pub fn get_file_extension(filename: &str) -> Option<&str> {
-        Path::new(filename).extension().and_then(OsStr::to_str)
+    #[test]
+    fn test_comments_survive_regeneration() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Корзина", "comment": "Корзина покупок, не мусорная"}, "Выход"]"#,
+        )
+        .unwrap();
+        generate_empty_dictionaries_from_static_basic(repo, vec!["en".to_owned()]).unwrap();
+        let mut dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(
+            dictionary["Корзина"].comment.as_deref(),
+            Some("Корзина покупок, не мусорная")
+        );
+        dictionary.get_mut("Выход").unwrap().comment = Some("Log out, not exit".to_owned());
+        write_translated_dictionary(repo, "en", &dictionary).unwrap();
+
+        generate_empty_dictionaries_from_static_basic(repo, vec!["en".to_owned()]).unwrap();
+        let words = parse_translated_dictionary(repo, "en").unwrap();
+        let exit = words.iter().find(|word| word.tag == "Выход").unwrap();
+        assert_eq!(exit.comment.as_deref(), Some("Log out, not exit"));
     }
 
-    #[doc = "Парсинг конфига"]
-    pub fn parse_config(config_path: Option<String>) -> Result<ConfigFileParameters, StaticDictionaryErrors> {
-        let config_dir = match config_path {
-            Some(path) => path,
-            None => format!(
-                "{}/config.dms.json",
-                env::current_dir()?.to_str().unwrap().to_owned()
-            ),
-        };
-        let config_data = fs::read_to_string(config_dir)?;
-        let config = ConfigFileParameters::from_json(&config_data)?;
-        Ok(config)
+    #[test]
+    fn test_locked_entries_are_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Войти", "Выйти", "Выход"]"#).unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Войти": "Sign in", "Выйти": "Sign out", "Выход": "Exit"}"#,
+        )
+        .unwrap();
+        let patterns = vec![regex::Regex::new("^Вы").unwrap()];
+        let languages = vec!["en".to_owned()];
+        assert_eq!(set_entries_locked(repo, &languages, &patterns, true).unwrap(), 2);
+        assert_eq!(
+            locked_tags(repo, "en").unwrap(),
+            ["Выйти".to_owned(), "Выход".to_owned()].into()
+        );
+
+        generate_empty_dictionaries_from_static_basic(repo, languages.clone()).unwrap();
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary["Войти"].value, "");
+        assert_eq!(dictionary["Выйти"].value, "Sign out");
+        assert!(dictionary["Выход"].locked);
+
+        let exit = vec![regex::Regex::new("^Выход$").unwrap()];
+        assert_eq!(set_entries_locked(repo, &languages, &exit, false).unwrap(), 1);
+        assert!(!read_translated_dictionary(repo, "en").unwrap()["Выход"].locked);
     }
-}
 
-#[doc = "Модули и утилиты для сборки итоговых словарей"]
-pub mod build_system {
+    #[test]
+    fn test_yaml_repository_keeps_its_format() {
+        let dir = tempfile::tempdir().unwrap();
+        init_new_dictionary_system(
+            Some(dir.path().to_str().unwrap().to_owned()),
+            "ru".to_owned(),
+            DictionaryFormat::Yaml,
+        )
+        .unwrap();
+        let repo_path = dir.path().join("dictionaries");
+        let repo = repo_path.to_str().unwrap();
+        let entries = vec![
+            BaseEntry::new("Да".to_owned()),
+            BaseEntry::new("Нет: \"нет\"".to_owned()),
+        ];
+        update_basic_dictionary_entries(repo, entries, false).unwrap();
+        assert_eq!(get_basic_dictionary(repo).unwrap(), "dictionary-ru.base.yaml");
 
-    #[doc = "Интеграция с фреймворком i18next"]
-    pub mod i18next_integration {
-        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+        generate_empty_dictionaries_from_static_basic(repo, vec!["en".to_owned()]).unwrap();
+        assert!(repo_path.join("dictionary-en.yaml").exists());
+        assert_eq!(find_all_translated_languages(repo).unwrap(), vec!["en"]);
 
-        use crate::errors::errors::BuildSystemErrors;
-        use crate::file_system::find_all_translated_dictionaries;
-        use crate::parser::get_dictionary_language;
-        use crate::static_translate::parse_translated_dictionary;
-        use std::fs;
-        use std::sync::{Arc, Mutex};
+        let mut dictionary = read_translated_dictionary(repo, "en").unwrap();
+        dictionary.get_mut("Нет: \"нет\"").unwrap().value = "No: \"no\"".to_owned();
+        write_translated_dictionary(repo, "en", &dictionary).unwrap();
+        let words = parse_translated_dictionary(repo, "en").unwrap();
+        assert!(words.iter().any(|word| word.word == "No: \"no\""));
 
-        #[doc = "Функция для сборки словарей из репозитория в итоговые словари для i18next"]
-        pub fn build_for_i18next(
-            dictionary_dir: &str,
-            output_directory: &str,
-            languages: Option<Vec<String>>,
-        ) -> Result<(), BuildSystemErrors> {
-            let languages = match languages {
-                Some(langs) => langs,
-                None => {
-                    let dictionaries = find_all_translated_dictionaries(dictionary_dir)?;
-                    dictionaries
-                        .par_iter()
-                        .map(|dictionary| get_dictionary_language(&dictionary).unwrap())
-                        .collect()
-                }
-            };
-            languages
-                .par_iter()
-                .try_for_each(|language| -> Result<(), BuildSystemErrors> {
-                    let dictionary_content = parse_translated_dictionary(dictionary_dir, language)?;
-                    fs::create_dir_all(format!("{}/{}", output_directory, language))?;
-                    let build_dictionary = fs::File::create_new(format!(
-                        "{}/{}/translation.json",
-                        output_directory, language
-                    ))?;
-                    let json_content = Arc::new(Mutex::new(serde_json::json!({})));
-
-                    dictionary_content.par_iter().try_for_each(
-                        |word| -> Result<(), BuildSystemErrors> {
-                            let mut json_object = json_content.lock().unwrap();
-                            json_object[&word.tag] = word.word.replace("\"", "").clone().into();
-                            Ok(())
-                        },
-                    )?;
+        let config = "base: ./src\nexclude: []\ndictionary_repo: ./dictionaries\noutput_dir: ./locales\ninclude: []\nmanual_translate: []\nfallbacks:\n  en: [ru]\n";
+        fs::write(dir.path().join("config.dms.yml"), config).unwrap();
+        let config = parse_config(Some(dir.path().join("config.dms.yml").to_str().unwrap().to_owned()))
+            .unwrap();
+        assert_eq!(config.fallbacks["en"], vec!["ru"]);
+    }
 
-                    serde_json::to_writer_pretty(
-                        &build_dictionary,
-                        &*json_content.lock().unwrap(),
-                    )?;
-                    Ok(())
-                })?;
-            Ok(())
-        }
+    #[test]
+    fn test_toml_config_is_parsed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.dms.toml");
+        let config = r#"
+base = "./src"
+exclude = ["node_modules"]
+dictionary_repo = "./dictionaries"
+output_dir = "./locales"
+manual_translate = []
+key_strategy = "slug"
+
+[[include]]
+[include.js]
+ext = ["js", "ts"]
+regexp-start = ["t\\(\""]
+regexp-end = ["\"\\)"]
+
+[fallbacks]
+"fr-CA" = ["fr", "en"]
+"#;
+        fs::write(&path, config).unwrap();
+        let config = read_config_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(config.exclude_files, vec!["node_modules"]);
+        assert_eq!(config.languages_configurations[0]["js"].file_extensions, vec!["js", "ts"]);
+        assert_eq!(config.fallbacks["fr-CA"], vec!["fr", "en"]);
     }
-}
 
-#[cfg(test)]
-mod tests {
+    #[test]
+    fn test_config_with_several_dictionary_repositories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.dms.yaml");
+        fs::write(
+            &path,
+            "base: ./src\nexclude: []\ndictionary_repo:\n  checkout: ./i18n/checkout\n  admin: ./i18n/admin\noutput_dir: ./locales\ninclude: []\nmanual_translate: []\n",
+        )
+        .unwrap();
+        let config_path = path.to_str().unwrap();
+        let repositories = resolve_repositories(config_path, None).unwrap();
+        let names: Vec<&str> = repositories.iter().map(|repository| repository.name.as_str()).collect();
+        assert_eq!(names, vec!["admin", "checkout"]);
+        assert_eq!(resolve_repositories(config_path, Some("checkout")).unwrap()[0].path, "./i18n/checkout");
+        assert!(matches!(
+            resolve_repositories(config_path, Some("billing")),
+            Err(StaticDictionaryErrors::InvalidConfig(_))
+        ));
+        assert_eq!(resolve_repositories("./i18n", None).unwrap()[0].path, "./i18n");
 
-    use super::types::*;
-    use crate::file_system::check_dictionary_exists;
-    use crate::parser::get_basic_dictionary;
-    use crate::parser::get_dictionary_by_lang;
-    use crate::parser::get_tags_from_dictionary;
-    use crate::parser::read_json_dictionary;
-    use crate::static_translate::parse_static_basic_dictionary;
-    use crate::web_api::LibreTranslateApi;
+        let list: ConfigFileParameters = ConfigFileParameters::from_json(
+            r#"{"base": "src", "exclude": [], "dictionary_repo": ["i18n/web", "i18n/mobile"], "output_dir": "out", "manual_translate": [], "include": []}"#,
+        )
+        .unwrap();
+        assert_eq!(list.dictionary_repo.primary(), "i18n/web");
+        assert_eq!(list.dictionary_repo.repositories()[1].name, "mobile");
+        let single: ConfigFileParameters = ConfigFileParameters::from_json(
+            r#"{"base": "src", "exclude": [], "dictionary_repo": "i18n", "output_dir": "out", "manual_translate": [], "include": []}"#,
+        )
+        .unwrap();
+        assert_eq!(serde_json::to_value(&single.dictionary_repo).unwrap(), "i18n");
+    }
 
     #[tokio::test]
     async fn test_libre_translator_on_localhost_works() {
@@ -912,7 +5844,7 @@ mod tests {
             .await;
         match result {
             Ok(word) => {
-                assert_eq!(word.word.trim().replace("\"", ""), "Hey");
+                assert_eq!(word.word.trim(), "Hey");
                 assert_eq!(word.language, "en");
                 assert_eq!(word.tag, test_word_clone.tag)
             }
@@ -938,6 +5870,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_starter_config_groups_extensions() {
+        use crate::file_system::{starter_config, StarterConfig};
+
+        let starter = StarterConfig {
+            extensions: ["ts", ".tsx", "vue", "rs"].map(String::from).to_vec(),
+            languages: vec!["en".to_owned()],
+            ..Default::default()
+        };
+        let config: ConfigFileParameters =
+            serde_json::from_value(starter_config(&starter).unwrap()).unwrap();
+        assert_eq!(config.dictionary_repo.primary(), "./dictionaries");
+        assert_eq!(config.languages, vec!["en"]);
+        let include = &config.languages_configurations[0];
+        assert_eq!(include["code"].file_extensions, vec!["ts", "tsx"]);
+        assert_eq!(include["markup"].file_extensions, vec!["vue"]);
+        assert!(include["rust"].preset.is_some());
+        assert!(config.translator.libretranslate_host.is_none());
+    }
+
     #[test]
     fn test_tags_parsed_correctly() {
         let file_path = "C:/Users/Timur/Desktop/auto-translator/cli/src/test.json";
@@ -0,0 +1,346 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+#[doc = "Атрибуты, текст которых виден пользователю. Используются, если в конфиге не указаны attributes"]
+pub const DEFAULT_TEXT_ATTRIBUTES: [&str; 4] = ["placeholder", "title", "alt", "aria-label"];
+
+// Элементы, содержимое которых не является текстом интерфейса
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+// Элементы HTML без закрывающего тега
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    // Начало и конец текстового узла
+    Text(usize, usize),
+    // Начало и конец значения атрибута
+    Attribute(usize, usize),
+}
+
+// Открывающий тег: имя, атрибуты с позициями значений и признак самозакрывающегося тега
+struct Tag<'a> {
+    name: String,
+    attributes: Vec<(&'a str, usize, usize)>,
+    self_closing: bool,
+    end: usize,
+}
+
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+fn find_from(source: &str, from: usize, pattern: &str) -> Option<usize> {
+    source[from..].find(pattern).map(|index| from + index)
+}
+
+// Закрывающий тег элемента без учета регистра
+fn find_closing_tag(source: &str, from: usize, name: &str) -> Option<usize> {
+    let lowercase = source[from..].to_ascii_lowercase();
+    lowercase
+        .find(&format!("</{}", name))
+        .map(|index| from + index)
+}
+
+fn parse_tag(source: &str, start: usize) -> Option<Tag<'_>> {
+    let bytes = source.as_bytes();
+    let mut position = start + 1;
+    while position < bytes.len()
+        && (bytes[position].is_ascii_alphanumeric() || b":-_.".contains(&bytes[position]))
+    {
+        position += 1;
+    }
+    let name = source[start + 1..position].to_ascii_lowercase();
+    let mut attributes = vec![];
+    loop {
+        while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+            position += 1;
+        }
+        match bytes.get(position)? {
+            b'>' => {
+                return Some(Tag {
+                    name,
+                    attributes,
+                    self_closing: false,
+                    end: position + 1,
+                })
+            }
+            b'/' if bytes.get(position + 1) == Some(&b'>') => {
+                return Some(Tag {
+                    name,
+                    attributes,
+                    self_closing: true,
+                    end: position + 2,
+                })
+            }
+            b'/' => {
+                position += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let name_start = position;
+        while position < bytes.len()
+            && !bytes[position].is_ascii_whitespace()
+            && !b"=>/\"'".contains(&bytes[position])
+        {
+            position += 1;
+        }
+        if position == name_start {
+            // Кавычка без имени атрибута: пропускается, чтобы разбор не зациклился
+            position += 1;
+            continue;
+        }
+        let attribute = &source[name_start..position];
+        while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+            position += 1;
+        }
+        if bytes.get(position) != Some(&b'=') {
+            attributes.push((attribute, position, position));
+            continue;
+        }
+        position += 1;
+        while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+            position += 1;
+        }
+        match bytes.get(position)? {
+            quote @ (b'"' | b'\'') => {
+                let value_start = position + 1;
+                let value_end = find_from(source, value_start, &(*quote as char).to_string())?;
+                attributes.push((attribute, value_start, value_end));
+                position = value_end + 1;
+            }
+            _ => {
+                let value_start = position;
+                while position < bytes.len()
+                    && !bytes[position].is_ascii_whitespace()
+                    && bytes[position] != b'>'
+                {
+                    position += 1;
+                }
+                attributes.push((attribute, value_start, position));
+            }
+        }
+    }
+}
+
+// Разбивает разметку на текстовые узлы и значения атрибутов с текстом интерфейса. Комментарии,
+// script, style и элементы с translate="no" пропускаются
+fn tokenize(source: &str, attributes: &[String]) -> Vec<Token> {
+    let mut tokens = vec![];
+    // Открытые элементы и признак того, что их содержимое переводится
+    let mut stack: Vec<(String, bool)> = vec![];
+    let translatable = |stack: &[(String, bool)]| stack.last().is_none_or(|(_, flag)| *flag);
+    let mut text_start = 0;
+    let mut position = 0;
+    while let Some(lt) = find_from(source, position, "<") {
+        let rest = &source[lt..];
+        let next = rest.as_bytes().get(1).copied().unwrap_or(b' ');
+        let is_markup = rest.starts_with("<!")
+            || rest.starts_with("<?")
+            || next == b'/'
+            || next.is_ascii_alphabetic();
+        if !is_markup {
+            // Знак < в тексте, например "a < b"
+            position = lt + 1;
+            continue;
+        }
+        if translatable(&stack) {
+            tokens.push(Token::Text(text_start, lt));
+        }
+        position = if rest.starts_with("<!--") {
+            find_from(source, lt, "-->").map_or(source.len(), |end| end + 3)
+        } else if rest.starts_with("<![CDATA[") {
+            let end = find_from(source, lt, "]]>").unwrap_or(source.len());
+            if translatable(&stack) {
+                tokens.push(Token::Text(lt + 9, end));
+            }
+            (end + 3).min(source.len())
+        } else if next == b'/' {
+            let end = find_from(source, lt, ">").map_or(source.len(), |end| end + 1);
+            let name = source[lt + 2..end.saturating_sub(1).max(lt + 2)]
+                .trim()
+                .to_ascii_lowercase();
+            if let Some(index) = stack.iter().rposition(|(open, _)| *open == name) {
+                stack.truncate(index);
+            }
+            end
+        } else if next.is_ascii_alphabetic() {
+            let Some(tag) = parse_tag(source, lt) else {
+                // Незакрытый тег в конце файла
+                text_start = source.len();
+                break;
+            };
+            let value = |name: &str| {
+                tag.attributes
+                    .iter()
+                    .find(|(attribute, _, _)| attribute.eq_ignore_ascii_case(name))
+                    .map(|(_, start, end)| source[*start..*end].trim().to_ascii_lowercase())
+            };
+            let element_translatable = match value("translate").as_deref() {
+                Some("no") => false,
+                Some(_) => true,
+                None => translatable(&stack),
+            };
+            if element_translatable {
+                for (attribute, start, end) in &tag.attributes {
+                    if attributes
+                        .iter()
+                        .any(|name| attribute.eq_ignore_ascii_case(name))
+                    {
+                        tokens.push(Token::Attribute(*start, *end));
+                    }
+                }
+            }
+            if tag.self_closing || VOID_ELEMENTS.contains(&tag.name.as_str()) {
+                tag.end
+            } else if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+                find_closing_tag(source, tag.end, &tag.name)
+                    .and_then(|closing| find_from(source, closing, ">"))
+                    .map_or(source.len(), |end| end + 1)
+            } else {
+                stack.push((tag.name, element_translatable));
+                tag.end
+            }
+        } else {
+            // <!DOCTYPE ...> и <?xml ...?>
+            find_from(source, lt, ">").map_or(source.len(), |end| end + 1)
+        };
+        text_start = position;
+    }
+    if translatable(&stack) && text_start < source.len() {
+        tokens.push(Token::Text(text_start, source.len()));
+    }
+    tokens
+}
+
+// Раскрывает сущности HTML: именованные из основного набора и числовые
+fn decode_entities(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    ENTITY
+        .get_or_init(|| Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap())
+        .replace_all(text, |captures: &regex::Captures| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                    u32::from_str_radix(&entity[2..], 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                }
+                _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            decoded.map_or_else(|| captures[0].to_owned(), String::from)
+        })
+        .into_owned()
+}
+
+// Фраза из текста разметки: пробелы схлопываются, подстановки {{ name }} (Vue, Angular) и {name}
+// (Svelte) становятся переменными {{name}}. Текст с выражениями в подстановках и текст без букв пропускается
+fn markup_phrase(text: &str) -> Option<String> {
+    static INTERPOLATION: OnceLock<Regex> = OnceLock::new();
+    let text = decode_entities(text)
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let mut simple = true;
+    let phrase = INTERPOLATION
+        .get_or_init(|| Regex::new(r"\{\{(.*?)\}\}|\{([^{}]*)\}").unwrap())
+        .replace_all(&text, |captures: &regex::Captures| {
+            let expression = captures
+                .get(1)
+                .or_else(|| captures.get(2))
+                .map_or("", |expression| expression.as_str().trim());
+            let is_path = !expression.is_empty()
+                && expression.split('.').all(|part| {
+                    !part.is_empty()
+                        && part
+                            .chars()
+                            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+                });
+            simple &= is_path;
+            format!("{{{{{}}}}}", expression)
+        })
+        .into_owned();
+    let has_letters = INTERPOLATION
+        .get()?
+        .replace_all(&phrase, "")
+        .chars()
+        .any(char::is_alphabetic);
+    (simple && has_letters).then_some(phrase)
+}
+
+#[doc = "Находит в разметке HTML, XML, Vue или Svelte текст интерфейса: текстовые узлы и значения атрибутов из attributes. Содержимое script и style, комментарии и элементы с translate=\"no\" пропускаются, а блоки Svelte ({#if}, {/if}, {:else}) делят текст на отдельные фразы. Возвращает фразы с номерами строк (начиная с 1)"]
+pub fn extract_markup_phrases(source: &str, attributes: &[String]) -> Vec<(String, usize)> {
+    static SVELTE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    let block = SVELTE_BLOCK.get_or_init(|| Regex::new(r"\{[#/:@][^{}]*\}").unwrap());
+    let mut phrases = vec![];
+    for token in tokenize(source, attributes) {
+        let (start, end) = match token {
+            Token::Text(start, end) | Token::Attribute(start, end) => (start, end),
+        };
+        let mut segments = vec![];
+        let mut segment_start = start;
+        if let Token::Text(..) = token {
+            for matched in block.find_iter(&source[start..end]) {
+                segments.push((segment_start, start + matched.start()));
+                segment_start = start + matched.end();
+            }
+        }
+        segments.push((segment_start, end));
+        for (start, end) in segments {
+            let text = &source[start..end];
+            if let Some(phrase) = markup_phrase(text) {
+                let leading = text.len() - text.trim_start().len();
+                phrases.push((phrase, line_at(source, start + leading)));
+            }
+        }
+    }
+    phrases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_nodes_and_attributes_are_extracted() {
+        let source = r#"<!DOCTYPE html>
+<template>
+  <!-- Закомментировано -->
+  <h1 class="title">Добро   пожаловать, {{ user.name }}!</h1>
+  <input placeholder="Поиск" :title="hint" data-id="42">
+  <p translate="no">Не переводить <b>это</b></p>
+  <img alt='Логотип &amp; знак'/>
+  <span>{{ count }}</span>
+  <span>{{ $t('Вызов') }} и текст</span>
+  {#if user}Выйти{:else}Войти{/if}
+</template>
+<script>
+const text = "<b>Не текст</b>";
+</script>
+<style>p::after { content: "Не текст"; }</style>
+"#;
+        let attributes: Vec<String> = DEFAULT_TEXT_ATTRIBUTES.map(String::from).to_vec();
+        assert_eq!(
+            extract_markup_phrases(source, &attributes),
+            vec![
+                ("Добро пожаловать, {{user.name}}!".to_owned(), 4),
+                ("Поиск".to_owned(), 5),
+                ("Логотип & знак".to_owned(), 7),
+                ("Выйти".to_owned(), 10),
+                ("Войти".to_owned(), 10),
+            ]
+        );
+    }
+}
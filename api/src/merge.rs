@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::dictionary_format::write_dictionary_file;
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::parser::get_basic_dictionary;
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_existing_dictionary,
+    write_translated_dictionary,
+};
+use crate::types::{BaseEntry, DictionaryEntry};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[doc = "Способ разрешения конфликтов при слиянии репозиториев"]
+pub enum MergeStrategy {
+    /// Оставлять записи текущего репозитория
+    #[default]
+    Ours,
+    /// Брать записи из присоединяемого репозитория
+    Theirs,
+    /// Спрашивать про каждый конфликт
+    Interactive,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(strategy: &str) -> Result<MergeStrategy, String> {
+        match strategy.to_lowercase().as_str() {
+            "ours" => Ok(MergeStrategy::Ours),
+            "theirs" => Ok(MergeStrategy::Theirs),
+            "interactive" => Ok(MergeStrategy::Interactive),
+            _ => Err(format!(
+                "Неизвестная стратегия {}, доступны ours, theirs и interactive",
+                strategy
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[doc = "Решение по конфликту слияния"]
+pub enum MergeChoice {
+    Ours,
+    Theirs,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Запись, которая по-разному заполнена в двух репозиториях"]
+pub struct MergeConflict {
+    pub tag: String,
+    /// Язык дочернего словаря, None для записи базового словаря
+    pub language: Option<String>,
+    /// Исходная фраза или перевод в текущем репозитории
+    pub ours: String,
+    /// Исходная фраза или перевод в присоединяемом репозитории
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Результат слияния репозиториев"]
+pub struct MergeReport {
+    /// Теги, добавленные в базовый словарь
+    pub base_added: Vec<String>,
+    /// Количество перенесенных переводов по языкам
+    pub translations_added: BTreeMap<String, usize>,
+    /// Количество найденных конфликтов
+    pub conflicts: usize,
+    /// Сколько конфликтов решено в пользу присоединяемого репозитория
+    pub taken_theirs: usize,
+}
+
+// Перевод записи одной строкой: значение или формы через " | "
+fn entry_text(entry: &DictionaryEntry) -> String {
+    entry
+        .words("", "")
+        .iter()
+        .map(|word| word.word.as_str())
+        .collect::<Vec<&str>>()
+        .join(" | ")
+}
+
+fn same_source(ours: &BaseEntry, theirs: &BaseEntry) -> bool {
+    ours.phrase == theirs.phrase
+        && ours.plural_forms == theirs.plural_forms
+        && ours.variants == theirs.variants
+}
+
+#[doc = "Переносит в репозиторий target базовый словарь и переводы из репозитория other. Новые записи и переводы добавляются, непереведенные записи заполняются переводами из other, а для записей, которые заполнены в обоих репозиториях по-разному, решение принимает resolve. Заблокированные записи target не меняются. После слияния все дочерние словари содержат все ключи базового словаря. Словари записываются только после того, как все они прочитаны"]
+pub fn merge_repositories(
+    target_dir: &str,
+    other_dir: &str,
+    mut resolve: impl FnMut(&MergeConflict) -> MergeChoice,
+) -> Result<MergeReport, StaticDictionaryErrors> {
+    let basic_language = get_basic_language(target_dir)?;
+    let other_language = get_basic_language(other_dir)?;
+    if basic_language != other_language {
+        return Err(StaticDictionaryErrors::IncompatibleRepositories(format!(
+            "языки базовых словарей {} и {} различаются",
+            basic_language, other_language
+        )));
+    }
+    let mut report = MergeReport::default();
+    let mut entries = parse_static_basic_entries(target_dir)?;
+    for theirs in parse_static_basic_entries(other_dir)? {
+        let Some(position) = entries.iter().position(|ours| ours.tag() == theirs.tag()) else {
+            report.base_added.push(theirs.tag().to_owned());
+            entries.push(theirs);
+            continue;
+        };
+        if same_source(&entries[position], &theirs) {
+            continue;
+        }
+        report.conflicts += 1;
+        let conflict = MergeConflict {
+            tag: theirs.tag().to_owned(),
+            language: None,
+            ours: entries[position].phrase.clone(),
+            theirs: theirs.phrase.clone(),
+        };
+        if resolve(&conflict) == MergeChoice::Theirs {
+            entries[position] = theirs;
+            report.taken_theirs += 1;
+        }
+    }
+
+    let mut languages = find_all_translated_languages(target_dir)?;
+    for language in find_all_translated_languages(other_dir)? {
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    let mut dictionaries = vec![];
+    for language in languages {
+        let mut dictionary = read_existing_dictionary(target_dir, &language)?;
+        let mut added = 0;
+        for (tag, theirs) in read_existing_dictionary(other_dir, &language)? {
+            let Some(ours) = dictionary.get_mut(&tag) else {
+                dictionary.insert(tag, theirs);
+                added += 1;
+                continue;
+            };
+            if ours.locked || !theirs.is_translated() || entry_text(ours) == entry_text(&theirs) {
+                continue;
+            }
+            if !ours.is_translated() {
+                *ours = theirs;
+                added += 1;
+                continue;
+            }
+            report.conflicts += 1;
+            let conflict = MergeConflict {
+                tag,
+                language: Some(language.clone()),
+                ours: entry_text(ours),
+                theirs: entry_text(&theirs),
+            };
+            if resolve(&conflict) == MergeChoice::Theirs {
+                *ours = theirs;
+                report.taken_theirs += 1;
+            }
+        }
+        for entry in &entries {
+            if !dictionary.contains_key(entry.tag()) {
+                dictionary.insert(
+                    entry.tag().to_owned(),
+                    DictionaryEntry::from_base(entry, &basic_language, &language),
+                );
+            }
+        }
+        report.translations_added.insert(language.clone(), added);
+        dictionaries.push((language, dictionary));
+    }
+
+    let basic_dictionary = get_basic_dictionary(target_dir)?;
+    write_dictionary_file(format!("{}/{}", target_dir, basic_dictionary), &entries)?;
+    for (language, dictionary) in &dictionaries {
+        write_translated_dictionary(target_dir, language, dictionary)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::static_translate::read_translated_dictionary;
+
+    fn repository(base: &str, translations: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), base).unwrap();
+        for (language, content) in translations {
+            fs::write(
+                dir.path().join(format!("dictionary-{}.json", language)),
+                content,
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    fn path(dir: &tempfile::TempDir) -> &str {
+        dir.path().to_str().unwrap()
+    }
+
+    #[test]
+    fn test_merge_with_conflicts() {
+        let ours = repository(
+            r#"["Вход", {"phrase": "Выйти", "key": "logout"}, "Корзина"]"#,
+            &[(
+                "en",
+                r#"{"Вход": "Sign in", "logout": "Log out", "Корзина": ""}"#,
+            )],
+        );
+        let theirs = repository(
+            r#"["Вход", {"phrase": "Выход", "key": "logout"}, "Корзина", "Заказы"]"#,
+            &[
+                (
+                    "en",
+                    r#"{"Вход": "Login", "logout": "Log out", "Корзина": "Cart", "Заказы": "Orders"}"#,
+                ),
+                ("de", r#"{"Вход": "Anmelden"}"#),
+            ],
+        );
+
+        let mut conflicts = vec![];
+        let report = merge_repositories(path(&ours), path(&theirs), |conflict| {
+            conflicts.push(conflict.clone());
+            match conflict.language {
+                Some(_) => MergeChoice::Theirs,
+                None => MergeChoice::Ours,
+            }
+        })
+        .unwrap();
+        assert_eq!(report.base_added, vec!["Заказы".to_owned()]);
+        assert_eq!((report.conflicts, report.taken_theirs), (2, 1));
+        assert_eq!(conflicts[0].ours, "Выйти");
+        assert_eq!(
+            (conflicts[1].ours.as_str(), conflicts[1].theirs.as_str()),
+            ("Sign in", "Login")
+        );
+        assert_eq!(report.translations_added["en"], 2);
+
+        let entries = parse_static_basic_entries(path(&ours)).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1].phrase, "Выйти");
+        let en = read_translated_dictionary(path(&ours), "en").unwrap();
+        assert_eq!(en["Вход"].value, "Login");
+        assert_eq!(en["Корзина"].value, "Cart");
+        assert_eq!(en["Заказы"].value, "Orders");
+        let de = read_translated_dictionary(path(&ours), "de").unwrap();
+        assert_eq!(de["Вход"].value, "Anmelden");
+        assert_eq!(de.len(), 4);
+    }
+
+    #[test]
+    fn test_merge_requires_same_basic_language() {
+        let ours = repository(r#"["Вход"]"#, &[]);
+        let theirs = tempfile::tempdir().unwrap();
+        fs::write(
+            theirs.path().join("dictionary-en.base.json"),
+            r#"["Sign in"]"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            merge_repositories(path(&ours), path(&theirs), |_| MergeChoice::Ours),
+            Err(StaticDictionaryErrors::IncompatibleRepositories(_))
+        ));
+    }
+}
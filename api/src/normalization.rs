@@ -0,0 +1,95 @@
+use unicode_normalization::UnicodeNormalization;
+
+use crate::types::BaseEntry;
+
+#[doc = "Приводит фразу к канонической форме: NFC, без пробелов по краям, с одиночными пробелами внутри"]
+pub fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .nfc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[doc = "Нормализует фразу, формы множественного числа и варианты записи базового словаря. Явно заданный тег не меняется"]
+pub fn normalize_entry(entry: BaseEntry) -> BaseEntry {
+    let normalize_values = |values: Option<std::collections::BTreeMap<String, String>>| {
+        values.map(|values| {
+            values
+                .into_iter()
+                .map(|(name, text)| (name, normalize_phrase(&text)))
+                .collect()
+        })
+    };
+    BaseEntry {
+        phrase: normalize_phrase(&entry.phrase),
+        plural_forms: normalize_values(entry.plural_forms),
+        variants: normalize_values(entry.variants),
+        ..entry
+    }
+}
+
+#[doc = "Нормализует новые записи перед слиянием с базовым словарем. Существующие записи не меняются, чтобы со стратегией phrase не потерять переводы под их тегами: новая фраза, которая совпадает с существующей после нормализации обеих, получает написание существующей и сливается с ней"]
+pub fn normalize_new_entries(basic_entries: &[BaseEntry], entries: Vec<BaseEntry>) -> Vec<BaseEntry> {
+    entries
+        .into_iter()
+        .map(normalize_entry)
+        .map(|entry| {
+            if basic_entries.iter().any(|existing| existing.phrase == entry.phrase) {
+                return entry;
+            }
+            match basic_entries
+                .iter()
+                .find(|existing| normalize_phrase(&existing.phrase) == entry.phrase)
+            {
+                Some(existing) => BaseEntry {
+                    phrase: existing.phrase.clone(),
+                    ..entry
+                },
+                None => entry,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visually_identical_phrases_are_equal_after_normalization() {
+        // "й" в виде одного символа и в виде "и" с комбинируемым бреве
+        let composed = "Мой  профиль ";
+        let decomposed = "  Мои\u{306}\tпрофиль";
+        assert_ne!(composed, decomposed);
+        assert_eq!(normalize_phrase(composed), "Мой профиль");
+        assert_eq!(normalize_phrase(decomposed), "Мой профиль");
+    }
+
+    #[test]
+    fn test_entry_forms_are_normalized() {
+        let entry: BaseEntry = serde_json::from_str(
+            r#"{"phrase": " яблоко ", "key": " apple ", "plural_forms": {"one": "{{count}}  яблоко"}}"#,
+        )
+        .unwrap();
+        let entry = normalize_entry(entry);
+        assert_eq!(entry.phrase, "яблоко");
+        assert_eq!(entry.key.as_deref(), Some(" apple "));
+        assert_eq!(entry.plural_forms.unwrap()["one"], "{{count}} яблоко");
+    }
+
+    #[test]
+    fn test_new_entries_take_spelling_of_existing_phrases() {
+        let existing = vec![BaseEntry::new("Мой  профиль ".to_owned())];
+        let entries = normalize_new_entries(
+            &existing,
+            vec![
+                BaseEntry::new("  Мои\u{306} профиль".to_owned()),
+                BaseEntry::new(" Выход ".to_owned()),
+            ],
+        );
+        assert_eq!(entries[0].phrase, "Мой  профиль ");
+        assert_eq!(entries[1].phrase, "Выход");
+    }
+}
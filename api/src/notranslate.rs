@@ -0,0 +1,109 @@
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+
+#[doc = "Открывающий маркер сегмента, который не нужно переводить"]
+pub const NOTRANSLATE_OPEN: &str = "<notranslate>";
+#[doc = "Закрывающий маркер сегмента, который не нужно переводить"]
+pub const NOTRANSLATE_CLOSE: &str = "</notranslate>";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Фраза, в которой защищенные сегменты заменены на метки-заглушки"]
+pub struct ProtectedPhrase {
+    /// Текст для отправки переводчику
+    pub text: String,
+    /// Сегменты в порядке номеров меток
+    pub segments: Vec<String>,
+}
+
+fn segment_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?s)<notranslate>(.*?)</notranslate>|`[^`]+`|https?://[^\s<>`]+").unwrap()
+    })
+}
+
+fn token_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    // Переводчики иногда меняют регистр или добавляют пробелы внутри метки
+    PATTERN.get_or_init(|| Regex::new(r"(?i)__\s*dms\s*(\d+)\s*__").unwrap())
+}
+
+fn token(index: usize) -> String {
+    format!("__DMS{}__", index)
+}
+
+#[doc = "Заменяет защищенные сегменты на метки. Защищаются содержимое <notranslate>...</notranslate> (без самих тегов), код в обратных кавычках и URL"]
+pub fn protect(phrase: &str) -> ProtectedPhrase {
+    let mut segments = vec![];
+    let text = segment_pattern()
+        .replace_all(phrase, |captures: &Captures| {
+            let segment = match captures.get(1) {
+                Some(inner) => inner.as_str(),
+                None => captures.get(0).unwrap().as_str(),
+            };
+            segments.push(segment.to_owned());
+            token(segments.len() - 1)
+        })
+        .into_owned();
+    ProtectedPhrase { text, segments }
+}
+
+impl ProtectedPhrase {
+    #[doc = "Есть ли во фразе защищенные сегменты"]
+    pub fn is_protected(&self) -> bool {
+        !self.segments.is_empty()
+    }
+
+    #[doc = "Возвращает защищенные сегменты на место меток в переведенном тексте"]
+    pub fn restore(&self, translated: &str) -> String {
+        token_pattern()
+            .replace_all(translated, |captures: &Captures| {
+                captures[1]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| self.segments.get(index))
+                    .cloned()
+                    .unwrap_or_else(|| captures[0].to_owned())
+            })
+            .into_owned()
+    }
+}
+
+#[doc = "Убирает маркеры <notranslate> из фразы, оставляя их содержимое"]
+pub fn strip_markers(phrase: &str) -> String {
+    phrase
+        .replace(NOTRANSLATE_OPEN, "")
+        .replace(NOTRANSLATE_CLOSE, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_are_protected_and_restored() {
+        let protected = protect(
+            "Откройте <notranslate>DMS Cloud</notranslate> и выполните `dms init` или зайдите на https://example.com/docs",
+        );
+        assert_eq!(
+            protected.text,
+            "Откройте __DMS0__ и выполните __DMS1__ или зайдите на __DMS2__"
+        );
+        assert_eq!(
+            protected.restore("Open __dms0__ and run __ DMS1 __ or visit __DMS2__"),
+            "Open DMS Cloud and run `dms init` or visit https://example.com/docs"
+        );
+    }
+
+    #[test]
+    fn test_plain_phrase_is_unchanged() {
+        let protected = protect("Привет, мир");
+        assert!(!protected.is_protected());
+        assert_eq!(protected.restore("Hello, world"), "Hello, world");
+        assert_eq!(
+            strip_markers("Это <notranslate>DMS</notranslate>"),
+            "Это DMS"
+        );
+    }
+}
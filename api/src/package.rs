@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backup::utc_timestamp;
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::atomic_write;
+use crate::language::LanguageCode;
+use crate::qa::placeholders;
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_existing_dictionary,
+    write_translated_dictionary,
+};
+use crate::types::{BaseEntry, DictionaryEntry, EntryStatus, Word};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[doc = "Фраза (или форма множественного числа и вариант) для перевода в пакете"]
+pub struct PackageEntry {
+    /// Ключ с суффиксами формы и варианта
+    pub tag: String,
+    /// Исходная фраза из базового словаря
+    pub source: String,
+    /// Перевод. При выгрузке пустой, а у устаревших записей - прежний перевод
+    #[serde(default)]
+    pub translation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Комментарий для переводчика
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Исходная фраза изменилась после перевода
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub outdated: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[doc = "Пакет переводов для внешнего переводчика: непереведенные и устаревшие записи одного языка"]
+pub struct TranslationPackage {
+    /// Идентификатор пакета: язык и время выгрузки. Записывается в загруженные из пакета записи
+    pub id: String,
+    /// Язык базового словаря
+    pub source_language: String,
+    pub language: String,
+    pub entries: Vec<PackageEntry>,
+}
+
+impl TranslationPackage {
+    #[doc = "Читает пакет из файла JSON"]
+    pub fn load(path: impl AsRef<Path>) -> Result<TranslationPackage, StaticDictionaryErrors> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    #[doc = "Записывает пакет в файл JSON"]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StaticDictionaryErrors> {
+        atomic_write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+#[doc = "Собирает пакет из записей базового словаря, которые не переведены на язык или перевод которых устарел. Заблокированные записи в пакет не попадают"]
+pub fn export_package(
+    dictionary_dir: &str,
+    language: &str,
+) -> Result<TranslationPackage, StaticDictionaryErrors> {
+    let language = String::from(LanguageCode::new(language)?);
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let dictionary = read_existing_dictionary(dictionary_dir, &language)?;
+    let mut entries = vec![];
+    for base in parse_static_basic_entries(dictionary_dir)? {
+        let entry = dictionary
+            .get(base.tag())
+            .cloned()
+            .unwrap_or_else(|| DictionaryEntry::from_base(&base, &basic_language, &language));
+        let outdated = entry.is_outdated(&base);
+        if entry.locked || (entry.is_translated() && !outdated) {
+            continue;
+        }
+        let translations: HashMap<String, String> = entry
+            .words(base.tag(), &language)
+            .into_iter()
+            .map(|word| (word.suffixed_tag(), word.word))
+            .collect();
+        for source in base.words_for_translation(&basic_language, &language) {
+            let tag = source.suffixed_tag();
+            entries.push(PackageEntry {
+                translation: translations.get(&tag).cloned().unwrap_or_default(),
+                tag,
+                source: source.word,
+                context: base.context.clone(),
+                comment: entry.comment.clone().or_else(|| base.comment.clone()),
+                outdated,
+            });
+        }
+    }
+    Ok(TranslationPackage {
+        id: format!("{}-{}", language, utc_timestamp(SystemTime::now())),
+        source_language: basic_language,
+        language,
+        entries,
+    })
+}
+
+#[doc = "Проверяет заполненный пакет и переносит переводы в дочерний словарь. Если в пакете есть неизвестные ключи, изменившиеся исходные фразы или переводы с другими переменными подстановки, ничего не меняется. Записи получают статус translated и идентификатор пакета, пустые переводы и заблокированные записи пропускаются. Возвращает количество измененных записей"]
+pub fn import_package(
+    dictionary_dir: &str,
+    package: &TranslationPackage,
+) -> Result<usize, StaticDictionaryErrors> {
+    let language = String::from(LanguageCode::new(&package.language)?);
+    let basic_language = get_basic_language(dictionary_dir)?;
+    if package.source_language != basic_language {
+        return Err(StaticDictionaryErrors::InvalidPackage(format!(
+            "пакет выгружен для базового словаря на языке {}, а в репозитории базовый язык {}",
+            package.source_language, basic_language
+        )));
+    }
+    let base_entries = parse_static_basic_entries(dictionary_dir)?;
+    let sources: HashMap<String, (Word, &BaseEntry)> = base_entries
+        .iter()
+        .flat_map(|base| {
+            base.words_for_translation(&basic_language, &language)
+                .into_iter()
+                .map(move |word| (word.suffixed_tag(), (word, base)))
+        })
+        .collect();
+
+    let mut problems = vec![];
+    for entry in &package.entries {
+        match sources.get(&entry.tag) {
+            None => problems.push(format!("{}: ключ не найден в базовом словаре", entry.tag)),
+            Some((source, _)) if source.word != entry.source => problems.push(format!(
+                "{}: исходная фраза изменилась (\"{}\" -> \"{}\")",
+                entry.tag, entry.source, source.word
+            )),
+            Some((source, _))
+                if !entry.translation.is_empty()
+                    && placeholders(&source.word) != placeholders(&entry.translation) =>
+            {
+                problems.push(format!(
+                    "{}: переменные подстановки в переводе не совпадают с исходной фразой",
+                    entry.tag
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    if !problems.is_empty() {
+        return Err(StaticDictionaryErrors::InvalidPackage(problems.join("\n")));
+    }
+
+    let mut dictionary = read_existing_dictionary(dictionary_dir, &language)?;
+    let mut changed = 0;
+    for package_entry in package
+        .entries
+        .iter()
+        .filter(|entry| !entry.translation.is_empty())
+    {
+        let (source, base) = &sources[&package_entry.tag];
+        let entry = dictionary
+            .entry(base.tag().to_owned())
+            .or_insert_with(|| DictionaryEntry::from_base(base, &basic_language, &language));
+        if entry.locked {
+            continue;
+        }
+        entry.set_word(&Word {
+            word: package_entry.translation.clone(),
+            ..source.clone()
+        });
+        entry.package = Some(package.id.clone());
+        if entry.is_translated() {
+            entry.status = EntryStatus::Translated;
+            entry.source_hash = Some(base.source_hash());
+        }
+        changed += 1;
+    }
+    if changed > 0 {
+        write_translated_dictionary(dictionary_dir, &language, &dictionary)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_translate::read_translated_dictionary;
+
+    #[test]
+    fn test_package_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выйти из {{app}}", "Корзина", "Заказы"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in", "Выйти из {{app}}": {"value": "Log out", "status": "translated", "source_hash": "000000000000"}, "Заказы": {"value": "", "locked": true}}"#,
+        )
+        .unwrap();
+
+        let mut package = export_package(repo, "en").unwrap();
+        assert!(package.id.starts_with("en-"));
+        let tags: Vec<(&str, bool)> = package
+            .entries
+            .iter()
+            .map(|entry| (entry.tag.as_str(), entry.outdated))
+            .collect();
+        assert_eq!(tags, vec![("Выйти из {{app}}", true), ("Корзина", false)]);
+        assert_eq!(package.entries[0].translation, "Log out");
+
+        package.entries[0].translation = "Log out of {{application}}".to_owned();
+        package.entries[1].translation = "Cart".to_owned();
+        assert!(matches!(
+            import_package(repo, &package),
+            Err(StaticDictionaryErrors::InvalidPackage(_))
+        ));
+        package.entries[0].translation = "Log out of {{app}}".to_owned();
+        let path = dir.path().join("package.json");
+        package.save(&path).unwrap();
+        let package = TranslationPackage::load(&path).unwrap();
+        assert_eq!(import_package(repo, &package).unwrap(), 2);
+
+        let en = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(en["Корзина"].value, "Cart");
+        assert_eq!(en["Корзина"].package.as_deref(), Some(package.id.as_str()));
+        assert_eq!(en["Вход"].package, None);
+        assert!(export_package(repo, "en").unwrap().entries.is_empty());
+    }
+}
@@ -0,0 +1,29 @@
+#[doc = "Получатель событий о ходе долгих операций: сканирования файлов, автоперевода и сборки. Этап определяется названием (например, языком перевода), поэтому несколько этапов могут идти одновременно. По умолчанию методы ничего не делают"]
+pub trait Progress: Send + Sync {
+    #[doc = "Начался этап из total шагов"]
+    fn start(&self, stage: &str, total: usize) {
+        let _ = (stage, total);
+    }
+
+    #[doc = "Выполнен очередной шаг этапа"]
+    fn advance(&self, stage: &str) {
+        let _ = stage;
+    }
+
+    #[doc = "Этап завершен"]
+    fn finish(&self, stage: &str) {
+        let _ = stage;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[doc = "Получатель, который не показывает ход операций"]
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
+
+#[doc = "Название этапа сканирования файлов проекта"]
+pub const SCAN_STAGE: &str = "scan";
+
+#[doc = "Название этапа сборки словарей, шаг этапа - один собранный язык"]
+pub const BUILD_STAGE: &str = "build";
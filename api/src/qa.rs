@@ -0,0 +1,562 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::glossary::Glossary;
+use crate::notranslate::strip_markers;
+use crate::spelling::SpellChecker;
+use crate::static_translate::{
+    pair_with_sources, parse_static_basic_entries, read_existing_dictionary,
+};
+use crate::types::Word;
+
+#[doc = "Максимальное отношение длины перевода к длине исходной фразы по умолчанию"]
+pub const DEFAULT_MAX_LENGTH_RATIO: f64 = 3.0;
+
+#[derive(Debug, Clone, PartialEq)]
+#[doc = "Параметры проверок качества перевода"]
+pub struct QaOptions {
+    /// Перевод длиннее исходной фразы больше, чем в это число раз, считается подозрительным
+    pub max_length_ratio: f64,
+    /// Директория со словарями hunspell для проверки орфографии. Без нее орфография не проверяется
+    pub spelling_dir: Option<String>,
+    /// Файл глоссария. Без него используется glossary.json репозитория словарей, если он есть
+    pub glossary: Option<String>,
+}
+
+impl Default for QaOptions {
+    fn default() -> QaOptions {
+        QaOptions {
+            max_length_ratio: DEFAULT_MAX_LENGTH_RATIO,
+            spelling_dir: None,
+            glossary: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "snake_case")]
+#[doc = "Виды проблем, которые находят проверки качества"]
+pub enum QaIssueKind {
+    /// В переводе нет переменной из исходной фразы
+    MissingPlaceholder(String),
+    /// В переводе есть переменная, которой нет в исходной фразе
+    ExtraPlaceholder(String),
+    /// Перевод начинается с пробела, а исходная фраза - нет (или наоборот)
+    LeadingWhitespace,
+    /// Перевод заканчивается пробелом, а исходная фраза - нет (или наоборот)
+    TrailingWhitespace,
+    /// В переводе есть двойные пробелы
+    DoubleSpace,
+    /// Перевод слишком длинный относительно исходной фразы
+    LengthRatio(f64),
+    /// Перевод совпадает с исходной фразой
+    SameAsSource,
+    /// Слова перевода нет в словаре проверки орфографии и в списке слов проекта
+    Misspelling(String),
+    /// Термин глоссария из исходной фразы переведен не утвержденным переводом
+    GlossaryTerm { term: String, expected: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Проблема, найденная в переводе"]
+pub struct QaIssue {
+    pub language: String,
+    /// Ключ с суффиксами формы и варианта
+    pub tag: String,
+    pub source: String,
+    pub translation: String,
+    pub kind: QaIssueKind,
+}
+
+impl Display for QaIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QaIssueKind::MissingPlaceholder(name) => {
+                write!(f, "в переводе нет переменной {}", name)
+            }
+            QaIssueKind::ExtraPlaceholder(name) => {
+                write!(f, "в переводе лишняя переменная {}", name)
+            }
+            QaIssueKind::LeadingWhitespace => {
+                write!(f, "пробелы в начале не совпадают с исходной фразой")
+            }
+            QaIssueKind::TrailingWhitespace => {
+                write!(f, "пробелы в конце не совпадают с исходной фразой")
+            }
+            QaIssueKind::DoubleSpace => write!(f, "двойной пробел в переводе"),
+            QaIssueKind::LengthRatio(ratio) => {
+                write!(f, "перевод длиннее исходной фразы в {:.1} раз", ratio)
+            }
+            QaIssueKind::SameAsSource => write!(f, "перевод совпадает с исходной фразой"),
+            QaIssueKind::Misspelling(word) => write!(f, "возможная ошибка в слове {}", word),
+            QaIssueKind::GlossaryTerm { term, expected } => {
+                write!(f, "термин {} должен переводиться как {}", term, expected)
+            }
+        }
+    }
+}
+
+impl Display for QaIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {} (\"{}\" -> \"{}\")",
+            self.language, self.tag, self.kind, self.source, self.translation
+        )
+    }
+}
+
+#[doc = "Находит переменные подстановки в тексте: {{name}}, {name}, ${name}, %s, %1$d"]
+pub fn placeholders(text: &str) -> BTreeSet<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"\{\{\s*[\w.\-]+\s*\}\}|\$\{\s*[\w.]+\s*\}|\{[\w.]+\}|%(?:\d+\$)?[sdif@]")
+            .unwrap()
+    });
+    pattern
+        .find_iter(text)
+        .map(|found| {
+            found
+                .as_str()
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect()
+        })
+        .collect()
+}
+
+#[doc = "Имена переменных подстановки в порядке появления: {{name}}, {name}, ${name} и {count, plural, ...} дают name и count"]
+pub fn placeholder_names(text: &str) -> Vec<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"\{\{\s*([\w.\-]+)\s*\}\}|\$\{\s*([\w.]+)\s*\}|\{([\w.]+)\s*[},]").unwrap()
+    });
+    let mut names: Vec<String> = vec![];
+    for captures in pattern.captures_iter(text) {
+        let Some(name) = captures.iter().skip(1).flatten().next() else {
+            continue;
+        };
+        if !names.iter().any(|known| known == name.as_str()) {
+            names.push(name.as_str().to_owned());
+        }
+    }
+    names
+}
+
+#[doc = "Проверяет перевод одной фразы. Пустые переводы не проверяются"]
+pub fn check_translation(source: &str, translation: &str, options: &QaOptions) -> Vec<QaIssueKind> {
+    let mut issues = vec![];
+    if translation.is_empty() || source.is_empty() {
+        return issues;
+    }
+    let source_placeholders = placeholders(source);
+    let translation_placeholders = placeholders(translation);
+    for missing in source_placeholders.difference(&translation_placeholders) {
+        issues.push(QaIssueKind::MissingPlaceholder(missing.to_owned()));
+    }
+    for extra in translation_placeholders.difference(&source_placeholders) {
+        issues.push(QaIssueKind::ExtraPlaceholder(extra.to_owned()));
+    }
+    let starts_with_space = |text: &str| text.starts_with(char::is_whitespace);
+    let ends_with_space = |text: &str| text.ends_with(char::is_whitespace);
+    if starts_with_space(source) != starts_with_space(translation) {
+        issues.push(QaIssueKind::LeadingWhitespace);
+    }
+    if ends_with_space(source) != ends_with_space(translation) {
+        issues.push(QaIssueKind::TrailingWhitespace);
+    }
+    if translation.contains("  ") && !source.contains("  ") {
+        issues.push(QaIssueKind::DoubleSpace);
+    }
+    let ratio = translation.chars().count() as f64 / source.chars().count() as f64;
+    if ratio > options.max_length_ratio {
+        issues.push(QaIssueKind::LengthRatio(ratio));
+    }
+    if translation.trim() == source.trim() {
+        issues.push(QaIssueKind::SameAsSource);
+    }
+    issues
+}
+
+// Переменные из базового словаря, которых нет в переводе и которые не нашлись по исходному тексту.
+// Формы множественного числа не проверяются: в форме one число часто пишется словом
+fn missing_declared(
+    source: &Word,
+    translation: &Word,
+    declared: &[String],
+    issues: &[QaIssueKind],
+) -> Vec<QaIssueKind> {
+    if translation.word.is_empty() || source.plural_form.is_some() {
+        return vec![];
+    }
+    let translated = placeholder_names(&translation.word);
+    let reported: Vec<String> = issues
+        .iter()
+        .filter_map(|issue| match issue {
+            QaIssueKind::MissingPlaceholder(placeholder) => Some(placeholder_names(placeholder)),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    declared
+        .iter()
+        .filter(|name| !translated.contains(name) && !reported.contains(name))
+        .map(|name| QaIssueKind::MissingPlaceholder(name.to_owned()))
+        .collect()
+}
+
+fn issues_for_pair(
+    source: &Word,
+    translation: &Word,
+    declared: &[String],
+    options: &QaOptions,
+    spell_checker: Option<&SpellChecker>,
+    glossary: Option<&Glossary>,
+) -> Vec<QaIssue> {
+    let source_text = strip_markers(&source.word);
+    let mut issues = check_translation(&source_text, &translation.word, options);
+    issues.extend(missing_declared(source, translation, declared, &issues));
+    if let Some(glossary) = glossary {
+        issues.extend(
+            glossary
+                .violations(&source_text, &translation.word, &translation.language)
+                .into_iter()
+                .map(|(term, expected)| QaIssueKind::GlossaryTerm { term, expected }),
+        );
+    }
+    if let Some(spell_checker) = spell_checker {
+        issues.extend(
+            spell_checker
+                .misspelled(&translation.word)
+                .into_iter()
+                .map(QaIssueKind::Misspelling),
+        );
+    }
+    issues
+        .into_iter()
+        .map(|kind| QaIssue {
+            language: translation.language.clone(),
+            tag: translation.suffixed_tag(),
+            source: source.word.clone(),
+            translation: translation.word.clone(),
+            kind,
+        })
+        .collect()
+}
+
+#[doc = "Проверяет дочерний словарь относительно базового. С spelling_dir в параметрах проверяется и орфография, для этого нужен словарь hunspell языка. Если задан или есть в репозитории глоссарий, проверяются и переводы его терминов"]
+pub fn check_dictionary(
+    dictionary_dir: &str,
+    language: &str,
+    options: &QaOptions,
+) -> Result<Vec<QaIssue>, StaticDictionaryErrors> {
+    let spell_checker = match &options.spelling_dir {
+        Some(spelling_dir) => Some(SpellChecker::open(spelling_dir, dictionary_dir, language)?),
+        None => None,
+    };
+    let glossary = match &options.glossary {
+        Some(path) => Some(Glossary::load(path)?),
+        None => Glossary::read(dictionary_dir)?,
+    };
+    Ok(pair_with_sources(dictionary_dir, language)?
+        .iter()
+        .flat_map(|paired| {
+            paired.pairs.iter().flat_map(|(source, translation)| {
+                issues_for_pair(
+                    source,
+                    translation,
+                    &paired.placeholders,
+                    options,
+                    spell_checker.as_ref(),
+                    glossary.as_ref(),
+                )
+            })
+        })
+        .collect())
+}
+
+#[doc = "Проверяет дочерние словари на указанных языках, или все словари репозитория, если языки не переданы"]
+pub fn check_repository(
+    dictionary_dir: &str,
+    languages: Option<Vec<String>>,
+    options: &QaOptions,
+) -> Result<Vec<QaIssue>, StaticDictionaryErrors> {
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut issues = vec![];
+    for language in languages {
+        issues.extend(check_dictionary(dictionary_dir, &language, options)?);
+    }
+    Ok(issues)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Итог проверки одного языка в CI"]
+pub struct CiLanguageSummary {
+    pub language: String,
+    /// Ключей базового словаря без перевода
+    pub untranslated: usize,
+    /// Переводов, исходная фраза которых изменилась после перевода
+    pub outdated: usize,
+    /// Проблем, найденных проверками качества
+    pub issues: usize,
+}
+
+impl CiLanguageSummary {
+    #[doc = "Язык проходит проверку: все ключи переведены, устаревших переводов и проблем нет"]
+    pub fn passed(&self) -> bool {
+        self.untranslated == 0 && self.outdated == 0 && self.issues == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[doc = "Почему ключ не проходит проверку в CI"]
+pub enum CiKeyProblem {
+    /// Значение или одна из форм не переведены
+    Untranslated,
+    /// Исходная фраза изменилась после перевода
+    Outdated,
+}
+
+impl Display for CiKeyProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CiKeyProblem::Untranslated => write!(f, "нет перевода"),
+            CiKeyProblem::Outdated => write!(f, "перевод устарел"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[doc = "Ключ базового словаря, который не переведен на язык или перевод которого устарел"]
+pub struct CiKey {
+    pub language: String,
+    pub tag: String,
+    pub problem: CiKeyProblem,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Результат проверки репозитория словарей в CI"]
+pub struct CiReport {
+    pub passed: bool,
+    pub languages: Vec<CiLanguageSummary>,
+    /// Непереведенные ключи и устаревшие переводы
+    pub keys: Vec<CiKey>,
+    pub issues: Vec<QaIssue>,
+}
+
+#[doc = "Проверяет словари для CI: считает по языкам непереведенные ключи, устаревшие переводы и проблемы проверок качества. Проверка пройдена, если у всех языков их нет"]
+pub fn ci_report(
+    dictionary_dir: &str,
+    languages: Option<Vec<String>>,
+    options: &QaOptions,
+) -> Result<CiReport, StaticDictionaryErrors> {
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let base_entries = parse_static_basic_entries(dictionary_dir)?;
+    let mut report = CiReport::default();
+    for language in languages {
+        let dictionary = read_existing_dictionary(dictionary_dir, &language)?;
+        let mut summary = CiLanguageSummary {
+            language: language.clone(),
+            ..Default::default()
+        };
+        for entry in &base_entries {
+            let problem = match dictionary.get(entry.tag()) {
+                Some(translated) if translated.is_outdated(entry) => CiKeyProblem::Outdated,
+                Some(translated) if translated.is_translated() => continue,
+                _ => CiKeyProblem::Untranslated,
+            };
+            match problem {
+                CiKeyProblem::Untranslated => summary.untranslated += 1,
+                CiKeyProblem::Outdated => summary.outdated += 1,
+            }
+            report.keys.push(CiKey {
+                language: language.clone(),
+                tag: entry.tag().to_owned(),
+                problem,
+            });
+        }
+        let issues = check_dictionary(dictionary_dir, &language, options)?;
+        summary.issues = issues.len();
+        report.languages.push(summary);
+        report.issues.extend(issues);
+    }
+    report.passed = report.languages.iter().all(CiLanguageSummary::passed);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_are_detected() {
+        let found = placeholders("Привет, {{ name }}! У вас {count} писем, ${user} и %1$s");
+        assert_eq!(
+            found,
+            BTreeSet::from([
+                "{{name}}".to_owned(),
+                "{count}".to_owned(),
+                "${user}".to_owned(),
+                "%1$s".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_placeholder_names_keep_order() {
+        assert_eq!(
+            placeholder_names("{{ name }}, ${user.id}: {count, plural, one {# file}} {name}"),
+            vec!["name", "user.id", "count"]
+        );
+        assert!(placeholder_names("Без переменных {}").is_empty());
+    }
+
+    #[test]
+    fn test_declared_placeholders_are_required_in_translations() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Привет, {{name}}", "placeholders": ["name"]}, {"phrase": "Привет, гость", "key": "guest", "placeholders": ["user"]}]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Привет, {{name}}": "Hi", "guest": "Hi, {{user}}"}"#,
+        )
+        .unwrap();
+        let issues = check_dictionary(repo, "en", &QaOptions::default()).unwrap();
+        let missing: Vec<&QaIssueKind> = issues
+            .iter()
+            .map(|issue| &issue.kind)
+            .filter(|kind| matches!(kind, QaIssueKind::MissingPlaceholder(_)))
+            .collect();
+        assert_eq!(
+            missing,
+            vec![&QaIssueKind::MissingPlaceholder("{{name}}".to_owned())]
+        );
+
+        std::fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Привет, {{name}}": "Hi, {{name}}", "guest": "Hi"}"#,
+        )
+        .unwrap();
+        let issues = check_dictionary(repo, "en", &QaOptions::default()).unwrap();
+        assert!(issues
+            .iter()
+            .any(|issue| issue.tag == "guest"
+                && issue.kind == QaIssueKind::MissingPlaceholder("user".to_owned())));
+    }
+
+    #[test]
+    fn test_repository_glossary_is_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Корзина пуста", "Перейти в корзину"]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Корзина пуста": "Your basket is empty", "Перейти в корзину": "Go to cart"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(crate::glossary::GLOSSARY_FILE),
+            r#"{"Корзина": {"en": "Cart"}, "корзину": {"en": "cart"}}"#,
+        )
+        .unwrap();
+        let issues = check_dictionary(repo, "en", &QaOptions::default()).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].tag, "Корзина пуста");
+        assert_eq!(
+            issues[0].kind,
+            QaIssueKind::GlossaryTerm {
+                term: "Корзина".to_owned(),
+                expected: "Cart".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn test_translation_checks() {
+        let options = QaOptions::default();
+        let issues = check_translation("Привет, {{name}}", "Hello, {{user}} ", &options);
+        assert!(issues.contains(&QaIssueKind::MissingPlaceholder("{{name}}".to_owned())));
+        assert!(issues.contains(&QaIssueKind::ExtraPlaceholder("{{user}}".to_owned())));
+        assert!(issues.contains(&QaIssueKind::TrailingWhitespace));
+
+        let issues = check_translation("OK", "OK", &options);
+        assert_eq!(issues, vec![QaIssueKind::SameAsSource]);
+
+        let issues = check_translation("Да", "Yes  absolutely, certainly", &options);
+        assert!(issues.contains(&QaIssueKind::DoubleSpace));
+        assert!(matches!(issues.last(), Some(QaIssueKind::LengthRatio(_))));
+
+        assert!(check_translation("Привет", "", &options).is_empty());
+        assert!(check_translation("Привет", "Hello", &options).is_empty());
+    }
+
+    #[test]
+    fn test_ci_report_counts_untranslated_and_outdated() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выход", "Корзина"]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in", "Выход": {"value": "Log out", "source_hash": "000000000000"}, "Корзина": ""}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("dictionary-de.json"),
+            r#"{"Вход": "Anmelden", "Выход": "Abmelden", "Корзина": "Warenkorb"}"#,
+        )
+        .unwrap();
+        let report = ci_report(repo, None, &QaOptions::default()).unwrap();
+        assert!(!report.passed);
+        let summary = |language: &str| {
+            report
+                .languages
+                .iter()
+                .find(|summary| summary.language == language)
+                .unwrap()
+                .clone()
+        };
+        assert!(summary("de").passed());
+        let en = summary("en");
+        assert_eq!((en.untranslated, en.outdated, en.issues), (1, 1, 0));
+        assert_eq!(
+            report.keys,
+            vec![
+                CiKey {
+                    language: "en".to_owned(),
+                    tag: "Выход".to_owned(),
+                    problem: CiKeyProblem::Outdated
+                },
+                CiKey {
+                    language: "en".to_owned(),
+                    tag: "Корзина".to_owned(),
+                    problem: CiKeyProblem::Untranslated
+                },
+            ]
+        );
+    }
+}
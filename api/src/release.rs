@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backup::utc_timestamp;
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::atomic_write;
+use crate::parser::get_dictionary_language;
+use crate::scan_cache::content_hash;
+use crate::static_translate::{status, RepositoryStatus};
+
+#[doc = "Каталог релизов внутри репозитория словарей"]
+pub const RELEASES_DIR: &str = ".dms/releases";
+
+#[doc = "Файл манифеста в каталоге релиза"]
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[doc = "Файл словаря в релизе"]
+pub struct ReleaseFile {
+    /// Путь относительно репозитория словарей
+    pub path: String,
+    /// Хеш SHA-256 содержимого
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[doc = "Манифест релиза: версия, время создания, состояние перевода и хеши файлов словарей"]
+pub struct ReleaseManifest {
+    pub version: String,
+    /// Время создания в UTC
+    pub created: String,
+    pub status: RepositoryStatus,
+    pub files: Vec<ReleaseFile>,
+}
+
+fn releases_path(dictionary_dir: &str) -> PathBuf {
+    Path::new(dictionary_dir).join(RELEASES_DIR)
+}
+
+// Версия становится именем каталога, поэтому разделители путей и .. не допускаются
+fn validate_version(version: &str) -> Result<(), StaticDictionaryErrors> {
+    let valid = !version.is_empty()
+        && !version.starts_with('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+    match valid {
+        true => Ok(()),
+        false => Err(StaticDictionaryErrors::InvalidRelease(format!(
+            "версия {} может содержать только латинские буквы, цифры и символы . - _ +",
+            version
+        ))),
+    }
+}
+
+// Файлы словарей репозитория и его пространств имен. Скрытые каталоги (.dms, .git) пропускаются
+fn dictionary_files(root: &Path, dir: &Path) -> Result<Vec<String>, StaticDictionaryErrors> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let filename = entry.file_name().to_string_lossy().into_owned();
+        if filename.starts_with('.') {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            files.extend(dictionary_files(root, &entry.path())?);
+        } else if get_dictionary_language(&filename).is_ok() {
+            let relative = entry
+                .path()
+                .strip_prefix(root)
+                .unwrap_or(&entry.path())
+                .to_owned();
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[doc = "Версии релизов репозитория по алфавиту"]
+pub fn list_releases(dictionary_dir: &str) -> Result<Vec<String>, StaticDictionaryErrors> {
+    let path = releases_path(dictionary_dir);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut releases = vec![];
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.path().join(MANIFEST_FILE).is_file() {
+            releases.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    releases.sort();
+    Ok(releases)
+}
+
+#[doc = "Сохраняет все словари репозитория (вместе с пространствами имен) в .dms/releases/<версия> и записывает манифест. Существующий релиз не перезаписывается"]
+pub fn create_release(
+    dictionary_dir: &str,
+    version: &str,
+) -> Result<ReleaseManifest, StaticDictionaryErrors> {
+    validate_version(version)?;
+    let target = releases_path(dictionary_dir).join(version);
+    if target.exists() {
+        return Err(StaticDictionaryErrors::InvalidRelease(format!(
+            "релиз {} уже существует",
+            version
+        )));
+    }
+    let status = status(dictionary_dir, None)?;
+    let mut files = vec![];
+    for path in dictionary_files(Path::new(dictionary_dir), Path::new(dictionary_dir))? {
+        let content = fs::read(Path::new(dictionary_dir).join(&path))?;
+        let destination = target.join(&path);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(destination, &content)?;
+        files.push(ReleaseFile {
+            path,
+            sha256: content_hash(&content),
+        });
+    }
+    let manifest = ReleaseManifest {
+        version: version.to_owned(),
+        created: utc_timestamp(SystemTime::now()),
+        status,
+        files,
+    };
+    // Манифест записывается последним: каталог без него не считается релизом
+    atomic_write(
+        target.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )?;
+    Ok(manifest)
+}
+
+#[doc = "Читает манифест релиза"]
+pub fn read_manifest(
+    dictionary_dir: &str,
+    version: &str,
+) -> Result<ReleaseManifest, StaticDictionaryErrors> {
+    validate_version(version)?;
+    let path = releases_path(dictionary_dir)
+        .join(version)
+        .join(MANIFEST_FILE);
+    if !path.is_file() {
+        return Err(StaticDictionaryErrors::InvalidRelease(format!(
+            "релиз {} не найден в {}",
+            version,
+            releases_path(dictionary_dir).display()
+        )));
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[doc = "Проверяет хеши файлов релиза по манифесту и возвращает каталог релиза, из которого можно собирать словари как из обычного репозитория"]
+pub fn release_directory(
+    dictionary_dir: &str,
+    version: &str,
+) -> Result<String, StaticDictionaryErrors> {
+    let manifest = read_manifest(dictionary_dir, version)?;
+    let directory = releases_path(dictionary_dir).join(version);
+    let mut problems = vec![];
+    for file in &manifest.files {
+        match fs::read(directory.join(&file.path)) {
+            Ok(content) if content_hash(&content) == file.sha256 => {}
+            Ok(_) => problems.push(format!("{} изменен после создания релиза", file.path)),
+            Err(_) => problems.push(format!("{} отсутствует", file.path)),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(StaticDictionaryErrors::InvalidRelease(format!(
+            "{}: {}",
+            version,
+            problems.join(", ")
+        )));
+    }
+    Ok(directory.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_snapshot_is_immutable() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("dictionary-ru.base.json"), r#"["Вход"]"#).unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in"}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("checkout")).unwrap();
+        fs::write(
+            dir.path().join("checkout/dictionary-ru.base.json"),
+            r#"["Оплата"]"#,
+        )
+        .unwrap();
+
+        let manifest = create_release(repo, "1.2.0").unwrap();
+        assert_eq!(manifest.status.languages[0].translated, 1);
+        let paths: Vec<&str> = manifest
+            .files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "checkout/dictionary-ru.base.json",
+                "dictionary-en.json",
+                "dictionary-ru.base.json"
+            ]
+        );
+        assert!(matches!(
+            create_release(repo, "1.2.0"),
+            Err(StaticDictionaryErrors::InvalidRelease(_))
+        ));
+        assert!(create_release(repo, "../1.3").is_err());
+        assert_eq!(list_releases(repo).unwrap(), vec!["1.2.0"]);
+
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Log in"}"#,
+        )
+        .unwrap();
+        let released = release_directory(repo, "1.2.0").unwrap();
+        assert_eq!(
+            fs::read_to_string(Path::new(&released).join("dictionary-en.json")).unwrap(),
+            r#"{"Вход": "Sign in"}"#
+        );
+        fs::write(Path::new(&released).join("dictionary-en.json"), "{}").unwrap();
+        assert!(matches!(
+            release_directory(repo, "1.2.0"),
+            Err(StaticDictionaryErrors::InvalidRelease(_))
+        ));
+    }
+}
@@ -0,0 +1,495 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::dictionary_format::DictionaryFormat;
+use crate::errors::errors::ReportErrors;
+use crate::language::LanguageCode;
+use crate::parser::get_dictionary_language;
+use crate::static_translate::parse_translated_dictionary_content;
+use crate::types::BaseEntry;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Состояние репозитория словарей в определенный момент: базовый словарь и переводы по языкам"]
+pub struct DictionarySnapshot {
+    /// Теги базового словаря
+    pub base: BTreeSet<String>,
+    /// Переводы: язык -> (ключ с суффиксом формы -> перевод)
+    pub translations: BTreeMap<String, BTreeMap<String, String>>,
+    /// Теги базового словаря с непустым переводом по языкам
+    pub translated: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Изменения в словаре одного языка"]
+pub struct LanguageChanges {
+    pub language: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    /// Процент переведенных ключей до изменений
+    pub coverage_before: f64,
+    /// Процент переведенных ключей после изменений
+    pub coverage_after: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Отчет об изменениях между двумя состояниями репозитория словарей"]
+pub struct DictionaryReport {
+    pub base_added: Vec<String>,
+    pub base_removed: Vec<String>,
+    pub languages: Vec<LanguageChanges>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Значение ключа, которое отличается в двух словарях"]
+pub struct ChangedValue {
+    pub key: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Разница между двумя файлами словарей: ключ -> значение"]
+pub struct DictionaryDiff {
+    pub added: BTreeMap<String, String>,
+    pub removed: BTreeMap<String, String>,
+    pub changed: Vec<ChangedValue>,
+}
+
+impl DictionarySnapshot {
+    #[doc = "Собирает снимок из файлов словарей по их названиям и содержимому"]
+    pub fn from_files(
+        files: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<DictionarySnapshot, ReportErrors> {
+        let mut snapshot = DictionarySnapshot::default();
+        for (filename, content) in files {
+            let Ok(language) = get_dictionary_language(&filename).map(String::from) else {
+                continue;
+            };
+            let format = DictionaryFormat::from_path(&filename);
+            if filename.contains(".base.") {
+                let entries: Vec<BaseEntry> = format.parse(&content)?;
+                snapshot
+                    .base
+                    .extend(entries.iter().map(|entry| entry.tag().to_owned()));
+            } else {
+                let words = parse_translated_dictionary_content(&content, &language, format)?;
+                snapshot.translated.insert(
+                    language.clone(),
+                    words
+                        .iter()
+                        .filter(|word| !word.word.is_empty())
+                        .map(|word| word.tag.clone())
+                        .collect(),
+                );
+                snapshot.translations.insert(
+                    language,
+                    words
+                        .into_iter()
+                        .map(|word| (word.suffixed_tag(), word.word))
+                        .collect(),
+                );
+            }
+        }
+        Ok(snapshot)
+    }
+
+    #[doc = "Читает снимок из директории с репозиторием словарей"]
+    pub fn from_directory(dictionary_dir: &str) -> Result<DictionarySnapshot, ReportErrors> {
+        let mut files = vec![];
+        for entry in fs::read_dir(dictionary_dir)? {
+            let entry = entry?;
+            let filename = entry.file_name().to_string_lossy().into_owned();
+            if get_dictionary_language(&filename).is_ok() {
+                files.push((filename, fs::read_to_string(entry.path())?));
+            }
+        }
+        DictionarySnapshot::from_files(files)
+    }
+
+    #[doc = "Читает снимок репозитория словарей из ревизии git. Путь к словарям указывается относительно текущей директории"]
+    pub fn from_git_ref(
+        dictionary_dir: &str,
+        git_ref: &str,
+    ) -> Result<DictionarySnapshot, ReportErrors> {
+        let listing = run_git(dictionary_dir, &["ls-tree", "--name-only", git_ref, "."])?;
+        let mut files = vec![];
+        for filename in listing.lines() {
+            let filename = filename.rsplit('/').next().unwrap_or(filename);
+            if get_dictionary_language(filename).is_ok() {
+                let content = run_git(
+                    dictionary_dir,
+                    &["show", &format!("{}:./{}", git_ref, filename)],
+                )?;
+                files.push((filename.to_owned(), content));
+            }
+        }
+        DictionarySnapshot::from_files(files)
+    }
+
+    #[doc = "Загружает снимок из директории, если она существует, иначе считает аргумент ревизией git"]
+    pub fn load(dictionary_dir: &str, source: &str) -> Result<DictionarySnapshot, ReportErrors> {
+        if Path::new(source).is_dir() {
+            DictionarySnapshot::from_directory(source)
+        } else {
+            DictionarySnapshot::from_git_ref(dictionary_dir, source)
+        }
+    }
+
+    #[doc = "Процент ключей базового словаря, у которых есть непустой перевод на языке"]
+    pub fn coverage(&self, language: &str) -> f64 {
+        if self.base.is_empty() {
+            return 0.0;
+        }
+        let Ok(language) = LanguageCode::new(language) else {
+            return 0.0;
+        };
+        let Some(translated) = self.translated.get(language.as_str()) else {
+            return 0.0;
+        };
+        let translated = self.base.intersection(translated).count();
+        translated as f64 * 100.0 / self.base.len() as f64
+    }
+}
+
+fn run_git(directory: &str, args: &[&str]) -> Result<String, ReportErrors> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(ReportErrors::GitError(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl DictionaryReport {
+    #[doc = "Сравнивает два состояния репозитория словарей"]
+    pub fn compare(before: &DictionarySnapshot, after: &DictionarySnapshot) -> DictionaryReport {
+        let empty = BTreeMap::new();
+        let languages: BTreeSet<&String> = before
+            .translations
+            .keys()
+            .chain(after.translations.keys())
+            .collect();
+        DictionaryReport {
+            base_added: after.base.difference(&before.base).cloned().collect(),
+            base_removed: before.base.difference(&after.base).cloned().collect(),
+            languages: languages
+                .into_iter()
+                .map(|language| {
+                    let old = before.translations.get(language).unwrap_or(&empty);
+                    let new = after.translations.get(language).unwrap_or(&empty);
+                    LanguageChanges {
+                        language: language.to_owned(),
+                        added: new
+                            .keys()
+                            .filter(|key| !old.contains_key(*key))
+                            .cloned()
+                            .collect(),
+                        removed: old
+                            .keys()
+                            .filter(|key| !new.contains_key(*key))
+                            .cloned()
+                            .collect(),
+                        changed: new
+                            .iter()
+                            .filter(|(key, value)| old.get(*key).is_some_and(|old| old != *value))
+                            .map(|(key, _)| key.to_owned())
+                            .collect(),
+                        coverage_before: before.coverage(language),
+                        coverage_after: after.coverage(language),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    #[doc = "Нет ли в отчете ни одного изменения"]
+    pub fn is_empty(&self) -> bool {
+        self.base_added.is_empty()
+            && self.base_removed.is_empty()
+            && self.languages.iter().all(|language| {
+                language.added.is_empty()
+                    && language.removed.is_empty()
+                    && language.changed.is_empty()
+            })
+    }
+
+    #[doc = "Форматирует отчет в Markdown для описания pull request"]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("## Изменения в словарях\n\n");
+        if self.is_empty() {
+            markdown.push_str("Изменений в словарях нет.\n");
+            return markdown;
+        }
+        let _ = writeln!(
+            markdown,
+            "Базовый словарь: **+{}** / **-{}** фраз\n",
+            self.base_added.len(),
+            self.base_removed.len()
+        );
+        markdown.push_str("| Язык | Добавлено | Удалено | Изменено | Покрытие |\n");
+        markdown.push_str("|---|---|---|---|---|\n");
+        for language in &self.languages {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {:.1}% → {:.1}% ({:+.1}) |",
+                language.language,
+                language.added.len(),
+                language.removed.len(),
+                language.changed.len(),
+                language.coverage_before,
+                language.coverage_after,
+                language.coverage_after - language.coverage_before
+            );
+        }
+        let details = [
+            ("Новые фразы базового словаря", &self.base_added),
+            ("Удаленные фразы базового словаря", &self.base_removed),
+        ];
+        for (title, phrases) in details {
+            write_details(&mut markdown, title, &[("", phrases)]);
+        }
+        for language in &self.languages {
+            write_details(
+                &mut markdown,
+                &language.language,
+                &[
+                    ("Добавлено", &language.added),
+                    ("Удалено", &language.removed),
+                    ("Изменено", &language.changed),
+                ],
+            );
+        }
+        markdown
+    }
+}
+
+fn write_details(markdown: &mut String, title: &str, groups: &[(&str, &Vec<String>)]) {
+    if groups.iter().all(|(_, keys)| keys.is_empty()) {
+        return;
+    }
+    let _ = write!(markdown, "\n<details><summary>{}</summary>\n\n", title);
+    for (name, keys) in groups {
+        if keys.is_empty() {
+            continue;
+        }
+        let list = keys
+            .iter()
+            .map(|key| format!("`{}`", key.replace('`', "'")))
+            .collect::<Vec<String>>()
+            .join(", ");
+        if name.is_empty() {
+            let _ = writeln!(markdown, "{}", list);
+        } else {
+            let _ = writeln!(markdown, "**{}:** {}\n", name, list);
+        }
+    }
+    markdown.push_str("</details>\n");
+}
+
+#[doc = "Значения словаря по ключам: для базового словаря - исходные фразы по тегам, для дочернего - переводы по ключам с суффиксом формы"]
+pub fn dictionary_values(
+    filename: &str,
+    content: &str,
+) -> Result<BTreeMap<String, String>, ReportErrors> {
+    let format = DictionaryFormat::from_path(filename);
+    if filename.contains(".base.") {
+        let entries: Vec<BaseEntry> = format.parse(content)?;
+        return Ok(entries
+            .into_iter()
+            .map(|entry| (entry.tag().to_owned(), entry.phrase))
+            .collect());
+    }
+    let language = get_dictionary_language(filename)
+        .map(String::from)
+        .unwrap_or_default();
+    Ok(parse_translated_dictionary_content(content, &language, format)?
+        .into_iter()
+        .map(|word| (word.suffixed_tag(), word.word))
+        .collect())
+}
+
+#[doc = "Читает значения файла словаря из рабочей копии или, если указана ревизия, из git"]
+pub fn read_dictionary_values(
+    path: &str,
+    revision: Option<&str>,
+) -> Result<BTreeMap<String, String>, ReportErrors> {
+    let path = Path::new(path);
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let content = match revision {
+        Some(revision) => {
+            let directory = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy(),
+                _ => ".".into(),
+            };
+            run_git(&directory, &["show", &format!("{}:./{}", revision, filename)])?
+        }
+        None => fs::read_to_string(path)?,
+    };
+    dictionary_values(&filename, &content)
+}
+
+impl DictionaryDiff {
+    #[doc = "Сравнивает значения двух словарей. Если compare_values выключен, сравниваются только наборы ключей - например, у дочернего словаря с базовым"]
+    pub fn compare(
+        before: &BTreeMap<String, String>,
+        after: &BTreeMap<String, String>,
+        compare_values: bool,
+    ) -> DictionaryDiff {
+        let only_in = |left: &BTreeMap<String, String>, right: &BTreeMap<String, String>| {
+            left.iter()
+                .filter(|(key, _)| !right.contains_key(*key))
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect()
+        };
+        DictionaryDiff {
+            added: only_in(after, before),
+            removed: only_in(before, after),
+            changed: after
+                .iter()
+                .filter(|_| compare_values)
+                .filter_map(|(key, value)| {
+                    let old = before.get(key)?;
+                    (old != value).then(|| ChangedValue {
+                        key: key.to_owned(),
+                        before: old.to_owned(),
+                        after: value.to_owned(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    #[doc = "Совпадают ли словари"]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[doc = "Публикует отчет комментарием к pull request через GitHub API"]
+pub async fn post_github_comment(
+    repository: &str,
+    pull_request: u64,
+    token: &str,
+    body: &str,
+) -> Result<(), ReportErrors> {
+    reqwest::Client::new()
+        .post(format!(
+            "https://api.github.com/repos/{}/issues/{}/comments",
+            repository, pull_request
+        ))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "dms-rs")
+        .bearer_auth(token)
+        .json(&serde_json::json!({ "body": body }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(base: &str, translations: &[(&str, &str)]) -> DictionarySnapshot {
+        let mut files = vec![("dictionary-ru.base.json".to_owned(), base.to_owned())];
+        for (language, content) in translations {
+            files.push((format!("dictionary-{}.json", language), content.to_string()));
+        }
+        DictionarySnapshot::from_files(files).unwrap()
+    }
+
+    #[test]
+    fn test_report_counts_changes_and_coverage() {
+        let before = snapshot(
+            r#"["Привет", "Пока"]"#,
+            &[("en", r#"{"Привет": "Hello", "Пока": ""}"#)],
+        );
+        let after = snapshot(
+            r#"["Привет", "Пока", "Спасибо"]"#,
+            &[("en", r#"{"Привет": "Hi", "Пока": "Bye", "Спасибо": ""}"#)],
+        );
+        let report = DictionaryReport::compare(&before, &after);
+        assert_eq!(report.base_added, vec!["Спасибо".to_owned()]);
+        let en = &report.languages[0];
+        assert_eq!(en.added, vec!["Спасибо".to_owned()]);
+        assert_eq!(en.changed.len(), 2);
+        assert_eq!(en.coverage_before, 50.0);
+        assert!((en.coverage_after - 66.666).abs() < 0.01);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| en | 1 | 0 | 2 | 50.0% → 66.7% (+16.7) |"));
+    }
+
+    #[test]
+    fn test_coverage_matches_languages_and_tags_exactly() {
+        let snapshot = snapshot(
+            r#"["Сохранить", "Открыть"]"#,
+            &[
+                ("pt", r#"{"Сохранить": "", "Сохранить_черновик": "Salvar rascunho"}"#),
+                ("pt-BR", r#"{"Сохранить": "Salvar", "Открыть": "Abrir"}"#),
+            ],
+        );
+        assert_eq!(snapshot.coverage("pt"), 0.0);
+        assert_eq!(snapshot.coverage("pt-BR"), 100.0);
+        assert_eq!(snapshot.coverage("pt_br"), 100.0);
+    }
+
+    #[test]
+    fn test_dictionary_diff() {
+        let before = dictionary_values(
+            "dictionary-en.json",
+            r#"{"Привет": "Hello", "Пока": "Bye", "Да": "Yes"}"#,
+        )
+        .unwrap();
+        let after = dictionary_values(
+            "dictionary-en.json",
+            r#"{"Привет": "Hi", "Да": "Yes", "Нет": "No"}"#,
+        )
+        .unwrap();
+        let diff = DictionaryDiff::compare(&before, &after, true);
+        assert_eq!(diff.added, BTreeMap::from([("Нет".to_owned(), "No".to_owned())]));
+        assert_eq!(diff.removed, BTreeMap::from([("Пока".to_owned(), "Bye".to_owned())]));
+        assert_eq!(
+            diff.changed,
+            vec![ChangedValue {
+                key: "Привет".into(),
+                before: "Hello".into(),
+                after: "Hi".into()
+            }]
+        );
+
+        let base = dictionary_values(
+            "dictionary-ru.base.json",
+            r#"["Привет", {"phrase": "Нет", "key": "no"}]"#,
+        )
+        .unwrap();
+        let against_base = DictionaryDiff::compare(&base, &after, false);
+        assert!(against_base.changed.is_empty());
+        assert_eq!(against_base.removed.keys().collect::<Vec<_>>(), vec!["no"]);
+        assert_eq!(against_base.added.len(), 2);
+        assert!(DictionaryDiff::compare(&after, &after, true).is_empty());
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let state = snapshot(r#"["Привет"]"#, &[("en", r#"{"Привет": "Hello"}"#)]);
+        let report = DictionaryReport::compare(&state, &state);
+        assert!(report.is_empty());
+        assert!(report.to_markdown().contains("Изменений в словарях нет"));
+    }
+}
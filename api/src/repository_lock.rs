@@ -0,0 +1,112 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::errors::errors::StaticDictionaryErrors;
+
+#[doc = "Файл блокировки внутри репозитория словарей"]
+pub const LOCK_FILE: &str = ".dms/lock";
+
+#[derive(Debug)]
+#[doc = "Рекомендательная блокировка репозитория словарей на время команды, которая его изменяет. Снимается, когда значение удаляется"]
+pub struct RepositoryLock {
+    path: PathBuf,
+}
+
+// Процесс, записанный в блокировку, уже завершился. Проверить можно только там, где есть /proc
+fn is_stale(holder: &str) -> bool {
+    let Some(pid) = holder
+        .split_whitespace()
+        .next()
+        .and_then(|pid| pid.parse::<u32>().ok())
+    else {
+        return false;
+    };
+    let processes = Path::new("/proc");
+    processes.is_dir() && !processes.join(pid.to_string()).exists()
+}
+
+impl RepositoryLock {
+    #[doc = "Захватывает блокировку репозитория для операции operation. Если репозиторий уже заблокирован другим запущенным процессом, возвращает ошибку RepositoryLocked. Блокировка завершившегося процесса (например, прерванного через Ctrl+C) снимается автоматически там, где это можно проверить"]
+    pub fn acquire(
+        dictionary_dir: &str,
+        operation: &str,
+    ) -> Result<RepositoryLock, StaticDictionaryErrors> {
+        // Директория репозитория не создается, если ее нет
+        fs::metadata(dictionary_dir)?;
+        let path = Path::new(dictionary_dir).join(LOCK_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut stale_removed = false;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{} {}", std::process::id(), operation)?;
+                    tracing::debug!("Репозиторий {} заблокирован командой {}", dictionary_dir, operation);
+                    return Ok(RepositoryLock { path });
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    let holder = fs::read_to_string(&path).unwrap_or_default();
+                    if !stale_removed && is_stale(&holder) {
+                        tracing::warn!("Снята блокировка завершившегося процесса: {}", holder.trim());
+                        fs::remove_file(&path)?;
+                        stale_removed = true;
+                        continue;
+                    }
+                    let mut holder = holder.split_whitespace();
+                    let (pid, operation) = (holder.next(), holder.next());
+                    return Err(StaticDictionaryErrors::RepositoryLocked(format!(
+                        "команда {} выполняется в процессе {}. Если dms не запущен, удалите {}",
+                        operation.unwrap_or("?"),
+                        pid.unwrap_or("?"),
+                        path.display()
+                    )));
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl Drop for RepositoryLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+
+        let lock = RepositoryLock::acquire(repo, "sync").unwrap();
+        assert!(matches!(
+            RepositoryLock::acquire(repo, "scan"),
+            Err(StaticDictionaryErrors::RepositoryLocked(_))
+        ));
+        drop(lock);
+        assert!(!dir.path().join(LOCK_FILE).exists());
+        let lock = RepositoryLock::acquire(repo, "scan").unwrap();
+        drop(lock);
+
+        assert!(
+            RepositoryLock::acquire(dir.path().join("missing").to_str().unwrap(), "sync").is_err()
+        );
+        assert!(!dir.path().join("missing").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_lock_of_finished_process_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join(".dms")).unwrap();
+        fs::write(dir.path().join(LOCK_FILE), format!("{} sync\n", u32::MAX)).unwrap();
+        assert!(RepositoryLock::acquire(repo, "scan").is_ok());
+    }
+}
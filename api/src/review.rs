@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::static_translate::{
+    pair_with_sources, read_translated_dictionary, write_translated_dictionary,
+};
+use crate::types::{EntryStatus, Word};
+
+#[derive(Debug, Clone, PartialEq)]
+#[doc = "Запись с машинным переводом, ожидающая проверки"]
+pub struct ReviewItem {
+    pub tag: String,
+    /// Пары (исходный текст, перевод) для значения, каждой формы множественного числа и варианта
+    pub pairs: Vec<(Word, Word)>,
+    /// Перевод взят из предложения автопереводчика и станет значением записи после утверждения
+    pub suggestion: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[doc = "Решение проверяющего по записи"]
+pub enum ReviewDecision {
+    /// Принять машинный перевод без изменений
+    Accept,
+    /// Заменить перевод исправленными словами
+    Edit(Vec<Word>),
+    /// Оставить запись непроверенной
+    Skip,
+}
+
+#[doc = "Возвращает записи дочернего словаря со статусом machine и записи с предложенным переводом вместе с исходными фразами. Заблокированные записи с предложениями пропускаются"]
+pub fn pending_reviews(
+    dictionary_dir: &str,
+    language: &str,
+) -> Result<Vec<ReviewItem>, StaticDictionaryErrors> {
+    Ok(pair_with_sources(dictionary_dir, language)?
+        .into_iter()
+        .filter_map(|paired| {
+            if paired.entry.suggestion.is_some() && !paired.entry.locked {
+                let sources: HashMap<String, Word> = paired
+                    .pairs
+                    .into_iter()
+                    .map(|(source, translation)| (translation.suffixed_tag(), source))
+                    .collect();
+                let pairs = paired
+                    .entry
+                    .suggested_words(&paired.tag, language)
+                    .into_iter()
+                    .map(|suggested| {
+                        let source = sources
+                            .get(&suggested.suffixed_tag())
+                            .cloned()
+                            .unwrap_or_else(|| {
+                                Word::new(String::new(), paired.tag.clone(), String::new())
+                            });
+                        (source, suggested)
+                    })
+                    .collect();
+                return Some(ReviewItem {
+                    tag: paired.tag,
+                    pairs,
+                    suggestion: true,
+                });
+            }
+            (paired.entry.status == EntryStatus::Machine).then_some(ReviewItem {
+                tag: paired.tag,
+                pairs: paired.pairs,
+                suggestion: false,
+            })
+        })
+        .collect())
+}
+
+#[doc = "Записывает решения проверки в дочерний словарь. Принятые и исправленные записи получают статус reviewed, принятое предложение становится значением записи, а исправленное удаляется. Возвращает количество проверенных записей"]
+pub fn apply_review_decisions(
+    dictionary_dir: &str,
+    language: &str,
+    decisions: &[(String, ReviewDecision)],
+) -> Result<usize, StaticDictionaryErrors> {
+    let mut dictionary = read_translated_dictionary(dictionary_dir, language)?;
+    let mut reviewed = 0;
+    for (tag, decision) in decisions {
+        let Some(entry) = dictionary.get_mut(tag) else {
+            continue;
+        };
+        match decision {
+            ReviewDecision::Accept => {
+                entry.promote_suggestion();
+            }
+            ReviewDecision::Edit(words) => {
+                entry.suggestion = None;
+                for word in words {
+                    entry.set_word(word);
+                }
+            }
+            ReviewDecision::Skip => continue,
+        }
+        entry.status = EntryStatus::Reviewed;
+        reviewed += 1;
+    }
+    write_translated_dictionary(dictionary_dir, language, &dictionary)?;
+    Ok(reviewed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_review_accept_edit_skip() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Привет", "Пока", "Спасибо"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{
+                "Привет": {"value": "Hi", "status": "machine"},
+                "Пока": {"value": "Bye", "status": "machine"},
+                "Спасибо": {"value": "Thanks", "status": "machine"}
+            }"#,
+        )
+        .unwrap();
+
+        let pending = pending_reviews(repo, "en").unwrap();
+        assert_eq!(pending.len(), 3);
+        let hello = pending.iter().find(|item| item.tag == "Привет").unwrap();
+        assert_eq!(hello.pairs[0].0.word, "Привет");
+        assert_eq!(hello.pairs[0].1.word, "Hi");
+
+        let edited = Word::new("Hello".to_owned(), "Привет".to_owned(), "en".to_owned());
+        let decisions = vec![
+            ("Привет".to_owned(), ReviewDecision::Edit(vec![edited])),
+            ("Пока".to_owned(), ReviewDecision::Accept),
+            ("Спасибо".to_owned(), ReviewDecision::Skip),
+        ];
+        assert_eq!(apply_review_decisions(repo, "en", &decisions).unwrap(), 2);
+
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary["Привет"].value, "Hello");
+        assert_eq!(dictionary["Привет"].status, EntryStatus::Reviewed);
+        assert_eq!(dictionary["Пока"].status, EntryStatus::Reviewed);
+        assert_eq!(dictionary["Спасибо"].status, EntryStatus::Machine);
+        assert_eq!(pending_reviews(repo, "en").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_review_promotes_suggestions() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Привет", "Пока", "Спасибо"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{
+                "Привет": {"value": "", "suggestion": {"value": "Hi"}},
+                "Пока": {"value": "Goodbye", "status": "translated", "suggestion": {"value": "Bye"}},
+                "Спасибо": {"value": "", "locked": true, "suggestion": {"value": "Thanks"}}
+            }"#,
+        )
+        .unwrap();
+
+        let pending = pending_reviews(repo, "en").unwrap();
+        assert_eq!(pending.len(), 2);
+        let hello = pending.iter().find(|item| item.tag == "Привет").unwrap();
+        assert!(hello.suggestion);
+        assert_eq!(hello.pairs[0].0.word, "Привет");
+        assert_eq!(hello.pairs[0].1.word, "Hi");
+
+        let edited = Word::new("Bye".to_owned(), "Пока".to_owned(), "en".to_owned());
+        let decisions = vec![
+            ("Привет".to_owned(), ReviewDecision::Accept),
+            ("Пока".to_owned(), ReviewDecision::Edit(vec![edited])),
+        ];
+        assert_eq!(apply_review_decisions(repo, "en", &decisions).unwrap(), 2);
+
+        let dictionary = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(dictionary["Привет"].value, "Hi");
+        assert_eq!(dictionary["Привет"].suggestion, None);
+        assert_eq!(dictionary["Привет"].status, EntryStatus::Reviewed);
+        assert_eq!(dictionary["Пока"].value, "Bye");
+        assert_eq!(dictionary["Пока"].suggestion, None);
+        assert!(dictionary["Спасибо"].suggestion.is_some());
+        assert!(pending_reviews(repo, "en").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::atomic_write;
+use crate::parser::types::ConfigFileParameters;
+
+#[doc = "Путь к кешу сканирования по умолчанию (относительно директории запуска)"]
+pub const DEFAULT_SCAN_CACHE_PATH: &str = ".dms/scan-cache.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[doc = "Хеши содержимого просканированных файлов. Файлы, которые не изменились с прошлого сканирования, пропускаются"]
+pub struct ScanCache {
+    /// Хеш настроек сканирования: если шаблоны, стратегия тегов или репозиторий изменились, кеш сбрасывается
+    pub settings: String,
+    /// Путь к файлу -> хеш SHA-256 его содержимого
+    pub files: BTreeMap<String, String>,
+}
+
+#[doc = "Хеш SHA-256 содержимого файла"]
+pub fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[doc = "Хеш настроек конфига, от которых зависит результат сканирования файла"]
+pub fn settings_hash(config: &ConfigFileParameters) -> String {
+    let settings = serde_json::json!({
+        "base": config.base_directory,
+        "include": config.languages_configurations,
+        "key_strategy": config.key_strategy,
+        "normalize_phrases": config.normalize_phrases,
+        "dictionary_repo": config.dictionary_repo,
+        "encoding": config.encoding,
+        "filters": config.filters,
+    });
+    content_hash(settings.to_string().as_bytes())
+}
+
+impl ScanCache {
+    #[doc = "Читает кеш. Если файла нет, он поврежден или записан с другими настройками, возвращается пустой кеш"]
+    pub fn load(path: impl AsRef<Path>, settings: &str) -> ScanCache {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<ScanCache>(&content).ok())
+            .filter(|cache| cache.settings == settings)
+            .unwrap_or_else(|| ScanCache {
+                settings: settings.to_owned(),
+                ..Default::default()
+            })
+    }
+
+    #[doc = "Записывает кеш, создавая директорию при необходимости"]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StaticDictionaryErrors> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    #[doc = "Файл не изменился с прошлого сканирования"]
+    pub fn is_unchanged(&self, file: &str, hash: &str) -> bool {
+        self.files.get(file).is_some_and(|cached| cached == hash)
+    }
+
+    #[doc = "Запоминает хеш просканированного файла"]
+    pub fn update(&mut self, file: &str, hash: String) {
+        self.files.insert(file.to_owned(), hash);
+    }
+
+    #[doc = "Удаляет из кеша файлы, которых больше нет среди файлов проекта"]
+    pub fn retain_files(&mut self, files: &[String]) {
+        self.files.retain(|file, _| files.contains(file));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_is_reset_when_settings_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".dms").join("scan-cache.json");
+        let mut cache = ScanCache::load(&path, "a");
+        assert!(cache.files.is_empty());
+        cache.update("src/app.js", content_hash(b"t(\"one\")"));
+        cache.save(&path).unwrap();
+
+        let cache = ScanCache::load(&path, "a");
+        assert!(cache.is_unchanged("src/app.js", &content_hash(b"t(\"one\")")));
+        assert!(!cache.is_unchanged("src/app.js", &content_hash(b"t(\"two\")")));
+        assert!(ScanCache::load(&path, "b").files.is_empty());
+    }
+}
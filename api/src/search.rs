@@ -0,0 +1,102 @@
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_translated_dictionary,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Найденное значение: исходная фраза базового словаря или перевод"]
+pub struct SearchMatch {
+    /// Ключ записи с суффиксом формы множественного числа или варианта
+    pub tag: String,
+    pub language: String,
+    pub value: String,
+}
+
+#[doc = "Регулярное выражение для поиска: подстрока ищется буквально, с regex запрос считается регулярным выражением"]
+pub fn search_pattern(
+    query: &str,
+    regex: bool,
+    ignore_case: bool,
+) -> Result<Regex, StaticDictionaryErrors> {
+    let pattern = match regex {
+        true => query.to_owned(),
+        false => regex::escape(query),
+    };
+    Ok(RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case)
+        .build()?)
+}
+
+#[doc = "Ищет записи, у которых тег, исходная фраза или перевод на любой язык подходят под шаблон. Сначала идут фразы базового словаря, затем переводы по языкам"]
+pub fn search_entries(
+    dictionary_dir: &str,
+    pattern: &Regex,
+) -> Result<Vec<SearchMatch>, StaticDictionaryErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let mut words = vec![];
+    for entry in parse_static_basic_entries(dictionary_dir)? {
+        words.extend(entry.words_for_translation(&basic_language, &basic_language));
+    }
+    for language in find_all_translated_languages(dictionary_dir)? {
+        for (tag, entry) in read_translated_dictionary(dictionary_dir, &language)? {
+            words.extend(entry.words(&tag, &language));
+        }
+    }
+    Ok(words
+        .into_iter()
+        .filter(|word| pattern.is_match(&word.tag) || pattern.is_match(&word.word))
+        .map(|word| SearchMatch {
+            tag: word.suffixed_tag(),
+            language: word.language,
+            value: word.word,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_search_tags_and_translations() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Вход", "key": "auth.login"}, "Выход", "Корзина"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"auth.login": "Sign in", "Выход": "Sign out", "Корзина": "Cart"}"#,
+        )
+        .unwrap();
+
+        let found = search_entries(repo, &search_pattern("sign", false, true).unwrap()).unwrap();
+        let values: Vec<(&str, &str)> = found
+            .iter()
+            .map(|found| (found.language.as_str(), found.value.as_str()))
+            .collect();
+        assert_eq!(values, vec![("en", "Sign in"), ("en", "Sign out")]);
+
+        let found =
+            search_entries(repo, &search_pattern("^auth\\.", true, false).unwrap()).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            (found[0].language.as_str(), found[0].value.as_str()),
+            ("ru", "Вход")
+        );
+
+        assert!(
+            search_entries(repo, &search_pattern("(", false, false).unwrap())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::errors::StaticDictionaryErrors;
+
+// Столько байт в начале файла проверяется на нулевые байты, как в git
+const BINARY_CHECK_LENGTH: usize = 8000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[doc = "Кодировка исходных файлов проекта при сканировании"]
+pub enum SourceEncoding {
+    /// Только UTF-8 (или файлы с BOM), остальные файлы пропускаются
+    #[default]
+    Utf8,
+    /// Файлы не в UTF-8 перекодируются из кодировки, определенной по содержимому
+    Auto,
+    /// Файлы не в UTF-8 перекодируются из указанной кодировки (windows-1251, shift_jis...)
+    Fixed(&'static Encoding),
+}
+
+impl FromStr for SourceEncoding {
+    type Err = String;
+
+    fn from_str(encoding: &str) -> Result<SourceEncoding, String> {
+        match encoding.to_lowercase().as_str() {
+            "auto" => Ok(SourceEncoding::Auto),
+            label => match Encoding::for_label(label.as_bytes()) {
+                Some(encoding) if encoding == UTF_8 => Ok(SourceEncoding::Utf8),
+                Some(encoding) => Ok(SourceEncoding::Fixed(encoding)),
+                None => Err(format!(
+                    "Неизвестная кодировка исходных файлов {}",
+                    encoding
+                )),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for SourceEncoding {
+    type Error = String;
+
+    fn try_from(encoding: String) -> Result<SourceEncoding, String> {
+        encoding.parse()
+    }
+}
+
+impl From<SourceEncoding> for String {
+    fn from(encoding: SourceEncoding) -> String {
+        encoding.to_string()
+    }
+}
+
+impl Display for SourceEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceEncoding::Utf8 => write!(f, "utf-8"),
+            SourceEncoding::Auto => write!(f, "auto"),
+            SourceEncoding::Fixed(encoding) => write!(f, "{}", encoding.name().to_lowercase()),
+        }
+    }
+}
+
+#[doc = "Двоичный файл: в начале содержимого есть нулевой байт"]
+pub fn is_binary(content: &[u8]) -> bool {
+    content[..content.len().min(BINARY_CHECK_LENGTH)].contains(&0)
+}
+
+#[doc = "Переводит содержимое файла в текст. Файлы с BOM (UTF-8, UTF-16) читаются в своей кодировке, остальные - как UTF-8 или в кодировке из настроек. Возвращает None для двоичных файлов и файлов, которые не удалось декодировать"]
+pub fn decode_source(content: &[u8], encoding: SourceEncoding) -> Option<String> {
+    if let Some((bom_encoding, bom_length)) = Encoding::for_bom(content) {
+        let (text, had_errors) = bom_encoding.decode_without_bom_handling(&content[bom_length..]);
+        return (!had_errors).then(|| text.into_owned());
+    }
+    if is_binary(content) {
+        return None;
+    }
+    if let Ok(text) = std::str::from_utf8(content) {
+        return Some(text.to_owned());
+    }
+    let encoding = match encoding {
+        SourceEncoding::Utf8 => return None,
+        SourceEncoding::Fixed(encoding) => encoding,
+        SourceEncoding::Auto => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(content, true);
+            detector.guess(None, true)
+        }
+    };
+    let (text, had_errors) = encoding.decode_without_bom_handling(content);
+    (!had_errors).then(|| text.into_owned())
+}
+
+#[doc = "Читает исходный файл проекта, см. decode_source"]
+pub fn read_source_file(
+    path: impl AsRef<Path>,
+    encoding: SourceEncoding,
+) -> Result<Option<String>, StaticDictionaryErrors> {
+    Ok(decode_source(&fs::read(path)?, encoding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_are_decoded_or_skipped() {
+        let (cp1251, _, _) = encoding_rs::WINDOWS_1251.encode("t(\"Привет\")");
+        assert_eq!(decode_source(&cp1251, SourceEncoding::Utf8), None);
+        assert_eq!(
+            decode_source(&cp1251, "windows-1251".parse().unwrap()).as_deref(),
+            Some("t(\"Привет\")")
+        );
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode("t(\"こんにちは、世界。ようこそ\")");
+        assert_eq!(
+            decode_source(&shift_jis, SourceEncoding::Auto).as_deref(),
+            Some("t(\"こんにちは、世界。ようこそ\")")
+        );
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert!(is_binary(png));
+        assert_eq!(decode_source(png, SourceEncoding::Auto), None);
+        assert_eq!(
+            decode_source(b"\xef\xbb\xbft(\"ok\")", SourceEncoding::Utf8).as_deref(),
+            Some("t(\"ok\")")
+        );
+        assert!("koi9".parse::<SourceEncoding>().is_err());
+        assert_eq!(
+            serde_json::to_string(&SourceEncoding::Fixed(encoding_rs::WINDOWS_1251)).unwrap(),
+            "\"windows-1251\""
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::atomic_write;
+use crate::static_translate::parse_static_basic_entries;
+
+#[doc = "Файл с местами использования фраз в репозитории словарей (рядом с базовым словарем)"]
+pub const LOCATIONS_FILE: &str = "locations.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[doc = "Место в исходном коде, где найдена фраза"]
+pub struct SourceLocation {
+    /// Путь к файлу в том виде, в каком он получен при сканировании (base из конфига + путь внутри)
+    pub file: String,
+    /// Номер строки, начиная с 1
+    pub line: usize,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+#[doc = "Места использования фраз: тег записи базового словаря -> места в исходном коде"]
+pub type SourceLocations = BTreeMap<String, Vec<SourceLocation>>;
+
+#[doc = "Путь к файлу в записи о месте использования: без ./ в начале и с / в качестве разделителя"]
+pub fn location_path(file: &Path) -> String {
+    let path = file.to_string_lossy().replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).to_owned()
+}
+
+#[doc = "Читает места использования фраз. Если сканирование еще не запускалось, возвращает пустой список"]
+pub fn read_source_locations(
+    dictionary_dir: &str,
+) -> Result<SourceLocations, StaticDictionaryErrors> {
+    let path = Path::new(dictionary_dir).join(LOCATIONS_FILE);
+    if !path.exists() {
+        return Ok(SourceLocations::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[doc = "Записывает места использования фраз в репозиторий"]
+pub fn write_source_locations(
+    dictionary_dir: &str,
+    locations: &SourceLocations,
+) -> Result<(), StaticDictionaryErrors> {
+    atomic_write(
+        Path::new(dictionary_dir).join(LOCATIONS_FILE),
+        serde_json::to_string_pretty(locations)?,
+    )?;
+    Ok(())
+}
+
+#[doc = "Заменяет места использования из пересканированного файла новыми (тег, строка). Теги, которые больше нигде не встречаются, удаляются"]
+pub fn replace_file_locations(
+    locations: &mut SourceLocations,
+    file: &str,
+    found: impl IntoIterator<Item = (String, usize)>,
+) {
+    for file_locations in locations.values_mut() {
+        file_locations.retain(|location| location.file != file);
+    }
+    for (tag, line) in found {
+        let location = SourceLocation {
+            file: file.to_owned(),
+            line,
+        };
+        let tag_locations = locations.entry(tag).or_default();
+        if !tag_locations.contains(&location) {
+            tag_locations.push(location);
+            tag_locations.sort();
+        }
+    }
+    locations.retain(|_, file_locations| !file_locations.is_empty());
+}
+
+#[doc = "Удаляет места использования в файлах, которых больше нет среди файлов проекта"]
+pub fn retain_files(locations: &mut SourceLocations, files: &[String]) {
+    for file_locations in locations.values_mut() {
+        file_locations.retain(|location| files.contains(&location.file));
+    }
+    locations.retain(|_, file_locations| !file_locations.is_empty());
+}
+
+#[doc = "Места использования записи. Для ключей с суффиксом формы множественного числа или варианта (apples_one) берутся места исходной записи"]
+pub fn locations_for<'a>(locations: &'a SourceLocations, tag: &str) -> &'a [SourceLocation] {
+    let mut tag = tag;
+    loop {
+        if let Some(found) = locations.get(tag) {
+            return found;
+        }
+        match tag.rsplit_once('_') {
+            Some((stem, _)) => tag = stem,
+            None => return &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Использование ключей базового словаря в исходном коде по данным последнего сканирования"]
+pub struct UsageReport {
+    /// Ключи базового словаря, которые не встречаются в исходном коде
+    pub unused: Vec<String>,
+    /// Ключи, которые встречаются в исходном коде, но отсутствуют в базовом словаре, с местами использования
+    pub missing: BTreeMap<String, Vec<SourceLocation>>,
+}
+
+impl UsageReport {
+    #[doc = "Все ключи используются и есть в базовом словаре"]
+    pub fn is_empty(&self) -> bool {
+        self.unused.is_empty() && self.missing.is_empty()
+    }
+}
+
+#[doc = "Сопоставляет базовый словарь с местами использования, записанными при сканировании: находит ключи без мест использования и места использования ключей, которых нет в базовом словаре. Исходные файлы заново не сканируются"]
+pub fn usage_report(dictionary_dir: &str) -> Result<UsageReport, StaticDictionaryErrors> {
+    let entries = parse_static_basic_entries(dictionary_dir)?;
+    let mut locations = read_source_locations(dictionary_dir)?;
+    let mut report = UsageReport::default();
+    for entry in &entries {
+        if locations.remove(entry.tag()).is_none() {
+            report.unused.push(entry.tag().to_owned());
+        }
+    }
+    report.missing = locations;
+    Ok(report)
+}
+
+#[doc = "Заменяет тег в исходных файлах только на строках мест использования и только в литерале (в двойных, одинарных или обратных кавычках), который стоит первым аргументом вызова: t(\"old\"), i18n.t('old', ...). Ничего не записывает, возвращает путь и новое содержимое каждого изменившегося файла"]
+pub fn rename_in_sources(
+    locations: &[SourceLocation],
+    old_tag: &str,
+    new_tag: &str,
+) -> Result<Vec<(String, String)>, StaticDictionaryErrors> {
+    let mut lines_by_file: BTreeMap<&str, BTreeSet<usize>> = BTreeMap::new();
+    for location in locations {
+        lines_by_file
+            .entry(location.file.as_str())
+            .or_default()
+            .insert(location.line);
+    }
+    let mut changed = vec![];
+    for (file, lines) in lines_by_file {
+        let content = fs::read_to_string(file)?;
+        let renamed: String = content
+            .split_inclusive('\n')
+            .enumerate()
+            .map(|(index, line)| match lines.contains(&(index + 1)) {
+                true => rename_call_argument(line, old_tag, new_tag),
+                false => line.to_owned(),
+            })
+            .collect();
+        if renamed != content {
+            changed.push((file.to_owned(), renamed));
+        }
+    }
+    Ok(changed)
+}
+
+// Заменяет литералы с тегом, перед которыми стоит открывающая скобка вызова. Остальные литералы строки (ключи объектов, атрибуты) не меняются
+fn rename_call_argument(line: &str, old_tag: &str, new_tag: &str) -> String {
+    let mut line = line.to_owned();
+    for quote in ['"', '\'', '`'] {
+        let literal = format!("{quote}{old_tag}{quote}");
+        let mut renamed = String::new();
+        let mut rest = line.as_str();
+        while let Some(position) = rest.find(&literal) {
+            renamed.push_str(&rest[..position]);
+            match renamed.trim_end().ends_with('(') {
+                true => renamed.push_str(&format!("{quote}{new_tag}{quote}")),
+                false => renamed.push_str(&literal),
+            }
+            rest = &rest[position + literal.len()..];
+        }
+        renamed.push_str(rest);
+        line = renamed;
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescanned_file_replaces_its_locations() {
+        let mut locations = SourceLocations::new();
+        replace_file_locations(
+            &mut locations,
+            "src/a.js",
+            [("greeting".to_owned(), 3), ("bye".to_owned(), 7)],
+        );
+        replace_file_locations(&mut locations, "src/b.js", [("greeting".to_owned(), 1)]);
+        replace_file_locations(&mut locations, "src/a.js", [("greeting".to_owned(), 4)]);
+
+        let greeting: Vec<String> = locations["greeting"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(greeting, vec!["src/a.js:4", "src/b.js:1"]);
+        assert!(!locations.contains_key("bye"));
+        assert_eq!(locations_for(&locations, "greeting_one").len(), 2);
+        assert!(locations_for(&locations, "unknown").is_empty());
+
+        retain_files(&mut locations, &["src/b.js".to_owned()]);
+        assert_eq!(locations["greeting"].len(), 1);
+    }
+
+    #[test]
+    fn test_rename_in_sources_changes_only_call_arguments() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("page.js");
+        fs::write(
+            &source,
+            "const page = { \"title\": t(\"title\") };\nt( 'title', { count });\nfield(\"title\");\n",
+        )
+        .unwrap();
+        let file = source.to_str().unwrap().to_owned();
+        let location = |line| SourceLocation {
+            file: file.clone(),
+            line,
+        };
+
+        let changed =
+            rename_in_sources(&[location(2), location(1), location(2)], "title", "page.title")
+                .unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(
+            changed[0].1,
+            "const page = { \"title\": t(\"page.title\") };\nt( 'page.title', { count });\nfield(\"title\");\n"
+        );
+        assert!(rename_in_sources(&[location(3)], "missing", "other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_usage_report_compares_locations_with_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"[{"phrase": "Вход", "key": "auth.login"}, "Корзина"]"#,
+        )
+        .unwrap();
+        let mut locations = SourceLocations::new();
+        replace_file_locations(
+            &mut locations,
+            "src/app.js",
+            [("auth.login".to_owned(), 2), ("auth.logout".to_owned(), 5)],
+        );
+        write_source_locations(repo, &locations).unwrap();
+
+        let report = usage_report(repo).unwrap();
+        assert_eq!(report.unused, vec!["Корзина"]);
+        assert_eq!(
+            report.missing.keys().collect::<Vec<_>>(),
+            vec!["auth.logout"]
+        );
+        assert_eq!(report.missing["auth.logout"][0].to_string(), "src/app.js:5");
+    }
+}
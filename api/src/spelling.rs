@@ -0,0 +1,427 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use encoding_rs::{Encoding, UTF_8};
+use regex::Regex;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::notranslate::protect;
+use crate::qa::placeholders;
+
+#[doc = "Директория со словарями hunspell по умолчанию"]
+pub const DEFAULT_SPELLING_DIR: &str = "/usr/share/hunspell";
+
+#[doc = "Список слов проекта в корне репозитория словарей, которые считаются написанными верно на всех языках: по одному на строку, строки с # пропускаются"]
+pub const SPELLING_WORDS_FILE: &str = "spelling-words.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagMode {
+    /// Флаг - один символ
+    Char,
+    /// Флаг - два символа (FLAG long)
+    Long,
+    /// Флаги - числа через запятую (FLAG num)
+    Number,
+}
+
+impl FlagMode {
+    fn split(&self, flags: &str) -> Vec<String> {
+        match self {
+            FlagMode::Char => flags.chars().map(String::from).collect(),
+            FlagMode::Long => flags
+                .chars()
+                .collect::<Vec<char>>()
+                .chunks(2)
+                .map(|flag| flag.iter().collect())
+                .collect(),
+            FlagMode::Number => flags
+                .split(',')
+                .map(str::trim)
+                .filter(|flag| !flag.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Affix {
+    flag: String,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    /// Условие для основы слова: для суффиксов проверяется конец основы, для префиксов - начало
+    condition: Regex,
+}
+
+impl Affix {
+    fn parse(
+        fields: &[&str],
+        prefix: bool,
+        cross_product: bool,
+    ) -> Result<Affix, StaticDictionaryErrors> {
+        let empty_as_zero = |value: &str| match value {
+            "0" => String::new(),
+            value => value.to_owned(),
+        };
+        // Флаги продолжения после / (суффиксы второго уровня) не поддерживаются
+        let add = fields[3].split('/').next().unwrap_or_default();
+        let condition = condition_pattern(fields.get(4).copied().unwrap_or("."));
+        let condition = match prefix {
+            true => format!("^(?:{})", condition),
+            false => format!("(?:{})$", condition),
+        };
+        Ok(Affix {
+            flag: fields[1].to_owned(),
+            cross_product,
+            strip: empty_as_zero(fields[2]),
+            add: empty_as_zero(add),
+            condition: Regex::new(&condition)?,
+        })
+    }
+
+    // Основа, от которой получено слово этим суффиксом
+    fn suffix_root(&self, word: &str) -> Option<String> {
+        let base = word.strip_suffix(self.add.as_str())?;
+        let root = format!("{}{}", base, self.strip);
+        (!base.is_empty() && self.condition.is_match(&root)).then_some(root)
+    }
+
+    // Основа, от которой получено слово этим префиксом
+    fn prefix_root(&self, word: &str) -> Option<String> {
+        let base = word.strip_prefix(self.add.as_str())?;
+        let root = format!("{}{}", self.strip, base);
+        (!base.is_empty() && self.condition.is_match(&root)).then_some(root)
+    }
+}
+
+// Условия hunspell - подмножество регулярных выражений: символы, . и классы в квадратных скобках
+fn condition_pattern(condition: &str) -> String {
+    let mut pattern = String::new();
+    let mut in_class = false;
+    for c in condition.chars() {
+        match c {
+            '[' => in_class = true,
+            ']' => in_class = false,
+            _ => {}
+        }
+        match c {
+            '[' | ']' | '.' => pattern.push(c),
+            '^' if in_class => pattern.push(c),
+            c if in_class && c != '\\' => pattern.push(c),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern
+}
+
+#[derive(Debug, Clone, Default)]
+#[doc = "Словарь проверки орфографии в формате hunspell (.aff и .dic). Поддерживаются префиксы и суффиксы первого уровня, FORBIDDENWORD и NEEDAFFIX, составные слова не поддерживаются"]
+pub struct HunspellDictionary {
+    stems: HashMap<String, HashSet<String>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    forbidden: Option<String>,
+    need_affix: Option<String>,
+}
+
+// Текст файла словаря в кодировке из строки SET файла .aff
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode(bytes).0.into_owned()
+}
+
+impl HunspellDictionary {
+    #[doc = "Разбирает содержимое файлов .aff и .dic"]
+    pub fn parse(aff: &str, dic: &str) -> Result<HunspellDictionary, StaticDictionaryErrors> {
+        let mut dictionary = HunspellDictionary::default();
+        let mut mode = FlagMode::Char;
+        let mut cross_products: HashMap<(bool, String), bool> = HashMap::new();
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => mode = FlagMode::Long,
+                ["FLAG", "num", ..] => mode = FlagMode::Number,
+                ["FORBIDDENWORD", flag, ..] => dictionary.forbidden = Some(flag.to_string()),
+                ["NEEDAFFIX", flag, ..] => dictionary.need_affix = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, ..] if fields.len() >= 4 => {
+                    let prefix = *kind == "PFX";
+                    let key = (prefix, flag.to_string());
+                    // Первая строка правила - заголовок с признаком перекрестных сочетаний и числом правил
+                    let Some(&cross_product) = cross_products.get(&key) else {
+                        cross_products.insert(key, fields[2] == "Y");
+                        continue;
+                    };
+                    let affix = Affix::parse(&fields, prefix, cross_product)?;
+                    match prefix {
+                        true => dictionary.prefixes.push(affix),
+                        false => dictionary.suffixes.push(affix),
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Первая строка .dic - примерное число слов
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            dictionary
+                .stems
+                .entry(word.to_owned())
+                .or_default()
+                .extend(mode.split(flags));
+        }
+        Ok(dictionary)
+    }
+
+    #[doc = "Читает словарь из файлов .aff и .dic в кодировке, указанной в .aff (по умолчанию UTF-8)"]
+    pub fn load(
+        aff_path: impl AsRef<Path>,
+        dic_path: impl AsRef<Path>,
+    ) -> Result<HunspellDictionary, StaticDictionaryErrors> {
+        let aff = fs::read(aff_path)?;
+        let encoding = String::from_utf8_lossy(&aff)
+            .lines()
+            .find_map(|line| line.strip_prefix("SET "))
+            .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+            .unwrap_or(UTF_8);
+        HunspellDictionary::parse(
+            &decode(&aff, encoding),
+            &decode(&fs::read(dic_path)?, encoding),
+        )
+    }
+
+    fn has_flag(flags: &HashSet<String>, flag: &Option<String>) -> bool {
+        flag.as_ref().is_some_and(|flag| flags.contains(flag))
+    }
+
+    // Основа есть в словаре с нужными флагами аффиксов
+    fn accepts_root(&self, root: &str, affix_flags: &[&str]) -> bool {
+        self.stems.get(root).is_some_and(|flags| {
+            !HunspellDictionary::has_flag(flags, &self.forbidden)
+                && affix_flags.iter().all(|flag| flags.contains(*flag))
+                && (!affix_flags.is_empty()
+                    || !HunspellDictionary::has_flag(flags, &self.need_affix))
+        })
+    }
+
+    #[doc = "Есть ли слово в словаре с учетом префиксов и суффиксов. Регистр не меняется"]
+    pub fn check(&self, word: &str) -> bool {
+        if let Some(flags) = self.stems.get(word) {
+            if HunspellDictionary::has_flag(flags, &self.forbidden) {
+                return false;
+            }
+        }
+        if self.accepts_root(word, &[]) {
+            return true;
+        }
+        for suffix in &self.suffixes {
+            let Some(root) = suffix.suffix_root(word) else {
+                continue;
+            };
+            if self.accepts_root(&root, &[&suffix.flag]) {
+                return true;
+            }
+            if !suffix.cross_product {
+                continue;
+            }
+            for prefix in self.prefixes.iter().filter(|prefix| prefix.cross_product) {
+                if let Some(root) = prefix.prefix_root(&root) {
+                    if self.accepts_root(&root, &[&suffix.flag, &prefix.flag]) {
+                        return true;
+                    }
+                }
+            }
+        }
+        self.prefixes.iter().any(|prefix| {
+            prefix
+                .prefix_root(word)
+                .is_some_and(|root| self.accepts_root(&root, &[&prefix.flag]))
+        })
+    }
+}
+
+#[doc = "Файлы .aff и .dic для языка: <язык>.dic или первый по алфавиту <язык>_<регион>.dic (en_US.dic для en)"]
+pub fn find_dictionary_files(spelling_dir: &str, language: &str) -> Option<(PathBuf, PathBuf)> {
+    let dir = Path::new(spelling_dir);
+    let language = language.replace('-', "_");
+    let mut candidates = vec![dir.join(format!("{}.dic", language))];
+    if let Ok(entries) = fs::read_dir(dir) {
+        let regional_prefix = format!("{}_", language);
+        let mut regional: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().is_some_and(|extension| extension == "dic")
+                    && path
+                        .file_stem()
+                        .is_some_and(|stem| stem.to_string_lossy().starts_with(&regional_prefix))
+            })
+            .collect();
+        regional.sort();
+        candidates.extend(regional);
+    }
+    candidates
+        .into_iter()
+        .find(|dic| dic.is_file() && dic.with_extension("aff").is_file())
+        .map(|dic| (dic.with_extension("aff"), dic))
+}
+
+#[doc = "Слова проекта из SPELLING_WORDS_FILE. Если файла нет, список пустой"]
+pub fn read_custom_words(dictionary_dir: &str) -> Result<HashSet<String>, StaticDictionaryErrors> {
+    let path = Path::new(dictionary_dir).join(SPELLING_WORDS_FILE);
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect())
+}
+
+#[derive(Debug, Clone)]
+#[doc = "Проверка орфографии переводов одного языка по словарю hunspell и списку слов проекта"]
+pub struct SpellChecker {
+    dictionary: HunspellDictionary,
+    custom_words: HashSet<String>,
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl SpellChecker {
+    pub fn new(dictionary: HunspellDictionary, custom_words: HashSet<String>) -> SpellChecker {
+        SpellChecker {
+            dictionary,
+            custom_words,
+        }
+    }
+
+    #[doc = "Загружает словарь hunspell для языка из spelling_dir и слова проекта из репозитория словарей"]
+    pub fn open(
+        spelling_dir: &str,
+        dictionary_dir: &str,
+        language: &str,
+    ) -> Result<SpellChecker, StaticDictionaryErrors> {
+        let Some((aff, dic)) = find_dictionary_files(spelling_dir, language) else {
+            return Err(StaticDictionaryErrors::SpellingDictionaryNotFound(format!(
+                "в {} нет файлов {}.aff и {}.dic",
+                spelling_dir,
+                language.replace('-', "_"),
+                language.replace('-', "_")
+            )));
+        };
+        Ok(SpellChecker::new(
+            HunspellDictionary::load(aff, dic)?,
+            read_custom_words(dictionary_dir)?,
+        ))
+    }
+
+    // Слово с заглавной буквы (в начале предложения) и слово из заглавных букв проверяются и в строчном виде
+    fn is_correct(&self, word: &str) -> bool {
+        let lowercase = word.to_lowercase();
+        let mut variants = vec![word.to_owned()];
+        if word.chars().next().is_some_and(char::is_uppercase) {
+            let mut chars = word.chars();
+            let first: String = chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_lowercase)
+                .collect();
+            variants.push(first + chars.as_str());
+        }
+        if !word.chars().any(char::is_lowercase) {
+            variants.push(capitalize(&lowercase));
+            variants.push(lowercase);
+        }
+        variants
+            .iter()
+            .any(|variant| self.custom_words.contains(variant) || self.dictionary.check(variant))
+    }
+
+    #[doc = "Слова текста с ошибками в порядке появления. Переменные подстановки, код, URL, сегменты <notranslate> и слова с цифрами не проверяются"]
+    pub fn misspelled(&self, text: &str) -> Vec<String> {
+        static WORD: OnceLock<Regex> = OnceLock::new();
+        let word_pattern = WORD.get_or_init(|| Regex::new(r"[\p{L}\p{M}\p{N}_'’-]+").unwrap());
+        let mut text = protect(text).text;
+        for placeholder in placeholders(&text) {
+            text = text.replace(&placeholder, " ");
+        }
+        let mut misspelled: Vec<String> = vec![];
+        for found in word_pattern.find_iter(&text) {
+            let word = found.as_str().trim_matches(['\'', '’', '-']);
+            if !word.chars().any(char::is_alphabetic)
+                || word.chars().any(|c| c.is_numeric() || c == '_')
+                || self.is_correct(word)
+            {
+                continue;
+            }
+            // Слово через дефис верно, если верна каждая его часть
+            if word.contains('-')
+                && word
+                    .split('-')
+                    .filter(|part| !part.is_empty())
+                    .all(|part| self.is_correct(part))
+            {
+                continue;
+            }
+            if !misspelled.iter().any(|known| known == word) {
+                misspelled.push(word.to_owned());
+            }
+        }
+        misspelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nFORBIDDENWORD !\n\nPFX U Y 1\nPFX U 0 un .\n\nSFX S Y 2\nSFX S 0 s [^y]\nSFX S y ies y\n";
+    const DIC: &str = "5\nand\nlock/SU\ncity/S\nhelpful/U\nirregardless/!\n";
+
+    #[test]
+    fn test_hunspell_affixes() {
+        let dictionary = HunspellDictionary::parse(AFF, DIC).unwrap();
+        for word in ["lock", "locks", "unlock", "unlocks", "cities", "unhelpful"] {
+            assert!(dictionary.check(word), "{}", word);
+        }
+        for word in ["citys", "uncity", "helpfuls", "irregardless", "Lock"] {
+            assert!(!dictionary.check(word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_misspelled_words_skip_placeholders_and_custom_words() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("en_US.aff"), AFF).unwrap();
+        fs::write(dir.path().join("en_US.dic"), DIC).unwrap();
+        fs::write(
+            dir.path().join(SPELLING_WORDS_FILE),
+            "# Названия продуктов\nAcme\n",
+        )
+        .unwrap();
+        let spelling_dir = dir.path().to_str().unwrap();
+        assert!(matches!(
+            SpellChecker::open(spelling_dir, spelling_dir, "de"),
+            Err(StaticDictionaryErrors::SpellingDictionaryNotFound(_))
+        ));
+        let checker = SpellChecker::open(spelling_dir, spelling_dir, "en").unwrap();
+        assert_eq!(
+            checker.misspelled(
+                "Unlock {{count}} Acme citys, LOCKS and `lockz` 2nd cities-locks lokc citys"
+            ),
+            vec!["citys", "lokc"]
+        );
+    }
+}
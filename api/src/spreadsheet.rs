@@ -0,0 +1,543 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::{atomic_write, find_all_translated_languages};
+use crate::language::{validate_languages, LanguageCode};
+use crate::static_translate::{
+    get_basic_language, pair_with_sources, parse_static_basic_entries, read_existing_dictionary,
+    write_translated_dictionary,
+};
+use crate::types::{DictionaryEntry, EntryStatus, Word};
+
+#[doc = "Заголовок колонки с ключами"]
+pub const TAG_COLUMN: &str = "tag";
+#[doc = "Заголовок колонки с исходными фразами"]
+pub const SOURCE_COLUMN: &str = "source";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Строка таблицы переводов: одна фраза (или форма множественного числа и вариант) на всех языках"]
+pub struct TableRow {
+    /// Ключ с суффиксами формы и варианта
+    pub tag: String,
+    /// Исходная фраза из базового словаря
+    pub source: String,
+    /// Переводы: язык -> текст
+    pub translations: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Таблица переводов для обмена с переводчиками через Excel или Google Sheets"]
+pub struct TranslationTable {
+    /// Языки в порядке колонок
+    pub languages: Vec<String>,
+    pub rows: Vec<TableRow>,
+}
+
+impl TranslationTable {
+    #[doc = "Собирает таблицу из дочерних словарей на указанных языках, или из всех словарей репозитория, если языки не переданы"]
+    pub fn from_repository(
+        dictionary_dir: &str,
+        languages: Option<Vec<String>>,
+    ) -> Result<TranslationTable, StaticDictionaryErrors> {
+        let languages = match languages {
+            Some(languages) => languages,
+            None => find_all_translated_languages(dictionary_dir)?,
+        };
+        let mut rows: BTreeMap<String, TableRow> = BTreeMap::new();
+        for language in &languages {
+            for (source, translation) in pair_with_sources(dictionary_dir, language)?
+                .into_iter()
+                .flat_map(|paired| paired.pairs)
+            {
+                let row = rows
+                    .entry(translation.suffixed_tag())
+                    .or_insert_with(|| TableRow {
+                        tag: translation.suffixed_tag(),
+                        ..Default::default()
+                    });
+                if row.source.is_empty() {
+                    row.source = source.word;
+                }
+                row.translations
+                    .insert(language.to_owned(), translation.word);
+            }
+        }
+        Ok(TranslationTable {
+            languages,
+            rows: rows.into_values().collect(),
+        })
+    }
+
+    #[doc = "Заголовки колонок: tag, source и по одной колонке на язык"]
+    pub fn header(&self) -> Vec<String> {
+        [TAG_COLUMN, SOURCE_COLUMN]
+            .iter()
+            .map(|column| column.to_string())
+            .chain(self.languages.iter().cloned())
+            .collect()
+    }
+
+    #[doc = "Ячейки строки в порядке колонок"]
+    pub fn cells(&self, row: &TableRow) -> Vec<String> {
+        [row.tag.clone(), row.source.clone()]
+            .into_iter()
+            .chain(
+                self.languages
+                    .iter()
+                    .map(|language| row.translations.get(language).cloned().unwrap_or_default()),
+            )
+            .collect()
+    }
+
+    #[doc = "Собирает таблицу из строк с заголовком. Колонки tag и source ищутся по названию, остальные считаются языками"]
+    pub fn from_records(
+        records: impl IntoIterator<Item = Vec<String>>,
+    ) -> Result<TranslationTable, StaticDictionaryErrors> {
+        let mut records = records.into_iter();
+        let header = records.next().unwrap_or_default();
+        let column = |name: &str| header.iter().position(|column| column.trim() == name);
+        let Some(tag_column) = column(TAG_COLUMN) else {
+            return Err(StaticDictionaryErrors::InvalidTable(format!(
+                "нет колонки {}",
+                TAG_COLUMN
+            )));
+        };
+        let source_column = column(SOURCE_COLUMN);
+        let language_columns: Vec<(usize, String)> = header
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != tag_column && Some(*index) != source_column)
+            .map(|(index, language)| (index, language.trim().to_owned()))
+            .filter(|(_, language)| !language.is_empty())
+            .collect();
+        let cell =
+            |record: &Vec<String>, index: usize| record.get(index).cloned().unwrap_or_default();
+        let rows = records
+            .filter(|record| !cell(record, tag_column).is_empty())
+            .map(|record| TableRow {
+                tag: cell(&record, tag_column),
+                source: source_column
+                    .map(|index| cell(&record, index))
+                    .unwrap_or_default(),
+                translations: language_columns
+                    .iter()
+                    .map(|(index, language)| (language.to_owned(), cell(&record, *index)))
+                    .collect(),
+            })
+            .collect();
+        Ok(TranslationTable {
+            languages: language_columns
+                .into_iter()
+                .map(|(_, language)| language)
+                .collect(),
+            rows,
+        })
+    }
+
+    #[doc = "Записывает таблицу в CSV"]
+    pub fn write_csv(&self, writer: impl Write) -> Result<(), StaticDictionaryErrors> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(self.header())?;
+        for row in &self.rows {
+            writer.write_record(self.cells(row))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    #[doc = "Читает таблицу из CSV"]
+    pub fn read_csv(reader: impl Read) -> Result<TranslationTable, StaticDictionaryErrors> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+        let records = reader
+            .records()
+            .map(|record| Ok(record?.iter().map(|cell| cell.to_owned()).collect()))
+            .collect::<Result<Vec<Vec<String>>, csv::Error>>()?;
+        TranslationTable::from_records(records)
+    }
+
+    #[doc = "Переносит переводы из таблицы в дочерние словари. Пустые ячейки, неизвестные ключи и заблокированные записи пропускаются. Возвращает количество измененных записей"]
+    pub fn merge_into_repository(
+        &self,
+        dictionary_dir: &str,
+    ) -> Result<usize, StaticDictionaryErrors> {
+        validate_languages(&self.languages)?;
+        let basic_language = get_basic_language(dictionary_dir)?;
+        let base_entries = parse_static_basic_entries(dictionary_dir)?;
+        let mut changed = 0;
+        for language in &self.languages {
+            let mut dictionary = read_existing_dictionary(dictionary_dir, language)?;
+            for entry in &base_entries {
+                dictionary.entry(entry.tag().to_owned()).or_insert_with(|| {
+                    DictionaryEntry::from_base(entry, &basic_language, language)
+                });
+            }
+            let words: HashMap<String, Word> = dictionary
+                .iter()
+                .flat_map(|(tag, entry)| entry.words(tag, language))
+                .map(|word| (word.suffixed_tag(), word))
+                .collect();
+            let mut language_changed = 0;
+            for row in &self.rows {
+                let Some(text) = row
+                    .translations
+                    .get(language)
+                    .filter(|text| !text.is_empty())
+                else {
+                    continue;
+                };
+                let Some(word) = words.get(&row.tag).filter(|word| &word.word != text) else {
+                    continue;
+                };
+                let entry = dictionary.get_mut(&word.tag).unwrap();
+                if entry.locked {
+                    continue;
+                }
+                entry.set_word(&Word {
+                    word: text.to_owned(),
+                    ..word.clone()
+                });
+                entry.status = EntryStatus::Translated;
+                entry.package = None;
+                language_changed += 1;
+            }
+            if language_changed > 0 {
+                write_translated_dictionary(dictionary_dir, language, &dictionary)?;
+            }
+            changed += language_changed;
+        }
+        Ok(changed)
+    }
+}
+
+#[doc = "Заголовок колонки с языком в общем листе XLSX"]
+pub const LANGUAGE_COLUMN: &str = "language";
+#[doc = "Колонки листа XLSX для бюро переводов"]
+pub const XLSX_COLUMNS: [&str; 5] = ["tag", "source", "translation", "status", "context"];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[doc = "Перевод одной фразы (или формы и варианта) на один язык вместе со статусом и контекстом"]
+pub struct TranslationRecord {
+    pub language: String,
+    /// Ключ с суффиксами формы и варианта
+    pub tag: String,
+    pub source: String,
+    pub translation: String,
+    pub status: EntryStatus,
+    pub context: Option<String>,
+}
+
+#[doc = "Собирает переводы из дочерних словарей на указанных языках, или из всех словарей репозитория, если языки не переданы"]
+pub fn translation_records(
+    dictionary_dir: &str,
+    languages: Option<Vec<String>>,
+) -> Result<Vec<TranslationRecord>, StaticDictionaryErrors> {
+    let languages = match languages {
+        Some(languages) => languages,
+        None => find_all_translated_languages(dictionary_dir)?,
+    };
+    let mut records = vec![];
+    for language in &languages {
+        for paired in pair_with_sources(dictionary_dir, language)? {
+            for (source, translation) in paired.pairs {
+                records.push(TranslationRecord {
+                    language: language.to_owned(),
+                    tag: translation.suffixed_tag(),
+                    source: source.word,
+                    translation: translation.word,
+                    status: paired.entry.status,
+                    context: paired.entry.context.clone(),
+                });
+            }
+        }
+    }
+    Ok(records)
+}
+
+fn status_name(status: EntryStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_owned))
+        .unwrap_or_default()
+}
+
+fn write_sheet(
+    worksheet: &mut rust_xlsxwriter::Worksheet,
+    records: &[&TranslationRecord],
+    with_language: bool,
+) -> Result<(), StaticDictionaryErrors> {
+    let bold = rust_xlsxwriter::Format::new().set_bold();
+    let header = with_language
+        .then_some(LANGUAGE_COLUMN)
+        .into_iter()
+        .chain(XLSX_COLUMNS);
+    for (column, name) in header.enumerate() {
+        worksheet.write_string_with_format(0, column as u16, name, &bold)?;
+        worksheet.set_column_width(column as u16, 30)?;
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    for (row, record) in records.iter().enumerate() {
+        let cells = with_language
+            .then(|| record.language.clone())
+            .into_iter()
+            .chain([
+                record.tag.clone(),
+                record.source.clone(),
+                record.translation.clone(),
+                status_name(record.status),
+                record.context.clone().unwrap_or_default(),
+            ]);
+        for (column, cell) in cells.enumerate() {
+            worksheet.write_string(row as u32 + 1, column as u16, cell)?;
+        }
+    }
+    Ok(())
+}
+
+#[doc = "Записывает переводы в XLSX: по листу на каждый язык, или один общий лист с колонкой language"]
+pub fn write_xlsx(
+    records: &[TranslationRecord],
+    combined: bool,
+    path: &str,
+) -> Result<(), StaticDictionaryErrors> {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    if combined {
+        let worksheet = workbook.add_worksheet().set_name("translations")?;
+        write_sheet(worksheet, &records.iter().collect::<Vec<_>>(), true)?;
+    } else {
+        let mut by_language: BTreeMap<&str, Vec<&TranslationRecord>> = BTreeMap::new();
+        for record in records {
+            by_language
+                .entry(&record.language)
+                .or_default()
+                .push(record);
+        }
+        for (language, records) in by_language {
+            let worksheet = workbook.add_worksheet().set_name(language)?;
+            write_sheet(worksheet, &records, false)?;
+        }
+    }
+    atomic_write(path, workbook.save_to_buffer()?)?;
+    Ok(())
+}
+
+#[doc = "Читает переводы из XLSX. Язык берется из колонки language, а если ее нет - из названия листа"]
+pub fn read_xlsx(path: &str) -> Result<Vec<TranslationRecord>, StaticDictionaryErrors> {
+    use calamine::Reader;
+
+    let mut workbook: calamine::Xlsx<_> = calamine::open_workbook(path)?;
+    let mut records = vec![];
+    for sheet in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&sheet)?;
+        let mut rows = range.rows().map(|row| {
+            row.iter()
+                .map(|cell| cell.to_string())
+                .collect::<Vec<String>>()
+        });
+        let header = rows.next().unwrap_or_default();
+        let column = |name: &str| header.iter().position(|column| column.trim() == name);
+        let (Some(tag_column), Some(translation_column)) = (column("tag"), column("translation"))
+        else {
+            return Err(StaticDictionaryErrors::InvalidTable(format!(
+                "на листе {} нет колонок tag и translation",
+                sheet
+            )));
+        };
+        let language_column = column(LANGUAGE_COLUMN);
+        let source_column = column(SOURCE_COLUMN);
+        let context_column = column("context");
+        for row in rows {
+            let cell = |index: Option<usize>| {
+                index
+                    .and_then(|index| row.get(index))
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            if cell(Some(tag_column)).is_empty() {
+                continue;
+            }
+            records.push(TranslationRecord {
+                language: match language_column {
+                    Some(_) => cell(language_column),
+                    None => sheet.clone(),
+                },
+                tag: cell(Some(tag_column)),
+                source: cell(source_column),
+                translation: cell(Some(translation_column)),
+                status: EntryStatus::default(),
+                context: Some(cell(context_column)).filter(|context| !context.is_empty()),
+            });
+        }
+    }
+    Ok(records)
+}
+
+#[doc = "Проверяет переводы из файла бюро перед импортом: языки должны быть корректными, ключи - существовать в базовом словаре, а исходные фразы - не меняться с момента выгрузки. Возвращает список проблем"]
+pub fn validate_records(
+    dictionary_dir: &str,
+    records: &[TranslationRecord],
+) -> Result<Vec<String>, StaticDictionaryErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let base_entries = parse_static_basic_entries(dictionary_dir)?;
+    let mut sources: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut problems = vec![];
+    for record in records {
+        if let Err(err) = LanguageCode::new(&record.language) {
+            problems.push(format!("{}: {}", record.tag, err));
+            continue;
+        }
+        let language_sources = sources.entry(record.language.clone()).or_insert_with(|| {
+            base_entries
+                .iter()
+                .flat_map(|entry| entry.words_for_translation(&basic_language, &record.language))
+                .map(|word| (word.suffixed_tag(), word.word))
+                .collect()
+        });
+        match language_sources.get(&record.tag) {
+            None => problems.push(format!(
+                "[{}] {}: ключ не найден в базовом словаре",
+                record.language, record.tag
+            )),
+            Some(source) if !record.source.is_empty() && source != &record.source => {
+                problems.push(format!(
+                    "[{}] {}: исходная фраза изменилась (\"{}\" -> \"{}\")",
+                    record.language, record.tag, record.source, source
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(problems)
+}
+
+impl From<&[TranslationRecord]> for TranslationTable {
+    fn from(records: &[TranslationRecord]) -> TranslationTable {
+        let mut languages: Vec<String> = vec![];
+        let mut rows: BTreeMap<&str, TableRow> = BTreeMap::new();
+        for record in records {
+            if !languages.contains(&record.language) {
+                languages.push(record.language.clone());
+            }
+            rows.entry(&record.tag)
+                .or_insert_with(|| TableRow {
+                    tag: record.tag.clone(),
+                    source: record.source.clone(),
+                    ..Default::default()
+                })
+                .translations
+                .insert(record.language.clone(), record.translation.clone());
+        }
+        TranslationTable {
+            languages,
+            rows: rows.into_values().collect(),
+        }
+    }
+}
+
+#[doc = "Проверяет файл XLSX от бюро переводов и переносит переводы в репозиторий. При найденных проблемах ничего не меняется. Возвращает количество измененных записей"]
+pub fn import_xlsx(dictionary_dir: &str, path: &str) -> Result<usize, StaticDictionaryErrors> {
+    let records = read_xlsx(path)?;
+    let problems = validate_records(dictionary_dir, &records)?;
+    if !problems.is_empty() {
+        return Err(StaticDictionaryErrors::InvalidTable(problems.join("\n")));
+    }
+    TranslationTable::from(records.as_slice()).merge_into_repository(dictionary_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::static_translate::read_translated_dictionary;
+
+    #[test]
+    fn test_csv_round_trip_merges_translator_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Привет", "Выход", {"phrase": "яблоки", "plural_forms": {"one": "{{count}} яблоко"}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Привет": "Hello", "Выход": {"value": "Exit", "status": "reviewed", "locked": true}}"#,
+        )
+        .unwrap();
+
+        let table = TranslationTable::from_repository(repo, None).unwrap();
+        let mut csv = vec![];
+        table.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("tag,source,en\n"));
+        assert!(csv.contains("Привет,Привет,Hello\n"));
+
+        let edited = "tag,source,en,de\nПривет,Привет,\"Hi, there\",Hallo\nВыход,Выход,Quit,\nяблоки_one,{{count}} яблоко,{{count}} apple,\n";
+        let table = TranslationTable::read_csv(edited.as_bytes()).unwrap();
+        assert_eq!(table.languages, vec!["en", "de"]);
+        assert_eq!(table.merge_into_repository(repo).unwrap(), 3);
+
+        let en = read_translated_dictionary(repo, "en").unwrap();
+        assert_eq!(en["Привет"].value, "Hi, there");
+        assert_eq!(en["Привет"].status, EntryStatus::Translated);
+        assert_eq!(en["Выход"].value, "Exit");
+        assert_eq!(
+            en["яблоки"].plural_forms.as_ref().unwrap()["one"],
+            "{{count}} apple"
+        );
+        assert_eq!(
+            read_translated_dictionary(repo, "de").unwrap()["Привет"].value,
+            "Hallo"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_export_is_validated_and_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Привет", {"phrase": "Пока", "context": "прощание"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Привет": "Hello", "Пока": {"value": "", "context": "прощание"}}"#,
+        )
+        .unwrap();
+        let path = dir.path().join("agency.xlsx");
+        let path = path.to_str().unwrap();
+
+        let mut records = translation_records(repo, None).unwrap();
+        write_xlsx(&records, false, path).unwrap();
+        let exported = read_xlsx(path).unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].language, "en");
+        assert_eq!(exported[0].tag, "Пока");
+        assert_eq!(exported[0].context.as_deref(), Some("прощание"));
+
+        records[0].translation = "Bye".to_owned();
+        records.push(TranslationRecord {
+            language: "en".to_owned(),
+            tag: "Неизвестно".to_owned(),
+            ..Default::default()
+        });
+        write_xlsx(&records, true, path).unwrap();
+        assert!(matches!(
+            import_xlsx(repo, path),
+            Err(StaticDictionaryErrors::InvalidTable(_))
+        ));
+
+        records.pop();
+        write_xlsx(&records, true, path).unwrap();
+        assert_eq!(import_xlsx(repo, path).unwrap(), 1);
+        assert_eq!(
+            read_translated_dictionary(repo, "en").unwrap()["Пока"].value,
+            "Bye"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_languages;
+use crate::qa::placeholders;
+use crate::static_translate::{
+    get_basic_language, parse_static_basic_entries, read_translated_dictionary,
+};
+use crate::types::Word;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Одна из самых длинных строк словаря"]
+pub struct LongestString {
+    /// Ключ с суффиксом формы множественного числа или варианта
+    pub tag: String,
+    pub text: String,
+    pub characters: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[doc = "Объем текста в словаре одного языка"]
+pub struct LanguageStatistics {
+    pub language: String,
+    /// Статистика базового словаря
+    pub base: bool,
+    /// Ключей с непустым текстом
+    pub entries: usize,
+    /// Слов без учета переменных подстановки
+    pub words: usize,
+    /// Символов вместе с пробелами
+    pub characters: usize,
+    /// Ключей, в тексте которых есть переменные подстановки
+    pub with_placeholders: usize,
+    /// Слов исходного текста в ключах, которые еще не переведены на язык. Для базового словаря 0
+    pub untranslated_source_words: usize,
+    /// Самые длинные строки по убыванию длины
+    pub longest: Vec<LongestString>,
+}
+
+#[doc = "Количество слов в тексте. Переменные подстановки ({{name}}, %s и другие) словами не считаются"]
+pub fn word_count(text: &str) -> usize {
+    let mut text = text.to_owned();
+    for placeholder in placeholders(&text) {
+        text = text.replace(&placeholder, " ");
+    }
+    text.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+// Считает статистику по словам (значениям, формам и вариантам) всех ключей языка
+fn language_statistics(
+    language: &str,
+    base: bool,
+    words: &[Word],
+    longest: usize,
+) -> LanguageStatistics {
+    let words: Vec<&Word> = words.iter().filter(|word| !word.word.is_empty()).collect();
+    let mut statistics = LanguageStatistics {
+        language: language.to_owned(),
+        base,
+        entries: words
+            .iter()
+            .map(|word| word.tag.as_str())
+            .collect::<BTreeSet<&str>>()
+            .len(),
+        words: words.iter().map(|word| word_count(&word.word)).sum(),
+        characters: words.iter().map(|word| word.word.chars().count()).sum(),
+        with_placeholders: words
+            .iter()
+            .filter(|word| !placeholders(&word.word).is_empty())
+            .map(|word| word.tag.as_str())
+            .collect::<BTreeSet<&str>>()
+            .len(),
+        ..Default::default()
+    };
+    let mut strings: Vec<LongestString> = words
+        .iter()
+        .map(|word| LongestString {
+            tag: word.suffixed_tag(),
+            text: word.word.clone(),
+            characters: word.word.chars().count(),
+        })
+        .collect();
+    strings.sort_by_key(|string| Reverse(string.characters));
+    strings.truncate(longest);
+    statistics.longest = strings;
+    statistics
+}
+
+#[doc = "Считает слова, символы и ключи с переменными подстановки для базового словаря и каждого дочернего, а также сколько слов исходного текста осталось перевести на каждый язык. longest задает, сколько самых длинных строк вернуть для каждого языка"]
+pub fn repository_statistics(
+    dictionary_dir: &str,
+    longest: usize,
+) -> Result<Vec<LanguageStatistics>, StaticDictionaryErrors> {
+    let basic_language = get_basic_language(dictionary_dir)?;
+    let base_entries = parse_static_basic_entries(dictionary_dir)?;
+    let base_words: Vec<Word> = base_entries
+        .iter()
+        .flat_map(|entry| entry.words_for_translation(&basic_language, &basic_language))
+        .collect();
+    let mut statistics = vec![language_statistics(
+        &basic_language,
+        true,
+        &base_words,
+        longest,
+    )];
+    let mut languages = find_all_translated_languages(dictionary_dir)?;
+    languages.sort();
+    for language in languages {
+        let dictionary = read_translated_dictionary(dictionary_dir, &language)?;
+        let words: Vec<Word> = dictionary
+            .iter()
+            .flat_map(|(tag, entry)| entry.words(tag, &language))
+            .collect();
+        let mut language_statistics = language_statistics(&language, false, &words, longest);
+        language_statistics.untranslated_source_words = base_entries
+            .iter()
+            .filter(|entry| {
+                dictionary
+                    .get(entry.tag())
+                    .is_none_or(|translated| !translated.is_translated())
+            })
+            .flat_map(|entry| entry.words_for_translation(&basic_language, &language))
+            .map(|word| word_count(&word.word))
+            .sum();
+        statistics.push(language_statistics);
+    }
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_statistics_count_words_and_placeholders() {
+        assert_eq!(word_count("Привет, {{name}}! У вас %d новых - писем"), 5);
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Войти в аккаунт", "Привет, {{name}}", "Корзина"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Войти в аккаунт": "Sign in", "Привет, {{name}}": "Hello, {{name}}", "Корзина": ""}"#,
+        )
+        .unwrap();
+
+        let statistics = repository_statistics(repo, 1).unwrap();
+        let ru = &statistics[0];
+        assert!(ru.base);
+        assert_eq!((ru.entries, ru.words, ru.with_placeholders), (3, 5, 1));
+        assert_eq!(ru.characters, 15 + 16 + 7);
+        assert_eq!(ru.longest.len(), 1);
+        assert_eq!(ru.longest[0].characters, 16);
+
+        let en = &statistics[1];
+        assert_eq!((en.language.as_str(), en.entries, en.words), ("en", 2, 3));
+        assert_eq!(en.untranslated_source_words, 1);
+        assert_eq!(en.longest[0].tag, "Привет, {{name}}");
+    }
+}
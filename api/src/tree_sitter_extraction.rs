@@ -0,0 +1,141 @@
+use tree_sitter::{Language, Node, Parser};
+
+use crate::errors::errors::StaticDictionaryErrors;
+
+#[doc = "Функции перевода по умолчанию, если в конфиге не указаны call_patterns"]
+pub const DEFAULT_CALL_NAMES: [&str; 1] = ["t"];
+
+#[doc = "Грамматика tree-sitter для расширения файла: JavaScript (с JSX), TypeScript или TSX"]
+pub fn grammar_for_extension(extension: &str) -> Option<Language> {
+    match extension {
+        "js" | "jsx" | "mjs" | "cjs" => Some(tree_sitter_javascript::LANGUAGE.into()),
+        "ts" | "mts" | "cts" => Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+        "tsx" => Some(tree_sitter_typescript::LANGUAGE_TSX.into()),
+        _ => None,
+    }
+}
+
+// Содержимое строки или шаблона без подстановок. Экранированные кавычки и обратная косая черта раскрываются
+fn literal_text(node: Node, source: &[u8]) -> Option<String> {
+    let mut text = String::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let part = child.utf8_text(source).ok()?;
+        match child.kind() {
+            "string_fragment" => text.push_str(part),
+            "escape_sequence" => match part {
+                "\\\"" | "\\'" | "\\`" | "\\\\" => text.push_str(&part[1..]),
+                _ => text.push_str(part),
+            },
+            // Шаблон с ${...} нельзя перевести как одну фразу
+            "template_substitution" => return None,
+            _ => {}
+        }
+    }
+    Some(text)
+}
+
+fn first_string_argument(call: Node, source: &[u8]) -> Option<String> {
+    let arguments = call.child_by_field_name("arguments")?;
+    // Тегированный шаблон: t`Привет`
+    if arguments.kind() == "template_string" {
+        return literal_text(arguments, source);
+    }
+    let mut cursor = arguments.walk();
+    let first = arguments.named_children(&mut cursor).next()?;
+    match first.kind() {
+        "string" | "template_string" => literal_text(first, source),
+        _ => None,
+    }
+}
+
+#[doc = "Находит в исходном коде первые строковые аргументы вызовов функций перевода и текст внутри JSX. Комментарии и шаблоны с подстановками пропускаются. Возвращает фразы с номерами строк (начиная с 1)"]
+pub fn extract_phrases(
+    source: &str,
+    language: &Language,
+    call_names: &[String],
+) -> Result<Vec<(String, usize)>, StaticDictionaryErrors> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .map_err(|err| StaticDictionaryErrors::InvalidConfig(err.to_string()))?;
+    let Some(tree) = parser.parse(source, None) else {
+        return Ok(vec![]);
+    };
+    let bytes = source.as_bytes();
+    let is_translation_call = |function: Node| {
+        function.utf8_text(bytes).is_ok_and(|name| {
+            let name: String = name.split_whitespace().collect();
+            match call_names.is_empty() {
+                true => DEFAULT_CALL_NAMES.contains(&name.as_str()),
+                false => call_names.contains(&name),
+            }
+        })
+    };
+
+    let mut phrases = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let line = node.start_position().row + 1;
+        match node.kind() {
+            "call_expression"
+                if node
+                    .child_by_field_name("function")
+                    .is_some_and(is_translation_call) =>
+            {
+                if let Some(phrase) = first_string_argument(node, bytes) {
+                    phrases.push((phrase, line));
+                }
+            }
+            "jsx_text" => {
+                let text = node.utf8_text(bytes).unwrap_or_default();
+                // JSX схлопывает пробелы и переносы строк в тексте
+                let phrase = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                if !phrase.is_empty() {
+                    let leading_lines = text[..text.len() - text.trim_start().len()]
+                        .matches('\n')
+                        .count();
+                    phrases.push((phrase, line + leading_lines));
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    Ok(phrases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calls_and_jsx_text_are_extracted_without_false_positives() {
+        let source = r#"
+// t("Закомментировано")
+const title = t('Оформить заказ');
+const greeting = t(`Привет, ${name}`);
+i18n.t("Скажи \"да\"", { count: list.filter((x) => x.ok).length });
+export const Page = () => (
+  <div title={t("Подсказка")}>
+    Добро пожаловать
+    <b>{count}</b>
+  </div>
+);
+"#;
+        let names = vec!["t".to_owned(), "i18n.t".to_owned()];
+        let phrases =
+            extract_phrases(source, &grammar_for_extension("tsx").unwrap(), &names).unwrap();
+        assert_eq!(
+            phrases,
+            vec![
+                ("Оформить заказ".to_owned(), 3),
+                ("Скажи \"да\"".to_owned(), 5),
+                ("Подсказка".to_owned(), 7),
+                ("Добро пожаловать".to_owned(), 8),
+            ]
+        );
+    }
+}
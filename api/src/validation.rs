@@ -0,0 +1,261 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+
+use serde::de::{DeserializeOwned, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::dictionary_format::{parse_relaxed_json, DictionaryFormat};
+use crate::errors::errors::StaticDictionaryErrors;
+use crate::file_system::find_all_translated_dictionaries;
+use crate::parser::get_basic_dictionary;
+use crate::types::{BaseEntry, TranslatedDictionary};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "snake_case")]
+#[doc = "Виды нарушений целостности репозитория словарей"]
+pub enum ValidationIssueKind {
+    /// Ключ базового словаря отсутствует в дочернем словаре
+    MissingKey,
+    /// Ключ дочернего словаря отсутствует в базовом словаре
+    OrphanKey,
+    /// Перевод или одна из его форм пустые
+    EmptyValue,
+    /// Ключ встречается в словаре несколько раз
+    DuplicateKey,
+    /// Файл словаря не удалось разобрать
+    MalformedFile(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[doc = "Нарушение целостности, найденное в файле словаря"]
+pub struct ValidationIssue {
+    /// Имя файла словаря
+    pub file: String,
+    /// Ключ записи, None для ошибок всего файла
+    pub tag: Option<String>,
+    pub kind: ValidationIssueKind,
+}
+
+impl Display for ValidationIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssueKind::MissingKey => write!(f, "ключа нет в дочернем словаре"),
+            ValidationIssueKind::OrphanKey => write!(f, "ключа нет в базовом словаре"),
+            ValidationIssueKind::EmptyValue => write!(f, "пустой перевод"),
+            ValidationIssueKind::DuplicateKey => write!(f, "ключ повторяется"),
+            ValidationIssueKind::MalformedFile(error) => {
+                write!(f, "файл не удалось разобрать: {}", error)
+            }
+        }
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tag {
+            Some(tag) => write!(f, "[{}] {}: {}", self.file, tag, self.kind),
+            None => write!(f, "[{}] {}", self.file, self.kind),
+        }
+    }
+}
+
+// Ключи верхнего уровня дочернего словаря в порядке записи, вместе с повторами, которые теряются при разборе в map
+struct TopLevelKeys(Vec<String>);
+
+impl<'de> Deserialize<'de> for TopLevelKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TopLevelKeys, D::Error> {
+        struct KeysVisitor;
+
+        impl<'de> Visitor<'de> for KeysVisitor {
+            type Value = TopLevelKeys;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("объект с переводами")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TopLevelKeys, A::Error> {
+                let mut keys = vec![];
+                while let Some(key) = map.next_key::<String>()? {
+                    map.next_value::<IgnoredAny>()?;
+                    keys.push(key);
+                }
+                Ok(TopLevelKeys(keys))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TopLevelKeys, A::Error> {
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(TopLevelKeys(vec![]))
+            }
+        }
+
+        deserializer.deserialize_any(KeysVisitor)
+    }
+}
+
+// Разбирает файл словаря и возвращает текст ошибки с местом, где разбор не удался
+fn parse_file<T: DeserializeOwned>(format: DictionaryFormat, content: &str) -> Result<T, String> {
+    match format {
+        DictionaryFormat::Json => parse_relaxed_json(content).map_err(|err| err.to_string()),
+        DictionaryFormat::Yaml => serde_yaml::from_str(content).map_err(|err| err.to_string()),
+    }
+}
+
+// Ключи, которые встречаются больше одного раза, по одному разу в порядке первого повтора
+fn duplicates<'a>(keys: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut repeated: Vec<String> = vec![];
+    for key in keys {
+        if !seen.insert(key) && !repeated.iter().any(|known| known == key) {
+            repeated.push(key.to_owned());
+        }
+    }
+    repeated
+}
+
+#[doc = "Проверяет целостность репозитория словарей: файлы, которые не удается разобрать, повторяющиеся ключи, ключи базового словаря без записи в дочернем, записи дочерних словарей без ключа в базовом и пустые переводы"]
+pub fn validate_repository(
+    dictionary_dir: &str,
+) -> Result<Vec<ValidationIssue>, StaticDictionaryErrors> {
+    let mut issues = vec![];
+    let issue = |file: &str, tag: Option<&str>, kind| ValidationIssue {
+        file: file.to_owned(),
+        tag: tag.map(str::to_owned),
+        kind,
+    };
+
+    let basic_dictionary = get_basic_dictionary(dictionary_dir)?;
+    let content = fs::read_to_string(Path::new(dictionary_dir).join(&basic_dictionary))?;
+    let base_tags: Option<BTreeSet<String>> = match parse_file::<Vec<BaseEntry>>(
+        DictionaryFormat::from_path(&basic_dictionary),
+        &content,
+    ) {
+        Ok(entries) => {
+            for tag in duplicates(entries.iter().map(BaseEntry::tag)) {
+                issues.push(issue(
+                    &basic_dictionary,
+                    Some(&tag),
+                    ValidationIssueKind::DuplicateKey,
+                ));
+            }
+            Some(entries.iter().map(|entry| entry.tag().to_owned()).collect())
+        }
+        Err(error) => {
+            issues.push(issue(
+                &basic_dictionary,
+                None,
+                ValidationIssueKind::MalformedFile(error),
+            ));
+            None
+        }
+    };
+
+    let mut translated_dictionaries = find_all_translated_dictionaries(dictionary_dir)?;
+    translated_dictionaries.sort();
+    for filename in translated_dictionaries {
+        let content = fs::read_to_string(Path::new(dictionary_dir).join(&filename))?;
+        let format = DictionaryFormat::from_path(&filename);
+        let dictionary = match parse_file::<TranslatedDictionary>(format, &content) {
+            Ok(dictionary) => dictionary,
+            Err(error) => {
+                issues.push(issue(
+                    &filename,
+                    None,
+                    ValidationIssueKind::MalformedFile(error),
+                ));
+                continue;
+            }
+        };
+        if let Ok(TopLevelKeys(keys)) = parse_file::<TopLevelKeys>(format, &content) {
+            for tag in duplicates(keys.iter().map(String::as_str)) {
+                issues.push(issue(
+                    &filename,
+                    Some(&tag),
+                    ValidationIssueKind::DuplicateKey,
+                ));
+            }
+        }
+        if let Some(base_tags) = &base_tags {
+            for tag in base_tags
+                .iter()
+                .filter(|tag| !dictionary.contains_key(*tag))
+            {
+                issues.push(issue(&filename, Some(tag), ValidationIssueKind::MissingKey));
+            }
+        }
+        for (tag, entry) in &dictionary {
+            if base_tags
+                .as_ref()
+                .is_some_and(|base_tags| !base_tags.contains(tag))
+            {
+                issues.push(issue(&filename, Some(tag), ValidationIssueKind::OrphanKey));
+            } else if !entry.is_translated() {
+                issues.push(issue(&filename, Some(tag), ValidationIssueKind::EmptyValue));
+            }
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_issues_are_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("dictionary-ru.base.json"),
+            r#"["Вход", "Выход", {"phrase": "Выход", "key": "Вход"}, "Отмена"]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dictionary-en.json"),
+            r#"{"Вход": "Sign in", "Выход": "", "Вход": "Log in", "Лишний": "Extra"}"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("dictionary-de.yaml"), "Вход: [unclosed").unwrap();
+
+        let issues: Vec<(String, Option<String>, ValidationIssueKind)> = validate_repository(repo)
+            .unwrap()
+            .into_iter()
+            .map(|issue| (issue.file, issue.tag, issue.kind))
+            .collect();
+        let found = |file: &str, tag: &str, kind: ValidationIssueKind| {
+            issues.contains(&(file.to_owned(), Some(tag.to_owned()), kind))
+        };
+        assert!(found(
+            "dictionary-ru.base.json",
+            "Вход",
+            ValidationIssueKind::DuplicateKey
+        ));
+        assert!(found(
+            "dictionary-en.json",
+            "Вход",
+            ValidationIssueKind::DuplicateKey
+        ));
+        assert!(found(
+            "dictionary-en.json",
+            "Отмена",
+            ValidationIssueKind::MissingKey
+        ));
+        assert!(found(
+            "dictionary-en.json",
+            "Лишний",
+            ValidationIssueKind::OrphanKey
+        ));
+        assert!(found(
+            "dictionary-en.json",
+            "Выход",
+            ValidationIssueKind::EmptyValue
+        ));
+        assert!(issues
+            .iter()
+            .any(|(file, tag, kind)| file == "dictionary-de.yaml"
+                && tag.is_none()
+                && matches!(kind, ValidationIssueKind::MalformedFile(_))));
+        assert_eq!(issues.len(), 6);
+    }
+}
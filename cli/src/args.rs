@@ -1,6 +1,14 @@
 pub mod cli_args {
+    use api::build_system::properties::PropertiesEncoding;
+    use api::build_system::vue_i18n::ModuleLanguage;
+    use api::build_system::xliff::XliffVersion;
+    use api::build_system::BuildOptions;
+    use api::dictionary_format::DictionaryFormat;
+    use api::language::LanguageCode;
+    use api::merge::MergeStrategy;
     use api::types::ApiArgs;
     use clap::{Args, Parser, Subcommand};
+    use std::str::FromStr;
 
     #[derive(Parser, Debug)]
     #[clap(version = "0.4 Experimental", about = "Утилита для управления репозиторием JSON-словарей и переводом в ручном или автоматическом режиме", long_about = None)]
@@ -8,9 +16,55 @@ pub mod cli_args {
     pub struct TranslatorCli {
         #[clap(subcommand)]
         pub subcommand: CliSubcommands,
+        /// Репозиторий словарей из dictionary_repo конфига. Без него translate, sync и build, которым вместо директории репозитория передан путь к конфигу, выполняются для всех репозиториев
+        #[clap(long, global = true)]
+        pub repo: Option<String>,
+        /// Конфиг проекта (config.dms.json, .yaml или .toml). Без него используется конфиг из текущей директории. Из конфига берутся репозиторий словарей, директория сборки, языки и настройки переводчика, если они не указаны в командной строке
+        #[clap(long, global = true, env = "DMS_CONFIG")]
+        pub config: Option<String>,
+        /// Формат вывода: text, json или github. С json каждая команда выводит результат одним объектом JSON для скриптов и CI, а интерактивные и работающие до прерывания команды завершаются с ошибкой, с github check и validate выводят проблемы аннотациями GitHub Actions
+        #[clap(id = "output_format", long = "output", global = true, default_value = "text")]
+        pub output: OutputFormat,
+        /// Подробный журнал работы в stderr: -v - основные шаги, -vv - отладка, -vvv - все события. RUST_LOG задает фильтр вручную
+        #[clap(short, long, global = true, action = clap::ArgAction::Count)]
+        pub verbose: u8,
+        /// Выводить в журнал только ошибки
+        #[clap(short, long, global = true, conflicts_with = "verbose")]
+        pub quiet: bool,
+        /// Пробный запуск: показать, какие файлы команда создала бы, изменила или удалила и какие запросы отправила бы переводчику, ничего не меняя
+        #[clap(long, global = true)]
+        pub dry_run: bool,
+        /// Не спрашивать подтверждение перед перезаписью файлов словарей и удалением записей (автоперевод, генерация пустых словарей, --prune) и перезаписывать существующий конфиг в init config. Нужен для запуска в скриптах и CI
+        #[clap(short, long, global = true, visible_alias = "force")]
+        pub yes: bool,
     }
 
-    #[derive(Debug, Subcommand)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[doc = "Формат вывода результатов команд"]
+    pub enum OutputFormat {
+        #[default]
+        Text,
+        Json,
+        Github,
+    }
+
+    impl FromStr for OutputFormat {
+        type Err = String;
+
+        fn from_str(format: &str) -> Result<OutputFormat, String> {
+            match format.to_lowercase().as_str() {
+                "text" => Ok(OutputFormat::Text),
+                "json" => Ok(OutputFormat::Json),
+                "github" => Ok(OutputFormat::Github),
+                _ => Err(format!(
+                    "Неизвестный формат вывода {}, доступны text, json и github",
+                    format
+                )),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
     pub enum CliSubcommands {
         #[clap(subcommand)]
         /// Перевести текст в статических словарях
@@ -22,9 +76,461 @@ pub mod cli_args {
         Build(FrameworkType),
         /// Просканировать файлы в проекте для добавления фраз в базовый словарь
         Scan(ScanningArguments),
+        #[clap(subcommand)]
+        /// Составить отчет об изменениях в словарях
+        Report(ReportType),
+        /// Обновить дочерние словари репозитория до формата v2
+        Migrate(MigrateArgs),
+        /// Проверить машинные переводы: принять, исправить или пропустить каждый
+        Review(ReviewArgs),
+        /// Проверить качество переводов: переменные, пробелы, длину и непереведенные фразы
+        Check(CheckArgs),
+        /// Показать, сколько ключей переведено на каждый язык
+        Status(StatusArgs),
+        /// Проверить целостность репозитория: недостающие, лишние, пустые и повторяющиеся ключи
+        Validate(ValidateArgs),
+        /// Сравнить два файла словарей, словарь с его версией в ревизии git или дочерний словарь с базовым
+        Diff(DiffArgs),
+        /// Добавить новые ключи базового словаря во все дочерние словари, не трогая существующие переводы
+        Sync(SyncArgs),
+        #[clap(subcommand)]
+        /// Операции над отдельными ключами во всем репозитории
+        Key(KeyCommand),
+        /// Показать ключи, которые не используются в исходном коде, и ключи из кода, которых нет в базовом словаре, по данным последнего сканирования
+        Unused(UnusedArgs),
+        /// Найти записи по тегу, исходной фразе или переводу на любой язык
+        Search(SearchArgs),
+        /// Перенести в репозиторий базовый словарь и переводы из другого репозитория
+        Merge(MergeArgs),
+        /// Привести файлы словарей к единому виду: сортировка ключей, отступы, перевод строки в конце и Unicode NFC
+        Fmt(FmtArgs),
+        /// Показать объем текста по языкам: слова, символы, ключи с переменными и самые длинные строки
+        Stats(StatsArgs),
+        /// Найти разные ключи с одинаковыми исходными фразами или переводами, которые можно объединить
+        Duplicates(DuplicatesArgs),
+        /// Показать журнал изменений ключей: когда, кем и какой командой изменено значение
+        Log(LogArgs),
+        /// Сохранить неизменяемый снимок всех словарей с манифестом для воспроизводимой сборки (build --release)
+        Release(ReleaseArgs),
+        #[clap(subcommand)]
+        /// Выгрузить непереведенные записи в пакет для внешнего переводчика и загрузить выполненный пакет
+        Package(PackageCommand),
+        /// Восстановить словари из резервной копии, которая создается перед автопереводом, sync --prune, импортом, удалением ключей и слиянием
+        Rollback(RollbackArgs),
+        /// Заблокировать записи, чтобы автоперевод и массовые операции их не меняли
+        Lock(LockArgs),
+        /// Снять блокировку с записей
+        Unlock(LockArgs),
+        #[clap(subcommand)]
+        /// Выгрузить переводы в таблицу для переводчиков
+        Export(ExportType),
+        #[clap(subcommand)]
+        /// Загрузить переводы из таблицы обратно в репозиторий
+        Import(ImportType),
+        /// Проверить конфиг, репозитории словарей, доступность переводчика и права на запись в директорию сборки
+        Doctor(DoctorArgs),
+        /// Следить за проектом: сканировать измененные файлы, переводить новые фразы автопереводчиком и пересобирать словари i18next
+        Watch(WatchArgs),
+        /// Запустить локальный редактор переводов и REST API для чтения и изменения переводов репозитория словарей
+        Serve(ServeArgs),
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[doc = "Автопереводчик для dms watch и dms serve. Хостинг берется из translator в конфиге"]
+    pub enum AutoTranslator {
+        #[default]
+        Libretranslate,
+        Deepl,
+    }
+
+    impl FromStr for AutoTranslator {
+        type Err = String;
+
+        fn from_str(translator: &str) -> Result<AutoTranslator, String> {
+            match translator.to_lowercase().as_str() {
+                "libretranslate" => Ok(AutoTranslator::Libretranslate),
+                "deepl" => Ok(AutoTranslator::Deepl),
+                _ => Err(format!(
+                    "Неизвестный переводчик {}, доступны libretranslate и deepl",
+                    translator
+                )),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
+    #[doc = "Форматы экспорта переводов"]
+    pub enum ExportType {
+        /// Таблица CSV: tag, source и по колонке на каждый язык
+        Csv(ExportArgs),
+        /// Книга XLSX для бюро переводов: tag, source, translation, status и context
+        Xlsx(XlsxExportArgs),
+        /// Файлы XLIFF по одному на каждый язык (output - директория)
+        Xliff(XliffExportArgs),
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
+    #[doc = "Форматы импорта переводов"]
+    pub enum ImportType {
+        /// Таблица CSV, выгруженная командой export csv
+        Csv(ImportArgs),
+        /// Книга XLSX, выгруженная командой export xlsx. Перед импортом проверяются языки, ключи и исходные фразы
+        Xlsx(ImportArgs),
+        /// Переведенный файл XLIFF 1.2 или 2.0
+        Xliff(ImportArgs),
+        /// Каталог gettext PO. Язык берется из заголовка Language или из названия файла
+        Po(ImportArgs),
+        /// Файлы существующего проекта на i18next (<язык>/<namespace>.json) или ngx-translate и vue-i18n (<язык>.json). Создает новый репозиторий
+        I18next(FrameworkImportArgs),
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды export"]
+    pub struct ExportArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Путь к итоговому файлу (для xliff - директория)
+        pub output: String,
+        /// Языки для выгрузки. Если не указаны, выгружаются все дочерние словари
+        #[clap(long = "language", short = 'l')]
+        pub languages: Vec<LanguageCode>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды export xlsx"]
+    pub struct XlsxExportArgs {
+        #[clap(flatten)]
+        pub export: ExportArgs,
+        /// Записать все языки на один лист с колонкой language вместо листа на каждый язык
+        #[clap(long)]
+        pub combined: bool,
     }
 
-    #[derive(Debug, Subcommand)]
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды export xliff"]
+    pub struct XliffExportArgs {
+        #[clap(flatten)]
+        pub export: ExportArgs,
+        /// Версия XLIFF: 1.2 или 2.0
+        #[clap(long = "xliff-version", default_value = "1.2")]
+        pub version: XliffVersion,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды import"]
+    pub struct ImportArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Путь к таблице с переводами
+        pub input: String,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для импорта файлов фреймворка в новый репозиторий"]
+    pub struct FrameworkImportArgs {
+        /// Директория, в которой будет создан репозиторий словарей
+        pub dictionary_path: String,
+        /// Директория с файлами переводов проекта
+        pub input: String,
+        /// Язык, файлы которого станут базовым словарем
+        #[clap(long)]
+        pub basic_language: LanguageCode,
+        /// Формат файлов словарей: json или yaml
+        #[clap(long, default_value = "json")]
+        pub format: DictionaryFormat,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команд lock и unlock"]
+    pub struct LockArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Теги записей (с флагом --pattern - регулярные выражения)
+        #[clap(required = true)]
+        pub tags: Vec<String>,
+        /// Языки словарей. Если не указаны, меняются все дочерние словари
+        #[clap(long = "language", short = 'l')]
+        pub languages: Vec<LanguageCode>,
+        /// Считать теги регулярными выражениями
+        #[clap(long)]
+        pub pattern: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды check"]
+    pub struct CheckArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Языки для проверки. Если не указаны, проверяются все дочерние словари
+        pub languages: Vec<LanguageCode>,
+        /// Максимальное отношение длины перевода к длине исходной фразы
+        #[clap(long, default_value_t = api::qa::DEFAULT_MAX_LENGTH_RATIO)]
+        pub max_length_ratio: f64,
+        /// Проверить орфографию переводов по словарям hunspell. Слова из spelling-words.txt в репозитории считаются верными
+        #[clap(long)]
+        pub spelling: bool,
+        /// Директория со словарями hunspell <язык>.aff и <язык>.dic
+        #[clap(long, default_value = api::spelling::DEFAULT_SPELLING_DIR)]
+        pub spelling_dir: String,
+        /// Файл глоссария с утвержденными переводами терминов. По умолчанию glossary.json в репозитории, если он есть
+        #[clap(long)]
+        pub glossary: Option<String>,
+        /// Режим CI: проверить также непереведенные ключи и устаревшие переводы, вывести краткую сводку по языкам и завершиться с кодом 1, если что-то найдено
+        #[clap(long)]
+        pub ci: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды status"]
+    pub struct StatusArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Языки для отчета. Если не указаны, учитываются все дочерние словари
+        pub languages: Vec<LanguageCode>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды validate"]
+    pub struct ValidateArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды diff"]
+    pub struct DiffArgs {
+        /// Файл словаря. При сравнении двух файлов - старая версия
+        pub before: String,
+        /// Новая версия словаря. Если не указана, файл сравнивается со своей версией в ревизии --rev, а без нее - с базовым словарем из той же директории
+        pub after: Option<String>,
+        /// Ревизия git, из которой берется старая версия файла
+        #[clap(long)]
+        pub rev: Option<String>,
+        /// Не выделять изменения цветом
+        #[clap(long)]
+        pub no_color: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды sync"]
+    pub struct SyncArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Удалить из дочерних словарей ключи, которых больше нет в базовом. Заблокированные записи сохраняются
+        #[clap(long)]
+        pub prune: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды review"]
+    pub struct ReviewArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Язык словаря для проверки
+        pub language: LanguageCode,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды migrate"]
+    pub struct MigrateArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды unused"]
+    pub struct UnusedArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды search"]
+    pub struct SearchArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Искомая подстрока или, с --regex, регулярное выражение
+        pub query: String,
+        /// Считать запрос регулярным выражением
+        #[clap(long)]
+        pub regex: bool,
+        /// Не различать регистр букв
+        #[clap(short, long)]
+        pub ignore_case: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды merge"]
+    pub struct MergeArgs {
+        /// Директория с репозиторием словарей, в который переносятся записи
+        pub dictionary_path: String,
+        /// Директория с присоединяемым репозиторием словарей
+        pub other: String,
+        /// Разрешение конфликтов: ours (оставить свои записи), theirs (взять чужие) или interactive (спрашивать)
+        #[clap(long, default_value = "ours")]
+        pub strategy: MergeStrategy,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды fmt"]
+    pub struct FmtArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Только проверить оформление, не изменяя файлы. Если какой-то файл нужно переформатировать, команда завершается с ненулевым кодом
+        #[clap(long)]
+        pub check: bool,
+        /// Сортировать по тегам и записи базового словаря. Порядок базового словаря используется при сборке, поэтому по умолчанию он сохраняется
+        #[clap(long)]
+        pub sort_base: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды stats"]
+    pub struct StatsArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Сколько самых длинных строк показать для каждого языка
+        #[clap(long, default_value_t = 3)]
+        pub longest: usize,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды duplicates"]
+    pub struct DuplicatesArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Языки, переводы на которые сравниваются. Если не указаны, проверяются все дочерние словари
+        pub languages: Vec<LanguageCode>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды log"]
+    pub struct LogArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Тег записи. Если не указан, выводится весь журнал
+        pub tag: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды release"]
+    pub struct ReleaseArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Версия релиза (1.4.0, 2024-05)
+        #[clap(required_unless_present = "list")]
+        pub version: Option<String>,
+        /// Показать созданные релизы
+        #[clap(long)]
+        pub list: bool,
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
+    #[doc = "Операции с пакетами переводов"]
+    pub enum PackageCommand {
+        /// Сохранить непереведенные и устаревшие записи языка вместе с исходными фразами и контекстом в файл пакета
+        Export(PackageExportArgs),
+        /// Проверить выполненный пакет и перенести переводы в дочерний словарь
+        Import(PackageImportArgs),
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды package export"]
+    pub struct PackageExportArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Язык перевода
+        pub language: LanguageCode,
+        /// Путь к файлу пакета или директория для него. По умолчанию <идентификатор пакета>.json в текущей директории
+        #[clap(short = 'o', long)]
+        pub file: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды package import"]
+    pub struct PackageImportArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Путь к выполненному пакету
+        pub input: String,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды rollback"]
+    pub struct RollbackArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Имя резервной копии. По умолчанию восстанавливается последняя
+        pub backup: Option<String>,
+        /// Показать резервные копии вместо восстановления
+        #[clap(long)]
+        pub list: bool,
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
+    #[doc = "Операции над ключами"]
+    pub enum KeyCommand {
+        /// Переименовать ключ в базовом словаре, всех дочерних словарях и местах использования
+        Rename(KeyRenameArgs),
+        /// Удалить ключи из базового словаря, всех дочерних словарей и мест использования
+        Remove(KeyRemoveArgs),
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды key rename"]
+    pub struct KeyRenameArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Текущий тег записи
+        pub old: String,
+        /// Новый тег записи
+        pub new: String,
+        /// Заменить тег и в вызовах t("...") на строках исходных файлов, где он был найден при сканировании
+        #[clap(long)]
+        pub rewrite_sources: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды key remove"]
+    pub struct KeyRemoveArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Теги или glob-шаблоны тегов, например auth.*
+        #[clap(required = true)]
+        pub tags: Vec<String>,
+        /// Показать, какие записи будут удалены, ничего не изменяя. Включается глобальным --dry-run
+        #[clap(skip)]
+        pub dry_run: bool,
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
+    #[doc = "Варианты отчетов"]
+    pub enum ReportType {
+        /// Markdown-отчет об изменениях словарей для описания pull request
+        Pr(PrReportArgs),
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды report pr"]
+    pub struct PrReportArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Исходное состояние: ревизия git или директория со снимком словарей
+        pub from: String,
+        /// Итоговое состояние: ревизия git или директория со снимком словарей (по умолчанию - текущие файлы)
+        pub to: Option<String>,
+        /// Репозиторий GitHub (owner/repo), в pull request которого нужно опубликовать отчет
+        #[clap(long, requires = "pr")]
+        pub github_repo: Option<String>,
+        /// Номер pull request для публикации отчета
+        #[clap(long, requires = "github_repo")]
+        pub pr: Option<u64>,
+        /// Токен GitHub API
+        #[clap(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+        pub github_token: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
     #[doc = "Варианты режима перевода"]
     pub enum TranslateType {
         /// Создать пустые словари на основе базового для ручного перевода
@@ -35,27 +541,128 @@ pub mod cli_args {
         Auto(ApiVariants),
     }
 
-    #[derive(Debug, Subcommand)]
+    #[derive(Debug, Clone, Subcommand)]
     #[doc = "Варианты фреймворков для сборки словарей"]
     pub enum FrameworkType {
         /// Сборка в словари, совместимые с фреймворком i18next
         I18next(BuildArgs),
+        /// Сборка шаблона messages.pot и каталогов <язык>.po для gettext
+        Gettext(GettextBuildArgs),
+        /// Сборка файлов app_<язык>.arb для Flutter
+        Arb(BuildArgs),
+        /// Сборка файлов <язык>.ts для Qt Linguist и lrelease
+        Qt(CatalogBuildArgs),
+        /// Сборка файлов messages_<язык>.properties для Spring и других JVM-фреймворков
+        Properties(PropertiesBuildArgs),
+        /// Сборка словарей <язык>.yml для Rails и ruby-i18n
+        Rails(BuildArgs),
+        /// Сборка словарей для vue-i18n: JSON на каждый язык или один модуль messages.js/messages.ts
+        VueI18n(BuildArgs),
+        /// Сборка словарей <язык>.json для ngx-translate
+        NgxTranslate(NgxTranslateBuildArgs),
+        /// Сборка файлов messages.<язык>.xlf для встроенной интернационализации Angular
+        AngularXlf(CatalogBuildArgs),
+        /// Сборка файлов _locales/<язык>/messages.json для браузерных расширений
+        Webextension(BuildArgs),
+        /// Сборка языковых файлов <язык>/messages.php для Laravel
+        Laravel(BuildArgs),
+        /// Генерация translations.d.ts с ключами базового словаря и их переменными
+        Types(TypesBuildArgs),
+        /// Генерация модуля translations.rs для встраивания переводов в приложение на Rust
+        Rust(CatalogBuildArgs),
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды build types"]
+    pub struct TypesBuildArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Директория для translations.d.ts
+        pub output_directory: String,
+        /// Шаблон пути файла с типами с переменными {output_dir} и {namespace}
+        #[clap(long)]
+        pub output_template: Option<String>,
+        /// Дополнительно расширить CustomTypeOptions i18next типом resources
+        #[clap(long)]
+        pub i18next: bool,
+        /// Собрать словари из релиза с этой версией вместо текущего состояния репозитория
+        #[clap(long)]
+        pub release: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды build ngx-translate"]
+    pub struct NgxTranslateBuildArgs {
+        #[clap(flatten)]
+        pub build: BuildArgs,
+        /// Развернуть ключи с точками (checkout.payment.title) во вложенные объекты
+        #[clap(long)]
+        pub nested: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды build properties"]
+    pub struct PropertiesBuildArgs {
+        #[clap(flatten)]
+        pub build: BuildArgs,
+        /// Кодировка: iso-8859-1 (символы вне ASCII экранируются как \uXXXX) или utf-8
+        #[clap(long, default_value = "iso-8859-1")]
+        pub encoding: PropertiesEncoding,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для сборки каталогов, которые не используют запасные локали из конфига (qt, angular-xlf, rust)"]
+    pub struct CatalogBuildArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Директория для итоговых файлов
+        pub output_directory: String,
+        /// Языки файлов. Если не указаны, собираются все дочерние словари
+        pub languages: Option<Vec<LanguageCode>>,
+        /// Шаблон пути итоговых файлов с переменными {output_dir}, {lang} и {namespace}. В модуле rust, общем для всех языков, {lang} недоступна
+        #[clap(long)]
+        pub output_template: Option<String>,
+        /// Собрать словари из релиза с этой версией вместо текущего состояния репозитория
+        #[clap(long)]
+        pub release: Option<String>,
     }
 
-    #[derive(Debug, Args)]
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды build gettext"]
+    pub struct GettextBuildArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Директория для файлов POT и PO
+        pub output_directory: String,
+        /// Языки каталогов PO. Если не указаны, собираются все дочерние словари
+        pub languages: Option<Vec<LanguageCode>>,
+        /// Шаблон пути каталогов PO с переменными {output_dir}, {lang} и {namespace} (домен messages). Шаблон POT всегда записывается в директорию для файлов
+        #[clap(long)]
+        pub output_template: Option<String>,
+        /// Скомпилировать каталоги в бинарные файлы .mo (без msgfmt)
+        #[clap(long)]
+        pub mo: bool,
+        /// Собрать словари из релиза с этой версией вместо текущего состояния репозитория
+        #[clap(long)]
+        pub release: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Args)]
     #[doc = "Аргументы для команды translate manual"]
     pub struct ManualTranslationArgs {
         /// Репозиторий со словарями
         pub dictionary_path: String,
         /// Языки для перевода
-        pub languages: Vec<String>,
+        pub languages: Vec<LanguageCode>,
     }
 
-    #[derive(Subcommand, Debug)]
+    #[derive(Subcommand, Debug, Clone)]
     #[doc = "Варианты API для автоперевода"]
     pub enum ApiVariants {
         /// Перевод с использованием LibreTranslate API
         Libretranslate(LibreTranslateArgs),
+        /// Перевод с использованием DeepL API (учитывает контекст фраз)
+        Deepl(DeepLArgs),
     }
 
     #[derive(Debug, Args, Clone)]
@@ -66,21 +673,134 @@ pub mod cli_args {
         /// Хостинг LibreTranslate
         pub host: String,
         /// Языки для перевода
-        pub languages: Vec<String>,
+        pub languages: Vec<LanguageCode>,
+        /// Записать переводы как предложения (suggestion), не меняя значений. Предложения утверждаются в dms review
+        #[clap(long)]
+        pub suggest: bool,
     }
 
-    #[derive(Debug, Args)]
+    #[derive(Debug, Args, Clone)]
+    #[doc = "Аргументы, передаваемые в DeepL API"]
+    pub struct DeepLArgs {
+        /// Директория с репозиторием словарей
+        pub dictionaries_path: String,
+        /// Ключ DeepL API
+        #[clap(long, env = "DEEPL_API_KEY")]
+        pub api_key: String,
+        /// Хостинг DeepL API (для платного тарифа - https://api.deepl.com)
+        #[clap(long, default_value = "https://api-free.deepl.com")]
+        pub host: String,
+        /// Языки для перевода
+        pub languages: Vec<LanguageCode>,
+        /// Записать переводы как предложения (suggestion), не меняя значений. Предложения утверждаются в dms review
+        #[clap(long)]
+        pub suggest: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     #[doc = "Аргументы для команды init"]
     pub struct InitializeArguments {
+        #[clap(subcommand)]
+        pub command: Option<InitCommand>,
         /// Язык, который будет использоваться в базовом словаре
-        pub basic_language: String,
+        #[clap(required = true)]
+        pub basic_language: Option<LanguageCode>,
         /// Директория, где будет инициализирован репозиторий
         pub directory: Option<String>,
+        /// Формат файлов словарей: json или yaml
+        #[clap(long, default_value = "json")]
+        pub format: DictionaryFormat,
+    }
+
+    #[derive(Debug, Clone, Subcommand)]
+    #[doc = "Подкоманды init"]
+    pub enum InitCommand {
+        /// Создать стартовый конфиг проекта, отвечая на вопросы о директориях, расширениях файлов и автопереводчике
+        Config(InitConfigArgs),
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды init config"]
+    pub struct InitConfigArgs {
+        /// Путь к создаваемому конфигу
+        #[clap(default_value = "config.dms.json")]
+        pub path: String,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды doctor"]
+    pub struct DoctorArgs {
+        /// Конфиг проекта. По умолчанию --config или конфиг из текущей директории
+        pub config_path: Option<String>,
+        /// Не проверять доступность хостингов переводчиков
+        #[clap(long)]
+        pub offline: bool,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды watch"]
+    pub struct WatchArgs {
+        /// Путь до конфигурационного файла
+        pub config_path: Option<String>,
+        /// Сколько миллисекунд ждать после последнего изменения файла, прежде чем запускать сканирование
+        #[clap(long, default_value_t = 300)]
+        pub debounce: u64,
+        /// Сканировать при запуске все файлы, не пропуская те, что не изменились с прошлого сканирования
+        #[clap(long)]
+        pub no_cache: bool,
+        /// Не переводить новые фразы автопереводчиком
+        #[clap(long)]
+        pub no_translate: bool,
+        /// Не пересобирать словари i18next
+        #[clap(long)]
+        pub no_build: bool,
+        /// Автопереводчик для новых фраз: libretranslate или deepl. Хостинг берется из translator в конфиге
+        #[clap(long, default_value = "libretranslate")]
+        pub translator: AutoTranslator,
+        /// Ключ DeepL API
+        #[clap(long, env = "DEEPL_API_KEY")]
+        pub api_key: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Args)]
+    #[doc = "Аргументы для команды serve"]
+    pub struct ServeArgs {
+        /// Директория с репозиторием словарей
+        pub dictionary_path: String,
+        /// Директория, в которую POST /api/build собирает словари i18next
+        pub output_directory: Option<String>,
+        /// Адрес, на котором сервер принимает запросы
+        #[clap(long, default_value = "127.0.0.1")]
+        pub host: String,
+        /// Порт сервера
+        #[clap(long, default_value_t = 7878)]
+        pub port: u16,
+        /// Путь до конфигурационного файла с настройками переводчика и сборки
+        #[clap(long)]
+        pub config_path: Option<String>,
+        /// Отдавать собранные словари i18next по /locales/{lng}/{ns}.json для i18next-http-backend и пересобирать их при изменении репозитория
+        #[clap(long)]
+        pub locales: bool,
+        /// Автопереводчик для POST /api/translate: libretranslate или deepl. Хостинг берется из translator в конфиге
+        #[clap(long, default_value = "libretranslate")]
+        pub translator: AutoTranslator,
+        /// Ключ DeepL API
+        #[clap(long, env = "DEEPL_API_KEY")]
+        pub api_key: Option<String>,
     }
 
     impl Into<ApiArgs> for LibreTranslateArgs {
         fn into(self) -> ApiArgs {
             ApiArgs::new(None, self.host)
+                .with_suggestions(self.suggest)
+        }
+    }
+
+    impl From<DeepLArgs> for ApiArgs {
+        fn from(args: DeepLArgs) -> ApiArgs {
+            ApiArgs::new(Some(args.api_key), args.host)
+                .with_suggestions(args.suggest)
         }
     }
 
@@ -92,7 +812,32 @@ pub mod cli_args {
         /// Директория с итоговыми словарями
         pub output_directory: String,
         /// По умолчанию, утилита будет собирать все словари, если нужно обновить какой-то конкретный, то можно указать их список при сборке
-        pub languages: Option<Vec<String>>,
+        pub languages: Option<Vec<LanguageCode>>,
+        /// Шаблон пути итоговых файлов с переменными {output_dir}, {lang} и {namespace}
+        #[clap(long)]
+        pub output_template: Option<String>,
+        /// Путь к config.dms.json или config.dms.yaml с цепочками запасных локалей (fallbacks)
+        #[clap(long)]
+        pub config_path: Option<String>,
+        /// Собрать все языки в один модуль messages.js или messages.ts (js, ts). Сейчас поддерживается для vue-i18n
+        #[clap(long)]
+        pub module: Option<ModuleLanguage>,
+        /// Записывать итоговые JSON в одну строку без отступов
+        #[clap(long)]
+        pub minify: bool,
+        /// Собрать словари из релиза с этой версией вместо текущего состояния репозитория
+        #[clap(long)]
+        pub release: Option<String>,
+    }
+
+    impl From<&BuildArgs> for BuildOptions {
+        fn from(args: &BuildArgs) -> BuildOptions {
+            BuildOptions {
+                output_template: args.output_template.clone(),
+                minify: args.minify,
+                ..Default::default()
+            }
+        }
     }
 
     #[derive(Debug, Clone, Args)]
@@ -100,5 +845,78 @@ pub mod cli_args {
     pub struct ScanningArguments {
         /// Путь до конфигурационного файла
         pub config_path: Option<String>,
+        /// Следить за изменениями файлов и добавлять новые фразы в базовый словарь сразу после сохранения
+        #[clap(long)]
+        pub watch: bool,
+        /// Сканировать все файлы, не пропуская те, что не изменились с прошлого сканирования
+        #[clap(long)]
+        pub no_cache: bool,
+        /// После сканирования показать записи, фраз которых больше нет в исходном коде, и удалить их после подтверждения
+        #[clap(long, conflicts_with = "watch")]
+        pub prune: bool,
+        /// Показать, какие фразы были бы добавлены в какие базовые словари, ничего не записывая. Включается глобальным --dry-run
+        #[clap(skip)]
+        pub dry_run: bool,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Command, CommandFactory, Parser};
+
+    use super::cli_args::TranslatorCli;
+
+    // Конечные подкоманды с путем от корня: translate auto deepl, build i18next...
+    fn leaf_commands(command: &Command, path: Vec<String>, leaves: &mut Vec<(Vec<String>, Command)>) {
+        if !command.has_subcommands() {
+            leaves.push((path, command.clone()));
+            return;
+        }
+        // help добавляет clap, и она завершается выводом справки
+        for subcommand in command.get_subcommands().filter(|subcommand| subcommand.get_name() != "help") {
+            let mut subcommand_path = path.clone();
+            subcommand_path.push(subcommand.get_name().to_owned());
+            leaf_commands(subcommand, subcommand_path, leaves);
+        }
+    }
+
+    // Значение обязательного аргумента: первое из допустимых, код языка или число
+    fn positional_value(arg: &clap::Arg, numeric: bool) -> String {
+        match arg.get_possible_values().first() {
+            Some(value) => value.get_name().to_owned(),
+            None if numeric => "1".to_owned(),
+            None => "en".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_every_subcommand_parses_with_global_options() {
+        let mut command = TranslatorCli::command();
+        command.build();
+        command.clone().debug_assert();
+        let mut leaves = vec![];
+        leaf_commands(&command, vec![], &mut leaves);
+        assert!(leaves.len() > 40);
+        for (path, leaf) in leaves {
+            let parse = |numeric: bool| {
+                let mut argv = vec!["dms".to_owned(), "--output".to_owned(), "json".to_owned()];
+                argv.extend(path.iter().cloned());
+                for arg in leaf.get_arguments().filter(|arg| arg.is_required_set()) {
+                    if let Some(long) = arg.get_long() {
+                        argv.push(format!("--{}={}", long, positional_value(arg, numeric)));
+                    }
+                }
+                argv.extend(
+                    leaf.get_positionals()
+                        .map(|arg| positional_value(arg, numeric)),
+                );
+                TranslatorCli::try_parse_from(argv)
+            };
+            let parsed = parse(false).or_else(|_| parse(true));
+            match parsed {
+                Ok(cli) => assert_eq!(cli.output, super::cli_args::OutputFormat::Json),
+                Err(err) => panic!("dms {} не разбирается: {}", path.join(" "), err),
+            }
+        }
     }
 }
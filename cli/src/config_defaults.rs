@@ -0,0 +1,197 @@
+use std::env;
+use std::path::Path;
+
+use api::file_system::{read_config_file, CONFIG_FILE_NAMES};
+use api::parser::types::ConfigFileParameters;
+use clap::{Command, CommandFactory};
+
+use crate::args::cli_args::TranslatorCli;
+
+// Команды, которым вместо репозитория можно передать конфиг, чтобы выполнить их для всех репозиториев из dictionary_repo
+const MULTI_REPOSITORY_COMMANDS: [&str; 3] = ["translate", "sync", "build"];
+
+#[doc = "Конфиг проекта, из которого берутся значения аргументов по умолчанию"]
+struct ProjectConfig {
+    path: String,
+    config: ConfigFileParameters,
+}
+
+// Значение глобального параметра до разбора аргументов: --name value или --name=value
+fn option_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    args[..end].iter().enumerate().find_map(|(index, arg)| {
+        match arg.strip_prefix(prefix.as_str()) {
+            Some(value) => Some(value.to_owned()),
+            None if arg == name => args.get(index + 1).cloned(),
+            None => None,
+        }
+    })
+}
+
+#[doc = "Путь к конфигу из --config или DMS_CONFIG"]
+pub fn explicit_config(args: &[String]) -> Option<String> {
+    option_value(args, "--config").or_else(|| env::var("DMS_CONFIG").ok())
+}
+
+#[doc = "Формат вывода из --output до разбора аргументов, чтобы сообщить об ошибке конфига в нужном формате"]
+pub fn output_value(args: &[String]) -> Option<String> {
+    option_value(args, "--output")
+}
+
+// Конфиг из --config (ошибка чтения возвращается) или первый из CONFIG_FILE_NAMES в текущей директории (ошибка чтения пропускается)
+fn load_config(args: &[String]) -> Result<Option<ProjectConfig>, String> {
+    if let Some(path) = explicit_config(args) {
+        return match read_config_file(&path) {
+            Ok(config) => Ok(Some(ProjectConfig { path, config })),
+            Err(err) => Err(format!("Не удалось прочитать конфиг {}: {}", path, err)),
+        };
+    }
+    let Some(path) = CONFIG_FILE_NAMES
+        .iter()
+        .find(|name| Path::new(name).is_file())
+    else {
+        return Ok(None);
+    };
+    Ok(read_config_file(path).ok().map(|config| ProjectConfig {
+        path: path.to_string(),
+        config,
+    }))
+}
+
+// Сколько элементов командной строки занимает параметр: флаг - один, параметр со значением без = - два
+fn option_width(command: &Command, token: &str) -> usize {
+    let arg = match token.strip_prefix("--") {
+        Some(long) if long.contains('=') => return 1,
+        Some(long) => command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long)),
+        None if token.chars().count() == 2 => token.chars().last().and_then(|short| {
+            command
+                .get_arguments()
+                .find(|arg| arg.get_short() == Some(short))
+        }),
+        None => None,
+    };
+    match arg {
+        Some(arg) if arg.get_action().takes_values() => 2,
+        _ => 1,
+    }
+}
+
+// Значение позиционного аргумента из конфига
+fn positional_default(
+    id: &str,
+    leaf: &Command,
+    top: &str,
+    project: &ProjectConfig,
+) -> Option<String> {
+    let config = &project.config;
+    let value = match id {
+        "dictionary_path" | "dictionaries_path" if MULTI_REPOSITORY_COMMANDS.contains(&top) => {
+            project.path.clone()
+        }
+        "dictionary_path" | "dictionaries_path" => config.dictionary_repo.primary().to_owned(),
+        "output_directory" => config.output_dir.clone(),
+        "host" if leaf.get_name() == "libretranslate" => {
+            config.translator.libretranslate_host.clone()?
+        }
+        "config_path" => project.path.clone(),
+        _ => return None,
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+#[doc = "Дополняет командную строку значениями из конфига проекта (--config, DMS_CONFIG или config.dms.* в текущей директории). Если первый позиционный аргумент команды не существующий путь, перед ним подставляются репозиторий словарей (для translate, sync и build - сам конфиг), директория сборки output_dir и хостинг LibreTranslate. Если языки не переданы, подставляются languages из конфига. Хостинг DeepL, порог уверенности и --config-path сборки подставляются, если не указаны"]
+pub fn with_config_defaults(mut args: Vec<String>) -> Result<Vec<String>, String> {
+    let mut command = TranslatorCli::command();
+    command.build();
+    let mut leaf = &command;
+    let mut top = String::new();
+    let mut position = 1;
+    while position < args.len() && leaf.has_subcommands() {
+        let token = &args[position];
+        if token == "--" {
+            break;
+        }
+        if token.starts_with('-') {
+            position += option_width(leaf, token);
+            continue;
+        }
+        let Some(subcommand) = leaf.find_subcommand(token) else {
+            break;
+        };
+        if top.is_empty() {
+            top = subcommand.get_name().to_owned();
+        }
+        leaf = subcommand;
+        position += 1;
+    }
+    if leaf.has_subcommands() || position > args.len() {
+        return Ok(args);
+    }
+    let Some(project) = load_config(&args)? else {
+        return Ok(args);
+    };
+
+    let mut user_positionals = vec![];
+    let mut index = position;
+    while index < args.len() {
+        let token = &args[index];
+        if token == "--" {
+            user_positionals.extend(args[index + 1..].iter().cloned());
+            break;
+        }
+        if token.starts_with('-') && token.len() > 1 {
+            index += option_width(leaf, token);
+        } else {
+            user_positionals.push(token.clone());
+            index += 1;
+        }
+    }
+
+    let positionals: Vec<&str> = leaf
+        .get_positionals()
+        .map(|arg| arg.get_id().as_str())
+        .collect();
+    let mut inserted = vec![];
+    if user_positionals
+        .first()
+        .is_none_or(|first| !Path::new(first).exists())
+    {
+        for id in &positionals {
+            match positional_default(id, leaf, &top, &project) {
+                Some(value) => inserted.push(value),
+                None => break,
+            }
+        }
+    }
+    let filled = inserted.len() + user_positionals.len();
+    let languages = positionals.iter().position(|id| *id == "languages");
+    if languages == Some(filled) && !project.config.languages.is_empty() {
+        args.extend(project.config.languages.iter().cloned());
+    }
+
+    let has_option = |long: &str| {
+        let prefix = format!("--{}=", long);
+        args[position..]
+            .iter()
+            .any(|arg| *arg == format!("--{}", long) || arg.starts_with(&prefix))
+    };
+    let accepts = |long: &str| leaf.get_arguments().any(|arg| arg.get_long() == Some(long));
+    let translator = &project.config.translator;
+    let mut options = vec![];
+    if leaf.get_name() == "deepl" && !has_option("host") {
+        if let Some(host) = &translator.deepl_host {
+            options.push(format!("--host={}", host));
+        }
+    }
+    if accepts("config-path") && !has_option("config-path") {
+        options.push(format!("--config-path={}", project.path));
+    }
+    args.splice(position..position, options.into_iter().chain(inserted));
+    Ok(args)
+}
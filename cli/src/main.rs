@@ -1,24 +1,251 @@
 #![allow(unused_variables)]
 
-use std::error::Error;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use api::build_system::laravel::build_for_laravel;
+use api::build_system::properties::build_for_properties;
+use api::build_system::qt::build_for_qt;
+use api::build_system::rails::build_for_rails;
+use api::build_system::rust_codegen::build_rust_module;
+use api::build_system::typescript::build_type_definitions;
+use api::build_system::vue_i18n::build_for_vue_i18n;
+use api::build_system::webextension::build_for_webextension;
 use api::build_system::i18next_integration::build_for_i18next;
-use api::file_system::init_new_dictionary_system;
-use api::parser::scan_files_for_phrases;
-use api::static_translate::autotranslate_from_basic_dictionary;
+use api::build_system::angular::{build_for_angular_xlf, build_for_ngx_translate};
+use api::build_system::arb::build_for_arb;
+use api::build_system::gettext::{build_for_gettext, import_po};
+use api::build_system::i18next_import::import_i18next;
+use api::build_system::xliff::{export_xliff, import_xliff};
+use api::build_system::{BuildOptions, BuildReport};
+use api::file_system::{init_new_dictionary_system, write_starter_config, StarterConfig};
+use api::file_system::migrate_repository;
+use api::file_system::{parse_config, CONFIG_FILE_NAMES};
+use api::file_system::resolve_repositories;
+use api::file_system::{atomic_write, find_all_translated_languages};
+use api::parser::{
+    find_unused_entries, get_basic_dictionary, preview_scan_files, scan_files_for_phrases_with_progress,
+    watch_files_for_phrases, watch_scan_batches, ScanPreview, ScanReport,
+};
+use api::qa::{check_repository, ci_report, CiReport, QaOptions};
+use api::report::{
+    post_github_comment, read_dictionary_values, DictionaryDiff, DictionaryReport,
+    DictionarySnapshot,
+};
+use api::source_locations::{
+    locations_for, read_source_locations, usage_report, UsageReport, LOCATIONS_FILE,
+};
+use api::spreadsheet::{import_xlsx, translation_records, write_xlsx, TranslationTable};
+use api::annotations::{
+    ci_annotations, qa_annotations, validation_annotations, Annotation, AnnotationLevel,
+};
+use api::backup::{create_backup, list_backups, rollback};
+use api::release::{create_release, list_releases, release_directory, ReleaseManifest};
+use api::package::{export_package, import_package, TranslationPackage};
+use api::repository_lock::RepositoryLock;
+use api::formatting::format_repository;
+use api::merge::{merge_repositories, MergeChoice, MergeConflict, MergeReport, MergeStrategy};
+use api::statistics::{repository_statistics, LanguageStatistics};
+use api::duplicates::{find_duplicates, DuplicateGroup};
+use api::history::{read_history, record_changes, HistoryRecord, RepositoryValues};
+use api::search::{search_entries, search_pattern, SearchMatch};
+use api::review::{apply_review_decisions, pending_reviews, ReviewDecision};
+use api::progress::{NoProgress, Progress, BUILD_STAGE};
+use api::doctor::{diagnose, Diagnostic, DiagnosticStatus};
+use api::dry_run::{PlannedChange, PlannedWrite, Sandbox};
+use api::dictionary_format::translated_dictionary_path;
+use api::static_translate::{
+    autotranslate_from_basic_dictionary, autotranslate_plan, autotranslate_untranslated,
+    autotranslate_with_progress,
+};
 use api::static_translate::generate_empty_dictionaries_from_static_basic;
-use api::types::TranslatorApis;
+use api::static_translate::set_entries_locked;
+use api::static_translate::{remove_entries, remove_matching_entries, rename_entry};
+use api::static_translate::{status, RepositoryStatus};
+use api::static_translate::{sync_dictionaries, SyncReport};
+use api::types::{ApiArgs, TranslatorApis};
+use api::validation::validate_repository;
 use clap::Parser;
+use regex::Regex;
 
 mod args;
+mod config_defaults;
+mod progress;
+mod serve;
 use crate::CliSubcommands::*;
 use args::cli_args::FrameworkType;
 use args::cli_args::*;
+use progress::BarProgress;
+
+// Хотя бы одна команда завершилась ошибкой, и dms вернет код 1
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+// Передан --yes: файлы словарей перезаписываются и записи удаляются без подтверждения
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+// Передан --output json: ошибки команд тоже выводятся объектом JSON
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+// Печатает сообщение об ошибке команды и запоминает, что dms нужно завершить с кодом 1
+macro_rules! fail {
+    ($($arg:tt)*) => {{
+        match JSON_OUTPUT.load(Ordering::Relaxed) {
+            true => print_json_error(&format!($($arg)*)),
+            false => println!($($arg)*),
+        }
+        FAILED.store(true, Ordering::Relaxed);
+    }};
+}
 
 #[tokio::main]
-async fn main() -> Result<(), reqwest::Error> {
-    let args = TranslatorCli::parse();
-    match args.subcommand {
+async fn main() -> Result<ExitCode, reqwest::Error> {
+    let args: Vec<String> = std::env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let args = match config_defaults::with_config_defaults(args.clone()) {
+        Ok(args) => TranslatorCli::parse_from(args),
+        Err(err) => {
+            let output = config_defaults::output_value(&args)
+                .and_then(|output| output.parse().ok())
+                .unwrap_or_default();
+            exit_with_error(output, &format!("Ошибка: {}", err))
+        }
+    };
+    init_logging(args.verbose, args.quiet);
+    let output = args.output;
+    let dry_run = args.dry_run;
+    ASSUME_YES.store(args.yes, Ordering::Relaxed);
+    JSON_OUTPUT.store(output == OutputFormat::Json, Ordering::Relaxed);
+    // Индикаторы хода мешали бы разбору JSON, поэтому показываются только в текстовом выводе
+    let progress: Arc<dyn Progress> =
+        Arc::new(BarProgress::new(output == OutputFormat::Text && !args.quiet));
+    let subcommands = match for_each_repository(args.subcommand, args.repo.as_deref()) {
+        Ok(subcommands) => subcommands,
+        Err(err) => exit_with_error(output, &format!("Ошибка: {}", err)),
+    };
+    let several = subcommands.len() > 1;
+    for (repository, mut subcommand) in subcommands {
+        if several {
+            eprintln!("Репозиторий словарей {}:", repository);
+        }
+        if let Err(err) = use_release(&mut subcommand) {
+            exit_with_error(output, &format!("Ошибка: {}", err));
+        }
+        if output == OutputFormat::Json {
+            if let Some(reason) = json_unsupported(&subcommand) {
+                exit_with_error(output, &format!("Ошибка: {}", reason));
+            }
+        }
+        if dry_run {
+            if let Some(flag) = dry_run_flag(&mut subcommand) {
+                *flag = true;
+            }
+        }
+        run(subcommand, output, progress.clone(), dry_run).await?;
+    }
+    match FAILED.load(Ordering::Relaxed) {
+        true => Ok(ExitCode::FAILURE),
+        false => Ok(ExitCode::SUCCESS),
+    }
+}
+
+// Журнал пишется в stderr, чтобы не смешиваться с результатом команды. Уровень -v и --quiet относится к dms и api, остальные библиотеки пишут только предупреждения
+fn init_logging(verbose: u8, quiet: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => "error",
+        (false, 0) => "warn",
+        (false, 1) => "info",
+        (false, 2) => "debug",
+        (false, _) => "trace",
+    };
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(format!("warn,api={0},dms={0}", level)));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_target(false)
+        .without_time()
+        .init();
+}
+
+#[doc = "Выполняет команду для одного репозитория словарей"]
+async fn run(
+    subcommand: CliSubcommands,
+    output: OutputFormat,
+    progress: Arc<dyn Progress>,
+    dry_run: bool,
+) -> Result<(), reqwest::Error> {
+    tracing::debug!("Команда {:?}", subcommand);
+    // Пробный запуск не блокирует репозиторий, не создает резервную копию и не пишет журнал истории
+    if dry_run {
+        return run_dry(subcommand, output, progress).await;
+    }
+    if let Some((question, files)) = confirmation_target(&subcommand) {
+        if !confirm(&question, &files, output) {
+            return Ok(());
+        }
+    }
+    let target = lock_target(&subcommand);
+    let repository_lock = match &target {
+        Some((dictionary_path, operation)) => {
+            match RepositoryLock::acquire(dictionary_path, operation) {
+                Ok(lock) => Some(lock),
+                Err(err) => exit_with_error(output, &format!("Ошибка: {}", err)),
+            }
+        }
+        None => None,
+    };
+    if let Some((dictionary_path, operation)) = backup_target(&subcommand) {
+        match create_backup(dictionary_path, operation) {
+            Ok(backup) => eprintln!("Создана резервная копия {}", backup.name),
+            Err(err) => {
+                drop(repository_lock);
+                exit_with_error(
+                    output,
+                    &format!("Не удалось создать резервную копию, операция отменена: {}", err),
+                );
+            }
+        }
+    }
+    // Значения до команды, чтобы записать изменения в журнал истории
+    let history = target.and_then(|(dictionary_path, operation)| {
+        let before = RepositoryValues::read(&dictionary_path).ok()?;
+        Some((dictionary_path, operation, before))
+    });
+    let result = execute(subcommand, output, progress).await;
+    if let Some((dictionary_path, operation, before)) = history {
+        if let Err(err) = record_changes(&dictionary_path, operation, &before) {
+            eprintln!("Не удалось записать изменения в журнал истории: {}", err);
+        }
+    }
+    drop(repository_lock);
+    result
+}
+
+#[doc = "Выполняет команду без блокировки репозитория и резервной копии"]
+async fn execute(
+    subcommand: CliSubcommands,
+    output: OutputFormat,
+    progress: Arc<dyn Progress>,
+) -> Result<(), reqwest::Error> {
+    let json = output == OutputFormat::Json;
+    match subcommand {
+        Translate(translate_type) if json => {
+            match run_translate(translate_type).await {
+                Ok(languages) => print_json(&serde_json::json!({
+                    "success": true,
+                    "languages": languages,
+                })),
+                Err(err) => print_json_error(&err.to_string()),
+            }
+        }
         Translate(translate_type) => {
             match translate_type {
                 TranslateType::Manual(arguments) => {
@@ -28,7 +255,7 @@ async fn main() -> Result<(), reqwest::Error> {
                     );
                     let generate_result = generate_empty_dictionaries_from_static_basic(
                         &arguments.dictionary_path,
-                        arguments.languages,
+                        arguments.languages.into_iter().map(String::from).collect(),
                     );
                     match generate_result {
                     Ok(()) => {
@@ -37,11 +264,32 @@ async fn main() -> Result<(), reqwest::Error> {
                     Err(err) => {
                         match err {api::errors::errors::StaticDictionaryErrors::BasicDictionaryNotFound=>{println!("Ошибка: Не удалось найти базовый словарь!")}
                         api::errors::errors::StaticDictionaryErrors::JSONParsingError(_)=>{println!("Ошибка: Не удалось спарсить JSON файл словаря!")},
+                        api::errors::errors::StaticDictionaryErrors::YAMLParsingError(_)=>{println!("Ошибка: Не удалось спарсить YAML файл словаря!")},
+                        api::errors::errors::StaticDictionaryErrors::TOMLParsingError(_)=>{println!("Ошибка: Не удалось спарсить TOML файл конфига!")},
                         api::errors::errors::StaticDictionaryErrors::APIError(_)=>{println!("Ошибка: Ошибка при обращении к API!")},
                         api::errors::errors::StaticDictionaryErrors::IOError(_)=>{println!("Ошибка: Не удалось создать файлы!")},
                         api::errors::errors::StaticDictionaryErrors::AsyncError(_) => todo!(),
-                        api::errors::errors::StaticDictionaryErrors::RegexError(_) => todo!()
+                        api::errors::errors::StaticDictionaryErrors::RegexError(_) => todo!(),
+                        api::errors::errors::StaticDictionaryErrors::UnknownLanguage(_)
+                        | api::errors::errors::StaticDictionaryErrors::InvalidLocale(_)
+                        | api::errors::errors::StaticDictionaryErrors::CSVError(_)
+                        | api::errors::errors::StaticDictionaryErrors::InvalidTable(_)
+                        | api::errors::errors::StaticDictionaryErrors::XlsxWriteError(_)
+                        | api::errors::errors::StaticDictionaryErrors::XlsxReadError(_)
+                        | api::errors::errors::StaticDictionaryErrors::GlobError(_)
+                        | api::errors::errors::StaticDictionaryErrors::WatchError(_)
+                        | api::errors::errors::StaticDictionaryErrors::InvalidConfig(_)
+                        | api::errors::errors::StaticDictionaryErrors::EntryNotFound(_)
+                        | api::errors::errors::StaticDictionaryErrors::MissingKey(..)
+                        | api::errors::errors::StaticDictionaryErrors::DuplicateTag(_)
+                        | api::errors::errors::StaticDictionaryErrors::IncompatibleRepositories(_)
+                        | api::errors::errors::StaticDictionaryErrors::BackupNotFound(_)
+                        | api::errors::errors::StaticDictionaryErrors::RepositoryLocked(_)
+                        | api::errors::errors::StaticDictionaryErrors::InvalidPackage(_)
+                        | api::errors::errors::StaticDictionaryErrors::SpellingDictionaryNotFound(_)
+                        | api::errors::errors::StaticDictionaryErrors::InvalidRelease(_) => println!("Ошибка: {}", err),
                     }
+                    FAILED.store(true, Ordering::Relaxed);
                     }
                 }
                 }
@@ -50,18 +298,36 @@ async fn main() -> Result<(), reqwest::Error> {
                     match api {
                         ApiVariants::Libretranslate(args) => {
                             let args_clone = args.clone();
-                            let result = autotranslate_from_basic_dictionary(
+                            let result = autotranslate_with_progress(
                                 &args.dictionaries_path,
-                                args.languages,
+                                args.languages.into_iter().map(String::from).collect(),
                                 TranslatorApis::LibreTranslate,
                                 args_clone.into(),
+                                progress.clone(),
                             )
                             .await;
                             match result {
                                 Ok(_) => println!("Словари переведены успешно"),
                                 // TODO: Заменить на корректную обработку ошибки
                                 Err(err) => {
-                                    println!("{:?}", err)
+                                    fail!("{:?}", err)
+                                }
+                            }
+                        }
+                        ApiVariants::Deepl(args) => {
+                            let args_clone = args.clone();
+                            let result = autotranslate_with_progress(
+                                &args.dictionaries_path,
+                                args.languages.into_iter().map(String::from).collect(),
+                                TranslatorApis::DeepL,
+                                args_clone.into(),
+                                progress.clone(),
+                            )
+                            .await;
+                            match result {
+                                Ok(_) => println!("Словари переведены успешно"),
+                                Err(err) => {
+                                    fail!("{:?}", err)
                                 }
                             }
                         }
@@ -70,49 +336,2327 @@ async fn main() -> Result<(), reqwest::Error> {
             }
         }
 
-        Init(args) => match init_new_dictionary_system(args.directory, args.basic_language) {
+        Init(InitializeArguments {
+            command: Some(InitCommand::Config(args)),
+            ..
+        }) if Path::new(&args.path).exists() && !ASSUME_YES.load(Ordering::Relaxed) => {
+            println!("Конфиг {} уже существует, для перезаписи используйте --yes", args.path)
+        }
+        Init(InitializeArguments {
+            command: Some(InitCommand::Config(args)),
+            ..
+        }) => match run_init_config(&args) {
+            Ok(starter) => {
+                println!("Конфиг {} создан", args.path);
+                if !Path::new(&starter.dictionary_repo).exists() {
+                    println!(
+                        "Репозиторий словарей {} еще не создан, его создает команда dms init <язык>",
+                        starter.dictionary_repo
+                    );
+                }
+            }
+            Err(err) => fail!("Не удалось создать конфиг: {}", err),
+        },
+        Init(args) => match init_new_dictionary_system(
+            args.directory.clone(),
+            args.basic_language.map(String::from).unwrap_or_default(),
+            args.format,
+        ) {
+            Ok(_) if json => print_json(&serde_json::json!({
+                "success": true,
+                "directory": args.directory.unwrap_or_else(|| ".".to_owned()),
+            })),
             Ok(_) => {
                 println!("Новый репозиторий словарей создан успешно");
             }
-            Err(error) => match error {
+            Err(error) if json => print_json_error(&format!("Ошибка: {}", error)),
+            Err(error) => {
+                FAILED.store(true, Ordering::Relaxed);
+                match error {
                 api::errors::errors::StaticDictionaryErrors::BasicDictionaryNotFound => {}
                 api::errors::errors::StaticDictionaryErrors::JSONParsingError(_) => {}
+                api::errors::errors::StaticDictionaryErrors::YAMLParsingError(_) => {}
+                api::errors::errors::StaticDictionaryErrors::TOMLParsingError(_) => {}
                 api::errors::errors::StaticDictionaryErrors::APIError(_) => {}
                 api::errors::errors::StaticDictionaryErrors::IOError(_) => {
                     println!("Произошла ошибка при инициализации нового репозитория словарей. Возможно, у вас уже создан репозиторий в директории, где вы пытаетесь его создать")
                 }
                 api::errors::errors::StaticDictionaryErrors::AsyncError(_) => todo!(),
                 api::errors::errors::StaticDictionaryErrors::RegexError(_) => todo!(),
-            },
+                api::errors::errors::StaticDictionaryErrors::UnknownLanguage(_)
+                | api::errors::errors::StaticDictionaryErrors::InvalidLocale(_)
+                | api::errors::errors::StaticDictionaryErrors::CSVError(_)
+                | api::errors::errors::StaticDictionaryErrors::InvalidTable(_)
+                | api::errors::errors::StaticDictionaryErrors::XlsxWriteError(_)
+                | api::errors::errors::StaticDictionaryErrors::XlsxReadError(_)
+                | api::errors::errors::StaticDictionaryErrors::GlobError(_)
+                | api::errors::errors::StaticDictionaryErrors::WatchError(_)
+                | api::errors::errors::StaticDictionaryErrors::InvalidConfig(_)
+                | api::errors::errors::StaticDictionaryErrors::EntryNotFound(_)
+                | api::errors::errors::StaticDictionaryErrors::MissingKey(..)
+                | api::errors::errors::StaticDictionaryErrors::DuplicateTag(_)
+                | api::errors::errors::StaticDictionaryErrors::IncompatibleRepositories(_)
+                | api::errors::errors::StaticDictionaryErrors::BackupNotFound(_)
+                | api::errors::errors::StaticDictionaryErrors::RepositoryLocked(_)
+                | api::errors::errors::StaticDictionaryErrors::InvalidPackage(_)
+                | api::errors::errors::StaticDictionaryErrors::SpellingDictionaryNotFound(_)
+                | api::errors::errors::StaticDictionaryErrors::InvalidRelease(_) => {
+                    println!("Ошибка: {}", error)
+                }
+                }
+            }
         },
 
-        Build(framework) => match framework {
-            FrameworkType::I18next(args) => {
-                let result = build_for_i18next(
+        Build(framework) => match build_with_progress(framework, progress.as_ref()) {
+            Ok(built) if output == OutputFormat::Json => print_json(&serde_json::json!({
+                "success": true,
+                "files": built.files,
+                "inherited": built.inherited,
+            })),
+            Ok(built) => print_build_output(&built),
+            Err(error) if output == OutputFormat::Json => print_json_error(&error),
+            Err(error) => fail!("{}", error),
+        },
+        Scan(args) if args.dry_run => match preview_scan_files(args.config_path) {
+            Ok(preview) if json => match serde_json::to_string_pretty(&preview) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(preview) => print_scan_preview(&preview),
+            Err(err) => fail!("Произошла ошибка при сканировании файлов: {}", err),
+        },
+        Scan(args) if args.watch => {
+            println!("Отслеживание изменений файлов, для выхода нажмите Ctrl+C");
+            let result = watch_files_for_phrases(args.config_path, !args.no_cache, |file, result| match result {
+                Ok(scanned) => println!(
+                    "{}: найдено фраз {}, новых {}",
+                    file.display(),
+                    scanned.found(),
+                    scanned.added.len()
+                ),
+                Err(err) => println!("{}: ошибка при сканировании: {}", file.display(), err),
+            });
+            if let Err(err) = result {
+                fail!("Произошла ошибка при сканировании файлов: {}", err);
+            }
+        }
+        Scan(args) => {
+            let result = scan_files_for_phrases_with_progress(
+                args.config_path.clone(),
+                !args.no_cache,
+                progress.as_ref(),
+            );
+            match result {
+                // Удаленные записи дополняют отчет, чтобы вывести один объект JSON
+                Ok(report) if json => {
+                    let mut value = serde_json::json!(report);
+                    if args.prune {
+                        match run_prune(args.config_path, output) {
+                            Ok(removed) => value["pruned"] = removed.into(),
+                            Err(err) => {
+                                fail!("Произошла ошибка при удалении записей: {}", err);
+                                return Ok(());
+                            }
+                        }
+                    }
+                    print_json(&value);
+                    return Ok(());
+                }
+                Ok(report) => print_scan_report(&report),
+                Err(err) => {
+                    fail!("Произошла ошибка при сканировании файлов: {}", err);
+                    if json {
+                        return Ok(());
+                    }
+                }
+            }
+            if args.prune {
+                match run_prune(args.config_path, output) {
+                    Ok(0) => println!("Неиспользуемые записи не удалены"),
+                    Ok(removed) => println!("Удалено записей: {}", removed),
+                    Err(err) => fail!("Произошла ошибка при удалении записей: {}", err),
+                }
+            }
+        }
+        Migrate(args) => match migrate_repository(&args.dictionary_path) {
+            Ok(migrated) if json => print_json(&serde_json::json!({
+                "success": true,
+                "migrated": migrated,
+            })),
+            Ok(migrated) => {
+                for dictionary in &migrated {
+                    println!("Обновлен словарь {}", dictionary);
+                }
+                println!("Миграция завершена, обновлено словарей: {}", migrated.len());
+            }
+            Err(err) => fail!("Произошла ошибка при миграции словарей: {}", err),
+        },
+        Review(args) => match run_review(&args) {
+            Ok(reviewed) => println!("Проверено записей: {}", reviewed),
+            Err(err) => fail!("Произошла ошибка при проверке переводов: {}", err),
+        },
+        Check(args) if args.ci => {
+            let languages = (!args.languages.is_empty())
+                .then(|| args.languages.into_iter().map(String::from).collect());
+            let options = QaOptions {
+                max_length_ratio: args.max_length_ratio,
+                spelling_dir: args.spelling.then_some(args.spelling_dir),
+                glossary: args.glossary,
+            };
+            match ci_report(&args.dictionary_path, languages, &options) {
+                Ok(report) => {
+                    match output {
+                        OutputFormat::Json => print_json(&serde_json::json!(report)),
+                        OutputFormat::Github => {
+                            let locations =
+                                read_source_locations(&args.dictionary_path).unwrap_or_default();
+                            print_annotations(&ci_annotations(&report, &locations));
+                            print_ci_summary(&report);
+                        }
+                        OutputFormat::Text => {
+                            for issue in &report.issues {
+                                println!("{}", issue);
+                            }
+                            print_ci_summary(&report);
+                        }
+                    }
+                    if !report.passed {
+                        FAILED.store(true, Ordering::Relaxed);
+                    }
+                }
+                Err(err) => fail!("Произошла ошибка при проверке словарей: {}", err),
+            }
+        }
+        Check(args) => {
+            let languages = (!args.languages.is_empty())
+                .then(|| args.languages.into_iter().map(String::from).collect());
+            let options = QaOptions {
+                max_length_ratio: args.max_length_ratio,
+                spelling_dir: args.spelling.then_some(args.spelling_dir),
+                glossary: args.glossary,
+            };
+            match check_repository(&args.dictionary_path, languages, &options) {
+                Ok(issues) if json => match serde_json::to_string_pretty(&issues) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+                },
+                Ok(issues) if issues.is_empty() => println!("Проблем в переводах не найдено"),
+                Ok(issues) if output == OutputFormat::Github => {
+                    let locations =
+                        read_source_locations(&args.dictionary_path).unwrap_or_default();
+                    print_annotations(&qa_annotations(&issues, &locations));
+                    println!("Найдено проблем: {}", issues.len());
+                }
+                Ok(issues) => {
+                    let locations =
+                        read_source_locations(&args.dictionary_path).unwrap_or_default();
+                    for issue in &issues {
+                        println!("{}", issue);
+                        for location in locations_for(&locations, &issue.tag) {
+                            println!("    используется в {}", location);
+                        }
+                    }
+                    println!("Найдено проблем: {}", issues.len());
+                }
+                Err(err) => fail!("Произошла ошибка при проверке словарей: {}", err),
+            }
+        }
+        Status(args) => {
+            let languages = (!args.languages.is_empty())
+                .then(|| args.languages.into_iter().map(String::from).collect());
+            match status(&args.dictionary_path, languages) {
+                Ok(report) if json => match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+                },
+                Ok(report) => print_status(&report),
+                Err(err) => fail!("Произошла ошибка при чтении словарей: {}", err),
+            }
+        }
+        Watch(args) => {
+            if let Err(err) = run_watch(args).await {
+                fail!("Произошла ошибка при отслеживании изменений: {}", err);
+            }
+        }
+        Serve(args) => {
+            if let Err(err) = serve::run_serve(args).await {
+                fail!("Произошла ошибка при работе сервера: {}", err);
+            }
+        }
+        Doctor(args) => {
+            let diagnostics = diagnose(args.config_path.as_deref(), !args.offline).await;
+            match json {
+                true => print_json(&serde_json::json!(diagnostics)),
+                false => print_diagnostics(&diagnostics),
+            }
+            if diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.status == DiagnosticStatus::Error)
+            {
+                FAILED.store(true, Ordering::Relaxed);
+            }
+        }
+        Validate(args) => match validate_repository(&args.dictionary_path) {
+            Ok(issues) if json => {
+                match serde_json::to_string_pretty(&issues) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+                }
+                if !issues.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+            Ok(issues) if issues.is_empty() => println!("Репозиторий словарей в порядке"),
+            Ok(issues) if output == OutputFormat::Github => {
+                let locations = read_source_locations(&args.dictionary_path).unwrap_or_default();
+                print_annotations(&validation_annotations(
                     &args.dictionary_path,
-                    &args.output_directory,
-                    args.languages,
-                );
-                match result {
-                    Ok(()) => {
-                        println!("Сборка завершена успешно!")
+                    &issues,
+                    &locations,
+                ));
+                println!("Найдено проблем: {}", issues.len());
+                std::process::exit(1);
+            }
+            Ok(issues) => {
+                for issue in &issues {
+                    println!("{}", issue);
+                }
+                println!("Найдено проблем: {}", issues.len());
+                std::process::exit(1);
+            }
+            Err(err) => {
+                fail!("Произошла ошибка при проверке словарей: {}", err);
+                std::process::exit(1);
+            }
+        },
+        Diff(args) => match run_diff(&args) {
+            Ok(diff) if json => match serde_json::to_string_pretty(&diff) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(diff) => print_diff(&diff, !args.no_color && io::stdout().is_terminal()),
+            Err(err) => fail!("Произошла ошибка при сравнении словарей: {}", err),
+        },
+        Sync(args) => match sync_dictionaries(&args.dictionary_path, args.prune) {
+            Ok(report) if json => match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(report) => print_sync_report(&report),
+            Err(err) => fail!("Произошла ошибка при синхронизации словарей: {}", err),
+        },
+        Key(KeyCommand::Rename(args)) => {
+            match rename_entry(&args.dictionary_path, &args.old, &args.new, args.rewrite_sources) {
+                Ok(report) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "old": args.old,
+                    "new": args.new,
+                    "languages": report.languages,
+                    "source_files": report.source_files,
+                })),
+                Ok(report) => {
+                    println!("Ключ {} переименован в {}", args.old, args.new);
+                    if !report.languages.is_empty() {
+                        println!("Дочерние словари: {}", report.languages.join(", "));
                     }
-                    Err(error) => {
-                        println!("{:?}", error)
+                    for file in &report.source_files {
+                        println!("Изменен файл {}", file);
                     }
                 }
+                Err(err) => fail!("Ошибка: {}", err),
             }
+        }
+        Key(KeyCommand::Remove(args)) => {
+            match remove_matching_entries(&args.dictionary_path, &args.tags, args.dry_run) {
+                Ok(report) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "dry_run": args.dry_run,
+                    "tags": report.tags,
+                    "languages": report.languages,
+                })),
+                Ok(report) => {
+                    if args.dry_run {
+                        println!("Пробный запуск, словари не изменяются");
+                    }
+                    for tag in &report.tags {
+                        println!("  - {}", tag);
+                    }
+                    for (language, removed) in &report.languages {
+                        println!("{}: записей {}", language, removed);
+                    }
+                    match args.dry_run {
+                        true => println!("Будет удалено ключей: {}", report.tags.len()),
+                        false => println!("Удалено ключей: {}", report.tags.len()),
+                    }
+                }
+                Err(err) => fail!("Ошибка: {}", err),
+            }
+        }
+        Unused(args) if !Path::new(&args.dictionary_path).join(LOCATIONS_FILE).exists() => {
+            match json {
+                true => print_json_error("места использования фраз не найдены, сначала запустите dms scan"),
+                false => println!("Места использования фраз не найдены, сначала запустите dms scan"),
+            }
+        }
+        Unused(args) => match usage_report(&args.dictionary_path) {
+            Ok(report) if json => match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(report) => print_usage_report(&report),
+            Err(err) => fail!("Произошла ошибка при чтении словарей: {}", err),
         },
-        Scan(args) => {
-            let result = scan_files_for_phrases(args.config_path);
+        Search(args) => {
+            let found = search_pattern(&args.query, args.regex, args.ignore_case)
+                .and_then(|pattern| search_entries(&args.dictionary_path, &pattern));
+            match found {
+                Ok(found) if json => match serde_json::to_string_pretty(&found) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+                },
+                Ok(found) => print_search_results(&args.dictionary_path, &found),
+                Err(err) => fail!("Произошла ошибка при поиске: {}", err),
+            }
+        }
+        Merge(args) => match run_merge(&args) {
+            Ok(report) if json => print_json(&serde_json::json!({
+                "success": true,
+                "report": report,
+            })),
+            Ok(report) => {
+                println!("Добавлено записей в базовый словарь: {}", report.base_added.len());
+                for (language, added) in &report.translations_added {
+                    println!("{}: перенесено переводов {}", language, added);
+                }
+                println!(
+                    "Конфликтов: {}, принято из другого репозитория: {}",
+                    report.conflicts, report.taken_theirs
+                );
+            }
+            Err(err) => fail!("Произошла ошибка при слиянии репозиториев: {}", err),
+        },
+        Fmt(args) => match format_repository(&args.dictionary_path, args.sort_base, args.check) {
+            Ok(changed) if json => {
+                print_json(&serde_json::json!({
+                    "success": !args.check || changed.is_empty(),
+                    "check": args.check,
+                    "files": changed,
+                }));
+                if args.check && !changed.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+            Ok(changed) if changed.is_empty() => println!("Все словари уже отформатированы"),
+            Ok(changed) if args.check => {
+                for filename in &changed {
+                    println!("Требует форматирования: {}", filename);
+                }
+                std::process::exit(1);
+            }
+            Ok(changed) => {
+                for filename in &changed {
+                    println!("Отформатирован {}", filename);
+                }
+            }
+            Err(err) => {
+                fail!("Произошла ошибка при форматировании словарей: {}", err);
+                std::process::exit(1);
+            }
+        },
+        Stats(args) => match repository_statistics(&args.dictionary_path, args.longest) {
+            Ok(statistics) if json => match serde_json::to_string_pretty(&statistics) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(statistics) => print_statistics(&statistics),
+            Err(err) => fail!("Произошла ошибка при чтении словарей: {}", err),
+        },
+        Duplicates(args) => {
+            let languages = (!args.languages.is_empty())
+                .then(|| args.languages.into_iter().map(String::from).collect());
+            match find_duplicates(&args.dictionary_path, languages) {
+                Ok(groups) if json => match serde_json::to_string_pretty(&groups) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+                },
+                Ok(groups) if groups.is_empty() => {
+                    println!("Ключей с одинаковыми фразами или переводами не найдено")
+                }
+                Ok(groups) => print_duplicates(&groups),
+                Err(err) => fail!("Произошла ошибка при чтении словарей: {}", err),
+            }
+        }
+        Log(args) => match read_history(&args.dictionary_path, args.tag.as_deref()) {
+            Ok(records) if json => match serde_json::to_string_pretty(&records) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(records) if records.is_empty() => println!("В журнале истории нет изменений"),
+            Ok(records) => print_history(&records),
+            Err(err) => fail!("Произошла ошибка при чтении журнала истории: {}", err),
+        },
+        Release(args) if args.list => match list_releases(&args.dictionary_path) {
+            Ok(releases) if json => match serde_json::to_string_pretty(&releases) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(releases) if releases.is_empty() => println!("Релизов словарей еще нет"),
+            Ok(releases) => releases.iter().for_each(|release| println!("{}", release)),
+            Err(err) => fail!("Произошла ошибка при чтении релизов: {}", err),
+        },
+        Release(args) => match create_release(&args.dictionary_path, args.version.as_deref().unwrap_or_default()) {
+            Ok(manifest) if json => match serde_json::to_string_pretty(&manifest) {
+                Ok(json) => println!("{}", json),
+                Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+            },
+            Ok(manifest) => print_release(&manifest),
+            Err(err) => fail!("Произошла ошибка при создании релиза: {}", err),
+        },
+        Package(PackageCommand::Export(args)) => {
+            let result = export_package(&args.dictionary_path, args.language.as_str()).and_then(|package| {
+                let default_name = format!("{}.json", package.id);
+                let output = match &args.file {
+                    Some(path) if Path::new(path).is_dir() => {
+                        Path::new(path).join(default_name).to_string_lossy().into_owned()
+                    }
+                    Some(path) => path.clone(),
+                    None => default_name,
+                };
+                package.save(&output).map(|_| (package, output))
+            });
             match result {
-                Ok(()) => println!("Файлы успешно просканированы!"),
-                Err(err) => println!(
-                    "Произошла ошибка при сканировании файлов: {:?}",
-                    err.source()
+                Ok((package, output)) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "id": package.id,
+                    "file": output,
+                    "entries": package.entries.len(),
+                })),
+                Ok((package, output)) => println!(
+                    "Пакет {} сохранен в {}, фраз для перевода: {}",
+                    package.id,
+                    output,
+                    package.entries.len()
                 ),
+                Err(err) => fail!("Произошла ошибка при выгрузке пакета: {}", err),
+            }
+        }
+        Package(PackageCommand::Import(args)) => {
+            let result = TranslationPackage::load(&args.input).and_then(|package| {
+                import_package(&args.dictionary_path, &package).map(|changed| (package, changed))
+            });
+            match result {
+                Ok((package, changed)) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "id": package.id,
+                    "updated": changed,
+                })),
+                Ok((package, changed)) => {
+                    println!("Из пакета {} обновлено записей: {}", package.id, changed)
+                }
+                Err(err) => fail!("Произошла ошибка при загрузке пакета:\n{}", err),
+            }
+        }
+        Rollback(args) if args.list => match list_backups(&args.dictionary_path) {
+            Ok(backups) if json => print_json(&serde_json::json!(backups)),
+            Ok(backups) if backups.is_empty() => println!("Резервных копий нет"),
+            Ok(backups) => {
+                for backup in backups {
+                    println!("{}", backup);
+                }
+            }
+            Err(err) => fail!("Ошибка: {}", err),
+        },
+        Rollback(args) => match rollback(&args.dictionary_path, args.backup.as_deref()) {
+            Ok(backup) if json => print_json(&serde_json::json!({
+                "success": true,
+                "backup": backup,
+            })),
+            Ok(backup) => println!(
+                "Словари восстановлены из резервной копии {}, файлов: {}",
+                backup.name,
+                backup.files.len()
+            ),
+            Err(err) => fail!("Произошла ошибка при восстановлении словарей: {}", err),
+        },
+        Lock(args) => match run_lock(&args, true) {
+            Ok(changed) if json => print_json(&serde_json::json!({
+                "success": true,
+                "locked": changed,
+            })),
+            Ok(changed) => println!("Заблокировано записей: {}", changed),
+            Err(err) => fail!("Ошибка: {}", err),
+        },
+        Unlock(args) => match run_lock(&args, false) {
+            Ok(changed) if json => print_json(&serde_json::json!({
+                "success": true,
+                "unlocked": changed,
+            })),
+            Ok(changed) => println!("Разблокировано записей: {}", changed),
+            Err(err) => fail!("Ошибка: {}", err),
+        },
+        Export(export_type) => match export_type {
+            ExportType::Csv(args) => match run_export(&args) {
+                Ok(rows) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "file": args.output,
+                    "rows": rows,
+                })),
+                Ok(rows) => println!("Выгружено строк: {}", rows),
+                Err(err) => fail!("Произошла ошибка при выгрузке переводов: {}", err),
+            },
+            ExportType::Xlsx(args) => {
+                let result = translation_records(&args.export.dictionary_path, export_languages(&args.export))
+                    .and_then(|records| {
+                        write_xlsx(&records, args.combined, &args.export.output).map(|_| records.len())
+                    });
+                match result {
+                    Ok(rows) if json => print_json(&serde_json::json!({
+                        "success": true,
+                        "file": args.export.output,
+                        "rows": rows,
+                    })),
+                    Ok(rows) => println!("Выгружено строк: {}", rows),
+                    Err(err) => fail!("Произошла ошибка при выгрузке переводов: {}", err),
+                }
+            }
+            ExportType::Xliff(args) => {
+                let result = export_xliff(
+                    &args.export.dictionary_path,
+                    &args.export.output,
+                    export_languages(&args.export),
+                    args.version,
+                );
+                match result {
+                    Ok(written) if json => print_json(&serde_json::json!({
+                        "success": true,
+                        "files": written,
+                    })),
+                    Ok(written) => {
+                        for path in &written {
+                            println!("Создан файл {}", path);
+                        }
+                    }
+                    Err(err) => fail!("Произошла ошибка при выгрузке переводов: {}", err),
+                }
+            }
+        },
+        Import(import_type) => match import_type {
+            ImportType::Csv(args) => {
+                let result = File::open(&args.input)
+                    .map_err(api::errors::errors::StaticDictionaryErrors::from)
+                    .and_then(TranslationTable::read_csv)
+                    .and_then(|table| table.merge_into_repository(&args.dictionary_path));
+                match result {
+                    Ok(changed) if json => print_json(&serde_json::json!({
+                        "success": true,
+                        "updated": changed,
+                    })),
+                    Ok(changed) => println!("Обновлено записей: {}", changed),
+                    Err(err) => fail!("Произошла ошибка при загрузке переводов: {}", err),
+                }
+            }
+            ImportType::Xlsx(args) => match import_xlsx(&args.dictionary_path, &args.input) {
+                Ok(changed) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "updated": changed,
+                })),
+                Ok(changed) => println!("Обновлено записей: {}", changed),
+                Err(err) => fail!("Произошла ошибка при загрузке переводов:\n{}", err),
+            },
+            ImportType::Xliff(args) => match import_xliff(&args.dictionary_path, &args.input) {
+                Ok(changed) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "updated": changed,
+                })),
+                Ok(changed) => println!("Обновлено записей: {}", changed),
+                Err(err) => fail!("Произошла ошибка при загрузке переводов: {}", err),
+            },
+            ImportType::Po(args) => match import_po(&args.dictionary_path, &args.input) {
+                Ok(changed) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "updated": changed,
+                })),
+                Ok(changed) => println!("Обновлено переводов: {}", changed),
+                Err(err) => fail!("Произошла ошибка при загрузке переводов: {}", err),
+            },
+            ImportType::I18next(args) => match import_i18next(
+                &args.input,
+                &args.dictionary_path,
+                args.basic_language.as_ref(),
+                args.format,
+            ) {
+                Ok(imported) if json => print_json(&serde_json::json!({
+                    "success": true,
+                    "repositories": imported,
+                })),
+                Ok(imported) => {
+                    for repository in imported {
+                        println!(
+                            "Создан репозиторий {}: записей {}, языки: {}",
+                            repository.directory,
+                            repository.entries,
+                            repository.languages.join(", ")
+                        );
+                    }
+                }
+                Err(err) => fail!("Произошла ошибка при загрузке переводов: {}", err),
+            },
+        },
+        Report(report_type) => match report_type {
+            ReportType::Pr(args) => {
+                let before = DictionarySnapshot::load(&args.dictionary_path, &args.from);
+                let after = match &args.to {
+                    Some(to) => DictionarySnapshot::load(&args.dictionary_path, to),
+                    None => DictionarySnapshot::from_directory(&args.dictionary_path),
+                };
+                match (before, after) {
+                    (Ok(before), Ok(after)) => {
+                        let markdown = DictionaryReport::compare(&before, &after).to_markdown();
+                        if !json {
+                            println!("{}", markdown);
+                        }
+                        let mut published = None;
+                        if let (Some(repository), Some(pr)) = (&args.github_repo, args.pr) {
+                            match &args.github_token {
+                                Some(token) => {
+                                    match post_github_comment(repository, pr, token, &markdown)
+                                        .await
+                                    {
+                                        Ok(()) if json => published = Some(pr),
+                                        Ok(()) => println!("Отчет опубликован в pull request #{}", pr),
+                                        Err(err) => {
+                                            fail!("Ошибка: {}", err);
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+                                None => {
+                                    fail!(
+                                        "Ошибка: для публикации отчета нужен токен GitHub (--github-token или GITHUB_TOKEN)"
+                                    );
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        if json {
+                            print_json(&serde_json::json!({
+                                "success": true,
+                                "markdown": markdown,
+                                "published": published,
+                            }));
+                        }
+                    }
+                    (Err(err), _) | (_, Err(err)) => {
+                        fail!("Произошла ошибка при составлении отчета: {}", err)
+                    }
+                }
+            }
+        },
+    }
+    Ok(())
+}
+
+// Вопросы для стартового конфига. Пустой ответ или конец ввода оставляют значение по умолчанию
+fn run_init_config(
+    args: &InitConfigArgs,
+) -> Result<StarterConfig, api::errors::errors::StaticDictionaryErrors> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut ask = |question: &str, default: &str| -> String {
+        print!("{} [{}]: ", question, default);
+        let _ = io::stdout().flush();
+        match lines.next() {
+            Some(Ok(answer)) if !answer.trim().is_empty() => answer.trim().to_owned(),
+            _ => default.to_owned(),
+        }
+    };
+    let list = |answer: String| -> Vec<String> {
+        answer
+            .split([',', ' '])
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect()
+    };
+    let defaults = StarterConfig::default();
+    let mut starter = StarterConfig {
+        base_directory: ask("Директория исходного кода", &defaults.base_directory),
+        dictionary_repo: ask("Репозиторий словарей", &defaults.dictionary_repo),
+        output_dir: ask("Директория для собранных словарей", &defaults.output_dir),
+        extensions: list(ask(
+            "Расширения файлов для сканирования",
+            &defaults.extensions.join(", "),
+        )),
+        languages: list(ask("Языки перевода через запятую", "")),
+        ..defaults
+    };
+    match ask("Автопереводчик: none, libretranslate или deepl", "none")
+        .to_lowercase()
+        .as_str()
+    {
+        "none" => {}
+        "libretranslate" => {
+            starter.translator.libretranslate_host =
+                Some(ask("Хостинг LibreTranslate", "http://localhost:5000"))
+        }
+        "deepl" => {
+            starter.translator.deepl_host =
+                Some(ask("Хостинг DeepL API", "https://api-free.deepl.com"));
+            println!("Ключ DeepL API в конфиг не записывается, передайте его через DEEPL_API_KEY");
+        }
+        other => println!("Неизвестный автопереводчик {}, настройки переводчика не записаны", other),
+    }
+    write_starter_config(&args.path, &starter, ASSUME_YES.load(Ordering::Relaxed))?;
+    Ok(starter)
+}
+
+#[doc = "Интерактивная проверка машинных переводов. Решения сохраняются и при досрочном выходе"]
+fn run_review(args: &ReviewArgs) -> Result<usize, api::errors::errors::StaticDictionaryErrors> {
+    let pending = pending_reviews(&args.dictionary_path, args.language.as_str())?;
+    if pending.is_empty() {
+        println!("Нет машинных переводов и предложений для проверки");
+        return Ok(0);
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut prompt = |text: &str| -> Option<String> {
+        print!("{}", text);
+        io::stdout().flush().ok()?;
+        lines.next()?.ok()
+    };
+    let mut decisions = vec![];
+    'review: for (index, item) in pending.iter().enumerate() {
+        let suggestion = if item.suggestion { " (предложение)" } else { "" };
+        println!("\n[{}/{}] {}{}", index + 1, pending.len(), item.tag, suggestion);
+        for (source, translation) in &item.pairs {
+            let form = translation.suffixed_tag();
+            let form = form.strip_prefix(&item.tag).unwrap_or("");
+            println!("  {}{}: {}", source.language, form, source.word);
+            println!("  {}{}: {}", translation.language, form, translation.word);
+        }
+        loop {
+            let Some(answer) = prompt("[a] принять, [e] исправить, [s] пропустить, [q] выйти: ") else {
+                break 'review;
+            };
+            match answer.trim() {
+                "a" => decisions.push((item.tag.clone(), ReviewDecision::Accept)),
+                "e" => {
+                    let mut edited = vec![];
+                    for (_, translation) in &item.pairs {
+                        let Some(text) = prompt(&format!(
+                            "  {} (Enter - оставить \"{}\"): ",
+                            translation.suffixed_tag(),
+                            translation.word
+                        )) else {
+                            break 'review;
+                        };
+                        let text = text.trim();
+                        let mut word = translation.clone();
+                        if !text.is_empty() {
+                            word.word = text.to_owned();
+                        }
+                        edited.push(word);
+                    }
+                    decisions.push((item.tag.clone(), ReviewDecision::Edit(edited)));
+                }
+                "s" => decisions.push((item.tag.clone(), ReviewDecision::Skip)),
+                "q" => break 'review,
+                _ => continue,
+            }
+            break;
+        }
+    }
+    apply_review_decisions(&args.dictionary_path, args.language.as_str(), &decisions)
+}
+
+fn run_merge(args: &MergeArgs) -> Result<MergeReport, api::errors::errors::StaticDictionaryErrors> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut ask = |conflict: &MergeConflict| -> MergeChoice {
+        match &conflict.language {
+            Some(language) => println!("\n{} [{}]", conflict.tag, language),
+            None => println!("\n{} [базовый словарь]", conflict.tag),
+        }
+        println!("  наш:   {}", conflict.ours);
+        println!("  чужой: {}", conflict.theirs);
+        loop {
+            print!("[o] оставить наш, [t] взять чужой: ");
+            if io::stdout().flush().is_err() {
+                return MergeChoice::Ours;
+            }
+            match lines.next() {
+                Some(Ok(answer)) if answer.trim() == "o" => return MergeChoice::Ours,
+                Some(Ok(answer)) if answer.trim() == "t" => return MergeChoice::Theirs,
+                Some(Ok(_)) => continue,
+                _ => return MergeChoice::Ours,
+            }
+        }
+    };
+    merge_repositories(&args.dictionary_path, &args.other, |conflict| match args.strategy {
+        MergeStrategy::Ours => MergeChoice::Ours,
+        MergeStrategy::Theirs => MergeChoice::Theirs,
+        MergeStrategy::Interactive => ask(conflict),
+    })
+}
+
+fn print_scan_report(report: &ScanReport) {
+    for file in &report.files {
+        if !file.added.is_empty() || !file.removed.is_empty() {
+            println!(
+                "{}: новых фраз {}, уже в словаре {}, больше не используются {}",
+                file.file,
+                file.added.len(),
+                file.existing.len(),
+                file.removed.len()
+            );
+        }
+    }
+    let unreadable = report.unreadable();
+    if !unreadable.is_empty() {
+        println!("Пропущены двоичные файлы и файлы в неизвестной кодировке (см. параметр encoding):");
+        for file in unreadable {
+            println!("  {}", file);
+        }
+    }
+    println!(
+        "Файлы успешно просканированы! Просканировано файлов: {}, пропущено без изменений: {}",
+        report.files.len(),
+        report.skipped
+    );
+    println!(
+        "Новых фраз: {}, уже в словаре: {}, пропали из файлов: {}",
+        report.added(),
+        report.existing(),
+        report.removed()
+    );
+}
+
+fn run_diff(args: &DiffArgs) -> Result<DictionaryDiff, api::errors::errors::ReportErrors> {
+    if let Some(revision) = &args.rev {
+        let before = read_dictionary_values(&args.before, Some(revision))?;
+        let after = read_dictionary_values(args.after.as_ref().unwrap_or(&args.before), None)?;
+        return Ok(DictionaryDiff::compare(&before, &after, true));
+    }
+    if let Some(after) = &args.after {
+        let before = read_dictionary_values(&args.before, None)?;
+        let after = read_dictionary_values(after, None)?;
+        return Ok(DictionaryDiff::compare(&before, &after, true));
+    }
+    // Один файл без ревизии сравнивается с базовым словарем: значения у них разные по определению, поэтому важны только ключи
+    let directory = match Path::new(&args.before).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+        _ => ".".to_owned(),
+    };
+    let basic_dictionary = get_basic_dictionary(&directory)?;
+    let before = read_dictionary_values(
+        &Path::new(&directory).join(basic_dictionary).to_string_lossy(),
+        None,
+    )?;
+    let after = read_dictionary_values(&args.before, None)?;
+    Ok(DictionaryDiff::compare(&before, &after, false))
+}
+
+fn print_diff(diff: &DictionaryDiff, colored: bool) {
+    let paint = |color: &str, text: String| match colored {
+        true => format!("\x1b[{}m{}\x1b[0m", color, text),
+        false => text,
+    };
+    if diff.is_empty() {
+        println!("Различий нет");
+        return;
+    }
+    for (key, value) in &diff.added {
+        println!("{}", paint("32", format!("+ {}: {}", key, value)));
+    }
+    for (key, value) in &diff.removed {
+        println!("{}", paint("31", format!("- {}: {}", key, value)));
+    }
+    for changed in &diff.changed {
+        println!(
+            "{}",
+            paint(
+                "33",
+                format!("~ {}: {} -> {}", changed.key, changed.before, changed.after)
+            )
+        );
+    }
+    println!(
+        "Добавлено: {}, удалено: {}, изменено: {}",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+}
+
+// Путь к репозиторию словарей и директория сборки у команд, которые можно выполнить для репозиториев из конфига
+fn repository_paths(subcommand: &mut CliSubcommands) -> Option<(&mut String, Option<&mut String>)> {
+    match subcommand {
+        Translate(TranslateType::Manual(args)) => Some((&mut args.dictionary_path, None)),
+        Translate(TranslateType::Auto(ApiVariants::Libretranslate(args))) => {
+            Some((&mut args.dictionaries_path, None))
+        }
+        Translate(TranslateType::Auto(ApiVariants::Deepl(args))) => {
+            Some((&mut args.dictionaries_path, None))
+        }
+        Sync(args) => Some((&mut args.dictionary_path, None)),
+        Build(
+            FrameworkType::I18next(args)
+            | FrameworkType::Arb(args)
+            | FrameworkType::Rails(args)
+            | FrameworkType::VueI18n(args)
+            | FrameworkType::Webextension(args)
+            | FrameworkType::Laravel(args),
+        ) => Some((&mut args.dictionary_path, Some(&mut args.output_directory))),
+        Build(FrameworkType::NgxTranslate(args)) => Some((
+            &mut args.build.dictionary_path,
+            Some(&mut args.build.output_directory),
+        )),
+        Build(FrameworkType::Properties(args)) => Some((
+            &mut args.build.dictionary_path,
+            Some(&mut args.build.output_directory),
+        )),
+        Build(
+            FrameworkType::Qt(args) | FrameworkType::AngularXlf(args) | FrameworkType::Rust(args),
+        ) => Some((&mut args.dictionary_path, Some(&mut args.output_directory))),
+        Build(FrameworkType::Gettext(args)) => {
+            Some((&mut args.dictionary_path, Some(&mut args.output_directory)))
+        }
+        Build(FrameworkType::Types(args)) => {
+            Some((&mut args.dictionary_path, Some(&mut args.output_directory)))
+        }
+        _ => None,
+    }
+}
+
+// Почему команда не поддерживает --output json: она задает вопросы в терминале или работает до прерывания
+fn json_unsupported(subcommand: &CliSubcommands) -> Option<&'static str> {
+    match subcommand {
+        Init(InitializeArguments {
+            command: Some(InitCommand::Config(_)),
+            ..
+        }) => Some("init config задает вопросы в терминале и не поддерживает --output json"),
+        Review(_) => Some("review задает вопросы в терминале и не поддерживает --output json"),
+        Scan(args) if args.watch => {
+            Some("scan --watch работает до прерывания и не поддерживает --output json")
+        }
+        Scan(args) if args.prune && !ASSUME_YES.load(Ordering::Relaxed) => {
+            Some("scan --prune с --output json не может спросить подтверждение, используйте --yes")
+        }
+        Watch(_) | Serve(_) => {
+            Some("watch и serve работают до прерывания и не поддерживают --output json")
+        }
+        _ => None,
+    }
+}
+
+// Флаг пробного запуска команды, который включается глобальным --dry-run
+fn dry_run_flag(subcommand: &mut CliSubcommands) -> Option<&mut bool> {
+    match subcommand {
+        Scan(args) => Some(&mut args.dry_run),
+        Key(KeyCommand::Remove(args)) => Some(&mut args.dry_run),
+        _ => None,
+    }
+}
+
+// Путь, который команда изменяет и который при пробном запуске подменяется копией
+enum SandboxTarget<'a> {
+    /// Директория копируется целиком: репозиторий словарей или директория сборки
+    Directory(&'a mut String),
+    /// Выходной файл копируется, если он уже есть
+    File(&'a mut String),
+    /// Директория, в которую команда только добавляет файлы, подменяется пустой копией
+    Overlay(&'a mut String),
+    /// Директория, в которой init создает поддиректорию dictionaries
+    InitDirectory(&'a mut String),
+}
+
+// Что изменяет команда: директорию сборки для build, выходной файл для export и package export, репозиторий словарей для остальных изменяющих команд
+fn sandbox_target(subcommand: &mut CliSubcommands) -> Option<SandboxTarget<'_>> {
+    if let Build(_) = subcommand {
+        return repository_paths(subcommand)
+            .and_then(|(_, output_directory)| output_directory)
+            .map(SandboxTarget::Directory);
+    }
+    let directory = match subcommand {
+        Init(InitializeArguments {
+            command: Some(InitCommand::Config(args)),
+            ..
+        }) => return Some(SandboxTarget::File(&mut args.path)),
+        Init(args) => {
+            let directory = args.directory.get_or_insert_with(|| ".".to_owned());
+            return Some(SandboxTarget::InitDirectory(directory));
+        }
+        Export(ExportType::Csv(args)) => return Some(SandboxTarget::File(&mut args.output)),
+        Export(ExportType::Xlsx(args)) => {
+            return Some(SandboxTarget::File(&mut args.export.output))
+        }
+        // Пакет без --file сохраняется в текущую директорию под именем, которое известно только после выгрузки
+        Package(PackageCommand::Export(args)) => {
+            let is_file = args.file.as_ref().is_some_and(|path| !Path::new(path).is_dir());
+            let path = args.file.get_or_insert_with(|| ".".to_owned());
+            return Some(match is_file {
+                true => SandboxTarget::File(path),
+                false => SandboxTarget::Overlay(path),
+            });
+        }
+        Export(ExportType::Xliff(args)) => &mut args.export.output,
+        Translate(TranslateType::Manual(args)) => &mut args.dictionary_path,
+        Sync(args) => &mut args.dictionary_path,
+        Key(KeyCommand::Rename(args)) => &mut args.dictionary_path,
+        Merge(args) => &mut args.dictionary_path,
+        Package(PackageCommand::Import(args)) => &mut args.dictionary_path,
+        Fmt(args) if !args.check => &mut args.dictionary_path,
+        Rollback(args) if !args.list => &mut args.dictionary_path,
+        Lock(args) | Unlock(args) => &mut args.dictionary_path,
+        Review(args) => &mut args.dictionary_path,
+        Migrate(args) => &mut args.dictionary_path,
+        Release(args) if !args.list => &mut args.dictionary_path,
+        Import(ImportType::Csv(args))
+        | Import(ImportType::Xlsx(args))
+        | Import(ImportType::Xliff(args))
+        | Import(ImportType::Po(args)) => &mut args.dictionary_path,
+        Import(ImportType::I18next(args)) => &mut args.dictionary_path,
+        _ => return None,
+    };
+    Some(SandboxTarget::Directory(directory))
+}
+
+// Подменяет путь команды копией во временном каталоге
+fn create_sandbox(target: SandboxTarget) -> Result<Sandbox, api::errors::errors::StaticDictionaryErrors> {
+    let (path, sandbox) = match target {
+        SandboxTarget::Directory(path) => {
+            let sandbox = Sandbox::new(path)?;
+            (path, sandbox)
+        }
+        SandboxTarget::File(path) => {
+            let sandbox = Sandbox::file(path)?;
+            (path, sandbox)
+        }
+        SandboxTarget::Overlay(path) => {
+            let sandbox = Sandbox::overlay(path)?;
+            (path, sandbox)
+        }
+        SandboxTarget::InitDirectory(path) => {
+            let sandbox = Sandbox::new(&format!("{}/dictionaries", path))?;
+            let copy = sandbox.path();
+            *path = Path::new(&copy).parent().unwrap().to_string_lossy().into_owned();
+            return Ok(sandbox);
+        }
+    };
+    *path = sandbox.path();
+    Ok(sandbox)
+}
+
+#[doc = "Пробный запуск: scan и key remove показывают свой план, translate auto - сколько фраз отправил бы переводчику, сборка выполняется в копию директории сборки, а остальные изменяющие команды - над копией репозитория. После команды выводятся файлы, которые были бы созданы, изменены или удалены"]
+async fn run_dry(
+    mut subcommand: CliSubcommands,
+    output: OutputFormat,
+    progress: Arc<dyn Progress>,
+) -> Result<(), reqwest::Error> {
+    if let Translate(TranslateType::Auto(api)) = &subcommand {
+        print_translate_plan(api, output);
+        return Ok(());
+    }
+    // Конвейер watch и сервер serve работают до прерывания и меняют словари по мере событий, планировать в них нечего
+    if let Watch(_) | Serve(_) = &subcommand {
+        exit_with_error(output, "Ошибка: watch и serve не поддерживают пробный запуск");
+    }
+    // Комментарий в pull request нельзя опубликовать в копию
+    if let Report(ReportType::Pr(PrReportArgs { pr: Some(_), .. })) = &subcommand {
+        exit_with_error(
+            output,
+            "Ошибка: report pr --pr публикует отчет в GitHub и не поддерживает пробный запуск, запустите команду без --pr",
+        );
+    }
+    let sandbox = match sandbox_target(&mut subcommand).map(create_sandbox) {
+        Some(Ok(sandbox)) => Some(sandbox),
+        Some(Err(err)) => exit_with_error(output, &format!("Ошибка: {}", err)),
+        None => None,
+    };
+    match subcommand {
+        // Пути собранных файлов указывают на копию, поэтому вместо них выводится план
+        Build(framework) => {
+            if let Err(error) = build_with_progress(framework, progress.as_ref()) {
+                exit_with_error(output, &error);
             }
         }
+        subcommand => execute(subcommand, output, progress).await?,
+    }
+    if let Some(sandbox) = sandbox {
+        match sandbox.changes() {
+            Ok(changes) => print_planned_writes(output, &changes),
+            Err(err) => exit_with_error(output, &format!("Ошибка: {}", err)),
+        }
     }
     Ok(())
 }
+
+fn print_planned_writes(output: OutputFormat, changes: &[PlannedWrite]) {
+    if output == OutputFormat::Json {
+        print_json(&serde_json::json!({ "dry_run": true, "writes": changes }));
+        return;
+    }
+    if changes.is_empty() {
+        println!("Пробный запуск: команда не изменила бы ни одного файла");
+        return;
+    }
+    println!("Пробный запуск, файлы не изменены. Команда изменила бы:");
+    for write in changes {
+        let change = match write.change {
+            PlannedChange::Create => "создан",
+            PlannedChange::Modify => "изменен",
+            PlannedChange::Remove => "удален",
+        };
+        println!("  {:<8} {}", change, write.path);
+    }
+}
+
+// План автоперевода: фразы для каждого языка и словари, которые были бы записаны. Запросы к переводчику не отправляются
+fn print_translate_plan(api: &ApiVariants, output: OutputFormat) {
+    let (dictionary_path, host, languages) = match api {
+        ApiVariants::Libretranslate(args) => (&args.dictionaries_path, &args.host, &args.languages),
+        ApiVariants::Deepl(args) => (&args.dictionaries_path, &args.host, &args.languages),
+    };
+    let languages = languages.iter().cloned().map(String::from).collect();
+    let plan = match autotranslate_plan(dictionary_path, languages) {
+        Ok(plan) => plan,
+        Err(err) => exit_with_error(output, &format!("Ошибка: {}", err)),
+    };
+    let writes: Vec<String> = plan
+        .keys()
+        .filter_map(|language| translated_dictionary_path(dictionary_path, language).ok())
+        .collect();
+    if output == OutputFormat::Json {
+        print_json(&serde_json::json!({
+            "dry_run": true,
+            "host": host,
+            "requests": plan,
+            "writes": writes,
+        }));
+        return;
+    }
+    println!("Пробный запуск, запросы к {} не отправлены", host);
+    for ((language, count), path) in plan.iter().zip(&writes) {
+        println!("{}: фраз для перевода {}, словарь {}", language, count, path);
+    }
+}
+
+// Автопереводчик из translator в конфиге для dms watch и dms serve, ключ DeepL из --api-key или DEEPL_API_KEY
+fn configured_translator(
+    translator: AutoTranslator,
+    api_key: Option<String>,
+    config: &api::parser::types::ConfigFileParameters,
+) -> Result<(TranslatorApis, ApiArgs), api::errors::errors::StaticDictionaryErrors> {
+    let (api, api_args) = match translator {
+        AutoTranslator::Libretranslate => {
+            let host = config.translator.libretranslate_host.clone().ok_or_else(|| {
+                api::errors::errors::StaticDictionaryErrors::InvalidConfig(
+                    "для автоперевода нужен translator.libretranslate_host в конфиге".to_owned(),
+                )
+            })?;
+            (TranslatorApis::LibreTranslate, ApiArgs::new(None, host))
+        }
+        AutoTranslator::Deepl => {
+            let api_key = api_key.ok_or_else(|| {
+                api::errors::errors::StaticDictionaryErrors::InvalidConfig(
+                    "для автоперевода DeepL нужен ключ в --api-key или DEEPL_API_KEY".to_owned(),
+                )
+            })?;
+            let host = config
+                .translator
+                .deepl_host
+                .clone()
+                .unwrap_or_else(|| "https://api-free.deepl.com".to_owned());
+            (TranslatorApis::DeepL, ApiArgs::new(Some(api_key), host))
+        }
+    };
+    Ok((api, api_args))
+}
+
+#[doc = "Конвейер dms watch: сканирует измененные файлы, переводит новые фразы и пересобирает словари i18next, пока не будет прерван"]
+async fn run_watch(args: WatchArgs) -> Result<(), api::errors::errors::StaticDictionaryErrors> {
+    let config_path = args.config_path.clone().or_else(|| {
+        CONFIG_FILE_NAMES
+            .iter()
+            .find(|name| Path::new(name).is_file())
+            .map(|name| name.to_string())
+    });
+    let config = parse_config(config_path.clone())?;
+    let translator = match args.no_translate {
+        true => None,
+        false => Some(configured_translator(args.translator, args.api_key.clone(), &config)?),
+    };
+    let handle = tokio::runtime::Handle::current();
+    println!("Отслеживание изменений файлов, для выхода нажмите Ctrl+C");
+    tokio::task::spawn_blocking(move || {
+        let mut started = false;
+        watch_scan_batches(
+            config_path.clone(),
+            !args.no_cache,
+            Duration::from_millis(args.debounce),
+            |file, result| match result {
+                Ok(scanned) if !scanned.added.is_empty() => println!(
+                    "{}: новых фраз {}",
+                    file.display(),
+                    scanned.added.len()
+                ),
+                Ok(_) => {}
+                Err(err) => println!("{}: ошибка при сканировании: {}", file.display(), err),
+            },
+            |added| {
+                if let Some((api, api_args)) = &translator {
+                    for (dictionary_dir, tags) in added {
+                        handle.block_on(watch_translate(
+                            dictionary_dir,
+                            tags,
+                            &config,
+                            api.clone(),
+                            api_args.clone(),
+                        ));
+                    }
+                }
+                // Словари собираются при запуске и после каждого добавления фраз
+                if !args.no_build && (!started || !added.is_empty()) {
+                    watch_build(&config, config_path.as_deref());
+                }
+                started = true;
+            },
+        )
+    })
+    .await?
+}
+
+// Языки автоперевода по умолчанию: languages из конфига, без них - все языки репозитория
+fn default_languages(
+    dictionary_dir: &str,
+    config: &api::parser::types::ConfigFileParameters,
+) -> Vec<String> {
+    match config.languages.is_empty() {
+        true => find_all_translated_languages(dictionary_dir).unwrap_or_default(),
+        false => config.languages.clone(),
+    }
+}
+
+// Автоперевод записей без перевода с блокировкой репозитория и записью изменений в журнал истории. Переводы добавляются в существующие словари, переведенные записи не меняются. С tags переводятся только записи этих тегов
+async fn translate_locked(
+    dictionary_dir: &str,
+    languages: Vec<String>,
+    api: TranslatorApis,
+    api_args: ApiArgs,
+    tags: Option<&BTreeSet<String>>,
+    operation: &str,
+) -> Result<(), api::errors::errors::StaticDictionaryErrors> {
+    let _lock = RepositoryLock::acquire(dictionary_dir, operation)?;
+    let before = RepositoryValues::read(dictionary_dir).ok();
+    let result = autotranslate_untranslated(dictionary_dir, languages, api, api_args, tags).await;
+    if let Some(before) = before {
+        if let Err(err) = record_changes(dictionary_dir, operation, &before) {
+            eprintln!("Не удалось записать изменения в журнал истории: {}", err);
+        }
+    }
+    result
+}
+
+// Автоперевод новых фраз директории словарей в dms watch. Ошибки выводятся и не прерывают отслеживание
+async fn watch_translate(
+    dictionary_dir: &str,
+    tags: &BTreeSet<String>,
+    config: &api::parser::types::ConfigFileParameters,
+    api: TranslatorApis,
+    api_args: ApiArgs,
+) {
+    let languages = default_languages(dictionary_dir, config);
+    if languages.is_empty() {
+        return;
+    }
+    match translate_locked(dictionary_dir, languages.clone(), api, api_args, Some(tags), "watch").await {
+        Ok(()) => println!("{}: новые фразы переведены на {}", dictionary_dir, languages.join(", ")),
+        Err(err @ api::errors::errors::StaticDictionaryErrors::RepositoryLocked(_)) => {
+            println!("{}: перевод пропущен: {}", dictionary_dir, err)
+        }
+        Err(err) => println!("{}: ошибка при автопереводе: {}", dictionary_dir, err),
+    }
+}
+
+// Пересборка словарей i18next всех репозиториев в output_dir в dms watch. Ошибки выводятся и не прерывают отслеживание
+fn watch_build(config: &api::parser::types::ConfigFileParameters, config_path: Option<&str>) {
+    let repositories = config.dictionary_repo.repositories();
+    let several = repositories.len() > 1;
+    for repository in repositories {
+        let output_directory = match several {
+            true => Path::new(&config.output_dir)
+                .join(&repository.name)
+                .to_string_lossy()
+                .into_owned(),
+            false => config.output_dir.clone(),
+        };
+        match build_i18next(&repository.path, &output_directory, config_path) {
+            Ok(_) => println!(
+                "{}: словари i18next пересобраны в {}",
+                repository.path, output_directory
+            ),
+            Err(err) => println!("{}: ошибка при сборке: {}", repository.path, err),
+        }
+    }
+}
+
+// Сборка словарей i18next репозитория без индикаторов хода для dms watch и dms serve
+fn build_i18next(
+    dictionary_path: &str,
+    output_directory: &str,
+    config_path: Option<&str>,
+) -> Result<BuildOutput, String> {
+    let framework = FrameworkType::I18next(BuildArgs {
+        dictionary_path: dictionary_path.to_owned(),
+        output_directory: output_directory.to_owned(),
+        languages: None,
+        output_template: None,
+        config_path: config_path.map(str::to_owned),
+        module: None,
+        minify: false,
+        release: None,
+    });
+    build_with_progress(framework, &NoProgress)
+}
+
+// Перевод словарей для --output json. Возвращает переведенные языки
+async fn run_translate(
+    translate_type: TranslateType,
+) -> Result<Vec<String>, api::errors::errors::StaticDictionaryErrors> {
+    match translate_type {
+        TranslateType::Manual(args) => {
+            let languages: Vec<String> = args.languages.into_iter().map(String::from).collect();
+            generate_empty_dictionaries_from_static_basic(&args.dictionary_path, languages.clone())?;
+            Ok(languages)
+        }
+        TranslateType::Auto(ApiVariants::Libretranslate(args)) => {
+            let languages: Vec<String> =
+                args.languages.iter().cloned().map(String::from).collect();
+            autotranslate_from_basic_dictionary(
+                &args.dictionaries_path,
+                languages.clone(),
+                TranslatorApis::LibreTranslate,
+                args.clone().into(),
+            )
+            .await?;
+            Ok(languages)
+        }
+        TranslateType::Auto(ApiVariants::Deepl(args)) => {
+            let languages: Vec<String> =
+                args.languages.iter().cloned().map(String::from).collect();
+            autotranslate_from_basic_dictionary(
+                &args.dictionaries_path,
+                languages.clone(),
+                TranslatorApis::DeepL,
+                args.clone().into(),
+            )
+            .await?;
+            Ok(languages)
+        }
+    }
+}
+
+// Репозиторий и версия релиза для команд сборки с --release
+fn release_source(subcommand: &mut CliSubcommands) -> Option<(&mut String, &Option<String>)> {
+    match subcommand {
+        Build(
+            FrameworkType::I18next(args)
+            | FrameworkType::Arb(args)
+            | FrameworkType::Rails(args)
+            | FrameworkType::VueI18n(args)
+            | FrameworkType::Webextension(args)
+            | FrameworkType::Laravel(args),
+        ) => Some((&mut args.dictionary_path, &args.release)),
+        Build(FrameworkType::NgxTranslate(args)) => {
+            Some((&mut args.build.dictionary_path, &args.build.release))
+        }
+        Build(FrameworkType::Properties(args)) => {
+            Some((&mut args.build.dictionary_path, &args.build.release))
+        }
+        Build(
+            FrameworkType::Qt(args) | FrameworkType::AngularXlf(args) | FrameworkType::Rust(args),
+        ) => Some((&mut args.dictionary_path, &args.release)),
+        Build(FrameworkType::Gettext(args)) => Some((&mut args.dictionary_path, &args.release)),
+        Build(FrameworkType::Types(args)) => Some((&mut args.dictionary_path, &args.release)),
+        _ => None,
+    }
+}
+
+// Команда сборки с --release собирает словари из проверенного снимка релиза
+fn use_release(subcommand: &mut CliSubcommands) -> Result<(), api::errors::errors::StaticDictionaryErrors> {
+    if let Some((dictionary_path, Some(version))) = release_source(subcommand) {
+        *dictionary_path = release_directory(dictionary_path, version)?;
+    }
+    Ok(())
+}
+
+// Команда для каждого репозитория словарей. Если translate, sync или build передан путь к конфигу, команда выполняется для всех репозиториев из dictionary_repo или только для --repo. Сборка нескольких репозиториев записывается в поддиректории с их именами
+fn for_each_repository(
+    mut subcommand: CliSubcommands,
+    repo: Option<&str>,
+) -> Result<Vec<(String, CliSubcommands)>, api::errors::errors::StaticDictionaryErrors> {
+    let Some((dictionary_path, _)) = repository_paths(&mut subcommand) else {
+        return match repo {
+            Some(_) => Err(api::errors::errors::StaticDictionaryErrors::InvalidConfig(
+                "--repo поддерживается только командами translate, sync и build".to_owned(),
+            )),
+            None => Ok(vec![(String::new(), subcommand)]),
+        };
+    };
+    let repositories = resolve_repositories(dictionary_path, repo)?;
+    let several = repositories.len() > 1;
+    Ok(repositories
+        .into_iter()
+        .map(|repository| {
+            let mut subcommand = subcommand.clone();
+            if let Some((dictionary_path, output_directory)) = repository_paths(&mut subcommand) {
+                *dictionary_path = repository.path;
+                if let Some(output_directory) = output_directory.filter(|_| several) {
+                    *output_directory = Path::new(output_directory.as_str())
+                        .join(&repository.name)
+                        .to_string_lossy()
+                        .into_owned();
+                }
+            }
+            (repository.name, subcommand)
+        })
+        .collect())
+}
+
+// Существующие дочерние словари языков. Словари, которых еще нет, будут созданы и подтверждения не требуют
+fn existing_dictionaries(dictionary_path: &str, languages: &[String]) -> Vec<String> {
+    languages
+        .iter()
+        .filter_map(|language| translated_dictionary_path(dictionary_path, language).ok())
+        .filter(|path| Path::new(path).is_file())
+        .collect()
+}
+
+// Вопрос и файлы словарей для команд, которые перезаписывают словари или удаляют из них записи. Подтверждение для scan --prune спрашивается после сканирования, когда известны неиспользуемые записи
+fn confirmation_target(subcommand: &CliSubcommands) -> Option<(String, Vec<String>)> {
+    match subcommand {
+        Translate(TranslateType::Manual(args)) => {
+            let languages: Vec<String> = args.languages.iter().cloned().map(String::from).collect();
+            Some((
+                "Перезаписать словари пустыми? Сделанные в них переводы будут потеряны".to_owned(),
+                existing_dictionaries(&args.dictionary_path, &languages),
+            ))
+        }
+        Translate(TranslateType::Auto(api)) => {
+            let (dictionary_path, languages) = match api {
+                ApiVariants::Libretranslate(args) => (&args.dictionaries_path, &args.languages),
+                ApiVariants::Deepl(args) => (&args.dictionaries_path, &args.languages),
+            };
+            let languages: Vec<String> = languages.iter().cloned().map(String::from).collect();
+            Some((
+                "Перезаписать словари машинным переводом?".to_owned(),
+                existing_dictionaries(dictionary_path, &languages),
+            ))
+        }
+        Sync(args) if args.prune => {
+            let languages = find_all_translated_languages(&args.dictionary_path).ok()?;
+            Some((
+                "Удалить из словарей ключи, которых нет в базовом словаре?".to_owned(),
+                existing_dictionaries(&args.dictionary_path, &languages),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[doc = "Выводит файлы, которые операция перезапишет или из которых удалит записи, и спрашивает подтверждение. С --yes и без затронутых файлов подтверждение не нужно. В выводе JSON спросить нельзя, и без --yes операция отменяется с ошибкой, как и при закрытом вводе или вводе не из терминала"]
+fn confirm(question: &str, files: &[String], output: OutputFormat) -> bool {
+    if files.is_empty() || ASSUME_YES.load(Ordering::Relaxed) {
+        return true;
+    }
+    if output == OutputFormat::Json {
+        print_json_error("операция изменит файлы словарей, для запуска без подтверждения используйте --yes");
+        return false;
+    }
+    println!("Будут изменены файлы:");
+    for file in files {
+        println!("  {}", file);
+    }
+    print!("{} [y/N]: ", question);
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    // Ввод не из терминала (CI, пайп) считается закрытым: ответа на вопрос не будет
+    let stdin = io::stdin();
+    let read = if stdin.is_terminal() {
+        stdin.lock().read_line(&mut answer)
+    } else {
+        Ok(0)
+    };
+    match read {
+        Ok(0) | Err(_) => {
+            println!();
+            fail!("Нет ответа, операция отменена. Для запуска без подтверждения используйте --yes");
+            false
+        }
+        Ok(_) if matches!(answer.trim(), "y" | "Y" | "yes" | "д" | "да") => true,
+        Ok(_) => {
+            println!("Операция отменена");
+            false
+        }
+    }
+}
+
+// Репозиторий и название операции для команд, которые изменяют словари и должны блокировать репозиторий
+fn lock_target(subcommand: &CliSubcommands) -> Option<(String, &'static str)> {
+    let (dictionary_path, operation) = match subcommand {
+        Translate(TranslateType::Manual(args)) => {
+            (args.dictionary_path.clone(), "translate-manual")
+        }
+        Translate(TranslateType::Auto(ApiVariants::Libretranslate(args))) => {
+            (args.dictionaries_path.clone(), "translate-auto")
+        }
+        Translate(TranslateType::Auto(ApiVariants::Deepl(args))) => {
+            (args.dictionaries_path.clone(), "translate-auto")
+        }
+        // В режиме --watch сканирование не завершается, поэтому репозиторий не блокируется
+        Scan(args) if !args.watch && !args.dry_run => {
+            let config = parse_config(args.config_path.clone()).ok()?;
+            (config.dictionary_repo.primary().to_owned(), "scan")
+        }
+        Sync(args) => (args.dictionary_path.clone(), "sync"),
+        Key(KeyCommand::Rename(args)) => (args.dictionary_path.clone(), "key-rename"),
+        Key(KeyCommand::Remove(args)) if !args.dry_run => {
+            (args.dictionary_path.clone(), "key-remove")
+        }
+        Merge(args) => (args.dictionary_path.clone(), "merge"),
+        Package(PackageCommand::Import(args)) => (args.dictionary_path.clone(), "import"),
+        Fmt(args) if !args.check => (args.dictionary_path.clone(), "fmt"),
+        Rollback(args) if !args.list => (args.dictionary_path.clone(), "rollback"),
+        Lock(args) | Unlock(args) => (args.dictionary_path.clone(), "lock"),
+        Review(args) => (args.dictionary_path.clone(), "review"),
+        Migrate(args) => (args.dictionary_path.clone(), "migrate"),
+        Release(args) if !args.list => (args.dictionary_path.clone(), "release"),
+        Import(ImportType::Csv(args))
+        | Import(ImportType::Xlsx(args))
+        | Import(ImportType::Xliff(args))
+        | Import(ImportType::Po(args)) => (args.dictionary_path.clone(), "import"),
+        _ => return None,
+    };
+    Some((dictionary_path, operation))
+}
+
+// Репозиторий и название операции, если перед командой нужно сохранить резервную копию словарей
+fn backup_target(subcommand: &CliSubcommands) -> Option<(&str, &'static str)> {
+    match subcommand {
+        Translate(TranslateType::Auto(ApiVariants::Libretranslate(args))) => {
+            Some((&args.dictionaries_path, "translate"))
+        }
+        Translate(TranslateType::Auto(ApiVariants::Deepl(args))) => {
+            Some((&args.dictionaries_path, "translate"))
+        }
+        Sync(args) if args.prune => Some((&args.dictionary_path, "sync")),
+        Key(KeyCommand::Remove(args)) if !args.dry_run => {
+            Some((&args.dictionary_path, "key-remove"))
+        }
+        Merge(args) => Some((&args.dictionary_path, "merge")),
+        Package(PackageCommand::Import(args)) => Some((&args.dictionary_path, "import")),
+        Import(ImportType::Csv(args))
+        | Import(ImportType::Xlsx(args))
+        | Import(ImportType::Xliff(args))
+        | Import(ImportType::Po(args)) => Some((&args.dictionary_path, "import")),
+        _ => None,
+    }
+}
+
+fn print_sync_report(report: &SyncReport) {
+    if report.is_empty() {
+        println!("Дочерние словари уже совпадают с базовым");
+        return;
+    }
+    for (language, added) in &report.added {
+        let removed = &report.removed[language];
+        if added.is_empty() && removed.is_empty() {
+            continue;
+        }
+        println!(
+            "{}: добавлено {}, удалено {}",
+            language,
+            added.len(),
+            removed.len()
+        );
+        for tag in added {
+            println!("  + {}", tag);
+        }
+        for tag in removed {
+            println!("  - {}", tag);
+        }
+    }
+}
+
+fn print_usage_report(report: &UsageReport) {
+    if report.is_empty() {
+        println!("Все ключи базового словаря используются в исходном коде");
+        return;
+    }
+    if !report.unused.is_empty() {
+        println!("Не используются в исходном коде:");
+        for tag in &report.unused {
+            println!("  - {}", tag);
+        }
+    }
+    if !report.missing.is_empty() {
+        println!("Нет в базовом словаре:");
+        for (tag, locations) in &report.missing {
+            println!("  + {}", tag);
+            for location in locations {
+                println!("    используется в {}", location);
+            }
+        }
+    }
+    println!(
+        "Неиспользуемых ключей: {}, отсутствующих: {}",
+        report.unused.len(),
+        report.missing.len()
+    );
+}
+
+fn print_search_results(dictionary_path: &str, found: &[SearchMatch]) {
+    if found.is_empty() {
+        println!("Ничего не найдено");
+        return;
+    }
+    let locations = read_source_locations(dictionary_path).unwrap_or_default();
+    let mut tags: Vec<&str> = vec![];
+    for result in found {
+        if !tags.contains(&result.tag.as_str()) {
+            tags.push(&result.tag);
+        }
+    }
+    for tag in &tags {
+        println!("{}", tag);
+        for result in found.iter().filter(|result| result.tag == *tag) {
+            println!("  {}: {}", result.language, result.value);
+        }
+        for location in locations_for(&locations, tag) {
+            println!("  используется в {}", location);
+        }
+    }
+    println!("Найдено ключей: {}", tags.len());
+}
+
+fn print_history(records: &[HistoryRecord]) {
+    let value = |value: &Option<String>| match value {
+        Some(value) => format!("\"{}\"", value),
+        None => "(нет)".to_owned(),
+    };
+    for record in records {
+        let form = record
+            .form
+            .as_ref()
+            .map(|form| format!(" ({})", form))
+            .unwrap_or_default();
+        println!(
+            "{} {} {} [{}] {}{}: {} -> {}",
+            record.time,
+            record.user,
+            record.operation,
+            record.language,
+            record.tag,
+            form,
+            value(&record.before),
+            value(&record.after)
+        );
+    }
+}
+
+fn print_duplicates(groups: &[DuplicateGroup]) {
+    for group in groups {
+        match group.base {
+            true => println!("[{}] одинаковые исходные фразы \"{}\":", group.language, group.text),
+            false => println!("[{}] одинаковые переводы \"{}\":", group.language, group.text),
+        }
+        for tag in &group.tags {
+            println!("  {}", tag);
+        }
+    }
+    println!(
+        "Найдено групп: {}. Ключи с одинаковыми исходными фразами можно заменить в коде первым ключом группы и удалить остальные через dms key remove",
+        groups.len()
+    );
+}
+
+fn print_release(manifest: &ReleaseManifest) {
+    println!(
+        "Релиз {} сохранен ({}), файлов словарей: {}",
+        manifest.version,
+        manifest.created,
+        manifest.files.len()
+    );
+    for language in &manifest.status.languages {
+        println!(
+            "{}: переведено {} из {}",
+            language.language, language.translated, language.total
+        );
+    }
+}
+
+fn print_statistics(statistics: &[LanguageStatistics]) {
+    println!(
+        "{:<10} {:>8} {:>8} {:>10} {:>11} {:>16}",
+        "Язык", "Ключей", "Слов", "Символов", "С переменн.", "Слов к переводу"
+    );
+    for language in statistics {
+        let name = match language.base {
+            true => format!("{} (база)", language.language),
+            false => language.language.clone(),
+        };
+        let untranslated = match language.base {
+            true => "-".to_owned(),
+            false => language.untranslated_source_words.to_string(),
+        };
+        println!(
+            "{:<10} {:>8} {:>8} {:>10} {:>11} {:>16}",
+            name,
+            language.entries,
+            language.words,
+            language.characters,
+            language.with_placeholders,
+            untranslated
+        );
+    }
+    for language in statistics.iter().filter(|language| !language.longest.is_empty()) {
+        println!("\nСамые длинные строки ({}):", language.language);
+        for string in &language.longest {
+            println!("  {:>5}  {}: {}", string.characters, string.tag, string.text);
+        }
+    }
+}
+
+fn print_status(report: &RepositoryStatus) {
+    println!(
+        "Базовый словарь: {}, ключей: {}",
+        report.basic_language, report.total
+    );
+    if report.languages.is_empty() {
+        println!("Дочерних словарей нет");
+        return;
+    }
+    println!(
+        "{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>8}",
+        "Язык", "Переведено", "Пусто", "Машинный", "Ручной", "Устарело", "Готово"
+    );
+    for language in &report.languages {
+        println!(
+            "{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>7.1}%",
+            language.language,
+            language.translated,
+            language.empty,
+            language.machine,
+            language.human,
+            language.outdated,
+            language.percentage()
+        );
+    }
+}
+
+fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        let mark = match diagnostic.status {
+            DiagnosticStatus::Ok => "✓",
+            DiagnosticStatus::Warning => "!",
+            DiagnosticStatus::Error => "✗",
+        };
+        println!("{} {}: {}", mark, diagnostic.check, diagnostic.message);
+        if let Some(fix) = &diagnostic.fix {
+            println!("    {}", fix);
+        }
+    }
+    let count = |status| {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.status == status)
+            .count()
+    };
+    println!(
+        "Ошибок: {}, предупреждений: {}",
+        count(DiagnosticStatus::Error),
+        count(DiagnosticStatus::Warning)
+    );
+}
+
+fn print_ci_summary(report: &CiReport) {
+    for language in &report.languages {
+        println!(
+            "{} {}: не переведено {}, устарело {}, проблем {}",
+            if language.passed() { "✓" } else { "✗" },
+            language.language,
+            language.untranslated,
+            language.outdated,
+            language.issues
+        );
+    }
+    match report.passed {
+        true => println!("Проверка пройдена"),
+        false => println!("Проверка не пройдена"),
+    }
+}
+
+fn print_scan_preview(preview: &ScanPreview) {
+    println!("Пробное сканирование, словари не изменяются");
+    for (dictionary_dir, entries) in &preview.added {
+        println!("{}:", dictionary_dir);
+        for added in entries {
+            let entry = &added.entry;
+            match entry.tag() == entry.phrase {
+                true => println!("  + {} ({}:{})", entry.phrase, added.file, added.line),
+                false => println!(
+                    "  + {} ({}) ({}:{})",
+                    entry.tag(),
+                    entry.phrase,
+                    added.file,
+                    added.line
+                ),
+            }
+        }
+    }
+    if !preview.unreadable.is_empty() {
+        println!("Пропущены двоичные файлы и файлы в неизвестной кодировке (см. параметр encoding):");
+        for file in &preview.unreadable {
+            println!("  {}", file);
+        }
+    }
+    println!(
+        "Просканировано файлов: {}, будет добавлено фраз: {}",
+        preview.scanned,
+        preview.added_count()
+    );
+}
+
+#[doc = "Показывает записи, фраз которых больше нет в исходном коде, и после подтверждения удаляет их из всех словарей"]
+fn run_prune(
+    config_path: Option<String>,
+    output: OutputFormat,
+) -> Result<usize, api::errors::errors::StaticDictionaryErrors> {
+    let config = parse_config(config_path)?;
+    let unused = find_unused_entries(&config)?;
+    if unused.is_empty() {
+        if output != OutputFormat::Json {
+            println!("Все фразы базового словаря используются в исходном коде");
+        }
+        return Ok(0);
+    }
+    if output != OutputFormat::Json {
+        println!("Фразы, которых больше нет в исходном коде:");
+        for (dictionary_dir, entries) in &unused {
+            if unused.len() > 1 || *dictionary_dir != config.dictionary_repo.primary() {
+                println!("{}:", dictionary_dir);
+            }
+            for entry in entries {
+                match entry.tag() == entry.phrase {
+                    true => println!("  {}", entry.phrase),
+                    false => println!("  {} ({})", entry.tag(), entry.phrase),
+                }
+            }
+        }
+    }
+    let count: usize = unused.values().map(Vec::len).sum();
+    let mut files = vec![];
+    for dictionary_dir in unused.keys() {
+        files.push(
+            Path::new(dictionary_dir)
+                .join(get_basic_dictionary(dictionary_dir)?)
+                .to_string_lossy()
+                .into_owned(),
+        );
+        files.extend(existing_dictionaries(
+            dictionary_dir,
+            &find_all_translated_languages(dictionary_dir)?,
+        ));
+        let locations = Path::new(dictionary_dir).join(LOCATIONS_FILE);
+        if locations.is_file() {
+            files.push(locations.to_string_lossy().into_owned());
+        }
+    }
+    let question = format!("Удалить {} записей из базового и дочерних словарей?", count);
+    if !confirm(&question, &files, output) {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for (dictionary_dir, entries) in &unused {
+        let tags: Vec<String> = entries.iter().map(|entry| entry.tag().to_owned()).collect();
+        removed += remove_entries(dictionary_dir, &tags)?;
+    }
+    Ok(removed)
+}
+
+fn run_lock(args: &LockArgs, locked: bool) -> Result<usize, api::errors::errors::StaticDictionaryErrors> {
+    let patterns = args
+        .tags
+        .iter()
+        .map(|tag| match args.pattern {
+            true => Regex::new(tag),
+            false => Regex::new(&format!("^{}$", regex::escape(tag))),
+        })
+        .collect::<Result<Vec<Regex>, regex::Error>>()?;
+    let languages = match args.languages.is_empty() {
+        true => find_all_translated_languages(&args.dictionary_path)?,
+        false => args.languages.iter().map(|code| code.to_string()).collect(),
+    };
+    set_entries_locked(&args.dictionary_path, &languages, &patterns, locked)
+}
+
+fn export_languages(args: &ExportArgs) -> Option<Vec<String>> {
+    (!args.languages.is_empty()).then(|| args.languages.iter().map(|code| code.to_string()).collect())
+}
+
+fn run_export(args: &ExportArgs) -> Result<usize, api::errors::errors::StaticDictionaryErrors> {
+    let table = TranslationTable::from_repository(&args.dictionary_path, export_languages(args))?;
+    let mut content = vec![];
+    table.write_csv(&mut content)?;
+    atomic_write(&args.output, content)?;
+    Ok(table.rows.len())
+}
+
+#[derive(Debug, Default)]
+#[doc = "Результат сборки для вывода: созданные файлы и унаследованные переводы"]
+struct BuildOutput {
+    files: Vec<String>,
+    inherited: std::collections::BTreeMap<String, std::collections::BTreeMap<String, String>>,
+}
+
+impl From<BuildReport> for BuildOutput {
+    fn from(report: BuildReport) -> BuildOutput {
+        BuildOutput {
+            files: vec![],
+            inherited: report.inherited,
+        }
+    }
+}
+
+impl From<Vec<String>> for BuildOutput {
+    fn from(files: Vec<String>) -> BuildOutput {
+        BuildOutput {
+            files,
+            ..Default::default()
+        }
+    }
+}
+
+// Собирает словари выбранного фреймворка. Ошибка возвращается готовым сообщением
+fn run_build(framework: FrameworkType) -> Result<BuildOutput, String> {
+    let languages = |languages: Option<Vec<api::language::LanguageCode>>| {
+        languages.map(|languages| languages.into_iter().map(String::from).collect())
+    };
+    let options = |args: &BuildArgs| {
+        load_build_options(args).map_err(|err| format!("Не удалось прочитать конфиг: {}", err))
+    };
+    // Сборки без запасных локалей из конфига берут из аргументов только шаблон пути
+    let template_options = |output_template: Option<String>| BuildOptions {
+        output_template,
+        ..Default::default()
+    };
+    let result = match framework {
+        FrameworkType::I18next(args) => build_for_i18next(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages.clone()),
+            &options(&args)?,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Gettext(args) => build_for_gettext(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages),
+            &template_options(args.output_template),
+            args.mo,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Arb(args) => build_for_arb(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages.clone()),
+            &options(&args)?,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Properties(args) => build_for_properties(
+            &args.build.dictionary_path,
+            &args.build.output_directory,
+            languages(args.build.languages.clone()),
+            &options(&args.build)?,
+            args.encoding,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Rails(args) => build_for_rails(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages.clone()),
+            &options(&args)?,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::VueI18n(args) => build_for_vue_i18n(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages.clone()),
+            &options(&args)?,
+            args.module,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::NgxTranslate(args) => {
+            let options = options(&args.build)?;
+            build_for_ngx_translate(
+                &args.build.dictionary_path,
+                &args.build.output_directory,
+                languages(args.build.languages.clone()),
+                &options,
+                args.nested || options.nested_keys,
+            )
+            .map(BuildOutput::from)
+        }
+        FrameworkType::AngularXlf(args) => build_for_angular_xlf(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages),
+            &template_options(args.output_template),
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Webextension(args) => build_for_webextension(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages.clone()),
+            &options(&args)?,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Laravel(args) => build_for_laravel(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages.clone()),
+            &options(&args)?,
+        )
+        .map(BuildOutput::from),
+        FrameworkType::Types(args) => build_type_definitions(
+            &args.dictionary_path,
+            &args.output_directory,
+            &template_options(args.output_template),
+            args.i18next,
+        )
+        .map(|path| BuildOutput::from(vec![path])),
+        FrameworkType::Rust(args) => build_rust_module(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages),
+            &template_options(args.output_template),
+        )
+        .map(|path| BuildOutput::from(vec![path])),
+        FrameworkType::Qt(args) => build_for_qt(
+            &args.dictionary_path,
+            &args.output_directory,
+            languages(args.languages),
+            &template_options(args.output_template),
+        )
+        .map(BuildOutput::from),
+    };
+    result.map_err(|error| format!("Произошла ошибка при сборке: {}", error))
+}
+
+// Репозиторий и выбранные языки сборки, которую можно выполнить по одному языку. Для сборок в один общий файл (types, rust, модуль vue-i18n, пространства имен i18next) возвращает None
+fn per_language_build(
+    framework: &mut FrameworkType,
+) -> Option<(&str, &mut Option<Vec<api::language::LanguageCode>>)> {
+    match framework {
+        FrameworkType::Types(_) | FrameworkType::Rust(_) => None,
+        FrameworkType::VueI18n(args) if args.module.is_some() => None,
+        FrameworkType::I18next(args)
+            if load_build_options(args).is_ok_and(|options| !options.namespaces.is_empty()) =>
+        {
+            None
+        }
+        FrameworkType::I18next(args)
+        | FrameworkType::Arb(args)
+        | FrameworkType::Rails(args)
+        | FrameworkType::VueI18n(args)
+        | FrameworkType::Webextension(args)
+        | FrameworkType::Laravel(args) => Some((&args.dictionary_path, &mut args.languages)),
+        FrameworkType::Properties(args) => {
+            Some((&args.build.dictionary_path, &mut args.build.languages))
+        }
+        FrameworkType::NgxTranslate(args) => {
+            Some((&args.build.dictionary_path, &mut args.build.languages))
+        }
+        FrameworkType::Gettext(args) => Some((&args.dictionary_path, &mut args.languages)),
+        FrameworkType::Qt(args) | FrameworkType::AngularXlf(args) => {
+            Some((&args.dictionary_path, &mut args.languages))
+        }
+    }
+}
+
+// Собирает словари по одному языку, чтобы показать ход сборки. Если сборку нельзя разделить по языкам, она выполняется целиком
+fn build_with_progress(
+    mut framework: FrameworkType,
+    progress: &dyn Progress,
+) -> Result<BuildOutput, String> {
+    let languages = match per_language_build(&mut framework) {
+        Some((dictionary_path, languages)) => match languages.clone() {
+            Some(languages) => Some(languages),
+            None => find_all_translated_languages(dictionary_path)
+                .ok()
+                .and_then(|languages| {
+                    languages
+                        .iter()
+                        .map(|language| api::language::LanguageCode::new(language).ok())
+                        .collect()
+                }),
+        },
+        None => None,
+    };
+    let Some(languages) = languages.filter(|languages| languages.len() > 1) else {
+        return run_build(framework);
+    };
+    progress.start(BUILD_STAGE, languages.len());
+    let mut built = BuildOutput::default();
+    for language in languages {
+        let mut step = framework.clone();
+        if let Some((_, languages)) = per_language_build(&mut step) {
+            *languages = Some(vec![language]);
+        }
+        let step = run_build(step).inspect_err(|_| progress.finish(BUILD_STAGE))?;
+        // Общие файлы, например шаблон messages.pot, собираются на каждом шаге
+        for path in step.files {
+            if !built.files.contains(&path) {
+                built.files.push(path);
+            }
+        }
+        built.inherited.extend(step.inherited);
+        progress.advance(BUILD_STAGE);
+    }
+    progress.finish(BUILD_STAGE);
+    Ok(built)
+}
+
+fn print_build_output(built: &BuildOutput) {
+    for path in &built.files {
+        println!("Создан файл {}", path);
+    }
+    for (language, keys) in &built.inherited {
+        if keys.is_empty() {
+            continue;
+        }
+        println!("{}: унаследовано переводов: {}", language, keys.len());
+        for (key, fallback) in keys {
+            println!("  {} <- {}", key, fallback);
+        }
+    }
+    println!("Сборка завершена успешно!")
+}
+
+fn print_json(value: &serde_json::Value) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(err) => fail!("Не удалось сформировать отчет: {}", err),
+    }
+}
+
+// Ошибка команды в режиме --output json
+fn print_json_error(error: &str) {
+    FAILED.store(true, Ordering::Relaxed);
+    print_json(&serde_json::json!({ "success": false, "error": error }));
+}
+
+// Аннотации GitHub Actions, по одной на строку
+fn print_annotations(annotations: &[Annotation]) {
+    for annotation in annotations {
+        println!("{}", annotation);
+    }
+}
+
+// Выводит ошибку в выбранном формате и завершает процесс с кодом 1
+fn exit_with_error(output: OutputFormat, message: &str) -> ! {
+    match output {
+        OutputFormat::Json => print_json_error(message),
+        OutputFormat::Text => println!("{}", message),
+        OutputFormat::Github => print_annotations(&[Annotation {
+            level: AnnotationLevel::Error,
+            file: None,
+            line: None,
+            title: "dms".to_owned(),
+            message: message.to_owned(),
+        }]),
+    }
+    std::process::exit(1);
+}
+
+fn load_build_options(
+    args: &BuildArgs,
+) -> Result<BuildOptions, api::errors::errors::StaticDictionaryErrors> {
+    let mut options: BuildOptions = args.into();
+    if let Some(config_path) = &args.config_path {
+        let config = parse_config(Some(config_path.to_owned()))?;
+        options.fallbacks = config.fallbacks;
+        options.nested_keys = config.nested_keys;
+        options.namespaces = config.namespaces;
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use clap::Parser;
+
+    use super::*;
+
+    // Содержимое всех файлов директории по путям
+    fn snapshot(dir: &Path, files: &mut BTreeMap<PathBuf, Vec<u8>>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                snapshot(&path, files);
+            } else {
+                files.insert(path.clone(), fs::read(&path).unwrap());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_leaves_working_tree_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        let repository = format!("{}/dictionaries", root);
+        fs::create_dir_all(&repository).unwrap();
+        fs::write(format!("{}/dictionary-ru.base.json", repository), r#"["Вход", "Выход"]"#).unwrap();
+        fs::write(format!("{}/dictionary-en.json", repository), r#"{"Вход": "Sign in"}"#).unwrap();
+        fs::create_dir_all(format!("{}/locales", root)).unwrap();
+        fs::write(format!("{}/locales/en.json", root), r#"{"title": "Title"}"#).unwrap();
+        fs::write(format!("{}/locales/ru.json", root), r#"{"title": "Заголовок"}"#).unwrap();
+        fs::write(format!("{}/table.csv", root), "old").unwrap();
+        let mut before = BTreeMap::new();
+        snapshot(dir.path(), &mut before);
+
+        let commands = [
+            format!("init ru {}/new", root),
+            format!("init --format yaml en {}", root),
+            format!("export csv {} {}/table.csv", repository, root),
+            format!("export xlsx {} {}/table.xlsx", repository, root),
+            format!("export xliff {} {}/xliff", repository, root),
+            format!("package export {} en -o {}/package.json", repository, root),
+            format!("package export {} en -o {}", repository, root),
+            format!("import i18next {}/imported {}/locales --basic-language en", root, root),
+            format!("translate manual {} de", repository),
+            format!("key rename {} Вход login", repository),
+            format!("lock {} Вход", repository),
+            format!("fmt {}", repository),
+            format!("sync {}", repository),
+            format!("build i18next {} {}/build", repository, root),
+        ];
+        for command in commands {
+            let argv = ["dms", "--dry-run", "--yes"]
+                .into_iter()
+                .map(String::from)
+                .chain(command.split(' ').map(String::from));
+            let cli = TranslatorCli::try_parse_from(argv).unwrap();
+            run(cli.subcommand, OutputFormat::Text, Arc::new(NoProgress), true)
+                .await
+                .unwrap();
+            let mut after = BTreeMap::new();
+            snapshot(dir.path(), &mut after);
+            assert_eq!(before, after, "dms --dry-run {} изменила файлы", command);
+        }
+    }
+}
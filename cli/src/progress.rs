@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::io::{stderr, IsTerminal};
+use std::sync::Mutex;
+
+use api::progress::Progress;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+#[doc = "Индикаторы хода сканирования, автоперевода и сборки в stderr: по одной строке на этап с оставшимся временем. Если stderr не терминал или индикаторы выключены, ничего не выводится"]
+pub struct BarProgress {
+    bars: MultiProgress,
+    stages: Mutex<HashMap<String, ProgressBar>>,
+}
+
+impl BarProgress {
+    pub fn new(enabled: bool) -> BarProgress {
+        let target = match enabled && stderr().is_terminal() {
+            true => ProgressDrawTarget::stderr(),
+            false => ProgressDrawTarget::hidden(),
+        };
+        BarProgress {
+            bars: MultiProgress::with_draw_target(target),
+            stages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn stage(&self, stage: &str) -> Option<ProgressBar> {
+        self.stages.lock().unwrap().get(stage).cloned()
+    }
+}
+
+impl Progress for BarProgress {
+    fn start(&self, stage: &str, total: usize) {
+        let style = ProgressStyle::with_template(
+            "{prefix:>10} [{bar:40.cyan/blue}] {pos}/{len} (осталось {eta})",
+        )
+        .unwrap()
+        .progress_chars("=> ");
+        let bar = self
+            .bars
+            .add(ProgressBar::new(total as u64))
+            .with_style(style)
+            .with_prefix(stage.to_owned());
+        self.stages.lock().unwrap().insert(stage.to_owned(), bar);
+    }
+
+    fn advance(&self, stage: &str) {
+        if let Some(bar) = self.stage(stage) {
+            bar.inc(1);
+        }
+    }
+
+    fn finish(&self, stage: &str) {
+        if let Some(bar) = self.stages.lock().unwrap().remove(stage) {
+            bar.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::entries::{entry_view, list_keys, suggest_translation, update_entry, EntryUpdate};
+use api::errors::errors::StaticDictionaryErrors;
+use api::file_system::parse_config;
+use api::history::{record_changes, RepositoryValues};
+use api::parser::types::ConfigFileParameters;
+use api::parser::watch_repository;
+use api::repository_lock::RepositoryLock;
+use api::scan_cache::content_hash;
+use api::static_translate::status;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::sync::watch;
+
+use crate::args::cli_args::ServeArgs;
+use crate::{build_i18next, configured_translator, default_languages, translate_locked};
+
+// Операция, под которой сервер блокирует репозиторий и пишет изменения в журнал истории
+const OPERATION: &str = "serve";
+
+// Редактор переводов встроен в dms и отдается с корня сервера
+const INDEX_HTML: &str = include_str!("ui/index.html");
+const APP_JS: &str = include_str!("ui/app.js");
+const STYLE_CSS: &str = include_str!("ui/style.css");
+
+// Сколько ждать после последнего изменения словаря, прежде чем пересобирать /locales
+const REBUILD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[doc = "Настройки сервера, общие для всех запросов"]
+struct ServeState {
+    args: ServeArgs,
+    config: Option<ConfigFileParameters>,
+    /// Директория сборки из аргументов или output_dir конфига
+    output_directory: Option<String>,
+    /// Номер последней пересборки /locales, по изменению которого клиенты /locales/events получают событие reload
+    rebuilds: watch::Sender<u64>,
+}
+
+// Ошибка запроса: код ответа и сообщение, которое клиент получает как {"success": false, "error": "..."}
+struct ApiError(StatusCode, String);
+
+impl From<StaticDictionaryErrors> for ApiError {
+    fn from(err: StaticDictionaryErrors) -> ApiError {
+        let code = match err {
+            StaticDictionaryErrors::EntryNotFound(_)
+            | StaticDictionaryErrors::BasicDictionaryNotFound => StatusCode::NOT_FOUND,
+            StaticDictionaryErrors::RepositoryLocked(_) => StatusCode::CONFLICT,
+            StaticDictionaryErrors::UnknownLanguage(_)
+            | StaticDictionaryErrors::InvalidLocale(_)
+            | StaticDictionaryErrors::InvalidConfig(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(code, err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let ApiError(code, message) = self;
+        (code, Json(json!({"success": false, "error": message}))).into_response()
+    }
+}
+
+type ApiResult = Result<Json<Value>, ApiError>;
+
+impl ServeState {
+    fn dictionary_path(&self) -> &str {
+        &self.args.dictionary_path
+    }
+
+    fn config(&self) -> Result<&ConfigFileParameters, ApiError> {
+        self.config.as_ref().ok_or_else(|| {
+            ApiError(
+                StatusCode::BAD_REQUEST,
+                "сервер запущен без конфига, передайте его через --config-path".to_owned(),
+            )
+        })
+    }
+}
+
+// GET /api/languages: состояние перевода по языкам
+async fn languages(State(state): State<Arc<ServeState>>) -> ApiResult {
+    Ok(Json(json!(status(state.dictionary_path(), None)?)))
+}
+
+// GET /api/keys?language=en&untranslated=true: ключи базового словаря
+async fn keys(
+    State(state): State<Arc<ServeState>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let untranslated = query
+        .get("untranslated")
+        .is_some_and(|value| value == "true" || value == "1");
+    let keys = list_keys(
+        state.dictionary_path(),
+        query.get("language").map(String::as_str),
+        untranslated,
+    )?;
+    Ok(Json(json!(keys)))
+}
+
+// GET /api/entries/{tag}: запись со всеми переводами и местами использования
+async fn entry(State(state): State<Arc<ServeState>>, Path(tag): Path<String>) -> ApiResult {
+    Ok(Json(json!(entry_view(state.dictionary_path(), &tag)?)))
+}
+
+// PUT /api/entries/{tag}/{language}: изменение перевода записи
+async fn update(
+    State(state): State<Arc<ServeState>>,
+    Path((tag, language)): Path<(String, String)>,
+    Json(update): Json<EntryUpdate>,
+) -> ApiResult {
+    let dictionary_path = state.dictionary_path();
+    let _lock = RepositoryLock::acquire(dictionary_path, OPERATION)?;
+    let before = RepositoryValues::read(dictionary_path).ok();
+    let entry = update_entry(dictionary_path, &tag, &language, update)?;
+    if let Some(before) = before {
+        if let Err(err) = record_changes(dictionary_path, OPERATION, &before) {
+            eprintln!("Не удалось записать изменения в журнал истории: {}", err);
+        }
+    }
+    Ok(Json(json!({"success": true, "entry": entry})))
+}
+
+// POST /api/entries/{tag}/{language}/suggest: машинный перевод записи, который не записывается в словарь
+async fn suggest(
+    State(state): State<Arc<ServeState>>,
+    Path((tag, language)): Path<(String, String)>,
+) -> ApiResult {
+    let (api, api_args) = configured_translator(
+        state.args.translator,
+        state.args.api_key.clone(),
+        state.config()?,
+    )?;
+    let suggestion =
+        suggest_translation(state.dictionary_path(), &tag, &language, api, api_args).await?;
+    Ok(Json(json!({"success": true, "suggestion": suggestion})))
+}
+
+// POST /api/translate с необязательным {"languages": [...]}: автоперевод непереведенных фраз, переведенные через PUT и проверенные записи не меняются. Без языков переводятся languages из конфига или все языки репозитория
+async fn translate(State(state): State<Arc<ServeState>>, body: Option<Json<Value>>) -> ApiResult {
+    let config = state.config()?;
+    let (api, api_args) =
+        configured_translator(state.args.translator, state.args.api_key.clone(), config)?;
+    let languages = match body.as_ref().and_then(|Json(body)| body.get("languages")) {
+        Some(languages) => serde_json::from_value(languages.clone())
+            .map_err(|err| ApiError(StatusCode::BAD_REQUEST, format!("languages: {}", err)))?,
+        None => default_languages(state.dictionary_path(), config),
+    };
+    translate_locked(
+        state.dictionary_path(),
+        languages.clone(),
+        api,
+        api_args,
+        None,
+        OPERATION,
+    )
+    .await?;
+    Ok(Json(json!({"success": true, "languages": languages})))
+}
+
+// POST /api/build: сборка словарей i18next в директорию сборки из аргументов или output_dir конфига
+async fn build(State(state): State<Arc<ServeState>>) -> ApiResult {
+    let Some(output_directory) = &state.output_directory else {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            "директория сборки не указана".to_owned(),
+        ));
+    };
+    build_i18next(
+        state.dictionary_path(),
+        output_directory,
+        state.args.config_path.as_deref(),
+    )
+    .map_err(|err| ApiError(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    Ok(Json(
+        json!({"success": true, "output_directory": output_directory}),
+    ))
+}
+
+// Заголовки ответов /locales: запросы разрешены с любого адреса dev-сервера фронтенда, а браузер проверяет актуальность словаря при каждой загрузке
+fn locale_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        HeaderValue::from_static("*"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    headers
+}
+
+// Имя языка или пространства имен из пути запроса, которое не выводит за директорию сборки
+fn is_path_segment(segment: &str) -> bool {
+    !segment.is_empty() && !segment.starts_with('.') && !segment.contains(['/', '\\'])
+}
+
+// GET /locales/{lng}/{ns}.json: собранный словарь для i18next-http-backend. По ETag неизменный словарь не загружается повторно
+async fn locale(
+    State(state): State<Arc<ServeState>>,
+    Path((language, file)): Path<(String, String)>,
+    request_headers: HeaderMap,
+) -> Response {
+    let mut headers = locale_headers();
+    let path = match (&state.output_directory, file.strip_suffix(".json")) {
+        (Some(output_directory), Some(namespace))
+            if is_path_segment(&language) && is_path_segment(namespace) =>
+        {
+            PathBuf::from(output_directory).join(&language).join(&file)
+        }
+        _ => return (StatusCode::NOT_FOUND, headers).into_response(),
+    };
+    let Ok(content) = fs::read(path) else {
+        return (StatusCode::NOT_FOUND, headers).into_response();
+    };
+    let etag = format!("\"{}\"", content_hash(&content));
+    if request_headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes())
+    {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, etag);
+    }
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    (headers, content).into_response()
+}
+
+// OPTIONS /locales/{lng}/{ns}.json: ответ на предварительный запрос CORS, если бэкенд i18next добавляет свои заголовки
+async fn locale_preflight() -> Response {
+    let mut headers = locale_headers();
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static("GET, OPTIONS"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static("*"),
+    );
+    (StatusCode::NO_CONTENT, headers).into_response()
+}
+
+// GET /locales/events: поток server-sent events, в который после каждой пересборки приходит событие reload с ее номером
+async fn locale_events(State(state): State<Arc<ServeState>>) -> Response {
+    let events = futures::stream::unfold(state.rebuilds.subscribe(), |mut rebuilds| async move {
+        rebuilds.changed().await.ok()?;
+        let rebuild = *rebuilds.borrow_and_update();
+        let event = Event::default().event("reload").data(rebuild.to_string());
+        Some((Ok::<Event, Infallible>(event), rebuilds))
+    });
+    (
+        locale_headers(),
+        Sse::new(events).keep_alive(KeepAlive::default()),
+    )
+        .into_response()
+}
+
+// Пересборка словарей для /locales. Ошибки выводятся и не останавливают сервер
+fn rebuild_locales(state: &ServeState) {
+    let Some(output_directory) = &state.output_directory else {
+        return;
+    };
+    match build_i18next(
+        state.dictionary_path(),
+        output_directory,
+        state.args.config_path.as_deref(),
+    ) {
+        Ok(_) => {
+            println!("Словари i18next пересобраны в {}", output_directory);
+            state.rebuilds.send_modify(|rebuild| *rebuild += 1);
+        }
+        Err(err) => println!("Ошибка при сборке словарей i18next: {}", err),
+    }
+}
+
+// Маршруты редактора переводов и REST API
+fn router(state: Arc<ServeState>) -> Router {
+    let router = Router::new()
+        .route("/", get(|| async { Html(INDEX_HTML) }))
+        .route(
+            "/app.js",
+            get(|| async {
+                (
+                    [(header::CONTENT_TYPE, "text/javascript; charset=utf-8")],
+                    APP_JS,
+                )
+            }),
+        )
+        .route(
+            "/style.css",
+            get(|| async {
+                (
+                    [(header::CONTENT_TYPE, "text/css; charset=utf-8")],
+                    STYLE_CSS,
+                )
+            }),
+        )
+        .route("/api/languages", get(languages))
+        .route("/api/keys", get(keys))
+        .route("/api/entries/{tag}", get(entry))
+        .route("/api/entries/{tag}/{language}", put(update))
+        .route("/api/entries/{tag}/{language}/suggest", post(suggest))
+        .route("/api/translate", post(translate))
+        .route("/api/build", post(build));
+    let router = match state.args.locales {
+        true => router.route("/locales/events", get(locale_events)).route(
+            "/locales/{language}/{file}",
+            get(locale).options(locale_preflight),
+        ),
+        false => router,
+    };
+    router.with_state(state)
+}
+
+#[doc = "Запускает сервер dms serve и обрабатывает запросы, пока процесс не будет прерван. С --locales словари i18next собираются при запуске и пересобираются после каждого изменения репозитория"]
+pub async fn run_serve(args: ServeArgs) -> Result<(), StaticDictionaryErrors> {
+    let config = match &args.config_path {
+        Some(config_path) => Some(parse_config(Some(config_path.clone()))?),
+        None => None,
+    };
+    let output_directory = args
+        .output_directory
+        .clone()
+        .or_else(|| config.as_ref().map(|config| config.output_dir.clone()));
+    if args.locales && output_directory.is_none() {
+        return Err(StaticDictionaryErrors::InvalidConfig(
+            "для --locales нужна директория сборки в аргументах или output_dir в конфиге"
+                .to_owned(),
+        ));
+    }
+    let listener = tokio::net::TcpListener::bind((args.host.as_str(), args.port)).await?;
+    let address = listener.local_addr()?;
+    println!(
+        "Редактор переводов {} запущен на http://{}, для выхода нажмите Ctrl+C",
+        args.dictionary_path, address
+    );
+    let state = Arc::new(ServeState {
+        args,
+        config,
+        output_directory,
+        rebuilds: watch::channel(0).0,
+    });
+    if state.args.locales {
+        rebuild_locales(&state);
+        println!(
+            "Словари для i18next-http-backend: http://{}/locales/{{{{lng}}}}/{{{{ns}}}}.json",
+            address
+        );
+        let state = state.clone();
+        tokio::task::spawn_blocking(move || {
+            let dictionary_path = state.dictionary_path().to_owned();
+            if let Err(err) = watch_repository(&dictionary_path, REBUILD_DEBOUNCE, || {
+                rebuild_locales(&state)
+            }) {
+                println!("Не удалось отслеживать изменения репозитория: {}", err);
+            }
+        });
+    }
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
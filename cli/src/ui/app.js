@@ -0,0 +1,251 @@
+"use strict";
+
+// Редактор переводов dms serve: список ключей слева, запись выбранного ключа справа
+
+const state = {
+    language: null,
+    keys: [],
+    tag: null,
+    entry: null,
+};
+
+const element = (id) => document.getElementById(id);
+
+function showMessage(text, isError) {
+    const message = element("message");
+    message.textContent = text;
+    message.className = isError ? "error" : "";
+}
+
+async function request(method, path, body) {
+    const options = { method, headers: {} };
+    if (body !== undefined) {
+        options.headers["Content-Type"] = "application/json";
+        options.body = JSON.stringify(body);
+    }
+    const response = await fetch(path, options);
+    const result = await response.json();
+    if (!response.ok) {
+        throw new Error(result.error || response.statusText);
+    }
+    return result;
+}
+
+function entryPath(tag, suffix) {
+    return "/api/entries/" + encodeURIComponent(tag) + (suffix || "");
+}
+
+// Запись базового словаря хранится строкой или объектом
+function sourceEntry(source) {
+    return typeof source === "string" ? { phrase: source } : source;
+}
+
+async function loadLanguages() {
+    const report = await request("GET", "/api/languages");
+    const select = element("language");
+    const current = state.language;
+    select.replaceChildren();
+    for (const status of report.languages) {
+        const option = document.createElement("option");
+        option.value = status.language;
+        option.textContent = status.language;
+        select.append(option);
+    }
+    if (report.languages.length === 0) {
+        showMessage("В репозитории нет дочерних словарей, создайте их командой dms translate", true);
+        return;
+    }
+    state.language = current || report.languages[0].language;
+    select.value = state.language;
+    const status = report.languages.find((status) => status.language === state.language);
+    element("progress").textContent = status
+        ? "Переведено " + status.translated + " из " + report.total
+        : "";
+}
+
+async function loadKeys() {
+    if (!state.language) {
+        return;
+    }
+    const query = new URLSearchParams({
+        language: state.language,
+        untranslated: element("untranslated").checked,
+    });
+    state.keys = await request("GET", "/api/keys?" + query);
+    renderKeys();
+}
+
+function renderKeys() {
+    const filter = element("filter").value.toLowerCase();
+    const list = element("keys");
+    list.replaceChildren();
+    for (const key of state.keys) {
+        if (filter && !(key.tag + " " + key.phrase).toLowerCase().includes(filter)) {
+            continue;
+        }
+        const item = document.createElement("li");
+        item.textContent = key.phrase;
+        item.title = key.tag;
+        item.classList.toggle("untranslated", key.translated === false);
+        item.classList.toggle("selected", key.tag === state.tag);
+        item.addEventListener("click", () => selectKey(key.tag).catch(reportError));
+        list.append(item);
+    }
+}
+
+async function selectKey(tag) {
+    state.tag = tag;
+    state.entry = await request("GET", entryPath(tag));
+    renderKeys();
+    renderEntry();
+}
+
+function renderEntry() {
+    const entry = state.entry;
+    const source = sourceEntry(entry.source);
+    const translation = entry.translations[state.language] || {};
+    element("editor").hidden = false;
+    element("tag").textContent = entry.tag;
+    element("phrase").textContent = source.phrase;
+
+    const details = element("details");
+    details.replaceChildren();
+    const detail = (title, text) => {
+        if (!text) {
+            return;
+        }
+        const term = document.createElement("dt");
+        term.textContent = title;
+        const description = document.createElement("dd");
+        description.textContent = text;
+        details.append(term, description);
+    };
+    detail("Контекст", source.context);
+    detail("Комментарий базового словаря", source.comment);
+    for (const [form, text] of Object.entries(source.plural_forms || {})) {
+        detail("Форма " + form, text);
+    }
+    for (const [variant, text] of Object.entries(source.variants || {})) {
+        detail("Вариант " + variant, text);
+    }
+
+    const locations = element("locations");
+    locations.replaceChildren();
+    for (const location of entry.locations) {
+        const item = document.createElement("li");
+        item.textContent = location.file + ":" + location.line;
+        locations.append(item);
+    }
+    if (entry.locations.length === 0) {
+        const item = document.createElement("li");
+        item.textContent = "нет данных, запустите dms scan";
+        locations.append(item);
+    }
+
+    renderFields(source, translation);
+    element("comment").value = translation.comment || "";
+    element("entry-status").textContent = translation.status ? "Статус: " + translation.status : "";
+    renderSuggestion(translation.suggestion);
+}
+
+// Поля перевода: одно значение или по полю на каждую форму множественного числа и вариант
+function renderFields(source, translation) {
+    const fields = element("fields");
+    fields.replaceChildren();
+    const field = (name, title, value) => {
+        const label = document.createElement("label");
+        label.textContent = title;
+        const input = document.createElement("textarea");
+        input.rows = 2;
+        input.dataset.field = name;
+        input.value = value || "";
+        label.append(input);
+        fields.append(label);
+    };
+    const forms = translation.plural_forms || source.plural_forms;
+    const variants = translation.variants || source.variants;
+    if (!forms && !variants) {
+        field("value", "Перевод (" + state.language + ")", translation.value);
+    }
+    for (const form of Object.keys(forms || {})) {
+        field("plural_forms." + form, "Форма " + form, (translation.plural_forms || {})[form]);
+    }
+    for (const variant of Object.keys(variants || {})) {
+        field("variants." + variant, "Вариант " + variant, (translation.variants || {})[variant]);
+    }
+}
+
+function fillFields(suggestion) {
+    for (const input of element("fields").querySelectorAll("[data-field]")) {
+        const [group, name] = input.dataset.field.split(".");
+        const value = name ? (suggestion[group] || {})[name] : suggestion.value;
+        if (value !== undefined) {
+            input.value = value;
+        }
+    }
+}
+
+function renderSuggestion(suggestion) {
+    const box = element("suggestion");
+    box.replaceChildren();
+    box.hidden = !suggestion;
+    if (!suggestion) {
+        return;
+    }
+    const texts = [suggestion.value]
+        .concat(Object.values(suggestion.plural_forms || {}))
+        .concat(Object.values(suggestion.variants || {}))
+        .filter((text) => text);
+    const text = document.createElement("p");
+    text.textContent = "Машинный перевод: " + texts.join(" / ");
+    const accept = document.createElement("button");
+    accept.type = "button";
+    accept.textContent = "Подставить";
+    accept.addEventListener("click", () => fillFields(suggestion));
+    box.append(text, accept);
+}
+
+async function save(event) {
+    event.preventDefault();
+    const update = { comment: element("comment").value };
+    for (const input of element("fields").querySelectorAll("[data-field]")) {
+        const [group, name] = input.dataset.field.split(".");
+        if (name) {
+            update[group] = update[group] || {};
+            update[group][name] = input.value;
+        } else {
+            update.value = input.value;
+        }
+    }
+    const result = await request("PUT", entryPath(state.tag, "/" + state.language), update);
+    state.entry.translations[state.language] = result.entry;
+    renderEntry();
+    showMessage("Перевод " + state.tag + " сохранен", false);
+    await loadLanguages();
+    await loadKeys();
+}
+
+async function suggest() {
+    showMessage("Запрос к автопереводчику...", false);
+    const result = await request("POST", entryPath(state.tag, "/" + state.language + "/suggest"));
+    renderSuggestion(result.suggestion);
+    showMessage("", false);
+}
+
+function reportError(err) {
+    showMessage(err.message, true);
+}
+
+element("language").addEventListener("change", (event) => {
+    state.language = event.target.value;
+    loadLanguages()
+        .then(loadKeys)
+        .then(() => state.tag && selectKey(state.tag))
+        .catch(reportError);
+});
+element("untranslated").addEventListener("change", () => loadKeys().catch(reportError));
+element("filter").addEventListener("input", renderKeys);
+element("translation").addEventListener("submit", (event) => save(event).catch(reportError));
+element("suggest").addEventListener("click", () => suggest().catch(reportError));
+
+loadLanguages().then(loadKeys).catch(reportError);